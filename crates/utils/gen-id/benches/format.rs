@@ -0,0 +1,79 @@
+use divan::{Bencher, black_box};
+use gen_id::{Uuid, UuidFormat, UuidGenerator, format_uuid};
+
+fn main() {
+    divan::main();
+}
+
+const FORMATS: [UuidFormat; 4] = [
+    UuidFormat::Standard,
+    UuidFormat::StandardUppercase,
+    UuidFormat::Simple,
+    UuidFormat::SimpleUppercase,
+];
+
+/// Formatting through `to_string` and `to_uppercase`, as before the stack
+/// buffer rewrite
+fn format_with_to_string(format: UuidFormat, prefix: &str, uuid: &Uuid) -> String {
+    let formatted = match format {
+        UuidFormat::Standard => uuid.hyphenated().to_string(),
+        UuidFormat::StandardUppercase => uuid.hyphenated().to_string().to_uppercase(),
+        UuidFormat::Simple => uuid.simple().to_string(),
+        _ => uuid.simple().to_string().to_uppercase(),
+    };
+    format!("{prefix}{formatted}")
+}
+
+#[divan::bench(args = FORMATS)]
+fn to_string(bencher: Bencher, format: UuidFormat) {
+    let uuid = Uuid::new_v4();
+    bencher.bench(|| format_with_to_string(format, "order_", black_box(&uuid)));
+}
+
+#[divan::bench(args = FORMATS)]
+fn stack_buffer(bencher: Bencher, format: UuidFormat) {
+    let generator = UuidGenerator::v4()
+        .with_format(format)
+        .with_prefix("order_");
+    let uuid = Uuid::new_v4();
+    bencher.bench(|| generator.format(black_box(&uuid)));
+}
+
+const BATCH: usize = 1_000_000;
+
+fn batch_uuids() -> Vec<Uuid> {
+    (0..BATCH).map(|_| Uuid::new_v4()).collect()
+}
+
+/// A million IDs through uuid's own scalar encoders
+#[divan::bench(args = FORMATS, sample_count = 10)]
+fn batch_scalar(bencher: Bencher, format: UuidFormat) {
+    let uuids = batch_uuids();
+    bencher.bench(|| {
+        let mut buf = [0u8; 36];
+        black_box(&uuids)
+            .iter()
+            .map(|uuid| {
+                let encoded: &str = match format {
+                    UuidFormat::Standard => uuid.hyphenated().encode_lower(&mut buf),
+                    UuidFormat::StandardUppercase => uuid.hyphenated().encode_upper(&mut buf),
+                    UuidFormat::Simple => uuid.simple().encode_lower(&mut buf),
+                    _ => uuid.simple().encode_upper(&mut buf),
+                };
+                encoded.to_owned()
+            })
+            .collect::<Vec<String>>()
+    });
+}
+
+/// A million IDs through `format_uuid`, which uses uuid-simd with `simd`
+#[divan::bench(args = FORMATS, sample_count = 10)]
+fn batch_format_uuid(bencher: Bencher, format: UuidFormat) {
+    let uuids = batch_uuids();
+    bencher.bench(|| {
+        black_box(&uuids)
+            .iter()
+            .map(|uuid| format_uuid(uuid, format))
+            .collect::<Vec<String>>()
+    });
+}
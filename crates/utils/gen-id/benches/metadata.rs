@@ -0,0 +1,34 @@
+use divan::{Bencher, black_box};
+use gen_id::{ClientMetadata, OsType, UuidGenerator};
+
+fn main() {
+    divan::main();
+}
+
+const SIZES: [usize; 2] = [1_000, 1_000_000];
+
+fn metadata() -> ClientMetadata {
+    ClientMetadata::new(OsType::Linux, (6, 8), "web-prod-eu-west-1-07")
+        .with_user_agent("Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0")
+        .with_device_id("pid:4242")
+}
+
+#[divan::bench(args = SIZES, sample_count = 10)]
+fn per_item_loop(bencher: Bencher, count: usize) {
+    let generator = UuidGenerator::v7();
+    let metadata = metadata();
+    bencher.counter(count).bench(|| {
+        (0..count)
+            .map(|_| generator.generate_with_metadata(black_box(&metadata)))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[divan::bench(args = SIZES, sample_count = 10)]
+fn batch(bencher: Bencher, count: usize) {
+    let generator = UuidGenerator::v7();
+    let metadata = metadata();
+    bencher
+        .counter(count)
+        .bench(|| generator.generate_batch_with_metadata(count, black_box(&metadata)));
+}
@@ -0,0 +1,60 @@
+use divan::{AllocProfiler, Bencher, black_box};
+use gen_id::{AlphabetPreset, NanoIdGenerator, RngMode};
+
+// Counts allocations per iteration next to the timings
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
+
+fn main() {
+    divan::main();
+}
+
+/// A composite key built from a returned `String`, as before
+/// `generate_into`
+#[divan::bench]
+fn generate_then_push(bencher: Bencher) {
+    let generator = NanoIdGenerator::builder().prefix("ord_").build();
+    let mut key = String::with_capacity(64);
+    bencher.bench_local(|| {
+        key.clear();
+        key.push_str("tenant-7:");
+        key.push_str(&generator.generate());
+        black_box(&key);
+    });
+}
+
+/// The same key appended in place, without allocating
+#[divan::bench]
+fn generate_into(bencher: Bencher) {
+    let generator = NanoIdGenerator::builder().prefix("ord_").build();
+    let mut key = String::with_capacity(64);
+    bencher.bench_local(|| {
+        key.clear();
+        key.push_str("tenant-7:");
+        generator.generate_into(None, None, &mut key);
+        black_box(&key);
+    });
+}
+
+/// Throughput of each RNG mode at the reference NanoID length
+#[divan::bench(args = [RngMode::Secure, RngMode::Fast])]
+fn rng_mode(bencher: Bencher, mode: RngMode) {
+    let generator = NanoIdGenerator::builder().length(21).rng_mode(mode).build();
+    let mut id = String::with_capacity(21);
+    bencher
+        .counter(divan::counter::ItemsCount::new(1usize))
+        .bench_local(|| {
+            id.clear();
+            generator.generate_into(None, None, &mut id);
+            black_box(&id);
+        });
+}
+
+/// The reference NanoID in a stack array, without allocating
+#[divan::bench]
+fn generate_fixed(bencher: Bencher) {
+    let generator = NanoIdGenerator::with_preset(AlphabetPreset::UrlSafe);
+    bencher
+        .counter(divan::counter::ItemsCount::new(1usize))
+        .bench_local(|| generator.generate_fixed::<21>());
+}
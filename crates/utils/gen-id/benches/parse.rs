@@ -0,0 +1,31 @@
+use divan::{Bencher, black_box};
+use gen_id::{UuidGenerator, parse_uuid, parse_uuid_batch};
+
+fn main() {
+    divan::main();
+}
+
+const SIZES: [usize; 2] = [1_000, 100_000];
+
+fn inputs(count: usize) -> Vec<String> {
+    UuidGenerator::v4_seeded(1).generate_batch(count)
+}
+
+#[divan::bench(args = SIZES)]
+fn per_item_loop(bencher: Bencher, count: usize) {
+    let inputs = inputs(count);
+    bencher.counter(count).bench(|| {
+        black_box(&inputs)
+            .iter()
+            .map(|input| parse_uuid(input))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[divan::bench(args = SIZES)]
+fn batch(bencher: Bencher, count: usize) {
+    let inputs = inputs(count);
+    bencher
+        .counter(count)
+        .bench(|| parse_uuid_batch(black_box(&inputs).iter().map(String::as_str)));
+}
@@ -0,0 +1,432 @@
+use std::{
+    fmt,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+
+use crate::{
+    IdGenerator,
+    source::{self, Clock},
+    uuid::ClientMetadata,
+};
+
+const BASE36: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const FINGERPRINT_LEN: usize = 32;
+/// Upper bound of the random counter start, as in the reference
+/// implementation
+const INITIAL_COUNT_MAX: u64 = 476_782_367;
+
+/// Error for a CUID2 length outside [`Cuid2Generator::MIN_LENGTH`] to
+/// [`Cuid2Generator::MAX_LENGTH`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum Cuid2Error {
+    #[error("CUID2 length {length} is outside {min} to {max}")]
+    InvalidLength {
+        length: usize,
+        min: usize,
+        max: usize,
+    },
+}
+
+/// Generates CUID2-style IDs: a lowercase letter, then base36 digits of a
+/// hash over the time, a random salt, a counter and a host fingerprint
+///
+/// The IDs contain only `[a-z0-9]` and never start with a digit, so they
+/// work as HTML element ids, CSS selectors and URL slugs. They do not sort
+/// by time. See [`is_cuid2`] to validate one.
+///
+/// The fingerprint hashes the hostname from [`ClientMetadata`], the process
+/// ID and random entropy. The hash is SHA-512 where the reference uses
+/// SHA3-512. CUID2s are opaque and never decoded, so the IDs are
+/// interchangeable with other CUID2 libraries' as long as the shape matches.
+///
+/// ```
+/// use gen_id::{Cuid2Generator, is_cuid2};
+///
+/// let generator = Cuid2Generator::new(10)?;
+/// let id = generator.generate();
+/// assert!(is_cuid2(&id, 10));
+/// # Ok::<(), gen_id::Cuid2Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cuid2Generator {
+    length: usize,
+    host: Arc<str>,
+    fingerprint: Arc<str>,
+    counter: Arc<AtomicU64>,
+    rng: Option<Arc<InjectedRng>>,
+    clock: Option<Clock>,
+}
+
+impl Cuid2Generator {
+    pub const MIN_LENGTH: usize = 2;
+    pub const MAX_LENGTH: usize = 32;
+    pub const DEFAULT_LENGTH: usize = 24;
+
+    /// Creates a generator of `length`-character IDs
+    pub fn new(length: usize) -> Result<Self, Cuid2Error> {
+        if !(Self::MIN_LENGTH..=Self::MAX_LENGTH).contains(&length) {
+            return Err(Cuid2Error::InvalidLength {
+                length,
+                min: Self::MIN_LENGTH,
+                max: Self::MAX_LENGTH,
+            });
+        }
+        let mut generator = Self {
+            length,
+            host: Arc::from(host_info()),
+            fingerprint: Arc::from(""),
+            counter: Arc::new(AtomicU64::new(0)),
+            rng: None,
+            clock: None,
+        };
+        generator.reseed();
+        Ok(generator)
+    }
+
+    /// Draw the first letter, salt, counter start and fingerprint entropy
+    /// from `rng` instead of rand's thread-local CSPRNG
+    ///
+    /// Clones share the RNG. Combined with [`Self::with_clock`] and
+    /// [`Self::with_fingerprint`] the generator becomes reproducible, which
+    /// is meant for tests.
+    #[inline]
+    pub fn with_rng<R: RngCore + CryptoRng + Send + 'static>(mut self, rng: R) -> Self {
+        self.rng = Some(Arc::new(InjectedRng(Mutex::new(Box::new(rng)))));
+        self.reseed();
+        self
+    }
+
+    /// Take timestamps from `clock`, in milliseconds since the Unix epoch,
+    /// instead of the system time
+    #[inline]
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Some(Clock::new(clock));
+        self
+    }
+
+    /// Derive the fingerprint from `source` instead of the hostname and
+    /// process ID
+    ///
+    /// Random entropy is still mixed in, so two generators only share a
+    /// fingerprint when they also share an RNG seed.
+    #[inline]
+    pub fn with_fingerprint(mut self, source: &str) -> Self {
+        self.host = Arc::from(source);
+        self.reseed();
+        self
+    }
+
+    /// Length of every generated ID
+    #[inline]
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Generates one ID
+    pub fn generate(&self) -> String {
+        let mut input = String::with_capacity(128);
+        push_base36(&mut input, self.now());
+        self.push_entropy(&mut input, self.length);
+        push_base36(&mut input, self.counter.fetch_add(1, Ordering::Relaxed));
+        input.push_str(&self.fingerprint);
+
+        let mut id = String::with_capacity(self.length);
+        id.push(self.random_letter());
+        id.push_str(&hash(&input)[1..self.length]);
+        id
+    }
+
+    /// Generates `count` IDs
+    pub fn generate_batch(&self, count: usize) -> Vec<String> {
+        (0..count).map(|_| self.generate()).collect()
+    }
+
+    /// Pick a new counter start and fingerprint, from the injected RNG if
+    /// there is one
+    fn reseed(&mut self) {
+        let mut input = String::from(&*self.host);
+        self.push_entropy(&mut input, FINGERPRINT_LEN);
+        self.fingerprint = Arc::from(&hash(&input)[..FINGERPRINT_LEN]);
+
+        let start = self.next_u64() % INITIAL_COUNT_MAX;
+        self.counter = Arc::new(AtomicU64::new(start));
+    }
+
+    fn now(&self) -> u64 {
+        match &self.clock {
+            Some(clock) => clock.now_millis(),
+            None => source::system_millis(),
+        }
+    }
+
+    /// Append `len` random base36 digits
+    fn push_entropy(&self, out: &mut String, len: usize) {
+        let mut pushed = 0;
+        while pushed < len {
+            // Rejection sampling keeps the 36 digits equally likely
+            for byte in self.next_u64().to_le_bytes() {
+                let index = usize::from(byte & 63);
+                if index < BASE36.len() && pushed < len {
+                    out.push(char::from(BASE36[index]));
+                    pushed += 1;
+                }
+            }
+        }
+    }
+
+    fn random_letter(&self) -> char {
+        loop {
+            for byte in self.next_u64().to_le_bytes() {
+                let index = byte & 31;
+                if index < 26 {
+                    return char::from(b'a' + index);
+                }
+            }
+        }
+    }
+
+    fn next_u64(&self) -> u64 {
+        match &self.rng {
+            Some(rng) => rng
+                .0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .next_u64(),
+            None => rand::rng().next_u64(),
+        }
+    }
+}
+
+impl Default for Cuid2Generator {
+    /// A generator of [`Self::DEFAULT_LENGTH`]-character IDs
+    #[inline]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_LENGTH).expect("the default length is in range")
+    }
+}
+
+/// IDs of the generator's length
+impl IdGenerator for Cuid2Generator {
+    #[inline]
+    fn generate(&self) -> String {
+        Cuid2Generator::generate(self)
+    }
+
+    #[inline]
+    fn generate_batch(&self, count: usize) -> Vec<String> {
+        Cuid2Generator::generate_batch(self, count)
+    }
+}
+
+/// Whether `id` has the shape of a CUID2 of `expected_len` characters: a
+/// lowercase letter followed by lowercase letters and digits
+///
+/// Only the shape is checked, any `[a-z][a-z0-9]*` string of the right
+/// length passes.
+pub fn is_cuid2(id: &str, expected_len: usize) -> bool {
+    let bytes = id.as_bytes();
+    (Cuid2Generator::MIN_LENGTH..=Cuid2Generator::MAX_LENGTH).contains(&expected_len)
+        && bytes.len() == expected_len
+        && bytes[0].is_ascii_lowercase()
+        && bytes[1..]
+            .iter()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit())
+}
+
+/// A caller's RNG behind a lock, shared between clones and threads
+struct InjectedRng(Mutex<Box<dyn RngCore + Send>>);
+
+impl fmt::Debug for InjectedRng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("InjectedRng")
+    }
+}
+
+/// Hostname and process ID, the host half of the fingerprint
+fn host_info() -> String {
+    format!(
+        "{}pid:{}",
+        ClientMetadata::cached().hostname,
+        std::process::id()
+    )
+}
+
+/// SHA-512 of `input` in base36, without the leading digit
+///
+/// The leading digit of a 512-bit value is not uniform, so it is dropped as
+/// in the reference. That leaves about 98 digits, more than any length
+/// needs.
+fn hash(input: &str) -> String {
+    let digest = Sha512::digest(input.as_bytes());
+    let mut words = [0u32; 16];
+    for (word, chunk) in words.iter_mut().zip(digest.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().expect("chunks of 4"));
+    }
+
+    // Long division of the 512-bit value by 36 until nothing is left
+    let mut digits = Vec::with_capacity(100);
+    while words.iter().any(|&word| word != 0) {
+        let mut remainder = 0u64;
+        for word in &mut words {
+            let value = remainder << 32 | u64::from(*word);
+            *word = (value / 36) as u32;
+            remainder = value % 36;
+        }
+        digits.push(BASE36[remainder as usize]);
+    }
+    digits
+        .iter()
+        .rev()
+        .skip(1)
+        .map(|&digit| char::from(digit))
+        .collect()
+}
+
+fn push_base36(out: &mut String, mut value: u64) {
+    let mut digits = [0u8; 13];
+    let mut start = digits.len();
+    loop {
+        start -= 1;
+        digits[start] = BASE36[(value % 36) as usize];
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    out.push_str(std::str::from_utf8(&digits[start..]).expect("the alphabet is ASCII"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    fn seeded(seed: u64) -> Cuid2Generator {
+        Cuid2Generator::default()
+            .with_rng(ChaCha8Rng::seed_from_u64(seed))
+            .with_fingerprint("test-host")
+            .with_clock(|| 1_700_000_000_000)
+    }
+
+    #[test]
+    fn test_length_range() {
+        for length in [2, 10, 24, 32] {
+            let generator = Cuid2Generator::new(length).unwrap();
+            assert_eq!(generator.length(), length);
+            assert!(
+                generator
+                    .generate_batch(100)
+                    .iter()
+                    .all(|id| is_cuid2(id, length))
+            );
+        }
+        for length in [0, 1, 33, 100] {
+            assert_eq!(
+                Cuid2Generator::new(length).unwrap_err(),
+                Cuid2Error::InvalidLength {
+                    length,
+                    min: 2,
+                    max: 32
+                }
+            );
+        }
+        assert_eq!(Cuid2Generator::default().generate().len(), 24);
+    }
+
+    #[test]
+    fn test_character_distribution() {
+        let generator = Cuid2Generator::default();
+        let mut first = [0usize; 26];
+        let mut rest = [0usize; 36];
+        for id in generator.generate_batch(20_000) {
+            assert!(is_cuid2(&id, 24), "{id}");
+            let bytes = id.as_bytes();
+            first[usize::from(bytes[0] - b'a')] += 1;
+            for byte in &bytes[1..] {
+                let index = BASE36.iter().position(|digit| digit == byte).unwrap();
+                rest[index] += 1;
+            }
+        }
+
+        // 20,000 first letters average about 770 each
+        assert!(
+            first.iter().all(|&count| (500..1_100).contains(&count)),
+            "{first:?}"
+        );
+        // 460,000 later characters average about 12,800 each
+        assert!(
+            rest.iter().all(|&count| (11_500..14_000).contains(&count)),
+            "{rest:?}"
+        );
+    }
+
+    #[test]
+    fn test_unique() {
+        let generator = Cuid2Generator::new(12).unwrap();
+        let mut ids = generator.generate_batch(10_000);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 10_000);
+    }
+
+    #[test]
+    fn test_deterministic_with_injected_rng_and_clock() {
+        let first = seeded(7).generate_batch(5);
+        assert_eq!(first, seeded(7).generate_batch(5));
+        assert_ne!(first, seeded(8).generate_batch(5));
+        assert!(first.iter().all(|id| is_cuid2(id, 24)));
+
+        // The counter changes the hash even with a frozen clock and fingerprint
+        assert_eq!(
+            first.iter().collect::<std::collections::HashSet<_>>().len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_comes_from_host() {
+        let generator = Cuid2Generator::default();
+        assert_eq!(generator.fingerprint.len(), FINGERPRINT_LEN);
+        assert!(
+            generator
+                .fingerprint
+                .bytes()
+                .all(|byte| BASE36.contains(&byte))
+        );
+        // Fresh entropy per generator, even on the same host
+        assert_ne!(generator.fingerprint, Cuid2Generator::default().fingerprint);
+    }
+
+    #[test]
+    fn test_is_cuid2() {
+        assert!(is_cuid2("tz4a98xxat96iws9zmbrgj3a", 24));
+        assert!(is_cuid2("a1", 2));
+        assert!(!is_cuid2("tz4a98xxat96iws9zmbrgj3a", 10));
+        assert!(!is_cuid2("1z4a98xxat96iws9zmbrgj3a", 24));
+        assert!(!is_cuid2("Tz4a98xxat96iws9zmbrgj3a", 24));
+        assert!(!is_cuid2("tz4a98xxat96-ws9zmbrgj3a", 24));
+        assert!(!is_cuid2("a", 1));
+        assert!(!is_cuid2("", 0));
+    }
+
+    #[test]
+    fn test_base36() {
+        let mut out = String::new();
+        push_base36(&mut out, 0);
+        out.push(' ');
+        push_base36(&mut out, 35);
+        out.push(' ');
+        push_base36(&mut out, 1_700_000_000_000);
+        out.push(' ');
+        push_base36(&mut out, u64::MAX);
+        assert_eq!(out, "0 z loyw3v28 3w5e11264sgsf");
+    }
+}
@@ -0,0 +1,382 @@
+use alloc::string::{String, ToString};
+
+use uuid::{Uuid, Variant};
+
+use crate::uuid::{
+    UuidFormat, encoding, is_valid_uuid_hyphenated, is_valid_uuid_simple, parse_uuid,
+};
+
+/// Shortest and longest input [`detect_id_kind`] calls NanoID-like
+const NANOID_LEN: core::ops::RangeInclusive<usize> = 8..=64;
+/// Longest prefix [`detect_id_kind`] splits off a NanoID-like body
+const MAX_PREFIX_LEN: usize = 16;
+
+/// What kind of ID a string looks like, from [`detect_id_kind`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IdKind {
+    /// A UUID on its own, with its version number (0 for nil) and format
+    ///
+    /// Uppercase URNs are reported as [`UuidFormat::Urn`], which has no
+    /// uppercase variant.
+    Uuid { version: u8, format: UuidFormat },
+    /// A UUID behind a prefix ending in `_` or `:`, e.g. `usr_`
+    PrefixedUuid {
+        prefix: String,
+        version: u8,
+        format: UuidFormat,
+    },
+    /// URL-safe characters that could be a NanoID, with an optional prefix
+    /// of lowercase letters ending in `_` and the length after it
+    NanoIdLike {
+        prefix: Option<String>,
+        length: usize,
+    },
+    /// None of the above
+    Unknown,
+}
+
+/// Classify an ID from an untrusted mix of UUIDs, prefixed UUIDs and
+/// NanoIDs, using the crate's parsers
+///
+/// Checked in order:
+///
+/// 1. A UUID in any [`UuidFormat`], after trimming whitespace. The hex forms
+///    accept either case, braces and a `urn:uuid:` scheme, and 32 hex digits
+///    are always a simple UUID. Proquints accept either case and any
+///    version, as their groups are too strict to match by chance. The
+///    base32, base58 and base62 forms only count with an RFC variant and a
+///    version from 1 to 8, since random strings of those lengths often
+///    decode.
+/// 2. The same after a prefix ending in the last `_` or `:`.
+/// 3. 8 to 64 characters of the URL-safe NanoID alphabet `A-Za-z0-9_-`,
+///    not all digits and not hyphenated like a UUID.
+///
+/// This is a heuristic: a NanoID can happen to be valid base58, and a
+/// word such as `password` is NanoID-like.
+///
+/// ```
+/// use gen_id::{IdKind, UuidFormat, detect_id_kind};
+///
+/// assert_eq!(
+///     detect_id_kind("{550E8400-E29B-41D4-A716-446655440000}"),
+///     IdKind::Uuid { version: 4, format: UuidFormat::BracedUppercase }
+/// );
+/// assert_eq!(
+///     detect_id_kind("ord_V1StGXR8Z5jd"),
+///     IdKind::NanoIdLike { prefix: Some("ord_".into()), length: 12 }
+/// );
+/// ```
+pub fn detect_id_kind(input: &str) -> IdKind {
+    let input = input.trim_ascii();
+    if let Some((version, format)) = detect_uuid(input) {
+        return IdKind::Uuid { version, format };
+    }
+
+    if let Some(split) = input.rfind(['_', ':']).map(|index| index + 1)
+        && split > 1
+        && let Some((version, format)) = detect_uuid(&input[split..])
+    {
+        return IdKind::PrefixedUuid {
+            prefix: input[..split].to_string(),
+            version,
+            format,
+        };
+    }
+
+    if is_nanoid_like(input) {
+        let (prefix, body) = split_nanoid_prefix(input);
+        return IdKind::NanoIdLike {
+            prefix: prefix.map(ToString::to_string),
+            length: body.len(),
+        };
+    }
+
+    IdKind::Unknown
+}
+
+/// Version and format of a bare UUID in any format
+fn detect_uuid(input: &str) -> Option<(u8, UuidFormat)> {
+    if let Some(format) = hex_format(input) {
+        let uuid = parse_uuid(input).ok()?;
+        return Some((uuid.get_version_num() as u8, format));
+    }
+    if input.len() == encoding::PROQUINT_LEN {
+        let uuid = encoding::decode_proquint(input).ok()?;
+        return Some((uuid.get_version_num() as u8, UuidFormat::Proquint));
+    }
+
+    let (uuid, format) = match input.len() {
+        encoding::BASE32_LEN => (
+            encoding::decode_base32(input).ok()?,
+            UuidFormat::Base32Crockford,
+        ),
+        encoding::BASE58_LEN => encoding::decode_base58(input)
+            .ok()
+            .filter(is_rfc_uuid)
+            .map(|uuid| (uuid, UuidFormat::Base58))
+            .or_else(|| Some((encoding::decode_base62(input).ok()?, UuidFormat::Base62)))?,
+        _ => return None,
+    };
+    is_rfc_uuid(&uuid).then(|| (uuid.get_version_num() as u8, format))
+}
+
+/// The hex format `input` is spelled in, if it is one
+fn hex_format(input: &str) -> Option<UuidFormat> {
+    let upper = |lower, upper| {
+        if input.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            upper
+        } else {
+            lower
+        }
+    };
+
+    if let Some(scheme) = input.get(..9)
+        && scheme.eq_ignore_ascii_case("urn:uuid:")
+        && is_valid_uuid_hyphenated(&input[9..])
+    {
+        return Some(UuidFormat::Urn);
+    }
+    if let Some(inner) = input
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        && is_valid_uuid_hyphenated(inner)
+    {
+        return Some(upper(UuidFormat::Braced, UuidFormat::BracedUppercase));
+    }
+    if is_valid_uuid_hyphenated(input) {
+        return Some(upper(UuidFormat::Standard, UuidFormat::StandardUppercase));
+    }
+    if is_valid_uuid_simple(input) {
+        return Some(upper(UuidFormat::Simple, UuidFormat::SimpleUppercase));
+    }
+    None
+}
+
+/// A UUID with the RFC variant and a defined version, unlike most random
+/// strings that happen to decode
+fn is_rfc_uuid(uuid: &Uuid) -> bool {
+    uuid.get_variant() == Variant::RFC4122 && (1..=8).contains(&uuid.get_version_num())
+}
+
+fn is_nanoid_like(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    NANOID_LEN.contains(&bytes.len())
+        && bytes
+            .iter()
+            .all(|&byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-')
+        && !bytes.iter().all(u8::is_ascii_digit)
+        && !looks_hyphenated(bytes)
+}
+
+/// Hyphens where a UUID has them, e.g. a truncated UUID or one with a typo
+fn looks_hyphenated(bytes: &[u8]) -> bool {
+    [8, 13, 18, 23]
+        .iter()
+        .all(|&index| bytes.get(index) == Some(&b'-'))
+}
+
+/// Split off a prefix of lowercase letters ending in `_`, as
+/// `NanoIdGenerator` prefixes usually are
+fn split_nanoid_prefix(input: &str) -> (Option<&str>, &str) {
+    match input.find('_') {
+        Some(index)
+            if (1..=MAX_PREFIX_LEN).contains(&index)
+                && input[..index].bytes().all(|byte| byte.is_ascii_lowercase())
+                && input.len() - index > NANOID_LEN.start() / 2 =>
+        {
+            (Some(&input[..=index]), &input[index + 1..])
+        }
+        _ => (None, input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uuid::{format_uuid, parse_uuid};
+
+    const V4: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const V7: &str = "01890a5d-ac96-774b-bcce-b302099a8057";
+
+    fn uuid(version: u8, format: UuidFormat) -> IdKind {
+        IdKind::Uuid { version, format }
+    }
+
+    fn prefixed(prefix: &str, version: u8, format: UuidFormat) -> IdKind {
+        IdKind::PrefixedUuid {
+            prefix: prefix.to_string(),
+            version,
+            format,
+        }
+    }
+
+    fn nanoid(prefix: Option<&str>, length: usize) -> IdKind {
+        IdKind::NanoIdLike {
+            prefix: prefix.map(ToString::to_string),
+            length,
+        }
+    }
+
+    #[test]
+    fn test_corpus() {
+        let corpus = [
+            // Hex UUIDs in every spelling
+            (V4, uuid(4, UuidFormat::Standard)),
+            (V7, uuid(7, UuidFormat::Standard)),
+            (
+                "550E8400-E29B-41D4-A716-446655440000",
+                uuid(4, UuidFormat::StandardUppercase),
+            ),
+            (
+                "550e8400e29b41d4a716446655440000",
+                uuid(4, UuidFormat::Simple),
+            ),
+            (
+                "01890A5DAC96774BBCCEB302099A8057",
+                uuid(7, UuidFormat::SimpleUppercase),
+            ),
+            (
+                "{550e8400-e29b-41d4-a716-446655440000}",
+                uuid(4, UuidFormat::Braced),
+            ),
+            (
+                "{01890A5D-AC96-774B-BCCE-B302099A8057}",
+                uuid(7, UuidFormat::BracedUppercase),
+            ),
+            (
+                "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+                uuid(4, UuidFormat::Urn),
+            ),
+            (
+                "URN:UUID:550E8400-E29B-41D4-A716-446655440000",
+                uuid(4, UuidFormat::Urn),
+            ),
+            (
+                "  550e8400-e29b-41d4-a716-446655440000\n",
+                uuid(4, UuidFormat::Standard),
+            ),
+            (
+                "00000000-0000-0000-0000-000000000000",
+                uuid(0, UuidFormat::Standard),
+            ),
+            // 32 hex digits are a UUID even without a version, never a NanoID
+            (
+                "0123456789abcdef0123456789abcdef",
+                uuid(12, UuidFormat::Simple),
+            ),
+            (
+                "deadbeefdeadbeefdeadbeefdeadbeef",
+                uuid(11, UuidFormat::Simple),
+            ),
+            // Prefixed UUIDs
+            (
+                "usr_550e8400-e29b-41d4-a716-446655440000",
+                prefixed("usr_", 4, UuidFormat::Standard),
+            ),
+            (
+                "order:01890a5dac96774bbcceb302099a8057",
+                prefixed("order:", 7, UuidFormat::Simple),
+            ),
+            (
+                "org_team_550E8400E29B41D4A716446655440000",
+                prefixed("org_team_", 4, UuidFormat::SimpleUppercase),
+            ),
+            // NanoIDs
+            ("V1StGXR8_Z5jdHi6B-myT", nanoid(None, 21)),
+            ("XRTvrGk8Hvb6", nanoid(None, 12)),
+            ("ord_XRTvrGk8Hvb6", nanoid(Some("ord_"), 12)),
+            ("inv_8tnjIdMOvoQW", nanoid(Some("inv_"), 12)),
+            ("Ab_cdefghijk", nanoid(None, 12)),
+            // Neither
+            ("", IdKind::Unknown),
+            ("abc", IdKind::Unknown),
+            ("1234567890", IdKind::Unknown),
+            ("hello world", IdKind::Unknown),
+            ("550e8400-e29b-41d4-a716-44665544000g", IdKind::Unknown),
+            ("550e8400-e29b-41d4-a716-4466554400", IdKind::Unknown),
+            ("{550e8400-e29b-41d4-a716-446655440000", IdKind::Unknown),
+            ("id=550e8400", IdKind::Unknown),
+            (&"a".repeat(65), IdKind::Unknown),
+        ];
+        for (input, expected) in corpus {
+            assert_eq!(detect_id_kind(input), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_encoded_uuids() {
+        let formats = [
+            UuidFormat::Base32Crockford,
+            UuidFormat::Base58,
+            UuidFormat::Base62,
+            UuidFormat::Proquint,
+        ];
+        for text in [V4, V7] {
+            let value = parse_uuid(text).unwrap();
+            let version = value.get_version_num() as u8;
+            for format in formats {
+                let encoded = format_uuid(&value, format);
+                assert_eq!(detect_id_kind(&encoded), uuid(version, format), "{encoded}");
+                assert_eq!(
+                    detect_id_kind(&format!("cus_{encoded}")),
+                    prefixed("cus_", version, format),
+                    "{encoded}"
+                );
+            }
+        }
+        // Lowercase base32 decodes the same
+        let base32 = format_uuid(&parse_uuid(V7).unwrap(), UuidFormat::Base32Crockford);
+        assert_eq!(
+            detect_id_kind(&base32.to_lowercase()),
+            uuid(7, UuidFormat::Base32Crockford)
+        );
+    }
+
+    #[test]
+    fn test_random_strings_of_encoded_lengths_are_not_uuids() {
+        // Decodable, but without an RFC version and variant
+        assert_eq!(
+            detect_id_kind("7ZZZZZZZZZZZZZZZZZZZZZZZZZ"),
+            nanoid(None, 26)
+        );
+        assert_eq!(detect_id_kind("zzzzzzzzzzzzzzzzzzzzz1"), nanoid(None, 22));
+    }
+
+    #[test]
+    fn test_proquints() {
+        // Any version, in either case
+        let nil = format_uuid(&Uuid::nil(), UuidFormat::Proquint);
+        assert_eq!(detect_id_kind(&nil), uuid(0, UuidFormat::Proquint));
+        assert_eq!(
+            detect_id_kind(&nil.to_uppercase()),
+            uuid(0, UuidFormat::Proquint)
+        );
+        // A vowel where a consonant belongs is only NanoID-like
+        assert_eq!(
+            detect_id_kind("aabab-babab-babab-babab-babab-babab-babab-babab"),
+            nanoid(None, 47)
+        );
+    }
+
+    #[test]
+    fn test_every_format_round_trips() {
+        let value = parse_uuid(V7).unwrap();
+        for format in [
+            UuidFormat::Standard,
+            UuidFormat::Simple,
+            UuidFormat::StandardUppercase,
+            UuidFormat::SimpleUppercase,
+            UuidFormat::Braced,
+            UuidFormat::BracedUppercase,
+            UuidFormat::Urn,
+            UuidFormat::Base32Crockford,
+            UuidFormat::Base58,
+            UuidFormat::Base62,
+            UuidFormat::Proquint,
+        ] {
+            let text = format_uuid(&value, format);
+            assert_eq!(detect_id_kind(&text), uuid(7, format), "{text}");
+        }
+    }
+}
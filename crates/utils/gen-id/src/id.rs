@@ -0,0 +1,487 @@
+#[cfg(feature = "nanoid")]
+use crate::nanoid::{Alphabet, NanoIdConfig, NanoIdError, NanoIdGenerator, RngMode};
+#[cfg(any(feature = "uuid", feature = "nanoid"))]
+use crate::prefix::{PrefixError, PrefixPolicy};
+#[cfg(feature = "snowflake")]
+use crate::snowflake::SnowflakeGenerator;
+#[cfg(feature = "uuid")]
+use crate::uuid::{UuidFormat, UuidGenerator};
+
+/// An ID scheme chosen at runtime, e.g. per tenant
+///
+/// Implemented by `UuidGenerator`, `NanoIdGenerator` and
+/// `SnowflakeGenerator`, so callers can hold a `Box<dyn IdGenerator>`
+/// instead of matching on the scheme. The trait requires `Send + Sync`, so
+/// the box can be shared across threads.
+pub trait IdGenerator: Send + Sync {
+    /// Generate one ID, including any configured prefix
+    fn generate(&self) -> String;
+
+    /// Generate `count` IDs
+    fn generate_batch(&self, count: usize) -> Vec<String> {
+        (0..count).map(|_| self.generate()).collect()
+    }
+}
+
+/// Same as the inherent methods
+#[cfg(feature = "uuid")]
+impl IdGenerator for UuidGenerator {
+    #[inline]
+    fn generate(&self) -> String {
+        UuidGenerator::generate(self)
+    }
+
+    #[inline]
+    fn generate_batch(&self, count: usize) -> Vec<String> {
+        UuidGenerator::generate_batch(self, count)
+    }
+}
+
+/// IDs with the prefix and length the generator was built with
+#[cfg(feature = "nanoid")]
+impl IdGenerator for NanoIdGenerator {
+    #[inline]
+    fn generate(&self) -> String {
+        NanoIdGenerator::generate(self)
+    }
+
+    #[inline]
+    fn generate_batch(&self, count: usize) -> Vec<String> {
+        NanoIdGenerator::generate_batch(self, count)
+    }
+}
+
+/// IDs in decimal, e.g. for a scheme switch from Snowflake to UUIDs
+#[cfg(feature = "snowflake")]
+impl IdGenerator for SnowflakeGenerator {
+    #[inline]
+    fn generate(&self) -> String {
+        SnowflakeGenerator::generate(self).to_string()
+    }
+
+    #[inline]
+    fn generate_batch(&self, count: usize) -> Vec<String> {
+        SnowflakeGenerator::generate_batch(self, count)
+            .into_iter()
+            .map(|id| id.to_string())
+            .collect()
+    }
+}
+
+/// Error for an [`IdConfig`] that describes no usable generator
+#[cfg(any(feature = "uuid", feature = "nanoid"))]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum IdConfigError {
+    #[error("length must be at least 1")]
+    ZeroLength,
+
+    #[error("invalid prefix {prefix:?}: {source}")]
+    InvalidPrefix {
+        prefix: String,
+        #[source]
+        source: PrefixError,
+    },
+
+    #[cfg(feature = "nanoid")]
+    #[error(transparent)]
+    Nanoid(#[from] NanoIdError),
+}
+
+/// Declarative choice of ID scheme, e.g. per tenant in a service config
+///
+/// Serialized with a `kind` tag next to the scheme's settings, e.g.
+/// `{"kind":"uuid_v7","format":"simple","prefix":"trade_"}` or
+/// `{"kind":"nanoid","length":16,"prefix":"ord_"}`. Unknown kinds and
+/// fields, a zero length and prefixes outside the default [`PrefixPolicy`]
+/// are rejected when deserializing, and again by [`Self::build`] for
+/// configs built in code. The UUID and NanoID kinds need the `uuid` and
+/// `nanoid` features.
+#[cfg(any(feature = "uuid", feature = "nanoid"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)
+)]
+#[non_exhaustive]
+pub enum IdConfig {
+    #[cfg(feature = "uuid")]
+    UuidV4 {
+        #[cfg_attr(feature = "serde", serde(default))]
+        format: UuidFormat,
+        #[cfg_attr(feature = "serde", serde(default, deserialize_with = "de::prefix"))]
+        prefix: Option<String>,
+    },
+    #[cfg(feature = "uuid")]
+    UuidV7 {
+        #[cfg_attr(feature = "serde", serde(default))]
+        format: UuidFormat,
+        #[cfg_attr(feature = "serde", serde(default, deserialize_with = "de::prefix"))]
+        prefix: Option<String>,
+    },
+    #[cfg(feature = "nanoid")]
+    Nanoid {
+        #[cfg_attr(feature = "serde", serde(default, deserialize_with = "de::prefix"))]
+        prefix: Option<String>,
+        /// Defaults to 12 characters
+        #[cfg_attr(feature = "serde", serde(default, deserialize_with = "de::length"))]
+        length: Option<usize>,
+        /// Alphanumeric when unset, see [`Alphabet`] for the serialized form
+        #[cfg_attr(feature = "serde", serde(default))]
+        alphabet: Option<Alphabet>,
+        /// Secure when unset
+        #[cfg_attr(feature = "serde", serde(default))]
+        rng_mode: RngMode,
+    },
+}
+
+#[cfg(any(feature = "uuid", feature = "nanoid"))]
+impl IdConfig {
+    /// Check the settings without building a generator
+    pub fn validate(&self) -> Result<(), IdConfigError> {
+        match self {
+            #[cfg(feature = "uuid")]
+            Self::UuidV4 { prefix, .. } | Self::UuidV7 { prefix, .. } => validate_prefix(prefix),
+            #[cfg(feature = "nanoid")]
+            Self::Nanoid {
+                prefix,
+                length,
+                alphabet,
+                rng_mode,
+            } => nanoid(prefix, *length, alphabet, *rng_mode).map(drop),
+        }
+    }
+
+    /// Create the generator this config describes
+    pub fn build(&self) -> Result<Box<dyn IdGenerator>, IdConfigError> {
+        self.validate()?;
+
+        #[cfg(feature = "uuid")]
+        let uuid = |generator: UuidGenerator, format: UuidFormat, prefix: &Option<String>| {
+            let generator = generator.with_format(format);
+            match prefix {
+                Some(prefix) => generator.with_prefix(prefix.clone()),
+                None => generator,
+            }
+        };
+        Ok(match self {
+            #[cfg(feature = "uuid")]
+            Self::UuidV4 { format, prefix } => Box::new(uuid(UuidGenerator::v4(), *format, prefix)),
+            #[cfg(feature = "uuid")]
+            Self::UuidV7 { format, prefix } => Box::new(uuid(UuidGenerator::v7(), *format, prefix)),
+            #[cfg(feature = "nanoid")]
+            Self::Nanoid {
+                prefix,
+                length,
+                alphabet,
+                rng_mode,
+            } => Box::new(nanoid(prefix, *length, alphabet, *rng_mode)?),
+        })
+    }
+}
+
+/// Create the generator `config` describes, see [`IdConfig::build`]
+#[cfg(any(feature = "uuid", feature = "nanoid"))]
+#[inline]
+pub fn boxed_from_config(config: &IdConfig) -> Result<Box<dyn IdGenerator>, IdConfigError> {
+    config.build()
+}
+
+#[cfg(any(feature = "uuid", feature = "nanoid"))]
+fn validate_prefix(prefix: &Option<String>) -> Result<(), IdConfigError> {
+    let Some(prefix) = prefix else {
+        return Ok(());
+    };
+    PrefixPolicy::default()
+        .validate(prefix)
+        .map_err(|source| IdConfigError::InvalidPrefix {
+            prefix: prefix.clone(),
+            source,
+        })
+}
+
+#[cfg(feature = "nanoid")]
+fn nanoid(
+    prefix: &Option<String>,
+    length: Option<usize>,
+    alphabet: &Option<Alphabet>,
+    rng_mode: RngMode,
+) -> Result<NanoIdGenerator, IdConfigError> {
+    validate_prefix(prefix)?;
+    validate_length(length)?;
+
+    let mut config = NanoIdConfig::default().with_rng_mode(rng_mode);
+    if let Some(length) = length {
+        config = config.with_length(length);
+    }
+    config.prefix.clone_from(prefix);
+    config.alphabet.clone_from(alphabet);
+    Ok(config.build()?)
+}
+
+#[cfg(feature = "nanoid")]
+fn validate_length(length: Option<usize>) -> Result<(), IdConfigError> {
+    match length {
+        Some(0) => Err(IdConfigError::ZeroLength),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(all(feature = "serde", any(feature = "uuid", feature = "nanoid")))]
+mod de {
+    use serde::{Deserialize, Deserializer, de::Error};
+
+    pub(super) fn prefix<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<String>, D::Error> {
+        let prefix = Option::<String>::deserialize(deserializer)?;
+        super::validate_prefix(&prefix).map_err(D::Error::custom)?;
+        Ok(prefix)
+    }
+
+    #[cfg(feature = "nanoid")]
+    pub(super) fn length<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<usize>, D::Error> {
+        let length = Option::<usize>::deserialize(deserializer)?;
+        super::validate_length(length).map_err(D::Error::custom)?;
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "uuid")]
+    use crate::uuid::parse_uuid;
+
+    fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+    #[test]
+    fn test_trait_objects_are_send_and_sync() {
+        assert_send_sync::<Box<dyn IdGenerator>>();
+        assert_send_sync::<Box<dyn IdGenerator + Send + Sync>>();
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn test_uuid_generator_through_trait() {
+        let generator: Box<dyn IdGenerator> = Box::new(UuidGenerator::v7().with_prefix("u_"));
+
+        let id = generator.generate();
+        assert!(id.starts_with("u_"));
+        assert_eq!(parse_uuid(&id[2..]).unwrap().get_version_num(), 7);
+
+        let batch = generator.generate_batch(10);
+        assert_eq!(batch.len(), 10);
+        assert!(batch.windows(2).all(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn test_build_uuid_configs() {
+        for (config, version) in [
+            (
+                IdConfig::UuidV4 {
+                    format: UuidFormat::Simple,
+                    prefix: Some("trade_".to_string()),
+                },
+                4,
+            ),
+            (
+                IdConfig::UuidV7 {
+                    format: UuidFormat::Simple,
+                    prefix: Some("trade_".to_string()),
+                },
+                7,
+            ),
+        ] {
+            let id = boxed_from_config(&config).unwrap().generate();
+            assert_eq!(id.len(), "trade_".len() + 32);
+            let uuid = parse_uuid(&id["trade_".len()..]).unwrap();
+            assert_eq!(uuid.get_version_num(), version);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn test_build_rejects_invalid_prefix() {
+        let config = IdConfig::UuidV7 {
+            format: UuidFormat::Standard,
+            prefix: Some("trade id".to_string()),
+        };
+        let err = config.build().err().unwrap();
+        assert!(matches!(
+            err,
+            IdConfigError::InvalidPrefix {
+                source: PrefixError::InvalidChar { ch: ' ', .. },
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "invalid prefix \"trade id\": prefix contains ' ' at byte 5, allowed are ASCII \
+             letters, digits and \"_-:\""
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "snowflake")]
+    fn test_snowflake_through_trait() {
+        let generator: Box<dyn IdGenerator> =
+            Box::new(SnowflakeGenerator::new(1, std::time::UNIX_EPOCH).unwrap());
+        let ids: Vec<u64> = generator
+            .generate_batch(3)
+            .iter()
+            .map(|id| id.parse().unwrap())
+            .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(generator.generate().parse::<u64>().unwrap() > ids[2]);
+    }
+
+    #[test]
+    #[cfg(feature = "nanoid")]
+    fn test_nanoid_through_trait() {
+        let generator: Box<dyn IdGenerator> = Box::new(NanoIdGenerator::new());
+        assert_eq!(generator.generate().len(), 12);
+        assert_eq!(generator.generate_batch(3).len(), 3);
+
+        let generator: Box<dyn IdGenerator> =
+            Box::new(NanoIdGenerator::builder().prefix("ord_").length(8).build());
+        assert!(generator.generate().starts_with("ord_"));
+        assert_eq!(generator.generate().len(), 4 + 8);
+
+        let generator = IdConfig::Nanoid {
+            prefix: Some("ord_".to_string()),
+            length: Some(16),
+            alphabet: None,
+            rng_mode: RngMode::Secure,
+        }
+        .build()
+        .unwrap();
+        for id in generator.generate_batch(5) {
+            assert!(id.starts_with("ord_"), "{id}");
+            assert_eq!(id.len(), 4 + 16);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "nanoid")]
+    fn test_build_rejects_zero_length() {
+        let config = IdConfig::Nanoid {
+            prefix: None,
+            length: Some(0),
+            alphabet: None,
+            rng_mode: RngMode::Secure,
+        };
+        assert_eq!(config.validate(), Err(IdConfigError::ZeroLength));
+        assert!(config.build().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "nanoid")]
+    fn test_build_nanoid_alphabet_and_limits() {
+        let generator = IdConfig::Nanoid {
+            prefix: None,
+            length: Some(8),
+            alphabet: Some(Alphabet::new("01").unwrap()),
+            rng_mode: RngMode::Fast,
+        }
+        .build()
+        .unwrap();
+        let id = generator.generate();
+        assert!(id.chars().all(|ch| ch == '0' || ch == '1'), "{id}");
+
+        let config = IdConfig::Nanoid {
+            prefix: None,
+            length: Some(1000),
+            alphabet: None,
+            rng_mode: RngMode::Secure,
+        };
+        assert_eq!(
+            config.validate(),
+            Err(IdConfigError::Nanoid(NanoIdError::LengthTooLong {
+                length: 1000,
+                max: 256
+            }))
+        );
+        assert_eq!(
+            config.build().err().unwrap().to_string(),
+            "length 1000 exceeds the maximum of 256"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "uuid", feature = "nanoid"))]
+    fn test_config_serde() {
+        let configs: Vec<IdConfig> = serde_json::from_str(
+            r#"[
+                {"kind": "uuid_v7", "format": "simple", "prefix": "trade_"},
+                {"kind": "uuid_v4"},
+                {"kind": "nanoid", "length": 16, "prefix": "ord_"},
+                {"kind": "nanoid", "alphabet": {"custom": "ACGT"}, "rng_mode": "fast"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            configs,
+            [
+                IdConfig::UuidV7 {
+                    format: UuidFormat::Simple,
+                    prefix: Some("trade_".to_string()),
+                },
+                IdConfig::UuidV4 {
+                    format: UuidFormat::Standard,
+                    prefix: None,
+                },
+                IdConfig::Nanoid {
+                    prefix: Some("ord_".to_string()),
+                    length: Some(16),
+                    alphabet: None,
+                    rng_mode: RngMode::Secure,
+                },
+                IdConfig::Nanoid {
+                    prefix: None,
+                    length: None,
+                    alphabet: Some(Alphabet::new("ACGT").unwrap()),
+                    rng_mode: RngMode::Fast,
+                },
+            ]
+        );
+
+        for config in &configs {
+            let json = serde_json::to_string(config).unwrap();
+            assert_eq!(&serde_json::from_str::<IdConfig>(&json).unwrap(), config);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "uuid", feature = "nanoid"))]
+    fn test_config_serde_errors() {
+        let error = |json: &str| {
+            serde_json::from_str::<IdConfig>(json)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error(r#"{"kind": "ksuid"}"#).starts_with(
+            "unknown variant `ksuid`, expected one of `uuid_v4`, `uuid_v7`, `nanoid`"
+        ));
+        assert!(
+            error(r#"{"kind": "nanoid", "length": 0}"#).starts_with("length must be at least 1")
+        );
+        assert!(
+            error(r#"{"kind": "uuid_v7", "prefix": "a/b"}"#)
+                .starts_with("invalid prefix \"a/b\": prefix contains '/' at byte 1")
+        );
+        assert!(
+            error(r#"{"kind": "uuid_v7", "lenght": 16}"#).starts_with("unknown field `lenght`")
+        );
+        assert!(
+            error(r#"{"kind": "nanoid", "alphabet": {"custom": "ACGTA"}}"#)
+                .starts_with("alphabet repeats 'A' at index 4")
+        );
+        assert!(error(r#"{"format": "simple"}"#).starts_with("missing field `kind`"));
+    }
+}
@@ -0,0 +1,426 @@
+use std::{fmt, str::FromStr};
+
+use rand::RngCore;
+
+use crate::{
+    IdGenerator,
+    prefix::{PrefixError, PrefixPolicy},
+    source,
+    uuid::encoding::BASE62_ALPHABET,
+};
+
+/// Error for a string that is not a KSUID
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum KsuidError {
+    #[error("KSUIDs are 27 characters long, found {found}")]
+    InvalidLength { found: usize },
+
+    #[error("{ch:?} at index {index} is not in the base62 alphabet")]
+    InvalidChar { ch: char, index: usize },
+
+    #[error("base62 value does not fit in 160 bits")]
+    Overflow,
+
+    #[error("expected prefix `{0}`")]
+    MissingPrefix(String),
+
+    #[error("timestamp {0} is outside the KSUID range of 2014 to 2150")]
+    TimestampOutOfRange(u64),
+}
+
+/// A K-Sortable Unique IDentifier, Segment's 160-bit format
+///
+/// A big-endian `u32` of seconds since [`Self::EPOCH`] followed by 16
+/// random bytes, written as 27 base62 characters. Both the bytes and the
+/// strings sort by time to the second, so [`Ord`] orders by creation.
+///
+/// ```
+/// use gen_id::Ksuid;
+///
+/// let ksuid: Ksuid = "0ujtsYcgvSTl8PAuAdqWYSMnLOv".parse()?;
+/// assert_eq!(ksuid.timestamp(), 1_507_608_047);
+/// assert_eq!(ksuid.to_string(), "0ujtsYcgvSTl8PAuAdqWYSMnLOv");
+/// # Ok::<(), gen_id::KsuidError>(())
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Ksuid([u8; 20]);
+
+impl Ksuid {
+    /// Unix time of timestamp 0, in seconds (2014-05-13)
+    pub const EPOCH: u64 = 1_400_000_000;
+
+    /// Length of the base62 string form
+    pub const ENCODED_LEN: usize = 27;
+
+    /// All zero bytes, `000000000000000000000000000`
+    pub const NIL: Self = Self([0; 20]);
+
+    /// All one bits, `aWgEPTl1tmebfsQzFP4bxwgy80V`
+    pub const MAX: Self = Self([0xFF; 20]);
+
+    /// Generates a KSUID for the current second with a random payload from
+    /// rand's thread-local CSPRNG
+    pub fn generate() -> Self {
+        let mut payload = [0u8; 16];
+        rand::rng().fill_bytes(&mut payload);
+        Self::from_parts(source::system_millis() / 1000, payload)
+            .expect("the system clock is between 2014 and 2150")
+    }
+
+    /// Creates a KSUID from Unix seconds and a payload
+    ///
+    /// Fails for times the 32-bit timestamp cannot hold, before
+    /// [`Self::EPOCH`] or after early 2150.
+    pub fn from_parts(unix_secs: u64, payload: [u8; 16]) -> Result<Self, KsuidError> {
+        let timestamp = unix_secs
+            .checked_sub(Self::EPOCH)
+            .and_then(|secs| u32::try_from(secs).ok())
+            .ok_or(KsuidError::TimestampOutOfRange(unix_secs))?;
+
+        let mut bytes = [0u8; 20];
+        bytes[..4].copy_from_slice(&timestamp.to_be_bytes());
+        bytes[4..].copy_from_slice(&payload);
+        Ok(Self(bytes))
+    }
+
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    /// Creation time in Unix seconds
+    #[inline]
+    pub fn timestamp(&self) -> u64 {
+        let raw = u32::from_be_bytes([self.0[0], self.0[1], self.0[2], self.0[3]]);
+        Self::EPOCH + u64::from(raw)
+    }
+
+    /// The 16 random bytes after the timestamp
+    #[inline]
+    pub fn payload(&self) -> &[u8; 16] {
+        self.0[4..].try_into().expect("20 bytes minus 4")
+    }
+
+    /// Decode the 27-character base62 form, which is case-sensitive
+    pub fn parse(input: &str) -> Result<Self, KsuidError> {
+        // Characters rather than bytes, as in the UUID decoders
+        let found = input.chars().count();
+        if found != Self::ENCODED_LEN {
+            return Err(KsuidError::InvalidLength { found });
+        }
+
+        let mut words = [0u32; 5];
+        for (index, ch) in input.char_indices() {
+            let digit = BASE62_ALPHABET
+                .iter()
+                .position(|&c| char::from(c) == ch)
+                .ok_or(KsuidError::InvalidChar { ch, index })?;
+            let mut carry = digit as u64;
+            for word in words.iter_mut().rev() {
+                let value = u64::from(*word) * 62 + carry;
+                *word = value as u32;
+                carry = value >> 32;
+            }
+            if carry != 0 {
+                return Err(KsuidError::Overflow);
+            }
+        }
+
+        let mut bytes = [0u8; 20];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        Ok(Self(bytes))
+    }
+
+    /// Write the 27 base62 characters into `buf`, most significant first
+    fn encode<'a>(&self, buf: &'a mut [u8; Self::ENCODED_LEN]) -> &'a str {
+        let mut words = [0u32; 5];
+        for (word, chunk) in words.iter_mut().zip(self.0.chunks_exact(4)) {
+            *word = u32::from_be_bytes(chunk.try_into().expect("chunks of 4"));
+        }
+        // Long division of the 160-bit value by 62, once per digit
+        for slot in buf.iter_mut().rev() {
+            let mut remainder = 0u64;
+            for word in &mut words {
+                let value = remainder << 32 | u64::from(*word);
+                *word = (value / 62) as u32;
+                remainder = value % 62;
+            }
+            *slot = BASE62_ALPHABET[remainder as usize];
+        }
+        std::str::from_utf8(buf).expect("the alphabet is ASCII")
+    }
+}
+
+impl fmt::Display for Ksuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.encode(&mut [0; Self::ENCODED_LEN]))
+    }
+}
+
+impl fmt::Debug for Ksuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ksuid")
+            .field(&self.encode(&mut [0; Self::ENCODED_LEN]))
+            .finish()
+    }
+}
+
+impl FromStr for Ksuid {
+    type Err = KsuidError;
+
+    #[inline]
+    fn from_str(input: &str) -> Result<Self, KsuidError> {
+        Self::parse(input)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Ksuid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.encode(&mut [0; Self::ENCODED_LEN]))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Ksuid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        Self::parse(&input).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Generates prefixed KSUID strings, in the style of
+/// [`UuidGenerator`](crate::UuidGenerator)
+///
+/// ```
+/// use gen_id::KsuidGenerator;
+///
+/// let generator = KsuidGenerator::new().with_prefix("usr_");
+/// let id = generator.generate();
+/// assert_eq!(id.len(), 4 + 27);
+/// assert_eq!(generator.parse(&id)?.to_string(), id[4..]);
+/// # Ok::<(), gen_id::KsuidError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KsuidGenerator {
+    prefix: Option<String>,
+}
+
+impl KsuidGenerator {
+    #[inline]
+    pub const fn new() -> Self {
+        Self { prefix: None }
+    }
+
+    /// Set a prefix prepended to every ID
+    ///
+    /// The prefix is not validated, see [`Self::try_with_prefix`]. An empty
+    /// prefix means no prefix.
+    #[inline]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        self.prefix = (!prefix.is_empty()).then_some(prefix);
+        self
+    }
+
+    /// Set a prefix after checking it against the default [`PrefixPolicy`]
+    #[inline]
+    pub fn try_with_prefix(self, prefix: impl Into<String>) -> Result<Self, PrefixError> {
+        let prefix = prefix.into();
+        PrefixPolicy::default().validate(&prefix)?;
+        Ok(self.with_prefix(prefix))
+    }
+
+    /// Remove the prefix
+    #[inline]
+    pub fn without_prefix(mut self) -> Self {
+        self.prefix = None;
+        self
+    }
+
+    /// The prefix every ID starts with, if any
+    #[inline]
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    /// Generates one KSUID string with the prefix
+    pub fn generate(&self) -> String {
+        self.format(&Ksuid::generate())
+    }
+
+    /// Generates `count` KSUID strings with the prefix
+    pub fn generate_batch(&self, count: usize) -> Vec<String> {
+        (0..count).map(|_| self.generate()).collect()
+    }
+
+    /// Write `ksuid` with the prefix
+    pub fn format(&self, ksuid: &Ksuid) -> String {
+        let prefix = self.prefix().unwrap_or_default();
+        let mut id = String::with_capacity(prefix.len() + Ksuid::ENCODED_LEN);
+        id.push_str(prefix);
+        id.push_str(ksuid.encode(&mut [0; Ksuid::ENCODED_LEN]));
+        id
+    }
+
+    /// Strip the prefix and decode the KSUID after it
+    pub fn parse(&self, input: &str) -> Result<Ksuid, KsuidError> {
+        let ksuid = match self.prefix() {
+            Some(prefix) => input
+                .strip_prefix(prefix)
+                .ok_or_else(|| KsuidError::MissingPrefix(prefix.to_string()))?,
+            None => input,
+        };
+        Ksuid::parse(ksuid)
+    }
+}
+
+/// Base62 strings with the generator's prefix
+impl IdGenerator for KsuidGenerator {
+    #[inline]
+    fn generate(&self) -> String {
+        KsuidGenerator::generate(self)
+    }
+
+    #[inline]
+    fn generate_batch(&self, count: usize) -> Vec<String> {
+        KsuidGenerator::generate_batch(self, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// From the `ksuid -f inspect` examples of segmentio/ksuid
+    const KNOWN: [(&str, &str, u64, &str); 2] = [
+        (
+            "0ujtsYcgvSTl8PAuAdqWYSMnLOv",
+            "0669F7EFB5A1CD34B5F99D1154FB6853345C9735",
+            107_608_047,
+            "B5A1CD34B5F99D1154FB6853345C9735",
+        ),
+        (
+            "0ujzPyRiIAffKhBux4PvQdDqMHY",
+            "066A029C73FC1AA3B2446246D6E89FCD909E8FE8",
+            107_610_780,
+            "73FC1AA3B2446246D6E89FCD909E8FE8",
+        ),
+    ];
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+    }
+
+    #[test]
+    fn test_reference_values() {
+        for (string, raw, timestamp, payload) in KNOWN {
+            let ksuid = Ksuid::parse(string).unwrap();
+            assert_eq!(hex(ksuid.as_bytes()), raw);
+            assert_eq!(ksuid.timestamp(), Ksuid::EPOCH + timestamp);
+            assert_eq!(hex(ksuid.payload()), payload);
+            assert_eq!(ksuid.to_string(), string);
+        }
+        assert_eq!(Ksuid::NIL.to_string(), "000000000000000000000000000");
+        assert_eq!(Ksuid::MAX.to_string(), "aWgEPTl1tmebfsQzFP4bxwgy80V");
+        assert_eq!(
+            Ksuid::parse("aWgEPTl1tmebfsQzFP4bxwgy80V").unwrap(),
+            Ksuid::MAX
+        );
+    }
+
+    #[test]
+    fn test_from_parts() {
+        let payload = *Ksuid::parse(KNOWN[0].0).unwrap().payload();
+        let ksuid = Ksuid::from_parts(Ksuid::EPOCH + 107_608_047, payload).unwrap();
+        assert_eq!(ksuid.to_string(), KNOWN[0].0);
+
+        assert_eq!(
+            Ksuid::from_parts(Ksuid::EPOCH - 1, payload),
+            Err(KsuidError::TimestampOutOfRange(Ksuid::EPOCH - 1))
+        );
+        let last = Ksuid::EPOCH + u64::from(u32::MAX);
+        assert!(Ksuid::from_parts(last, payload).is_ok());
+        assert!(Ksuid::from_parts(last + 1, payload).is_err());
+    }
+
+    #[test]
+    fn test_generate_round_trip() {
+        let now = source::system_millis() / 1000;
+        for _ in 0..100 {
+            let ksuid = Ksuid::generate();
+            assert!(ksuid.timestamp().abs_diff(now) <= 1);
+            let string = ksuid.to_string();
+            assert_eq!(string.len(), 27);
+            assert_eq!(string.parse::<Ksuid>().unwrap(), ksuid);
+        }
+        assert_ne!(Ksuid::generate().payload(), Ksuid::generate().payload());
+    }
+
+    #[test]
+    fn test_ordering_follows_timestamp() {
+        let early = Ksuid::from_parts(Ksuid::EPOCH + 10, [0xFF; 16]).unwrap();
+        let late = Ksuid::from_parts(Ksuid::EPOCH + 11, [0x00; 16]).unwrap();
+        assert!(early < late);
+        assert!(early.to_string() < late.to_string());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Ksuid::parse("0ujtsYcgvSTl8PAuAdqWYSMnLO"),
+            Err(KsuidError::InvalidLength { found: 26 })
+        );
+        assert_eq!(
+            Ksuid::parse("0ujtsYcgvSTl8PAuAdqWYSMnL-v"),
+            Err(KsuidError::InvalidChar { ch: '-', index: 25 })
+        );
+        // One above the maximum
+        assert_eq!(
+            Ksuid::parse("aWgEPTl1tmebfsQzFP4bxwgy80W"),
+            Err(KsuidError::Overflow)
+        );
+        assert_eq!(
+            Ksuid::parse("zzzzzzzzzzzzzzzzzzzzzzzzzzz"),
+            Err(KsuidError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_generator_prefix() {
+        let generator = KsuidGenerator::new().with_prefix("usr_");
+        let ids = generator.generate_batch(3);
+        for id in &ids {
+            assert!(id.starts_with("usr_"), "{id}");
+            assert_eq!(generator.parse(id).unwrap().to_string(), id[4..]);
+        }
+        assert_eq!(
+            generator.parse(KNOWN[0].0),
+            Err(KsuidError::MissingPrefix("usr_".to_string()))
+        );
+        assert_eq!(generator.clone().without_prefix().prefix(), None);
+        assert!(KsuidGenerator::new().try_with_prefix("usr id").is_err());
+
+        let ksuid = Ksuid::parse(KNOWN[1].0).unwrap();
+        assert_eq!(generator.format(&ksuid), format!("usr_{}", KNOWN[1].0));
+        assert_eq!(format!("{ksuid:?}"), format!("Ksuid(\"{}\")", KNOWN[1].0));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let ksuid = Ksuid::parse(KNOWN[0].0).unwrap();
+        let json = serde_json::to_string(&ksuid).unwrap();
+        assert_eq!(json, format!("\"{}\"", KNOWN[0].0));
+        assert_eq!(serde_json::from_str::<Ksuid>(&json).unwrap(), ksuid);
+        assert!(serde_json::from_str::<Ksuid>("\"short\"").is_err());
+    }
+}
@@ -1,16 +1,238 @@
+//! ID generation and parsing
+//!
+//! UUIDs and NanoIDs sit behind the `uuid` and `nanoid` features, both on by
+//! default, so a service can build with only the one it uses. UUID parsing,
+//! formatting and metadata extraction need only `alloc`, so they also build
+//! with `default-features = false, features = ["alloc", "uuid"]` for
+//! `no_std` targets. Generation uses the OS RNG and clock and needs `std`,
+//! the default.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(feature = "alloc"))]
+compile_error!("gen-id needs the `alloc` feature, or `std` which enables it");
+
+#[cfg(feature = "cuid2")]
+mod cuid2;
+#[cfg(feature = "uuid")]
+mod detect;
+#[cfg(feature = "std")]
+mod id;
+#[cfg(feature = "ksuid")]
+mod ksuid;
 #[cfg(feature = "nanoid")]
 mod nanoid;
-mod uuid;
+#[cfg(feature = "obfuscate")]
+mod obfuscate;
+#[cfg(feature = "std")]
+mod prefix;
+mod prefixed;
+#[cfg(feature = "snowflake")]
+mod snowflake;
+#[cfg(feature = "std")]
+// The injectable clock is only used by some generators
+#[cfg_attr(not(feature = "uuid"), allow(dead_code))]
+pub(crate) mod source;
+#[cfg(feature = "std")]
+// Only the UUID and NanoID generators build reports
+#[cfg_attr(not(any(feature = "uuid", feature = "nanoid")), allow(dead_code))]
+mod strength;
+#[cfg(feature = "ulid")]
+mod ulid;
+/// UUID generation and parsing, also re-exported at the crate root
+///
+/// Both paths name the same types:
+///
+/// ```
+/// let uuid = gen_id::uuid::parse_uuid("01890a5d-ac96-774b-bcce-b302099a8057")?;
+/// let format: gen_id::uuid::UuidFormat = gen_id::UuidFormat::Simple;
+/// assert_eq!(gen_id::uuid::format_uuid(&uuid, format), "01890a5dac96774bbcceb302099a8057");
+/// # Ok::<(), gen_id::ParseError>(())
+/// ```
+#[cfg(feature = "uuid")]
+pub mod uuid;
 
 // Re-export UUID types
-pub use uuid::{ParseError, UuidFormat, UuidGenerator, UuidVersion, parse_uuid};
+#[cfg(feature = "uuid")]
+pub use ::uuid::Uuid;
+#[cfg(feature = "uuid")]
+pub use uuid::{
+    BatchParseError, EncodingError, InvalidInputReason, ParseError, UnknownVariantError,
+    UuidFormat, UuidVersion, clean_uuid_input, extract_timestamp_any, extract_v7_timestamp,
+    format_uuid, from_guid_le, from_proquint, from_short, from_slice, is_max, is_nil,
+    is_valid_uuid, is_valid_uuid_hyphenated, is_valid_uuid_simple, max, nil, parse_encoded,
+    parse_guid_registry, parse_prefixed, parse_uuid, parse_uuid_batch, parse_uuid_batch_strict,
+    parse_uuid_strict, reformat, reformat_prefixed, sort_by_timestamp, strip_affixes,
+    timestamp_key, to_guid_le, to_proquint, to_short, uuid_version, v7_timestamp_ms,
+};
+
+#[cfg(feature = "uuid")]
+pub use detect::{IdKind, detect_id_kind};
+pub use prefixed::{IdError, IdPrefix, PrefixedId};
+
+// Re-export generation types, which need the OS RNG and clock
+#[cfg(feature = "std")]
+pub use id::IdGenerator;
+#[cfg(all(feature = "std", any(feature = "uuid", feature = "nanoid")))]
+pub use id::{IdConfig, IdConfigError, boxed_from_config};
+#[cfg(feature = "std")]
+pub use prefix::{PrefixError, PrefixPolicy};
+#[cfg(feature = "std")]
+pub use strength::{IdStrengthReport, entropy_bits};
+#[cfg(all(feature = "std", feature = "uuid"))]
+pub use uuid::{
+    MatchError, NamedUuidGenerator, TimestampError, UuidGenerator, UuidGeneratorConfig,
+    VersionError,
+};
+
+// Re-export database types
+#[cfg(feature = "sqlx")]
+pub use uuid::DbUuid;
+
+// Re-export CUID2 types
+#[cfg(feature = "cuid2")]
+pub use cuid2::{Cuid2Error, Cuid2Generator, is_cuid2};
+
+// Re-export KSUID types
+#[cfg(feature = "ksuid")]
+pub use ksuid::{Ksuid, KsuidError, KsuidGenerator};
 
 // Re-export NanoID types
 #[cfg(feature = "nanoid")]
-pub use nanoid::NanoIdGenerator;
+pub use nanoid::{
+    Alphabet, AlphabetError, AlphabetPreset, NanoId, NanoIdConfig, NanoIdError, NanoIdGenerator,
+    NanoIdGeneratorBuilder, RngMode,
+};
+
+// Re-export obfuscation types
+#[cfg(feature = "obfuscate")]
+pub use obfuscate::{DEFAULT_OBFUSCATE_ALPHABET, IdObfuscator, ObfuscateError};
+
+// Re-export Snowflake types
+#[cfg(feature = "snowflake")]
+pub use snowflake::{
+    MACHINE_ID_BITS, SEQUENCE_BITS, SnowflakeError, SnowflakeGenerator, SnowflakeParts,
+    TIMESTAMP_BITS,
+};
+
+// Re-export ULID types
+#[cfg(feature = "ulid")]
+pub use ulid::{Ulid, UlidError, UlidGenerator};
 
 // Re-export metadata types when feature is enabled
-#[cfg(feature = "custom-uuid")]
+#[cfg(feature = "metadata")]
 pub use uuid::{
-    ClientMetadata, ExtractedMetadata, OsType, extract_metadata, parse_uuid_with_metadata,
+    ClientMetadata, ExtractedMetadata, HashKind, MetadataError, OsType, extract_metadata,
+    extract_metadata_from_bytes, extract_metadata_legacy, parse_prefixed_with_metadata,
+    parse_uuid_batch_with_metadata, parse_uuid_with_metadata, try_extract_metadata,
 };
+
+/// Runs with `--no-default-features --features alloc,uuid`, so CI notices
+/// when the parsing half starts to depend on `std`
+#[cfg(all(test, feature = "uuid", not(feature = "std")))]
+mod alloc_tests {
+    use super::*;
+
+    const V7: &str = "01890a5d-ac96-774b-bcce-b302099a8057";
+
+    #[test]
+    fn test_parse_and_format() {
+        let uuid = parse_uuid(clean_uuid_input(
+            "urn:uuid:{01890a5d-ac96-774b-bcce-b302099a8057}",
+        ))
+        .unwrap();
+        assert_eq!(format_uuid(&uuid, UuidFormat::Standard), V7);
+        assert_eq!(
+            format_uuid(&uuid, UuidFormat::Simple),
+            "01890a5dac96774bbcceb302099a8057"
+        );
+        assert_eq!(from_short(&to_short(&uuid)).unwrap(), uuid);
+        assert_eq!(reformat(V7, UuidFormat::Base58).unwrap().len(), 22);
+    }
+
+    #[test]
+    fn test_extract_timestamp() {
+        let uuid = parse_uuid(V7).unwrap();
+        assert_eq!(extract_v7_timestamp(&uuid), Some(0x0189_0a5d_ac96));
+        assert_eq!(uuid_version(V7).unwrap(), 7);
+    }
+
+    #[test]
+    #[cfg(feature = "metadata")]
+    fn test_extract_metadata_skips_plain_uuids() {
+        assert_eq!(extract_metadata(&parse_uuid(V7).unwrap()), None);
+        assert_eq!(extract_metadata(&Uuid::nil()), None);
+    }
+}
+
+/// Runs with `--no-default-features --features nanoid`, so CI notices when
+/// NanoID generation starts to reach into the UUID module
+#[cfg(all(test, feature = "nanoid", not(feature = "uuid")))]
+mod nanoid_only_tests {
+    use super::*;
+
+    define_id_prefix!(Order, "ord_");
+
+    #[test]
+    fn test_generate() {
+        let generator = NanoIdGenerator::builder().prefix("ord_").build();
+        let id = generator.generate();
+        assert!(id.starts_with("ord_"), "{id}");
+        assert_eq!(id.len(), 4 + 12);
+        assert_eq!(NanoIdGenerator::seeded(7).generate().len(), 12);
+        assert!(PrefixedId::<Order>::parse(&id).is_ok());
+    }
+
+    #[test]
+    fn test_config_and_prefix_policy() {
+        let generator = IdConfig::Nanoid {
+            prefix: Some("ord_".to_string()),
+            length: Some(16),
+            alphabet: None,
+            rng_mode: RngMode::Secure,
+        }
+        .build()
+        .unwrap();
+        assert_eq!(generator.generate().len(), 4 + 16);
+        assert!(PrefixPolicy::default().validate("ord id").is_err());
+    }
+}
+
+/// Runs with `--no-default-features --features std,uuid`, so CI notices when
+/// UUID generation or detection starts to depend on the NanoID module
+#[cfg(all(test, feature = "std", feature = "uuid", not(feature = "nanoid")))]
+mod uuid_only_tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_and_parse() {
+        let generator = UuidGenerator::v7().with_format(UuidFormat::Simple);
+        let id = generator.generate();
+        assert_eq!(parse_uuid(&id).unwrap().get_version_num(), 7);
+        assert_eq!(
+            detect_id_kind(&id),
+            IdKind::Uuid {
+                version: 7,
+                format: UuidFormat::Simple
+            }
+        );
+        assert!(matches!(
+            detect_id_kind("V1StGXR8_Z5jdHi6B-myT"),
+            IdKind::NanoIdLike { length: 21, .. }
+        ));
+    }
+
+    #[test]
+    fn test_config() {
+        let generator = IdConfig::UuidV4 {
+            format: UuidFormat::Standard,
+            prefix: Some("u_".to_string()),
+        }
+        .build()
+        .unwrap();
+        let id = generator.generate();
+        assert_eq!(parse_uuid(&id[2..]).unwrap().get_version_num(), 4);
+    }
+}
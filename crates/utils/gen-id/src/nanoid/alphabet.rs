@@ -0,0 +1,452 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use super::AlphabetError;
+
+/// Built-in NanoID alphabets
+///
+/// Entropy per character is `log2` of the alphabet size, see
+/// [`Self::bits_per_char`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum AlphabetPreset {
+    /// `0-9A-Za-z`, 62 characters, about 5.95 bits each
+    #[default]
+    Alphanumeric,
+    /// `0-9a-z`, 36 characters, about 5.17 bits each
+    ///
+    /// For systems that compare IDs case-insensitively.
+    Lowercase,
+    /// `0-9a-f`, 16 characters, exactly 4 bits each
+    Hex,
+    /// `A-Za-z0-9_-`, the reference NanoID alphabet, 64 characters, exactly
+    /// 6 bits each
+    UrlSafe,
+    /// Alphanumeric without the confusable `0`, `O`, `1`, `l` and `I`,
+    /// 57 characters, about 5.83 bits each
+    ///
+    /// For IDs that people read aloud or type from a screen.
+    NoLookalike,
+}
+
+const ALPHANUMERIC: [char; 62] =
+    ascii(b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz");
+const LOWERCASE: [char; 36] = ascii(b"0123456789abcdefghijklmnopqrstuvwxyz");
+const HEX: [char; 16] = ascii(b"0123456789abcdef");
+const URL_SAFE: [char; 64] =
+    ascii(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789_-");
+const NO_LOOKALIKE: [char; 57] =
+    ascii(b"23456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz");
+
+const fn ascii<const N: usize>(bytes: &[u8; N]) -> [char; N] {
+    let mut chars = ['\0'; N];
+    let mut i = 0;
+    while i < N {
+        chars[i] = bytes[i] as char;
+        i += 1;
+    }
+    chars
+}
+
+impl AlphabetPreset {
+    /// Every preset, in declaration order
+    pub const ALL: [Self; 5] = [
+        Self::Alphanumeric,
+        Self::Lowercase,
+        Self::Hex,
+        Self::UrlSafe,
+        Self::NoLookalike,
+    ];
+
+    /// The characters IDs are drawn from
+    #[inline]
+    pub const fn chars(&self) -> &'static [char] {
+        match self {
+            Self::Alphanumeric => &ALPHANUMERIC,
+            Self::Lowercase => &LOWERCASE,
+            Self::Hex => &HEX,
+            Self::UrlSafe => &URL_SAFE,
+            Self::NoLookalike => &NO_LOOKALIKE,
+        }
+    }
+
+    /// Number of characters
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.chars().len()
+    }
+
+    /// Always false, every preset has characters
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Bits of entropy each generated character carries
+    #[inline]
+    pub fn bits_per_char(&self) -> f64 {
+        (self.len() as f64).log2()
+    }
+
+    /// Whether `ch` belongs to this alphabet
+    #[inline]
+    pub fn contains(&self, ch: char) -> bool {
+        self.chars().contains(&ch)
+    }
+}
+
+/// Characters a [`NanoIdGenerator`](super::NanoIdGenerator) draws from, a
+/// preset or a validated custom set
+///
+/// Custom alphabets need 2 to 256 distinct ASCII characters, so in practice
+/// at most the 128 ASCII characters. Two alphabets are
+/// equal when they have the same characters in the same order, whether
+/// they came from a preset or not.
+///
+/// With the `serde` feature a preset is written as its name, e.g. `"hex"`,
+/// and a custom alphabet as `{"custom": "0123456789"}`.
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    repr: Repr,
+}
+
+#[derive(Debug, Clone)]
+enum Repr {
+    Preset(AlphabetPreset),
+    Custom(Arc<[char]>),
+}
+
+impl Alphabet {
+    /// Smallest custom alphabet, one character would give constant IDs
+    pub const MIN_LEN: usize = 2;
+    /// Largest custom alphabet, one random byte picks a character
+    pub const MAX_LEN: usize = 256;
+
+    /// Validate a custom alphabet
+    ///
+    /// Checks, in order, that every character is ASCII, that there are 2 to
+    /// 256 of them and that none repeats.
+    ///
+    /// ```
+    /// use gen_id::{Alphabet, AlphabetError};
+    ///
+    /// assert_eq!(Alphabet::new("01").unwrap().len(), 2);
+    /// assert_eq!(
+    ///     Alphabet::new("abca"),
+    ///     Err(AlphabetError::Duplicate { ch: 'a', index: 3 })
+    /// );
+    /// ```
+    pub fn new(chars: &str) -> Result<Self, AlphabetError> {
+        if let Some(ch) = chars.chars().find(|ch| !ch.is_ascii()) {
+            return Err(AlphabetError::NonAscii { ch });
+        }
+        let chars: Vec<char> = chars.chars().collect();
+        if chars.len() < Self::MIN_LEN {
+            return Err(AlphabetError::TooSmall { len: chars.len() });
+        }
+        if chars.len() > Self::MAX_LEN {
+            return Err(AlphabetError::TooLarge { len: chars.len() });
+        }
+        for (index, &ch) in chars.iter().enumerate() {
+            if chars[..index].contains(&ch) {
+                return Err(AlphabetError::Duplicate { ch, index });
+            }
+        }
+        Ok(Self {
+            repr: Repr::Custom(chars.into()),
+        })
+    }
+
+    /// Wrap a preset, usable in constants
+    #[inline]
+    pub const fn preset(preset: AlphabetPreset) -> Self {
+        Self {
+            repr: Repr::Preset(preset),
+        }
+    }
+
+    /// The preset this alphabet was created from, `None` for custom ones
+    #[inline]
+    pub fn as_preset(&self) -> Option<AlphabetPreset> {
+        match self.repr {
+            Repr::Preset(preset) => Some(preset),
+            Repr::Custom(_) => None,
+        }
+    }
+
+    /// The characters IDs are drawn from
+    #[inline]
+    pub fn chars(&self) -> &[char] {
+        match &self.repr {
+            Repr::Preset(preset) => preset.chars(),
+            Repr::Custom(chars) => chars,
+        }
+    }
+
+    /// Number of characters
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.chars().len()
+    }
+
+    /// Always false, alphabets have at least 2 characters
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Bits of entropy each generated character carries
+    #[inline]
+    pub fn bits_per_char(&self) -> f64 {
+        (self.len() as f64).log2()
+    }
+
+    /// Whether `ch` belongs to this alphabet
+    #[inline]
+    pub fn contains(&self, ch: char) -> bool {
+        self.chars().contains(&ch)
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::preset(AlphabetPreset::default())
+    }
+}
+
+impl From<AlphabetPreset> for Alphabet {
+    #[inline]
+    fn from(preset: AlphabetPreset) -> Self {
+        Self::preset(preset)
+    }
+}
+
+impl PartialEq for Alphabet {
+    fn eq(&self, other: &Self) -> bool {
+        self.chars() == other.chars()
+    }
+}
+
+impl Eq for Alphabet {}
+
+impl Hash for Alphabet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.chars().hash(state);
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::fmt;
+
+    use serde::{
+        Deserialize, Deserializer, Serialize, Serializer,
+        de::{self, MapAccess, Visitor, value::StrDeserializer},
+        ser::SerializeMap,
+    };
+
+    use super::{Alphabet, AlphabetPreset, Repr};
+
+    const CUSTOM: &str = "custom";
+
+    impl Serialize for Alphabet {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match &self.repr {
+                Repr::Preset(preset) => preset.serialize(serializer),
+                Repr::Custom(chars) => {
+                    let mut map = serializer.serialize_map(Some(1))?;
+                    map.serialize_entry(CUSTOM, &chars.iter().collect::<String>())?;
+                    map.end()
+                }
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Alphabet {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(AlphabetVisitor)
+        }
+    }
+
+    struct AlphabetVisitor;
+
+    impl<'de> Visitor<'de> for AlphabetVisitor {
+        type Value = Alphabet;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a preset name or {\"custom\": \"<characters>\"}")
+        }
+
+        fn visit_str<E: de::Error>(self, name: &str) -> Result<Alphabet, E> {
+            AlphabetPreset::deserialize(StrDeserializer::<E>::new(name)).map(Alphabet::preset)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Alphabet, A::Error> {
+            let Some(key) = map.next_key::<String>()? else {
+                return Err(de::Error::missing_field(CUSTOM));
+            };
+            if key != CUSTOM {
+                return Err(de::Error::unknown_field(&key, &[CUSTOM]));
+            }
+            let chars: String = map.next_value()?;
+            if let Some(key) = map.next_key::<String>()? {
+                return Err(de::Error::unknown_field(&key, &[CUSTOM]));
+            }
+            Alphabet::new(&chars).map_err(de::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_presets_have_no_duplicates() {
+        for alphabet in AlphabetPreset::ALL {
+            let unique: HashSet<_> = alphabet.chars().iter().collect();
+            assert_eq!(unique.len(), alphabet.len(), "{alphabet:?}");
+        }
+    }
+
+    #[test]
+    fn test_bits_per_char() {
+        let bits = |alphabet: AlphabetPreset| (alphabet.bits_per_char() * 100.0).round() / 100.0;
+        assert_eq!(bits(AlphabetPreset::Alphanumeric), 5.95);
+        assert_eq!(bits(AlphabetPreset::Lowercase), 5.17);
+        assert_eq!(bits(AlphabetPreset::Hex), 4.0);
+        assert_eq!(bits(AlphabetPreset::UrlSafe), 6.0);
+        assert_eq!(bits(AlphabetPreset::NoLookalike), 5.83);
+    }
+
+    #[test]
+    fn test_no_lookalike_excludes_confusables() {
+        for ch in ['0', 'O', '1', 'l', 'I'] {
+            assert!(!AlphabetPreset::NoLookalike.contains(ch), "{ch}");
+        }
+        let alphanumeric: HashSet<_> = AlphabetPreset::Alphanumeric.chars().iter().collect();
+        let no_lookalike: HashSet<_> = AlphabetPreset::NoLookalike.chars().iter().collect();
+        let dropped: HashSet<_> = alphanumeric.difference(&no_lookalike).copied().collect();
+        assert_eq!(dropped, HashSet::from([&'0', &'O', &'1', &'l', &'I']));
+    }
+
+    #[test]
+    fn test_url_safe_includes_separators() {
+        assert!(AlphabetPreset::UrlSafe.contains('-'));
+        assert!(AlphabetPreset::UrlSafe.contains('_'));
+        assert!(!AlphabetPreset::Alphanumeric.contains('-'));
+    }
+
+    #[test]
+    fn test_custom_alphabet_validation() {
+        let binary = Alphabet::new("01").unwrap();
+        assert_eq!(binary.chars(), ['0', '1']);
+        assert_eq!(binary.as_preset(), None);
+        assert_eq!(binary.bits_per_char(), 1.0);
+
+        let ascii: String = ('\0'..='\x7f').collect();
+        assert_eq!(Alphabet::new(&ascii).unwrap().len(), 128);
+    }
+
+    #[test]
+    fn test_duplicate_error() {
+        let err = Alphabet::new("0123456789abcdef0").unwrap_err();
+        assert_eq!(err, AlphabetError::Duplicate { ch: '0', index: 16 });
+        assert_eq!(
+            err.to_string(),
+            "alphabet repeats '0' at index 16, every character must be distinct"
+        );
+    }
+
+    #[test]
+    fn test_non_ascii_error() {
+        let err = Alphabet::new("abcé").unwrap_err();
+        assert_eq!(err, AlphabetError::NonAscii { ch: 'é' });
+        assert_eq!(
+            err.to_string(),
+            "alphabet contains 'é', only single-byte ASCII characters are allowed"
+        );
+        // Reported before the size, so a lone multi-byte character says why
+        assert_eq!(
+            Alphabet::new("🦀"),
+            Err(AlphabetError::NonAscii { ch: '🦀' })
+        );
+    }
+
+    #[test]
+    fn test_too_small_error() {
+        let err = Alphabet::new("x").unwrap_err();
+        assert_eq!(err, AlphabetError::TooSmall { len: 1 });
+        assert_eq!(
+            err.to_string(),
+            "alphabet needs at least 2 characters, found 1"
+        );
+        assert_eq!(Alphabet::new(""), Err(AlphabetError::TooSmall { len: 0 }));
+    }
+
+    #[test]
+    fn test_too_large_error() {
+        let err = Alphabet::new(&"a".repeat(257)).unwrap_err();
+        assert_eq!(err, AlphabetError::TooLarge { len: 257 });
+        assert_eq!(
+            err.to_string(),
+            "alphabet allows at most 256 characters, found 257"
+        );
+    }
+
+    #[test]
+    fn test_custom_equals_matching_preset() {
+        let hex = Alphabet::new("0123456789abcdef").unwrap();
+        assert_eq!(hex, AlphabetPreset::Hex.into());
+        assert_ne!(hex, AlphabetPreset::Lowercase.into());
+        assert_eq!(
+            Alphabet::default().as_preset(),
+            Some(AlphabetPreset::Alphanumeric)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let alphabets: Vec<Alphabet> =
+            serde_json::from_str(r#"["no_lookalike", {"custom": "abc"}]"#).unwrap();
+        assert_eq!(alphabets[0].as_preset(), Some(AlphabetPreset::NoLookalike));
+        assert_eq!(alphabets[1].chars(), ['a', 'b', 'c']);
+
+        let json = serde_json::to_string(&alphabets).unwrap();
+        assert_eq!(json, r#"["no_lookalike",{"custom":"abc"}]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<Alphabet>>(&json).unwrap(),
+            alphabets
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_errors() {
+        let error = |json: &str| {
+            serde_json::from_str::<Alphabet>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(
+            error(r#"{"custom": "abcb"}"#).starts_with("alphabet repeats 'b' at index 3"),
+            "{}",
+            error(r#"{"custom": "abcb"}"#)
+        );
+        assert!(error(r#""base64""#).starts_with("unknown variant `base64`"));
+        assert!(error(r#"{"chars": "ab"}"#).starts_with("unknown field `chars`"));
+        assert!(
+            error(r#"{"custom": "a"}"#)
+                .starts_with("alphabet needs at least 2 characters, found 1")
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use super::NanoIdGenerator;
+
+/// Check characters with the Luhn mod N algorithm, N being the alphabet size
+///
+/// The check character comes from the alphabet, so checked IDs look like
+/// any other. Every single-character substitution is caught, and every
+/// swap of two neighbouring characters except the first and last alphabet
+/// characters (`0` and `z` for alphanumeric), the mod N form of Luhn's
+/// `09`/`90` blind spot.
+impl NanoIdGenerator {
+    /// Generates a NanoID with a check character appended
+    ///
+    /// `length` counts the random characters, so the ID is one longer.
+    /// `None` falls back to the configured prefix and length.
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// let generator = NanoIdGenerator::builder().prefix("ref_").length(8).build();
+    /// let id = generator.generate_checked(None, None);
+    /// assert_eq!(id.len(), 4 + 8 + 1);
+    /// assert!(generator.verify_checksum(&id));
+    /// ```
+    pub fn generate_checked(&self, prefix: Option<&str>, length: Option<usize>) -> String {
+        let mut id = self.generate_with(prefix, length);
+        let prefix_len = prefix.or(self.prefix()).map_or(0, str::len);
+        let check = self.check_char(&id[prefix_len..]);
+        id.push(check);
+        id
+    }
+
+    /// Whether the last character of `id` is the check character of the
+    /// rest, as appended by [`Self::generate_checked`]
+    ///
+    /// Strips the configured prefix when `id` starts with it; strip a
+    /// per-call prefix first. False for characters outside the alphabet.
+    pub fn verify_checksum(&self, id: &str) -> bool {
+        let body = self
+            .prefix()
+            .and_then(|prefix| id.strip_prefix(prefix))
+            .unwrap_or(id);
+        if body.chars().nth(1).is_none() {
+            return false;
+        }
+        luhn_sum(self.alphabet().chars(), body, false).is_some_and(|sum| sum == 0)
+    }
+
+    fn check_char(&self, body: &str) -> char {
+        let chars = self.alphabet().chars();
+        let sum = luhn_sum(chars, body, true).expect("generated characters are in the alphabet");
+        chars[(chars.len() - sum) % chars.len()]
+    }
+}
+
+/// Luhn mod N sum of `input`, doubling every second character from the
+/// right, starting with the last when `double_last` (no check character
+/// yet). `None` for characters outside `chars`.
+fn luhn_sum(chars: &[char], input: &str, double_last: bool) -> Option<usize> {
+    let base = chars.len();
+    let mut double = double_last;
+    let mut sum = 0;
+    for ch in input.chars().rev() {
+        let mut addend = chars.iter().position(|&c| c == ch)?;
+        if double {
+            addend *= 2;
+            addend = addend / base + addend % base;
+        }
+        sum = (sum + addend) % base;
+        double = !double;
+    }
+    Some(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nanoid::AlphabetPreset;
+
+    #[test]
+    fn test_generated_ids_verify() {
+        for alphabet in AlphabetPreset::ALL {
+            let generator = NanoIdGenerator::with_preset(alphabet);
+            for _ in 0..50 {
+                let id = generator.generate_checked(Some("x_"), None);
+                assert_eq!(id.len(), 2 + 12 + 1);
+                assert!(generator.verify_checksum(&id[2..]), "{id}");
+                assert!(alphabet.contains(id.chars().last().unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_check_character() {
+        // From the right: c doubled is 24 = 0x18, 1 + 8 = 9; b is 11; a
+        // doubled is 20 = 0x14, 1 + 4 = 5. 25 mod 16 = 9, so 16 - 9 = 7
+        let generator = NanoIdGenerator::with_preset(AlphabetPreset::Hex);
+        assert_eq!(generator.check_char("abc"), '7');
+        assert!(generator.verify_checksum("abc7"));
+        assert!(!generator.verify_checksum("abc6"));
+    }
+
+    #[test]
+    fn test_substitutions_are_detected() {
+        let generator = NanoIdGenerator::builder().prefix("ord_").build();
+        for _ in 0..20 {
+            let id = generator.generate_checked(None, None);
+            assert!(generator.verify_checksum(&id));
+
+            let body: Vec<char> = id["ord_".len()..].chars().collect();
+            for position in 0..body.len() {
+                for &replacement in generator.alphabet().chars() {
+                    if replacement == body[position] {
+                        continue;
+                    }
+                    let mut typo = body.clone();
+                    typo[position] = replacement;
+                    let typo = format!("ord_{}", typo.iter().collect::<String>());
+                    assert!(!generator.verify_checksum(&typo), "{id} -> {typo}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_adjacent_transpositions_are_detected() {
+        let alphabet = AlphabetPreset::Alphanumeric;
+        let generator = NanoIdGenerator::with_preset(alphabet);
+        let blind_spot = |a: char, b: char| {
+            let (first, last) = (alphabet.chars()[0], alphabet.chars()[alphabet.len() - 1]);
+            (a, b) == (first, last) || (a, b) == (last, first)
+        };
+
+        for _ in 0..200 {
+            let id: Vec<char> = generator.generate_checked(None, None).chars().collect();
+            for position in 0..id.len() - 1 {
+                let (a, b) = (id[position], id[position + 1]);
+                if a == b {
+                    continue;
+                }
+                let mut typo = id.clone();
+                typo.swap(position, position + 1);
+                let typo: String = typo.into_iter().collect();
+                assert_eq!(generator.verify_checksum(&typo), blind_spot(a, b), "{typo}");
+            }
+        }
+
+        // The one undetected swap
+        let id = format!("ab0z{}", generator.check_char("ab0z"));
+        assert!(generator.verify_checksum(&id));
+        assert!(generator.verify_checksum(&id.replace("0z", "z0")));
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_input() {
+        let generator = NanoIdGenerator::new();
+        assert!(!generator.verify_checksum(""));
+        assert!(!generator.verify_checksum("0"));
+        assert!(!generator.verify_checksum("ab-c"));
+    }
+}
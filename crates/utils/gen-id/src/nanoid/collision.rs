@@ -0,0 +1,270 @@
+use super::{NanoIdGenerator, RngMode};
+use crate::{IdStrengthReport, strength};
+
+/// Collision sizing, using the birthday bound
+/// `p = 1 - exp(-count * (count - 1) / (2 * alphabet_len^length))`
+///
+/// Computed in log space, so absurd counts and lengths give 0 or 1 rather
+/// than NaN or infinity.
+impl NanoIdGenerator {
+    /// Probability that `count` random IDs of `length` characters from an
+    /// alphabet of `alphabet_len` contain at least one duplicate
+    ///
+    /// Alphabets of fewer than 2 characters and zero lengths allow a single
+    /// ID, so any two collide.
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// // A million default 12-character IDs
+    /// let p = NanoIdGenerator::collision_probability(62, 12, 1_000_000);
+    /// assert!(p > 1e-10 && p < 1e-9);
+    /// ```
+    pub fn collision_probability(alphabet_len: usize, length: usize, count: u64) -> f64 {
+        if count < 2 {
+            return 0.0;
+        }
+        if alphabet_len < 2 || length == 0 {
+            return 1.0;
+        }
+
+        let ln_space = length as f64 * (alphabet_len as f64).ln();
+        let ln_pairs = (count as f64).ln() + ((count - 1) as f64).ln() - 2f64.ln();
+        let expected = (ln_pairs - ln_space).exp();
+        -(-expected).exp_m1()
+    }
+
+    /// Shortest length that keeps the collision probability of `count` IDs
+    /// at or below `target_probability`
+    ///
+    /// At least 1. Returns `usize::MAX` when no length can reach the target:
+    /// a target of 0 (or NaN) with 2 or more IDs, or an alphabet of fewer
+    /// than 2 characters.
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// // 10,000 IDs a day for 10 years, at most a one in a million chance
+    /// let length = NanoIdGenerator::length_for(62, 10_000 * 365 * 10, 1e-6);
+    /// assert_eq!(length, 12);
+    /// ```
+    pub fn length_for(alphabet_len: usize, count: u64, target_probability: f64) -> usize {
+        if count < 2 || target_probability >= 1.0 {
+            return 1;
+        }
+        if alphabet_len < 2 || target_probability.is_nan() || target_probability <= 0.0 {
+            return usize::MAX;
+        }
+
+        // Solve `expected pairs / space = -ln(1 - p)` for the length
+        let ln_pairs = (count as f64).ln() + ((count - 1) as f64).ln() - 2f64.ln();
+        let ln_budget = (-(-target_probability).ln_1p()).ln();
+        let estimate = ((ln_pairs - ln_budget) / (alphabet_len as f64).ln()).ceil();
+        let mut length = if estimate >= usize::MAX as f64 {
+            usize::MAX
+        } else {
+            (estimate as usize).max(1)
+        };
+
+        // Correct rounding at the boundary of the closed-form estimate
+        let probability = |length| Self::collision_probability(alphabet_len, length, count);
+        while length < usize::MAX && probability(length) > target_probability {
+            length += 1;
+        }
+        while length > 1 && probability(length - 1) <= target_probability {
+            length -= 1;
+        }
+        length
+    }
+}
+
+/// Entropy of the configured alphabet and length
+impl NanoIdGenerator {
+    /// Bits of entropy in each ID at the configured length, see
+    /// [`entropy_bits`](crate::entropy_bits)
+    ///
+    /// ```
+    /// use gen_id::{AlphabetPreset, NanoIdGenerator};
+    ///
+    /// let generator = NanoIdGenerator::builder()
+    ///     .alphabet(AlphabetPreset::UrlSafe)
+    ///     .length(21)
+    ///     .build();
+    /// assert_eq!(generator.entropy_bits(), 126.0);
+    /// ```
+    pub fn entropy_bits(&self) -> f64 {
+        strength::entropy_bits(self.alphabet().len(), self.length())
+    }
+
+    /// [`Self::entropy_bits`] with the alphabet, length and RNG caveats, for
+    /// review documents
+    pub fn strength_report(&self) -> IdStrengthReport {
+        let scheme = format!("NanoID, {}-character alphabet", self.alphabet().len());
+        let report = IdStrengthReport::new(scheme, self.length(), self.entropy_bits());
+        if self.has_injected_rng() {
+            report.with_note("injected RNG, IDs are only as unpredictable as that RNG")
+        } else if self.rng_mode() == RngMode::Fast {
+            report.with_note("RngMode::Fast is not cryptographically secure")
+        } else {
+            report
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= expected * tolerance,
+            "{actual} not within {tolerance} of {expected}"
+        );
+    }
+
+    #[test]
+    fn test_entropy_bits() {
+        use crate::AlphabetPreset;
+
+        // Presets at the default length of 12
+        let expected = [
+            (AlphabetPreset::Alphanumeric, 71.45),
+            (AlphabetPreset::Lowercase, 62.04),
+            (AlphabetPreset::Hex, 48.0),
+            (AlphabetPreset::UrlSafe, 72.0),
+            (AlphabetPreset::NoLookalike, 69.99),
+        ];
+        for (preset, bits) in expected {
+            let actual = NanoIdGenerator::with_preset(preset).entropy_bits();
+            assert!((actual - bits).abs() < 0.01, "{preset:?}: {actual}");
+        }
+        let reference = NanoIdGenerator::builder()
+            .alphabet(AlphabetPreset::UrlSafe)
+            .length(21)
+            .build();
+        assert_eq!(reference.entropy_bits(), 126.0);
+    }
+
+    #[test]
+    fn test_strength_report() {
+        use rand_chacha::ChaCha8Rng;
+        use rand_core::SeedableRng;
+
+        let report = NanoIdGenerator::new().strength_report();
+        assert_eq!(
+            report.to_string(),
+            "NanoID, 62-character alphabet, 12 characters: 71.5 bits of entropy"
+        );
+        let fast = NanoIdGenerator::new()
+            .with_rng_mode(RngMode::Fast)
+            .strength_report();
+        assert_eq!(
+            fast.notes,
+            ["RngMode::Fast is not cryptographically secure"]
+        );
+        let injected = NanoIdGenerator::new()
+            .with_rng(ChaCha8Rng::seed_from_u64(0))
+            .strength_report();
+        assert_eq!(injected.notes.len(), 1);
+        assert_eq!(injected.entropy_bits, report.entropy_bits);
+    }
+
+    #[test]
+    fn test_reference_values() {
+        // nanoid collision calculator: default 21 URL-safe characters at
+        // 1000 IDs per hour take ~149 billion years to a 1% chance
+        let count = (1000.0 * 24.0 * 365.0 * 149e9) as u64;
+        assert_close(
+            NanoIdGenerator::collision_probability(64, 21, count),
+            0.01,
+            0.01,
+        );
+
+        // UUID v4, 122 random bits: 2.71e18 IDs for a 50% chance and 103
+        // trillion for one in a billion
+        assert_close(
+            NanoIdGenerator::collision_probability(2, 122, 2_710_000_000_000_000_000),
+            0.5,
+            0.01,
+        );
+        assert_close(
+            NanoIdGenerator::collision_probability(2, 122, 103_000_000_000_000),
+            1e-9,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn test_small_exact_values() {
+        // 16 two-character hex IDs fill 256 slots: 1 - exp(-120 / 256)
+        assert_close(
+            NanoIdGenerator::collision_probability(16, 2, 16),
+            1.0 - (-120.0f64 / 256.0).exp(),
+            1e-12,
+        );
+        assert_eq!(NanoIdGenerator::collision_probability(62, 12, 0), 0.0);
+        assert_eq!(NanoIdGenerator::collision_probability(62, 12, 1), 0.0);
+    }
+
+    #[test]
+    fn test_length_for_reference_values() {
+        assert_eq!(
+            NanoIdGenerator::length_for(64, 1_300_000_000_000_000_000, 0.01),
+            21
+        );
+        assert_eq!(
+            NanoIdGenerator::length_for(2, 103_000_000_000_000, 1e-9),
+            122
+        );
+        assert_eq!(NanoIdGenerator::length_for(16, 16, 0.5), 2);
+    }
+
+    #[test]
+    fn test_length_for_is_the_shortest() {
+        for alphabet_len in [2, 10, 16, 36, 57, 62, 64, 256] {
+            for count in [2, 1_000, 1_000_000, u64::MAX] {
+                for target in [0.5, 1e-3, 1e-9, 1e-30] {
+                    let length = NanoIdGenerator::length_for(alphabet_len, count, target);
+                    let p = |length| {
+                        NanoIdGenerator::collision_probability(alphabet_len, length, count)
+                    };
+                    assert!(p(length) <= target, "{alphabet_len} {count} {target}");
+                    assert!(length == 1 || p(length - 1) > target);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_extreme_inputs() {
+        let cases = [
+            (0, 12, 2),
+            (1, 12, 2),
+            (62, 0, 2),
+            (2, 1, u64::MAX),
+            (62, usize::MAX, u64::MAX),
+            (usize::MAX, usize::MAX, 2),
+            (256, 10_000, u64::MAX),
+        ];
+        for (alphabet_len, length, count) in cases {
+            let p = NanoIdGenerator::collision_probability(alphabet_len, length, count);
+            assert!(
+                (0.0..=1.0).contains(&p),
+                "{alphabet_len} {length} {count}: {p}"
+            );
+        }
+        assert_eq!(NanoIdGenerator::collision_probability(1, 100, 2), 1.0);
+        assert_eq!(NanoIdGenerator::collision_probability(2, 1, u64::MAX), 1.0);
+        assert_eq!(
+            NanoIdGenerator::collision_probability(62, usize::MAX, u64::MAX),
+            0.0
+        );
+
+        assert_eq!(NanoIdGenerator::length_for(62, 1, 0.0), 1);
+        assert_eq!(NanoIdGenerator::length_for(62, 2, 1.0), 1);
+        assert_eq!(NanoIdGenerator::length_for(62, 2, 0.0), usize::MAX);
+        assert_eq!(NanoIdGenerator::length_for(62, 2, f64::NAN), usize::MAX);
+        assert_eq!(NanoIdGenerator::length_for(1, 2, 0.5), usize::MAX);
+        assert!(NanoIdGenerator::length_for(2, u64::MAX, f64::MIN_POSITIVE) < 2000);
+    }
+}
@@ -0,0 +1,221 @@
+use super::{Alphabet, NanoIdError, NanoIdGenerator, RngMode, generator::DEFAULT_LENGTH};
+
+/// Declarative settings for a [`NanoIdGenerator`], e.g. from a config file
+///
+/// Missing fields fall back to 12 alphanumeric characters without prefix,
+/// drawn from the secure RNG.
+/// Nothing is checked until [`Self::build`], except that an invalid custom
+/// alphabet fails to deserialize with its [`AlphabetError`] message.
+///
+/// [`AlphabetError`]: super::AlphabetError
+///
+/// ```
+/// use gen_id::{AlphabetPreset, NanoIdConfig};
+///
+/// let generator = NanoIdConfig::default()
+///     .with_length(16)
+///     .with_prefix("ord_")
+///     .with_alphabet(AlphabetPreset::NoLookalike)
+///     .build()
+///     .unwrap();
+/// assert_eq!(generator.generate().len(), 4 + 16);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+#[non_exhaustive]
+pub struct NanoIdConfig {
+    pub length: usize,
+    pub prefix: Option<String>,
+    /// Alphanumeric when unset
+    pub alphabet: Option<Alphabet>,
+    /// [`RngMode::Fast`] only for IDs that need not be unguessable
+    pub rng_mode: RngMode,
+}
+
+impl Default for NanoIdConfig {
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_LENGTH,
+            prefix: None,
+            alphabet: None,
+            rng_mode: RngMode::Secure,
+        }
+    }
+}
+
+impl NanoIdConfig {
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_alphabet(mut self, alphabet: impl Into<Alphabet>) -> Self {
+        self.alphabet = Some(alphabet.into());
+        self
+    }
+
+    pub fn with_rng_mode(mut self, rng_mode: RngMode) -> Self {
+        self.rng_mode = rng_mode;
+        self
+    }
+
+    /// Create a generator with these settings
+    ///
+    /// Fails on the lengths and prefixes [`NanoIdGenerator::try_generate`]
+    /// would reject, so the generator's infallible methods are safe to use.
+    /// The alphabet was validated when it was created, an error from
+    /// [`Alphabet::new`] converts into [`NanoIdError::Alphabet`] for callers
+    /// that build the alphabet and the generator in one function.
+    pub fn build(&self) -> Result<NanoIdGenerator, NanoIdError> {
+        let mut builder = NanoIdGenerator::builder()
+            .length(self.length)
+            .rng_mode(self.rng_mode);
+        if let Some(prefix) = &self.prefix {
+            builder = builder.prefix(prefix.clone());
+        }
+        if let Some(alphabet) = &self.alphabet {
+            builder = builder.alphabet(alphabet.clone());
+        }
+        let generator = builder.build();
+        generator.validate(None, None)?;
+        Ok(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nanoid::{AlphabetError, AlphabetPreset};
+
+    #[test]
+    fn test_build_applies_settings() {
+        let generator = NanoIdConfig::default()
+            .with_length(8)
+            .with_prefix("ord_")
+            .with_alphabet(AlphabetPreset::Hex)
+            .build()
+            .unwrap();
+        assert_eq!(generator.length(), 8);
+        assert_eq!(generator.prefix(), Some("ord_"));
+        assert_eq!(generator.alphabet().as_preset(), Some(AlphabetPreset::Hex));
+        assert_eq!(generator.rng_mode(), RngMode::Secure);
+
+        let generator = NanoIdConfig::default().build().unwrap();
+        assert_eq!(generator.length(), DEFAULT_LENGTH);
+        assert_eq!(generator.prefix(), None);
+        assert_eq!(*generator.alphabet(), Alphabet::default());
+    }
+
+    #[test]
+    fn test_build_validates() {
+        let build = |config: NanoIdConfig| config.build().unwrap_err();
+        assert_eq!(
+            build(NanoIdConfig::default().with_length(0)),
+            NanoIdError::ZeroLength
+        );
+        assert_eq!(
+            build(NanoIdConfig::default().with_length(257)),
+            NanoIdError::LengthTooLong {
+                length: 257,
+                max: 256
+            }
+        );
+        assert_eq!(
+            build(NanoIdConfig::default().with_prefix("p".repeat(65))),
+            NanoIdError::PrefixTooLong { len: 65, max: 64 }
+        );
+    }
+
+    #[test]
+    fn test_alphabet_error_converts() {
+        let build = |chars: &str| -> Result<NanoIdGenerator, NanoIdError> {
+            NanoIdConfig::default()
+                .with_alphabet(Alphabet::new(chars)?)
+                .build()
+        };
+        let err = build("aa").unwrap_err();
+        assert_eq!(
+            err,
+            NanoIdError::Alphabet(AlphabetError::Duplicate { ch: 'a', index: 1 })
+        );
+        assert_eq!(
+            err.to_string(),
+            "alphabet repeats 'a' at index 1, every character must be distinct"
+        );
+        assert!(build("ab").is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let config: NanoIdConfig = serde_json::from_str(
+            r#"{"length": 10, "prefix": "inv_", "alphabet": {"custom": "0123456789"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            NanoIdConfig::default()
+                .with_length(10)
+                .with_prefix("inv_")
+                .with_alphabet(Alphabet::new("0123456789").unwrap())
+        );
+        let id = config.build().unwrap().generate();
+        assert!(id[4..].chars().all(|ch| ch.is_ascii_digit()), "{id}");
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<NanoIdConfig>(&json).unwrap(), config);
+
+        let config: NanoIdConfig = serde_json::from_str(r#"{"alphabet": "hex"}"#).unwrap();
+        assert_eq!(config.length, DEFAULT_LENGTH);
+        assert_eq!(config.alphabet, Some(AlphabetPreset::Hex.into()));
+        assert_eq!(config.rng_mode, RngMode::Secure);
+
+        let config: NanoIdConfig = serde_json::from_str(r#"{"rng_mode": "fast"}"#).unwrap();
+        assert_eq!(config.build().unwrap().rng_mode(), RngMode::Fast);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_reports_duplicate_characters() {
+        let error = serde_json::from_str::<NanoIdConfig>(r#"{"alphabet": {"custom": "abcdb"}}"#)
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("alphabet repeats 'b' at index 4"),
+            "{error}"
+        );
+
+        let error = serde_json::from_str::<NanoIdConfig>(r#"{"alphabet": {"custom": "0-9-"}}"#)
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("alphabet repeats '-' at index 3"),
+            "{error}"
+        );
+        let error =
+            serde_json::from_str::<NanoIdConfig>(r#"{"alphabet": {"custom": "αβγ"}}"#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("alphabet contains 'α', only single-byte ASCII"),
+            "{error}"
+        );
+
+        let error = serde_json::from_str::<NanoIdConfig>(r#"{"size": 12}"#).unwrap_err();
+        assert!(
+            error.to_string().starts_with("unknown field `size`"),
+            "{error}"
+        );
+    }
+}
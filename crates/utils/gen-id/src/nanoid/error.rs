@@ -0,0 +1,55 @@
+/// Why a custom alphabet was rejected
+///
+/// The messages name the offending character or count and the rule, so they
+/// can be shown to operators as they are.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum AlphabetError {
+    #[error("alphabet repeats {ch:?} at index {index}, every character must be distinct")]
+    Duplicate { ch: char, index: usize },
+
+    #[error("alphabet contains {ch:?}, only single-byte ASCII characters are allowed")]
+    NonAscii { ch: char },
+
+    #[error("alphabet needs at least 2 characters, found {len}")]
+    TooSmall { len: usize },
+
+    #[error("alphabet allows at most 256 characters, found {len}")]
+    TooLarge { len: usize },
+}
+
+/// Error for a NanoID request the generator cannot satisfy
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum NanoIdError {
+    #[error("length must be at least 1")]
+    ZeroLength,
+
+    #[error("length {length} exceeds the maximum of {max}")]
+    LengthTooLong { length: usize, max: usize },
+
+    #[error(transparent)]
+    Alphabet(#[from] AlphabetError),
+
+    #[error("prefix is {len} bytes long, the maximum is {max}")]
+    PrefixTooLong { len: usize, max: usize },
+
+    #[error(
+        "{count} unique IDs exceed half of the {keyspace} possible IDs of {length} characters, \
+         use a longer length or larger alphabet"
+    )]
+    KeyspaceTooSmall {
+        count: usize,
+        length: usize,
+        keyspace: u128,
+    },
+
+    #[error(
+        "only {unique} of {count} IDs were unique after {attempts} draws, is the RNG repeating?"
+    )]
+    RetriesExhausted {
+        count: usize,
+        unique: usize,
+        attempts: usize,
+    },
+}
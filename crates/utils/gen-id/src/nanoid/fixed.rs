@@ -0,0 +1,244 @@
+use std::{
+    borrow::Borrow,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    str,
+};
+
+/// A NanoID of exactly `N` characters held inline, from
+/// [`NanoIdGenerator::generate_fixed`](super::NanoIdGenerator::generate_fixed)
+///
+/// The `[u8; N]` lives on the stack and is `Copy`, for hot paths that should
+/// not allocate. Alphabets only hold ASCII characters, so the bytes are
+/// valid UTF-8 and the ID derefs to a `str`. Equality, ordering and hashing
+/// match the string form, and `Borrow<str>` lets a `HashSet<NanoId<N>>` be
+/// queried with a `&str`.
+///
+/// With the `serde` feature it is written as a string, and reading one
+/// requires exactly `N` ASCII characters.
+///
+/// ```
+/// use gen_id::{NanoId, NanoIdGenerator};
+///
+/// let id: NanoId<21> = NanoIdGenerator::new().generate_fixed::<21>();
+/// assert_eq!(id.len(), 21);
+/// assert_eq!(id, id.into_string().as_str());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NanoId<const N: usize>([u8; N]);
+
+impl<const N: usize> NanoId<N> {
+    /// Number of characters, and of bytes
+    pub const LEN: usize = N;
+
+    /// Wrap bytes drawn from an alphabet, which are all ASCII
+    #[inline]
+    pub(super) fn from_ascii(bytes: [u8; N]) -> Self {
+        debug_assert!(bytes.is_ascii(), "NanoID bytes must be ASCII");
+        Self(bytes)
+    }
+
+    /// Check that `id` has exactly `N` ASCII characters
+    #[cfg(any(feature = "serde", test))]
+    fn parse(id: &str) -> Option<Self> {
+        let bytes: [u8; N] = id.as_bytes().try_into().ok()?;
+        bytes.is_ascii().then_some(Self(bytes))
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.0).expect("NanoID bytes are ASCII")
+    }
+
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+
+    /// Copy the ID to the heap
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+impl<const N: usize> Deref for NanoId<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for NanoId<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Borrow<str> for NanoId<N> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Hashes as the `str`, as `Borrow<str>` requires
+impl<const N: usize> Hash for NanoId<N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl<const N: usize> PartialEq<str> for NanoId<N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for NanoId<N> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> fmt::Display for NanoId<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for NanoId<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NanoId({:?})", self.as_str())
+    }
+}
+
+impl<const N: usize> From<NanoId<N>> for String {
+    #[inline]
+    fn from(id: NanoId<N>) -> Self {
+        id.into_string()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for NanoId<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for NanoId<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Self::parse(&id).ok_or_else(|| {
+            serde::de::Error::custom(format_args!(
+                "expected a NanoID of {N} ASCII characters, found {id:?}"
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashSet, hash_map::RandomState},
+        hash::BuildHasher,
+    };
+
+    use super::*;
+    use crate::nanoid::{AlphabetPreset, NanoIdGenerator};
+
+    #[test]
+    fn test_generate_fixed() {
+        let generator = NanoIdGenerator::with_preset(AlphabetPreset::UrlSafe);
+        let id = generator.generate_fixed::<21>();
+        assert_eq!(id.len(), NanoId::<21>::LEN);
+        assert!(
+            id.chars().all(|ch| AlphabetPreset::UrlSafe.contains(ch)),
+            "{id}"
+        );
+        assert_ne!(generator.generate_fixed::<21>(), id);
+        assert_eq!(NanoIdGenerator::new().generate_fixed::<1>().len(), 1);
+    }
+
+    #[test]
+    fn test_matches_heap_generation() {
+        // Same alphabet and seed, so the same characters as generate_with
+        let heap = NanoIdGenerator::seeded(42).generate_with(None, Some(16));
+        let fixed = NanoIdGenerator::seeded(42).generate_fixed::<16>();
+        assert_eq!(fixed, heap.as_str());
+    }
+
+    #[test]
+    fn test_ignores_prefix_and_length() {
+        let generator = NanoIdGenerator::builder().prefix("ord_").length(8).build();
+        let id = generator.generate_fixed::<16>();
+        assert_eq!(id.len(), 16);
+        assert!(id.chars().all(|ch| ch.is_ascii_alphanumeric()), "{id}");
+    }
+
+    #[test]
+    fn test_str_conversions() {
+        let id = NanoId::from_ascii(*b"V1StGXR8_Z5jdHi6B-myT");
+        assert_eq!(id.as_str(), "V1StGXR8_Z5jdHi6B-myT");
+        assert_eq!(id, "V1StGXR8_Z5jdHi6B-myT");
+        assert_eq!(id, *"V1StGXR8_Z5jdHi6B-myT");
+        assert_ne!(id, "V1StGXR8_Z5jdHi6B-my");
+        assert_eq!(id.to_string(), "V1StGXR8_Z5jdHi6B-myT");
+        assert_eq!(format!("{id:?}"), r#"NanoId("V1StGXR8_Z5jdHi6B-myT")"#);
+        assert_eq!(String::from(id), id.into_string());
+        assert_eq!(id.as_ref(), id.as_str());
+        assert!(id.starts_with("V1St"));
+    }
+
+    #[test]
+    fn test_eq_hash_and_ord_match_str() {
+        let a = NanoId::from_ascii(*b"abc");
+        let b = NanoId::from_ascii(*b"abd");
+        assert_eq!(a, NanoId::from_ascii(*b"abc"));
+        assert_ne!(a, b);
+        assert_eq!(a.cmp(&b), "abc".cmp("abd"));
+
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(a), state.hash_one("abc"));
+
+        let set: HashSet<NanoId<3>> = [a, b].into();
+        assert!(set.contains("abc"));
+        assert!(!set.contains("abe"));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(NanoId::<3>::parse("abc"), Some(NanoId::from_ascii(*b"abc")));
+        assert_eq!(NanoId::<3>::parse("abcd"), None);
+        assert_eq!(NanoId::<3>::parse("ab"), None);
+        // Three bytes, but not ASCII
+        assert_eq!(NanoId::<3>::parse("a\u{e9}"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let id = NanoId::from_ascii(*b"V1StGXR8Z5jd");
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, r#""V1StGXR8Z5jd""#);
+        assert_eq!(serde_json::from_str::<NanoId<12>>(&json).unwrap(), id);
+
+        let error = serde_json::from_str::<NanoId<12>>(r#""short""#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with(r#"expected a NanoID of 12 ASCII characters, found "short""#),
+            "{error}"
+        );
+    }
+}
@@ -1,17 +1,37 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, PoisonError},
+};
+
+use rand_chacha::ChaCha8Rng;
+use rand_core::{RngCore, SeedableRng};
+
+use super::{
+    Alphabet, AlphabetError, AlphabetPreset, NanoId, NanoIdError, RngMode,
+    sample::{self, InjectedRng},
+};
+
 /// Default length for generated NanoIDs
 pub const DEFAULT_LENGTH: usize = 12;
 
-/// Alphanumeric alphabet including uppercase and lowercase letters and numbers
-const ALPHABET: [char; 62] = [
-    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
-    'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b',
-    'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u',
-    'v', 'w', 'x', 'y', 'z',
-];
+/// Draws allowed per requested ID in [`NanoIdGenerator::generate_batch_unique`]
+/// before giving up, plus a flat allowance for small batches
+const UNIQUE_DRAWS_PER_ID: usize = 4;
+const UNIQUE_EXTRA_DRAWS: usize = 16;
 
 /// A NanoID generator with customizable length and optional prefix support
-#[derive(Debug, Clone, Copy)]
-pub struct NanoIdGenerator;
+///
+/// The prefix, length and alphabet are fixed at construction, see
+/// [`Self::builder`]. Clones share an injected RNG.
+#[derive(Debug, Clone)]
+pub struct NanoIdGenerator {
+    alphabet: Alphabet,
+    prefix: Option<String>,
+    length: usize,
+    max_length: usize,
+    rng_mode: RngMode,
+    rng: Option<Arc<InjectedRng>>,
+}
 
 impl Default for NanoIdGenerator {
     #[inline]
@@ -21,18 +41,269 @@ impl Default for NanoIdGenerator {
 }
 
 impl NanoIdGenerator {
-    /// Creates a new NanoID generator
+    /// Longest length [`Self::try_generate`] accepts unless the builder
+    /// sets another
+    pub const DEFAULT_MAX_LENGTH: usize = 256;
+
+    /// Longest prefix, in bytes, [`Self::try_generate`] accepts
+    pub const MAX_PREFIX_LEN: usize = 64;
+
+    /// Creates a new NanoID generator using [`AlphabetPreset::Alphanumeric`],
+    /// without prefix and with the default length
     #[inline]
     pub const fn new() -> Self {
-        Self
+        Self::with_preset(AlphabetPreset::Alphanumeric)
+    }
+
+    /// Creates a generator drawing characters from a built-in alphabet
+    ///
+    /// ```
+    /// use gen_id::{AlphabetPreset, NanoIdGenerator};
+    ///
+    /// let id = NanoIdGenerator::with_preset(AlphabetPreset::NoLookalike).generate();
+    /// assert!(id.chars().all(|ch| AlphabetPreset::NoLookalike.contains(ch)));
+    /// ```
+    #[inline]
+    pub const fn with_preset(preset: AlphabetPreset) -> Self {
+        Self {
+            alphabet: Alphabet::preset(preset),
+            prefix: None,
+            length: DEFAULT_LENGTH,
+            max_length: Self::DEFAULT_MAX_LENGTH,
+            rng_mode: RngMode::Secure,
+            rng: None,
+        }
+    }
+
+    /// Creates a generator drawing characters from a custom alphabet
+    ///
+    /// Fails with an [`AlphabetError`] when `chars` has a non-ASCII
+    /// character, fewer than 2 or more than 256 characters, or a repeated
+    /// one, see [`Alphabet::new`].
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// let id = NanoIdGenerator::with_alphabet("ACGT").unwrap().generate();
+    /// assert!(id.chars().all(|ch| "ACGT".contains(ch)));
+    /// assert!(NanoIdGenerator::with_alphabet("ACGTA").is_err());
+    /// ```
+    pub fn with_alphabet(chars: &str) -> Result<Self, AlphabetError> {
+        Ok(Self::builder().alphabet(Alphabet::new(chars)?).build())
+    }
+
+    /// Start configuring a generator's prefix, length and alphabet
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// let generator = NanoIdGenerator::builder().prefix("order_").length(16).build();
+    /// let id = generator.generate();
+    /// assert!(id.starts_with("order_"));
+    /// assert_eq!(id.len(), 6 + 16);
+    /// ```
+    #[inline]
+    pub fn builder() -> NanoIdGeneratorBuilder {
+        NanoIdGeneratorBuilder::default()
+    }
+
+    /// Draw random bytes from `rng` instead of the OS-seeded default, e.g.
+    /// a mandated CSPRNG or a seeded RNG for golden tests
+    ///
+    /// Characters are still picked by masking and rejection, so every
+    /// character stays equally likely whatever the RNG. Clones share it,
+    /// behind a mutex taken once per ID. It takes precedence over the
+    /// [`RngMode`].
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    /// use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
+    ///
+    /// let generator = NanoIdGenerator::new().with_rng(ChaCha8Rng::seed_from_u64(42));
+    /// assert_eq!(generator.generate_with(Some("ord_"), None), "ord_XRTvrGk8Hvb6");
+    /// ```
+    #[inline]
+    pub fn with_rng<R: RngCore + Send + 'static>(mut self, rng: R) -> Self {
+        self.rng = Some(Arc::new(InjectedRng::new(rng)));
+        self
+    }
+
+    /// Creates a generator whose IDs follow from `seed`, for golden files
+    /// and other test fixtures
+    ///
+    /// **For tests only**: anyone who knows the seed can produce every ID.
+    /// The RNG is ChaCha8 seeded through rand_core's `seed_from_u64`, both of
+    /// which promise the same output on every platform and release, unlike
+    /// `SmallRng`. Clones continue the same sequence. The alphabet and
+    /// length are the defaults; use [`Self::with_rng`] with
+    /// [`Self::builder`] settings to seed other configurations.
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// let generator = NanoIdGenerator::seeded(42);
+    /// assert_eq!(generator.generate(), "XRTvrGk8Hvb6");
+    /// assert_eq!(generator.generate(), "8tnjIdMOvoQW");
+    /// assert_eq!(generator.generate(), "U2TaRqZcKJ7G");
+    /// ```
+    #[inline]
+    pub fn seeded(seed: u64) -> Self {
+        Self::new().with_rng(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Trade unguessability for speed with [`RngMode::Fast`]
+    ///
+    /// ```
+    /// use gen_id::{NanoIdGenerator, RngMode};
+    ///
+    /// // Correlation IDs for logs, never used as secrets
+    /// let generator = NanoIdGenerator::new().with_rng_mode(RngMode::Fast);
+    /// assert_eq!(generator.generate().len(), 12);
+    /// ```
+    #[inline]
+    pub fn with_rng_mode(mut self, mode: RngMode) -> Self {
+        self.rng_mode = mode;
+        self
+    }
+
+    /// Where random bytes come from unless an RNG was injected
+    #[inline]
+    pub const fn rng_mode(&self) -> RngMode {
+        self.rng_mode
+    }
+
+    /// The alphabet IDs are drawn from
+    #[inline]
+    pub const fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// The prefix every ID starts with, if any
+    #[inline]
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    /// Number of random characters after the prefix
+    #[inline]
+    pub const fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Longest length [`Self::try_generate`] accepts
+    #[inline]
+    pub const fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// Generates a single NanoID after checking the prefix and length
+    ///
+    /// `None` falls back to the configured prefix and length, which are
+    /// checked the same way. [`Self::generate_with`] stays permissive and
+    /// only debug-asserts the length.
+    ///
+    /// # Errors
+    ///
+    /// [`NanoIdError::ZeroLength`], [`NanoIdError::LengthTooLong`] above
+    /// [`Self::max_length`] (256 by default) and
+    /// [`NanoIdError::PrefixTooLong`] above [`Self::MAX_PREFIX_LEN`] bytes.
+    ///
+    /// ```
+    /// use gen_id::{NanoIdError, NanoIdGenerator};
+    ///
+    /// let generator = NanoIdGenerator::new();
+    /// assert_eq!(generator.try_generate(Some("ord_"), Some(16))?.len(), 4 + 16);
+    /// assert_eq!(generator.try_generate(None, Some(0)), Err(NanoIdError::ZeroLength));
+    /// # Ok::<(), NanoIdError>(())
+    /// ```
+    pub fn try_generate(
+        &self,
+        prefix: Option<&str>,
+        length: Option<usize>,
+    ) -> Result<String, NanoIdError> {
+        self.validate(prefix, length)?;
+        Ok(self.generate_with(prefix, length))
+    }
+
+    /// Generates a batch of NanoIDs, checking the prefix and length once
+    /// up front as [`Self::try_generate`] does
+    pub fn try_generate_batch(
+        &self,
+        count: usize,
+        prefix: Option<&str>,
+        length: Option<usize>,
+    ) -> Result<Vec<String>, NanoIdError> {
+        self.validate(prefix, length)?;
+        Ok(self.generate_batch_with(count, prefix, length))
+    }
+
+    pub(super) fn validate(
+        &self,
+        prefix: Option<&str>,
+        length: Option<usize>,
+    ) -> Result<(), NanoIdError> {
+        let length = length.unwrap_or(self.length);
+        if length == 0 {
+            return Err(NanoIdError::ZeroLength);
+        }
+        if length > self.max_length {
+            return Err(NanoIdError::LengthTooLong {
+                length,
+                max: self.max_length,
+            });
+        }
+        match prefix.or(self.prefix()) {
+            Some(prefix) if prefix.len() > Self::MAX_PREFIX_LEN => {
+                Err(NanoIdError::PrefixTooLong {
+                    len: prefix.len(),
+                    max: Self::MAX_PREFIX_LEN,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Generates a single NanoID with the configured prefix and length
+    #[inline]
+    pub fn generate(&self) -> String {
+        self.generate_with(None, None)
     }
 
-    /// Generates a single NanoID
+    /// Generates a batch of NanoIDs with the configured prefix and length
+    #[inline]
+    pub fn generate_batch(&self, count: usize) -> Vec<String> {
+        self.generate_batch_with(count, None, None)
+    }
+
+    /// Generates a NanoID of `N` characters on the stack, without allocating
+    ///
+    /// Draws from the configured alphabet and RNG like [`Self::generate`],
+    /// but ignores the prefix and length, which a fixed array cannot follow.
+    /// Custom alphabets are checked to be ASCII when built, so every
+    /// character fits one byte. `N` must not be 0.
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// let id = NanoIdGenerator::new().generate_fixed::<21>();
+    /// assert_eq!(id.len(), 21);
+    /// assert!(id.chars().all(|ch| ch.is_ascii_alphanumeric()));
+    /// ```
+    pub fn generate_fixed<const N: usize>(&self) -> NanoId<N> {
+        const { assert!(N > 0, "a fixed NanoID needs at least one character") };
+        let mut bytes = [0u8; N];
+        self.with_fill(|fill| sample::sample_bytes(&mut bytes, self.alphabet.chars(), fill));
+        NanoId::from_ascii(bytes)
+    }
+
+    /// Generates a single NanoID, overriding the prefix or length for this
+    /// call
     ///
     /// # Arguments
     ///
-    /// * `prefix` - Optional prefix to prepend to the NanoID
-    /// * `length` - Optional custom length (defaults to 12 if None)
+    /// * `prefix` - Prefix to prepend, or `None` for the configured one
+    /// * `length` - Custom length, or `None` for the configured one (12 by
+    ///   default)
     ///
     /// # Examples
     ///
@@ -40,37 +311,34 @@ impl NanoIdGenerator {
     /// use gen_id::NanoIdGenerator;
     ///
     /// let generator = NanoIdGenerator::new();
-    /// let id = generator.generate(None, None);
+    /// let id = generator.generate_with(None, None);
     /// assert_eq!(id.len(), 12); // default length
     ///
-    /// let id_with_prefix = generator.generate(Some("user_"), None);
+    /// let id_with_prefix = generator.generate_with(Some("user_"), None);
     /// assert!(id_with_prefix.starts_with("user_"));
     ///
-    /// let id_custom_length = generator.generate(None, Some(16));
+    /// let id_custom_length = generator.generate_with(None, Some(16));
     /// assert_eq!(id_custom_length.len(), 16);
     ///
-    /// let id_both = generator.generate(Some("item_"), Some(8));
+    /// let id_both = generator.generate_with(Some("item_"), Some(8));
     /// assert!(id_both.starts_with("item_"));
     /// assert_eq!(id_both.len(), 5 + 8); // "item_" + 8
     /// ```
     #[inline]
-    pub fn generate(&self, prefix: Option<&str>, length: Option<usize>) -> String {
-        let len = length.unwrap_or(DEFAULT_LENGTH);
-        let nanoid = nanoid::format(nanoid::rngs::default, &ALPHABET, len);
-
-        match prefix {
-            Some(p) => format!("{}{}", p, nanoid),
-            None => nanoid,
-        }
+    pub fn generate_with(&self, prefix: Option<&str>, length: Option<usize>) -> String {
+        self.with_fill(|fill| self.generate_from(prefix, length, fill))
     }
 
-    /// Generates a batch of NanoIDs
+    /// Generates a batch of NanoIDs, overriding the prefix or length for
+    /// this call
     ///
     /// # Arguments
     ///
     /// * `count` - The number of NanoIDs to generate
-    /// * `prefix` - Optional prefix to prepend to each NanoID
-    /// * `length` - Optional custom length (defaults to 12 if None)
+    /// * `prefix` - Prefix to prepend to each NanoID, or `None` for the
+    ///   configured one
+    /// * `length` - Custom length, or `None` for the configured one (12 by
+    ///   default)
     ///
     /// # Examples
     ///
@@ -78,24 +346,280 @@ impl NanoIdGenerator {
     /// use gen_id::NanoIdGenerator;
     ///
     /// let generator = NanoIdGenerator::new();
-    /// let ids = generator.generate_batch(5, None, None);
+    /// let ids = generator.generate_batch_with(5, None, None);
     /// assert_eq!(ids.len(), 5);
     ///
-    /// let ids_with_prefix = generator.generate_batch(3, Some("order_"), None);
+    /// let ids_with_prefix = generator.generate_batch_with(3, Some("order_"), None);
     /// assert_eq!(ids_with_prefix.len(), 3);
     /// assert!(ids_with_prefix[0].starts_with("order_"));
     ///
-    /// let ids_custom_length = generator.generate_batch(3, None, Some(16));
+    /// let ids_custom_length = generator.generate_batch_with(3, None, Some(16));
     /// assert_eq!(ids_custom_length[0].len(), 16);
     /// ```
     #[inline]
-    pub fn generate_batch(
+    pub fn generate_batch_with(
         &self,
         count: usize,
         prefix: Option<&str>,
         length: Option<usize>,
     ) -> Vec<String> {
-        (0..count).map(|_| self.generate(prefix, length)).collect()
+        self.iter(prefix, length).take(count).collect()
+    }
+
+    /// Append a NanoID to `out`, the same characters [`Self::generate_with`]
+    /// would return
+    ///
+    /// Builds composite keys without an intermediate `String`: with room in
+    /// `out`, nothing is allocated. `None` falls back to the configured
+    /// prefix and length.
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// let generator = NanoIdGenerator::new();
+    /// let mut key = String::from("tenant-7:");
+    /// generator.generate_into(Some("ord_"), None, &mut key);
+    /// assert!(key.starts_with("tenant-7:ord_"));
+    /// assert_eq!(key.len(), 9 + 4 + 12);
+    /// ```
+    #[inline]
+    pub fn generate_into(&self, prefix: Option<&str>, length: Option<usize>, out: &mut String) {
+        self.with_fill(|fill| self.push_from(prefix, length, fill, out));
+    }
+
+    /// Generate a batch of NanoIDs into `out`, replacing its contents
+    ///
+    /// The vector is cleared and refilled, so its capacity is reused and no
+    /// reallocation happens when it already holds `count` entries. `None`
+    /// falls back to the configured prefix and length.
+    #[inline]
+    pub fn generate_batch_into(
+        &self,
+        count: usize,
+        prefix: Option<&str>,
+        length: Option<usize>,
+        out: &mut Vec<String>,
+    ) {
+        out.clear();
+        out.reserve(count);
+        out.extend(self.iter(prefix, length).take(count));
+    }
+
+    /// Endless iterator of NanoIDs, generated lazily
+    ///
+    /// Streams IDs into files or channels without building a `Vec`. The
+    /// iterator owns a clone of the generator, so it is `Send + 'static`
+    /// and can move to another thread or a blocking task; it shares an
+    /// injected RNG. `None` falls back to the configured prefix and length.
+    ///
+    /// ```no_run
+    /// use std::{
+    ///     fs::File,
+    ///     io::{BufWriter, Write},
+    /// };
+    ///
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// let generator = NanoIdGenerator::builder().prefix("ord_").length(16).build();
+    /// let mut out = BufWriter::new(File::create("ids.txt")?);
+    /// for id in generator.iter(None, None).take(1_000_000) {
+    ///     writeln!(out, "{id}")?;
+    /// }
+    /// out.flush()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[inline]
+    pub fn iter(
+        &self,
+        prefix: Option<&str>,
+        length: Option<usize>,
+    ) -> impl Iterator<Item = String> + Send + use<> {
+        let generator = self.clone();
+        let prefix = prefix.map(str::to_owned);
+        std::iter::repeat_with(move || generator.generate_with(prefix.as_deref(), length))
+    }
+
+    /// Generates `count` distinct NanoIDs, redrawing duplicates
+    ///
+    /// Short lengths for human-facing codes collide quickly: 128 random
+    /// two-character hex IDs almost surely repeat one, which
+    /// [`Self::generate_batch_with`] would return as is. `None` falls back
+    /// to the configured prefix and length.
+    ///
+    /// # Performance
+    ///
+    /// Requests above half the keyspace (`alphabet_len ^ length`) are
+    /// rejected up front, so every draw is new with probability at least
+    /// 1/2 and a batch takes under 1.4 draws per ID on average. The IDs are
+    /// kept in a `HashSet`, which costs one hash per draw and memory for
+    /// the whole batch, and come out in no particular order. Draws stop
+    /// after `4 * count + 16`, which only a repeating RNG reaches.
+    ///
+    /// # Errors
+    ///
+    /// [`NanoIdError::KeyspaceTooSmall`] when `count` exceeds half the
+    /// keyspace, and [`NanoIdError::RetriesExhausted`] when the draw budget
+    /// runs out.
+    ///
+    /// ```
+    /// use gen_id::{AlphabetPreset, NanoIdGenerator};
+    ///
+    /// let generator = NanoIdGenerator::builder().alphabet(AlphabetPreset::NoLookalike).build();
+    /// let codes = generator.generate_batch_unique(1_000, Some("CPN-"), Some(6))?;
+    /// assert_eq!(codes.len(), 1_000);
+    ///
+    /// // 57^2 = 3249 possible codes, at most 1624 unique ones are handed out
+    /// assert!(generator.generate_batch_unique(2_000, None, Some(2)).is_err());
+    /// # Ok::<(), gen_id::NanoIdError>(())
+    /// ```
+    pub fn generate_batch_unique(
+        &self,
+        count: usize,
+        prefix: Option<&str>,
+        length: Option<usize>,
+    ) -> Result<Vec<String>, NanoIdError> {
+        let len = length.unwrap_or(self.length);
+        let keyspace =
+            (self.alphabet.len() as u128).checked_pow(len.try_into().unwrap_or(u32::MAX));
+        if let Some(keyspace) = keyspace
+            && count as u128 > keyspace / 2
+        {
+            return Err(NanoIdError::KeyspaceTooSmall {
+                count,
+                length: len,
+                keyspace,
+            });
+        }
+
+        let max_attempts = count
+            .saturating_mul(UNIQUE_DRAWS_PER_ID)
+            .saturating_add(UNIQUE_EXTRA_DRAWS);
+        self.with_fill(|fill| {
+            let mut ids = HashSet::with_capacity(count);
+            let mut attempts = 0;
+            while ids.len() < count {
+                if attempts == max_attempts {
+                    return Err(NanoIdError::RetriesExhausted {
+                        count,
+                        unique: ids.len(),
+                        attempts,
+                    });
+                }
+                attempts += 1;
+                ids.insert(self.generate_from(prefix, length, &mut *fill));
+            }
+            Ok(ids.into_iter().collect())
+        })
+    }
+
+    #[inline]
+    fn generate_from(
+        &self,
+        prefix: Option<&str>,
+        length: Option<usize>,
+        fill: impl FnMut(&mut [u8]),
+    ) -> String {
+        let mut id = String::new();
+        self.push_from(prefix, length, fill, &mut id);
+        id
+    }
+
+    /// Append an ID to `out`, reserving room for it once
+    #[inline]
+    fn push_from(
+        &self,
+        prefix: Option<&str>,
+        length: Option<usize>,
+        fill: impl FnMut(&mut [u8]),
+        out: &mut String,
+    ) {
+        let len = length.unwrap_or(self.length);
+        let prefix = prefix.or(self.prefix.as_deref()).unwrap_or_default();
+        debug_assert!(
+            (1..=self.max_length).contains(&len),
+            "NanoID length {len} outside 1..={}, use try_generate to get an error",
+            self.max_length
+        );
+
+        out.reserve(prefix.len() + len);
+        out.push_str(prefix);
+        sample::sample(out, self.alphabet.chars(), len, fill);
+    }
+
+    /// Run `f` with the byte source, locking an injected RNG once
+    #[inline]
+    pub(super) fn with_fill<T>(&self, f: impl FnOnce(&mut dyn FnMut(&mut [u8])) -> T) -> T {
+        match &self.rng {
+            Some(rng) => {
+                let mut rng = rng.0.lock().unwrap_or_else(PoisonError::into_inner);
+                f(&mut |bytes| rng.fill_bytes(bytes))
+            }
+            None => f(&mut |bytes| self.rng_mode.fill(bytes)),
+        }
+    }
+
+    /// Whether IDs come from a caller's RNG rather than the [`RngMode`]
+    #[inline]
+    pub(super) fn has_injected_rng(&self) -> bool {
+        self.rng.is_some()
+    }
+}
+
+/// Builder for a [`NanoIdGenerator`], from [`NanoIdGenerator::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct NanoIdGeneratorBuilder {
+    generator: NanoIdGenerator,
+}
+
+impl NanoIdGeneratorBuilder {
+    /// Prepend `prefix` to every ID
+    #[inline]
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.generator.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Number of random characters after the prefix, 12 by default
+    #[inline]
+    pub fn length(mut self, length: usize) -> Self {
+        self.generator.length = length;
+        self
+    }
+
+    /// Longest length [`NanoIdGenerator::try_generate`] accepts, 256 by
+    /// default
+    #[inline]
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.generator.max_length = max_length;
+        self
+    }
+
+    /// Draw characters from a preset or validated custom alphabet,
+    /// alphanumeric by default
+    #[inline]
+    pub fn alphabet(mut self, alphabet: impl Into<Alphabet>) -> Self {
+        self.generator.alphabet = alphabet.into();
+        self
+    }
+
+    /// Secure by default, see [`NanoIdGenerator::with_rng_mode`]
+    #[inline]
+    pub fn rng_mode(mut self, mode: RngMode) -> Self {
+        self.generator.rng_mode = mode;
+        self
+    }
+
+    /// Draw random bytes from `rng`, see [`NanoIdGenerator::with_rng`]
+    #[inline]
+    pub fn rng<R: RngCore + Send + 'static>(mut self, rng: R) -> Self {
+        self.generator = self.generator.with_rng(rng);
+        self
+    }
+
+    /// Finish the configuration
+    #[inline]
+    pub fn build(self) -> NanoIdGenerator {
+        self.generator
     }
 }
 
@@ -104,24 +628,41 @@ mod tests {
     use super::*;
     use std::collections::HashSet;
 
+    #[test]
+    fn test_seeded_sequence() {
+        let first = NanoIdGenerator::seeded(42).generate_batch(100);
+        assert_eq!(first, NanoIdGenerator::seeded(42).generate_batch(100));
+        assert_ne!(first, NanoIdGenerator::seeded(43).generate_batch(100));
+
+        // Clones continue one sequence rather than repeating it
+        let generator = NanoIdGenerator::seeded(42);
+        let clone = generator.clone();
+        assert_eq!(generator.generate(), first[0]);
+        assert_eq!(clone.generate(), first[1]);
+        assert_eq!(
+            generator.generate_with(Some("fx_"), None),
+            format!("fx_{}", first[2])
+        );
+    }
+
     #[test]
     fn test_default_length() {
         let generator = NanoIdGenerator::new();
-        let id = generator.generate(None, None);
+        let id = generator.generate_with(None, None);
         assert_eq!(id.len(), DEFAULT_LENGTH);
     }
 
     #[test]
     fn test_custom_length() {
         let generator = NanoIdGenerator::new();
-        let id = generator.generate(None, Some(20));
+        let id = generator.generate_with(None, Some(20));
         assert_eq!(id.len(), 20);
     }
 
     #[test]
     fn test_with_prefix() {
         let generator = NanoIdGenerator::new();
-        let id = generator.generate(Some("test_"), None);
+        let id = generator.generate_with(Some("test_"), None);
         assert!(id.starts_with("test_"));
         assert_eq!(id.len(), 5 + DEFAULT_LENGTH); // prefix + default length
     }
@@ -129,7 +670,7 @@ mod tests {
     #[test]
     fn test_alphanumeric_characters() {
         let generator = NanoIdGenerator::new();
-        let id = generator.generate(None, None);
+        let id = generator.generate_with(None, None);
 
         // Check that all characters are alphanumeric
         for ch in id.chars() {
@@ -137,11 +678,214 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_presets_only_use_their_characters() {
+        for alphabet in AlphabetPreset::ALL {
+            let generator = NanoIdGenerator::with_preset(alphabet);
+            assert_eq!(*generator.alphabet(), alphabet.into());
+            for id in generator.generate_batch_with(200, None, Some(32)) {
+                assert!(
+                    id.chars().all(|ch| alphabet.contains(ch)),
+                    "{id} outside {alphabet:?}"
+                );
+            }
+        }
+    }
+
+    /// Yields `0, 1, 2, ...`, wrapping at 256
+    struct CountingRng(u8);
+
+    impl RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_be_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_be_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                *byte = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_injected_rng() {
+        let generator = NanoIdGenerator::with_preset(AlphabetPreset::Hex).with_rng(CountingRng(0));
+        assert_eq!(generator.generate_with(None, Some(4)), "0123");
+
+        // Clones continue the same sequence. Each ID draws a block of 6
+        // bytes for 4 characters, so the next starts after the unused tail
+        assert_eq!(generator.clone().generate_with(None, Some(4)), "6789");
+        assert_eq!(
+            generator.generate_batch_with(2, None, Some(4)),
+            ["cdef", "2345"]
+        );
+
+        // An injected RNG wins over the mode
+        let generator = NanoIdGenerator::with_preset(AlphabetPreset::Hex)
+            .with_rng_mode(RngMode::Fast)
+            .with_rng(CountingRng(0));
+        assert_eq!(generator.generate_with(None, Some(4)), "0123");
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        // 5 characters mask bytes to 0..8 and reject 5, 6 and 7
+        let generator = NanoIdGenerator::with_alphabet("ACGTX")
+            .unwrap()
+            .with_rng(CountingRng(0));
+        assert_eq!(generator.generate_with(None, Some(6)), "ACGTXA");
+        assert_eq!(generator.alphabet().as_preset(), None);
+
+        assert_eq!(
+            NanoIdGenerator::with_alphabet("ACGTA").unwrap_err(),
+            AlphabetError::Duplicate { ch: 'A', index: 4 }
+        );
+        assert!(NanoIdGenerator::with_alphabet("A").is_err());
+    }
+
+    #[test]
+    fn test_injected_rng_stays_unbiased() {
+        // Bytes past the 62-character alphabet are rejected, not wrapped
+        let generator = NanoIdGenerator::new().with_rng(CountingRng(60));
+        let id = generator.generate_with(None, Some(4));
+        assert_eq!(id, "yz01");
+    }
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        use rand_chacha::{ChaCha8Rng, rand_core::SeedableRng};
+
+        let generate = || {
+            NanoIdGenerator::new()
+                .with_rng(ChaCha8Rng::seed_from_u64(7))
+                .generate_batch_with(3, Some("ord_"), Some(16))
+        };
+        assert_eq!(generate(), generate());
+    }
+
+    /// Yields `0, 1, ..., period - 1` over and over
+    struct CyclingRng {
+        next: u8,
+        period: u8,
+    }
+
+    impl RngCore for CyclingRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_be_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_be_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                *byte = self.next;
+                self.next = (self.next + 1) % self.period;
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_unique_removes_duplicates() {
+        // 128 of the 256 two-character hex IDs: a plain batch repeats some
+        // with probability 1 - e^-31.75
+        let generator = NanoIdGenerator::builder()
+            .alphabet(AlphabetPreset::Hex)
+            .length(2)
+            .build();
+        let plain: HashSet<_> = generator.generate_batch(128).into_iter().collect();
+        assert!(plain.len() < 128);
+
+        let ids = generator
+            .generate_batch_unique(128, Some("c-"), None)
+            .unwrap();
+        assert_eq!(ids.len(), 128);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 128);
+        assert!(ids.iter().all(|id| id.starts_with("c-") && id.len() == 4));
+
+        assert_eq!(generator.generate_batch_unique(0, None, None), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_batch_unique_rejects_exhausted_keyspace() {
+        let generator = NanoIdGenerator::with_preset(AlphabetPreset::Hex);
+        let err = generator
+            .generate_batch_unique(129, None, Some(2))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            NanoIdError::KeyspaceTooSmall {
+                count: 129,
+                length: 2,
+                keyspace: 256,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "129 unique IDs exceed half of the 256 possible IDs of 2 characters, use a longer \
+             length or larger alphabet"
+        );
+
+        // A zero length has a single possible ID
+        assert!(generator.generate_batch_unique(1, None, Some(0)).is_err());
+        // Keyspaces beyond u128 are never too small
+        assert!(generator.generate_batch_unique(3, None, Some(64)).is_ok());
+    }
+
+    #[test]
+    fn test_batch_unique_gives_up_on_repeating_rng() {
+        // Each one-character hex ID draws two bytes, so a period of 6 only
+        // ever produces the IDs 0, 2 and 4
+        let generator = NanoIdGenerator::builder()
+            .alphabet(AlphabetPreset::Hex)
+            .length(1)
+            .rng(CyclingRng { next: 0, period: 6 })
+            .build();
+        let mut ids = generator.generate_batch_unique(3, None, None).unwrap();
+        ids.sort();
+        assert_eq!(ids, ["0", "2", "4"]);
+
+        let err = generator.generate_batch_unique(5, None, None).unwrap_err();
+        assert_eq!(
+            err,
+            NanoIdError::RetriesExhausted {
+                count: 5,
+                unique: 3,
+                attempts: 36,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "only 3 of 5 IDs were unique after 36 draws, is the RNG repeating?"
+        );
+    }
+
+    #[test]
+    fn test_no_lookalike_ids_exclude_confusables() {
+        let generator = NanoIdGenerator::with_preset(AlphabetPreset::NoLookalike);
+        let ids = generator.generate_batch_with(500, None, Some(32)).concat();
+        assert!(!ids.contains(['0', 'O', '1', 'l', 'I']), "{ids}");
+    }
+
     #[test]
     fn test_batch_generation() {
         let generator = NanoIdGenerator::new();
         let batch_size = 10;
-        let ids = generator.generate_batch(batch_size, None, None);
+        let ids = generator.generate_batch_with(batch_size, None, None);
 
         assert_eq!(ids.len(), batch_size);
 
@@ -153,22 +897,39 @@ mod tests {
 
     #[test]
     fn test_batch_uniqueness() {
-        let generator = NanoIdGenerator::new();
-        let ids = generator.generate_batch(100, None, None);
+        for mode in [RngMode::Secure, RngMode::Fast] {
+            let generator = NanoIdGenerator::new().with_rng_mode(mode);
+            let ids = generator.generate_batch_with(10_000, None, None);
 
-        // Convert to HashSet to check uniqueness
-        let unique_ids: HashSet<_> = ids.iter().collect();
-        assert_eq!(
-            unique_ids.len(),
-            ids.len(),
-            "Generated IDs should be unique"
-        );
+            // Convert to HashSet to check uniqueness
+            let unique_ids: HashSet<_> = ids.iter().collect();
+            assert_eq!(unique_ids.len(), ids.len(), "{mode:?} IDs should be unique");
+        }
+    }
+
+    #[test]
+    fn test_fast_mode_across_threads() {
+        // Each thread seeds its own SmallRng, so threads never repeat
+        // each other's sequence
+        let generator = NanoIdGenerator::builder().rng_mode(RngMode::Fast).build();
+        assert_eq!(generator.rng_mode(), RngMode::Fast);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let generator = generator.clone();
+                std::thread::spawn(move || generator.generate_batch(2_500))
+            })
+            .collect();
+        let ids: HashSet<_> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(ids.len(), 10_000);
     }
 
     #[test]
     fn test_batch_with_prefix() {
         let generator = NanoIdGenerator::new();
-        let ids = generator.generate_batch(5, Some("item_"), Some(8));
+        let ids = generator.generate_batch_with(5, Some("item_"), Some(8));
 
         assert_eq!(ids.len(), 5);
         for id in ids {
@@ -180,31 +941,250 @@ mod tests {
     #[test]
     fn test_randomness() {
         let generator = NanoIdGenerator::new();
-        let id1 = generator.generate(None, None);
-        let id2 = generator.generate(None, None);
+        let id1 = generator.generate_with(None, None);
+        let id2 = generator.generate_with(None, None);
 
         // With high probability, two generated IDs should be different
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_builder_stores_configuration() {
+        let generator = NanoIdGenerator::builder()
+            .prefix("order_")
+            .length(16)
+            .alphabet(AlphabetPreset::Hex)
+            .build();
+        assert_eq!(generator.prefix(), Some("order_"));
+        assert_eq!(generator.length(), 16);
+        assert_eq!(generator.alphabet().as_preset(), Some(AlphabetPreset::Hex));
+
+        let id = generator.generate();
+        assert_eq!(id.len(), 6 + 16);
+        let random = id.strip_prefix("order_").unwrap();
+        assert!(
+            random.chars().all(|ch| AlphabetPreset::Hex.contains(ch)),
+            "{id}"
+        );
+
+        let ids = generator.generate_batch(5);
+        assert_eq!(ids.len(), 5);
+        assert!(
+            ids.iter()
+                .all(|id| id.starts_with("order_") && id.len() == 22)
+        );
+    }
+
+    #[test]
+    fn test_per_call_options_override_configuration() {
+        let generator = NanoIdGenerator::builder()
+            .prefix("order_")
+            .length(16)
+            .build();
+        assert_eq!(generator.generate_with(None, None).len(), 6 + 16);
+
+        let id = generator.generate_with(Some("item_"), None);
+        assert!(id.starts_with("item_"));
+        assert_eq!(id.len(), 5 + 16);
+
+        let id = generator.generate_with(None, Some(4));
+        assert!(id.starts_with("order_"));
+        assert_eq!(id.len(), 6 + 4);
+    }
+
+    #[test]
+    fn test_builder_rng() {
+        let generator = NanoIdGenerator::builder()
+            .alphabet(AlphabetPreset::Hex)
+            .length(4)
+            .rng(CountingRng(0))
+            .build();
+        assert_eq!(generator.generate(), "0123");
+    }
+
+    #[test]
+    fn test_iter_is_lazy_and_configured() {
+        let generator = NanoIdGenerator::builder()
+            .prefix("ord_")
+            .length(6)
+            .alphabet(AlphabetPreset::Hex)
+            .build();
+        let ids: Vec<_> = generator.iter(None, None).take(100).collect();
+        assert_eq!(ids.len(), 100);
+        for id in &ids {
+            assert_eq!(id.len(), 4 + 6);
+            assert!(
+                id[4..].chars().all(|ch| AlphabetPreset::Hex.contains(ch)),
+                "{id}"
+            );
+        }
+
+        let id = generator.iter(Some("x_"), Some(3)).next().unwrap();
+        assert!(id.starts_with("x_"));
+        assert_eq!(id.len(), 2 + 3);
+    }
+
+    #[test]
+    fn test_iter_moves_to_another_thread() {
+        fn assert_send_static<T: Send + 'static>(_: &T) {}
+
+        let generator = NanoIdGenerator::with_preset(AlphabetPreset::Hex).with_rng(CountingRng(0));
+        let iter = generator.iter(None, Some(4));
+        assert_send_static(&iter);
+        drop(generator);
+
+        let ids: Vec<String> = std::thread::spawn(move || iter.take(2).collect())
+            .join()
+            .unwrap();
+        assert_eq!(ids, ["0123", "6789"]);
+    }
+
+    #[test]
+    fn test_generate_into_matches_generate() {
+        let seeded = || {
+            NanoIdGenerator::builder()
+                .prefix("ord_")
+                .rng(CountingRng(7))
+                .build()
+        };
+
+        let mut key = String::from("tenant:");
+        seeded().generate_into(None, Some(10), &mut key);
+        assert_eq!(
+            key,
+            format!("tenant:{}", seeded().generate_with(None, Some(10)))
+        );
+
+        let mut key = String::new();
+        seeded().generate_into(Some("x_"), None, &mut key);
+        assert_eq!(key, seeded().generate_with(Some("x_"), None));
+    }
+
+    #[test]
+    fn test_generate_batch_into_reuses_vec() {
+        let generator = NanoIdGenerator::new();
+        let mut ids = vec!["stale".to_string(); 3];
+        generator.generate_batch_into(5, Some("b_"), Some(8), &mut ids);
+        assert_eq!(ids.len(), 5);
+        assert!(ids.iter().all(|id| id.starts_with("b_") && id.len() == 10));
+
+        let capacity = ids.capacity();
+        generator.generate_batch_into(5, None, None, &mut ids);
+        assert_eq!(ids.capacity(), capacity);
+        assert!(ids.iter().all(|id| id.len() == 12));
+
+        let seeded = || NanoIdGenerator::new().with_rng(CountingRng(0));
+        let mut ids = Vec::new();
+        seeded().generate_batch_into(3, None, None, &mut ids);
+        assert_eq!(ids, seeded().generate_batch(3));
+    }
+
+    #[test]
+    fn test_try_generate_length_bounds() {
+        let generator = NanoIdGenerator::new();
+        assert_eq!(generator.try_generate(None, Some(1)).unwrap().len(), 1);
+        assert_eq!(generator.try_generate(None, Some(256)).unwrap().len(), 256);
+        assert_eq!(generator.try_generate(None, None).unwrap().len(), 12);
+
+        assert_eq!(
+            generator.try_generate(None, Some(0)),
+            Err(NanoIdError::ZeroLength)
+        );
+        let err = generator.try_generate(None, Some(257)).unwrap_err();
+        assert_eq!(
+            err,
+            NanoIdError::LengthTooLong {
+                length: 257,
+                max: 256,
+            }
+        );
+        assert_eq!(err.to_string(), "length 257 exceeds the maximum of 256");
+        assert!(generator.try_generate(None, Some(10_000_000)).is_err());
+    }
+
+    #[test]
+    fn test_try_generate_custom_max_length() {
+        let generator = NanoIdGenerator::builder().length(40).max_length(32).build();
+        assert_eq!(generator.max_length(), 32);
+        assert_eq!(
+            generator.try_generate(None, None),
+            Err(NanoIdError::LengthTooLong {
+                length: 40,
+                max: 32
+            })
+        );
+        assert_eq!(generator.try_generate(None, Some(32)).unwrap().len(), 32);
+
+        let generator = NanoIdGenerator::builder().length(0).build();
+        assert_eq!(
+            generator.try_generate(None, None),
+            Err(NanoIdError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn test_try_generate_prefix_bounds() {
+        let generator = NanoIdGenerator::new();
+        let prefix = "p".repeat(NanoIdGenerator::MAX_PREFIX_LEN);
+        let id = generator.try_generate(Some(&prefix), None).unwrap();
+        assert_eq!(id.len(), 64 + 12);
+
+        let prefix = "p".repeat(NanoIdGenerator::MAX_PREFIX_LEN + 1);
+        let err = generator.try_generate(Some(&prefix), None).unwrap_err();
+        assert_eq!(err, NanoIdError::PrefixTooLong { len: 65, max: 64 });
+        assert_eq!(
+            err.to_string(),
+            "prefix is 65 bytes long, the maximum is 64"
+        );
+
+        // The configured prefix is checked too
+        let generator = NanoIdGenerator::builder().prefix(prefix).build();
+        assert!(generator.try_generate(None, None).is_err());
+        assert!(generator.try_generate(Some("ok_"), None).is_ok());
+    }
+
+    #[test]
+    fn test_try_generate_batch_validates_once() {
+        let generator = NanoIdGenerator::new();
+        let ids = generator
+            .try_generate_batch(4, Some("b_"), Some(6))
+            .unwrap();
+        assert_eq!(ids.len(), 4);
+        assert!(ids.iter().all(|id| id.len() == 2 + 6));
+
+        assert_eq!(
+            generator.try_generate_batch(4, None, Some(0)),
+            Err(NanoIdError::ZeroLength)
+        );
+        // Rejected even when no ID would be generated
+        assert!(generator.try_generate_batch(0, None, Some(257)).is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "use try_generate")]
+    fn test_generate_debug_asserts_length() {
+        NanoIdGenerator::new().generate_with(None, Some(0));
+    }
+
     #[test]
     fn test_runtime_prefix_and_length() {
         let generator = NanoIdGenerator::new();
 
         // Same generator, different prefixes
-        let user_id = generator.generate(Some("user_"), None);
-        let order_id = generator.generate(Some("order_"), None);
+        let user_id = generator.generate_with(Some("user_"), None);
+        let order_id = generator.generate_with(Some("order_"), None);
         assert!(user_id.starts_with("user_"));
         assert!(order_id.starts_with("order_"));
 
         // Same generator, different lengths
-        let short_id = generator.generate(None, Some(8));
-        let long_id = generator.generate(None, Some(20));
+        let short_id = generator.generate_with(None, Some(8));
+        let long_id = generator.generate_with(None, Some(20));
         assert_eq!(short_id.len(), 8);
         assert_eq!(long_id.len(), 20);
 
         // Combine both
-        let custom_id = generator.generate(Some("item_"), Some(16));
+        let custom_id = generator.generate_with(Some("item_"), Some(16));
         assert!(custom_id.starts_with("item_"));
         assert_eq!(custom_id.len(), 5 + 16);
     }
@@ -1,3 +1,17 @@
+mod alphabet;
+mod checksum;
+mod collision;
+mod config;
+mod error;
+mod fixed;
 mod generator;
+mod rng;
+mod sample;
+mod sortable;
 
-pub use generator::NanoIdGenerator;
+pub use alphabet::{Alphabet, AlphabetPreset};
+pub use config::NanoIdConfig;
+pub use error::{AlphabetError, NanoIdError};
+pub use fixed::NanoId;
+pub use generator::{NanoIdGenerator, NanoIdGeneratorBuilder};
+pub use rng::RngMode;
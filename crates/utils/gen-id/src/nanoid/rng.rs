@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+
+use rand::{RngCore, SeedableRng, rngs::SmallRng};
+
+use super::sample;
+
+/// Where a [`NanoIdGenerator`](super::NanoIdGenerator) without an injected
+/// RNG gets its random bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum RngMode {
+    /// rand's thread-local CSPRNG, ChaCha12 reseeded from the OS
+    ///
+    /// IDs are unguessable and safe as tokens.
+    #[default]
+    Secure,
+    /// A thread-local `SmallRng` (Xoshiro256++) seeded from the OS RNG when
+    /// a thread first uses it
+    ///
+    /// Skips the ChaCha rounds and reseeding, for IDs that only need to be
+    /// unique such as log correlation tokens. Compare both with the
+    /// `nanoid` bench before switching. Its output is predictable from a few
+    /// observed IDs, so never use it for secrets, session IDs, reset links
+    /// or anything else that must not be guessed.
+    Fast,
+}
+
+impl RngMode {
+    /// Fill `bytes` from this mode's thread-local RNG
+    #[inline]
+    pub(crate) fn fill(self, bytes: &mut [u8]) {
+        match self {
+            Self::Secure => sample::thread_fill(bytes),
+            Self::Fast => fast_fill(bytes),
+        }
+    }
+}
+
+thread_local! {
+    static FAST_RNG: RefCell<SmallRng> = RefCell::new(SmallRng::from_os_rng());
+}
+
+fn fast_fill(bytes: &mut [u8]) {
+    FAST_RNG.with_borrow_mut(|rng| rng.fill_bytes(bytes));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_rng_is_per_thread() {
+        let draw = || {
+            let mut bytes = [0u8; 32];
+            RngMode::Fast.fill(&mut bytes);
+            bytes
+        };
+        let here = draw();
+        let there = std::thread::spawn(draw).join().unwrap();
+        assert_ne!(here, there);
+        assert_ne!(here, draw());
+    }
+}
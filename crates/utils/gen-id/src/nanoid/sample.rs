@@ -0,0 +1,192 @@
+use std::{fmt, sync::Mutex};
+
+use rand_core::RngCore;
+
+/// Largest number of random bytes drawn at once
+const MAX_STEP: usize = 128;
+
+/// Append `len` characters drawn uniformly from `alphabet` to `id`
+///
+/// Each random byte is masked down to the smallest `2^k - 1` covering the
+/// alphabet, and bytes that land past its end are dropped, as in the
+/// reference NanoID algorithm. `byte % alphabet.len()` would instead favour
+/// the first `256 % len` characters whenever the size does not divide 256.
+/// `fill` is called again until enough bytes were accepted.
+///
+/// Bytes are drawn through a stack buffer, so nothing is allocated beyond
+/// growing `id`.
+#[inline]
+pub(crate) fn sample(id: &mut String, alphabet: &[char], len: usize, fill: impl FnMut(&mut [u8])) {
+    sample_with(alphabet, len, fill, |ch| id.push(ch));
+}
+
+/// Fill `out` with characters drawn as [`sample`] does, one byte each
+///
+/// Alphabets only hold ASCII, which [`Alphabet`](super::Alphabet) checks on
+/// construction, so every byte written is an ASCII character.
+pub(crate) fn sample_bytes(out: &mut [u8], alphabet: &[char], fill: impl FnMut(&mut [u8])) {
+    let mut next = out.iter_mut();
+    sample_with(alphabet, next.len(), fill, |ch| {
+        debug_assert!(ch.is_ascii(), "alphabet character {ch:?} is not ASCII");
+        if let Some(byte) = next.next() {
+            *byte = ch as u8;
+        }
+    });
+}
+
+/// Call `push` with `len` characters drawn uniformly from `alphabet`
+fn sample_with(
+    alphabet: &[char],
+    len: usize,
+    mut fill: impl FnMut(&mut [u8]),
+    mut push: impl FnMut(char),
+) {
+    debug_assert!(
+        (2..=256).contains(&alphabet.len()),
+        "alphabet must have 2 to 256 characters"
+    );
+    if len == 0 {
+        return;
+    }
+
+    let mask = alphabet.len().next_power_of_two() - 1;
+    // 1.6 * mask * len / size bytes, enough that one round usually suffices
+    let step = (8 * mask * len).div_ceil(5 * alphabet.len()).min(MAX_STEP);
+    let mut buf = [0u8; MAX_STEP];
+    let bytes = &mut buf[..step];
+    let mut remaining = len;
+    loop {
+        fill(bytes);
+        for &byte in bytes.iter() {
+            if let Some(&ch) = alphabet.get(usize::from(byte) & mask) {
+                push(ch);
+                remaining -= 1;
+                if remaining == 0 {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// A caller's RNG behind a lock, shared between generator clones
+pub(crate) struct InjectedRng(pub(crate) Mutex<Box<dyn RngCore + Send>>);
+
+impl InjectedRng {
+    pub(crate) fn new(rng: impl RngCore + Send + 'static) -> Self {
+        Self(Mutex::new(Box::new(rng)))
+    }
+}
+
+impl fmt::Debug for InjectedRng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("InjectedRng")
+    }
+}
+
+/// Fill `bytes` from rand's thread-local CSPRNG, the default byte source
+///
+/// ChaCha12, seeded and periodically reseeded from the OS.
+#[inline]
+pub(crate) fn thread_fill(bytes: &mut [u8]) {
+    rand::rng().fill_bytes(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alphabet(size: usize) -> Vec<char> {
+        ('!'..='~').take(size).collect()
+    }
+
+    /// Pearson's chi-squared statistic of the character counts against a
+    /// uniform distribution
+    fn chi_squared(alphabet: &[char], id: &str) -> f64 {
+        let mut counts = vec![0u64; alphabet.len()];
+        for ch in id.chars() {
+            let index = alphabet.iter().position(|&c| c == ch).unwrap();
+            counts[index] += 1;
+        }
+        let expected = id.len() as f64 / alphabet.len() as f64;
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn test_uniform_distribution() {
+        // Critical values at p = 1e-6 for 9 and 61 degrees of freedom
+        for (size, critical) in [(10, 44.8), (62, 128.5)] {
+            let alphabet = alphabet(size);
+            let mut id = String::new();
+            sample(&mut id, &alphabet, 300_000, thread_fill);
+            assert_eq!(id.len(), 300_000);
+
+            let statistic = chi_squared(&alphabet, &id);
+            assert!(statistic < critical, "size {size}: chi-squared {statistic}");
+        }
+    }
+
+    #[test]
+    fn test_every_byte_value_gives_equal_counts() {
+        // Each byte value once: masking keeps exactly 4 of each of the 62
+        // characters, where `byte % 62` would give the first 8 five times
+        let alphabet = alphabet(62);
+        let mut next = 0u8;
+        let mut counter = |bytes: &mut [u8]| {
+            for byte in bytes {
+                *byte = next;
+                next = next.wrapping_add(1);
+            }
+        };
+        let mut id = String::new();
+        sample(&mut id, &alphabet, 62 * 4, &mut counter);
+
+        for ch in &alphabet {
+            assert_eq!(id.matches(*ch).count(), 4, "{ch}");
+        }
+    }
+
+    #[test]
+    fn test_refills_until_enough_bytes_are_accepted() {
+        // Only byte 255 is ever produced, so half the rounds are rejected
+        let alphabet = alphabet(10);
+        let mut rounds = 0;
+        let mut id = String::new();
+        sample(&mut id, &alphabet, 3, |bytes: &mut [u8]| {
+            rounds += 1;
+            let byte = if rounds % 2 == 0 { 3 } else { 255 };
+            bytes.fill(byte);
+        });
+        assert_eq!(id, "$$$");
+        assert_eq!(rounds, 2);
+    }
+
+    #[test]
+    fn test_sample_bytes_matches_sample() {
+        let alphabet = alphabet(62);
+        let counter = || {
+            let mut next = 0u8;
+            move |bytes: &mut [u8]| {
+                for byte in bytes {
+                    *byte = next;
+                    next = next.wrapping_add(7);
+                }
+            }
+        };
+        let mut id = String::new();
+        sample(&mut id, &alphabet, 40, counter());
+        let mut bytes = [0u8; 40];
+        sample_bytes(&mut bytes, &alphabet, counter());
+        assert_eq!(bytes, id.as_bytes());
+    }
+
+    #[test]
+    fn test_zero_length() {
+        let mut id = String::from("p_");
+        sample(&mut id, &alphabet(16), 0, |_: &mut [u8]| unreachable!());
+        assert_eq!(id, "p_");
+    }
+}
@@ -0,0 +1,189 @@
+use super::{NanoIdGenerator, sample};
+use crate::source;
+
+/// Millisecond range sortable IDs cover, 2^47 ms or until the year 6429
+const TIMESTAMP_SPAN: u128 = 1 << 47;
+
+/// Roughly time-ordered IDs: a millisecond timestamp written in the
+/// alphabet, most significant digit first, followed by random characters
+///
+/// IDs from different milliseconds sort by time when the alphabet is in
+/// ASCII order, as [`AlphabetPreset::Alphanumeric`](super::AlphabetPreset::Alphanumeric),
+/// `Lowercase`, `Hex` and `NoLookalike` are. `UrlSafe` puts `A-Z` before
+/// the digits, so its IDs do not sort. IDs from the same millisecond are in
+/// random order.
+impl NanoIdGenerator {
+    /// Generates a NanoID whose first characters encode the current time
+    ///
+    /// The timestamp takes 8 characters with the default alphabet, more for
+    /// smaller alphabets (see [`Self::timestamp_len`]). `length` counts
+    /// them, so `Some(16)` leaves 8 random characters; a length shorter
+    /// than the timestamp still gets the whole timestamp. `None` falls back
+    /// to the configured prefix and length.
+    ///
+    /// ```
+    /// use gen_id::NanoIdGenerator;
+    ///
+    /// let generator = NanoIdGenerator::builder().prefix("evt_").length(16).build();
+    /// let id = generator.generate_sortable(None, None);
+    /// assert_eq!(id.len(), 4 + 16);
+    /// assert!(generator.decode_timestamp(&id).is_some());
+    /// ```
+    pub fn generate_sortable(&self, prefix: Option<&str>, length: Option<usize>) -> String {
+        let len = length.unwrap_or(self.length());
+        let prefix = prefix.or(self.prefix()).unwrap_or_default();
+        let chars = self.alphabet().chars();
+        let timestamp_len = self.timestamp_len();
+
+        let mut id = String::with_capacity(prefix.len() + len.max(timestamp_len));
+        id.push_str(prefix);
+        push_timestamp(&mut id, chars, timestamp_len, source::system_millis());
+        self.with_fill(|fill| {
+            sample::sample(&mut id, chars, len.saturating_sub(timestamp_len), fill)
+        });
+        id
+    }
+
+    /// Recover the Unix millisecond timestamp of a
+    /// [`Self::generate_sortable`] ID
+    ///
+    /// Strips the configured prefix when `id` starts with it; strip a
+    /// per-call prefix first. `None` when the ID is too short or its
+    /// timestamp characters are outside the alphabet.
+    pub fn decode_timestamp(&self, id: &str) -> Option<u64> {
+        let body = self
+            .prefix()
+            .and_then(|prefix| id.strip_prefix(prefix))
+            .unwrap_or(id);
+        let chars = self.alphabet().chars();
+        let base = chars.len() as u64;
+
+        body.get(..self.timestamp_len())?
+            .chars()
+            .try_fold(0u64, |millis, ch| {
+                let digit = chars.iter().position(|&c| c == ch)? as u64;
+                millis.checked_mul(base)?.checked_add(digit)
+            })
+    }
+
+    /// Number of leading characters that hold the timestamp, the fewest
+    /// that cover 2^47 milliseconds in this alphabet
+    ///
+    /// 8 for the alphanumeric and URL-safe alphabets, 9 for no-lookalike,
+    /// 10 for lowercase and 12 for hex.
+    pub fn timestamp_len(&self) -> usize {
+        let base = self.alphabet().len() as u128;
+        let mut span = 1u128;
+        let mut len = 0;
+        while span < TIMESTAMP_SPAN {
+            span *= base;
+            len += 1;
+        }
+        len
+    }
+}
+
+/// Append `millis` as `len` digits in base `chars.len()`, saturating at the
+/// largest value that fits
+fn push_timestamp(id: &mut String, chars: &[char], len: usize, millis: u64) {
+    let base = chars.len() as u128;
+    let max = base.pow(len as u32) - 1;
+    let mut value = u128::from(millis).min(max);
+
+    let mut digits = vec!['\0'; len];
+    for digit in digits.iter_mut().rev() {
+        *digit = chars[(value % base) as usize];
+        value /= base;
+    }
+    id.extend(digits);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+    use crate::nanoid::AlphabetPreset;
+
+    #[test]
+    fn test_timestamp_len() {
+        let len = |alphabet| NanoIdGenerator::with_preset(alphabet).timestamp_len();
+        assert_eq!(len(AlphabetPreset::Alphanumeric), 8);
+        assert_eq!(len(AlphabetPreset::UrlSafe), 8);
+        assert_eq!(len(AlphabetPreset::NoLookalike), 9);
+        assert_eq!(len(AlphabetPreset::Lowercase), 10);
+        assert_eq!(len(AlphabetPreset::Hex), 12);
+    }
+
+    #[test]
+    fn test_sortable_ids_sort_by_time() {
+        let generator = NanoIdGenerator::builder().prefix("evt_").length(16).build();
+
+        let mut ids = Vec::new();
+        let mut times = Vec::new();
+        for _ in 0..5 {
+            let before = source::system_millis();
+            let id = generator.generate_sortable(None, None);
+            let after = source::system_millis();
+            assert_eq!(id.len(), 4 + 16);
+
+            let millis = generator.decode_timestamp(&id).unwrap();
+            assert!((before..=after).contains(&millis), "{id}: {millis}");
+            ids.push(id);
+            times.push(millis);
+            thread::sleep(Duration::from_millis(2));
+        }
+
+        assert!(times.is_sorted_by(|a, b| a < b), "{times:?}");
+        assert!(ids.is_sorted_by(|a, b| a < b), "{ids:?}");
+    }
+
+    #[test]
+    fn test_encoding_preserves_order() {
+        for alphabet in [
+            AlphabetPreset::Alphanumeric,
+            AlphabetPreset::Lowercase,
+            AlphabetPreset::Hex,
+            AlphabetPreset::NoLookalike,
+        ] {
+            let generator = NanoIdGenerator::with_preset(alphabet);
+            let encode = |millis| {
+                let mut id = String::new();
+                push_timestamp(&mut id, alphabet.chars(), generator.timestamp_len(), millis);
+                id
+            };
+            let samples = [0, 1, 61, 62, 999, 1_700_000_000_000, 1_700_000_000_001];
+            for pair in samples.windows(2) {
+                assert!(encode(pair[0]) < encode(pair[1]), "{alphabet:?} {pair:?}");
+            }
+            for millis in samples {
+                assert_eq!(generator.decode_timestamp(&encode(millis)), Some(millis));
+            }
+        }
+    }
+
+    #[test]
+    fn test_timestamp_saturates() {
+        let generator = NanoIdGenerator::new();
+        let mut id = String::new();
+        push_timestamp(&mut id, AlphabetPreset::Alphanumeric.chars(), 8, u64::MAX);
+        assert_eq!(id, "zzzzzzzz");
+        assert_eq!(generator.decode_timestamp(&id), Some(62u64.pow(8) - 1));
+    }
+
+    #[test]
+    fn test_short_length_keeps_whole_timestamp() {
+        let generator = NanoIdGenerator::new();
+        let id = generator.generate_sortable(Some("x_"), Some(3));
+        assert_eq!(id.len(), 2 + 8);
+        assert!(generator.decode_timestamp(&id[2..]).is_some());
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_input() {
+        let generator = NanoIdGenerator::with_preset(AlphabetPreset::Hex);
+        assert_eq!(generator.decode_timestamp("0123"), None);
+        assert_eq!(generator.decode_timestamp("0123456789ag"), None);
+        assert_eq!(generator.decode_timestamp("000000000010"), Some(16));
+    }
+}
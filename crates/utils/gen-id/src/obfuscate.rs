@@ -0,0 +1,501 @@
+use alloc::{string::String, vec::Vec};
+
+/// The sqids default alphabet
+pub const DEFAULT_OBFUSCATE_ALPHABET: &str =
+    "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+const MIN_ALPHABET_LEN: usize = 3;
+const MAX_MIN_LENGTH: usize = 255;
+const MIN_BLOCKED_WORD_LEN: usize = 3;
+
+/// Error for an invalid [`IdObfuscator`] setup or an undecodable string
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ObfuscateError {
+    #[error("alphabet has {len} characters, it needs at least 3")]
+    AlphabetTooShort { len: usize },
+
+    #[error("alphabet character {ch:?} is not ASCII")]
+    NonAsciiAlphabet { ch: char },
+
+    #[error("alphabet repeats {ch:?} at index {index}")]
+    DuplicateChar { ch: char, index: usize },
+
+    #[error("minimum length {min_length} is above 255")]
+    MinLengthTooLarge { min_length: usize },
+
+    #[error("{ch:?} at index {index} is not in the alphabet")]
+    InvalidChar { ch: char, index: usize },
+
+    #[error("encoded number does not fit in 64 bits")]
+    Overflow,
+
+    #[error("not the canonical encoding of the numbers it decodes to")]
+    NotCanonical,
+
+    #[error("every candidate encoding contains a blocked word")]
+    AllBlocked,
+}
+
+/// Reversible encoding of `u64`s such as database keys into short strings,
+/// following the sqids algorithm
+///
+/// No lookup table is involved: the numbers are written in a base the size
+/// of the alphabet, and the alphabet is reshuffled per ID so consecutive
+/// numbers look unrelated. This hides counts and sequences from casual
+/// observers, it is **not** encryption, anyone with the alphabet and seed
+/// can decode.
+///
+/// A seed of 0 keeps the plain sqids alphabet order, so the output matches
+/// other sqids libraries given the same alphabet, minimum length and
+/// blocklist. Any other seed shuffles the alphabet first, giving each
+/// deployment its own encoding.
+///
+/// ```
+/// use gen_id::{DEFAULT_OBFUSCATE_ALPHABET, IdObfuscator};
+///
+/// let obfuscator = IdObfuscator::new(DEFAULT_OBFUSCATE_ALPHABET, 0, 0)?;
+/// assert_eq!(obfuscator.encode(&[1, 2, 3]), "86Rf07");
+/// assert_eq!(obfuscator.decode("86Rf07")?, [1, 2, 3]);
+/// # Ok::<(), gen_id::ObfuscateError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct IdObfuscator {
+    alphabet: Vec<u8>,
+    min_length: usize,
+    blocklist: Vec<String>,
+}
+
+impl IdObfuscator {
+    /// Creates an obfuscator over `alphabet`, padding IDs to at least
+    /// `min_length` characters
+    ///
+    /// The alphabet needs at least 3 distinct ASCII characters, and
+    /// `min_length` may be at most 255. Starts without a blocklist.
+    pub fn new(alphabet: &str, min_length: usize, seed: u64) -> Result<Self, ObfuscateError> {
+        if let Some(ch) = alphabet.chars().find(|ch| !ch.is_ascii()) {
+            return Err(ObfuscateError::NonAsciiAlphabet { ch });
+        }
+        if alphabet.len() < MIN_ALPHABET_LEN {
+            return Err(ObfuscateError::AlphabetTooShort {
+                len: alphabet.len(),
+            });
+        }
+        let bytes = alphabet.as_bytes();
+        for (index, &byte) in bytes.iter().enumerate() {
+            if bytes[..index].contains(&byte) {
+                return Err(ObfuscateError::DuplicateChar {
+                    ch: char::from(byte),
+                    index,
+                });
+            }
+        }
+        if min_length > MAX_MIN_LENGTH {
+            return Err(ObfuscateError::MinLengthTooLarge { min_length });
+        }
+
+        let mut alphabet = bytes.to_vec();
+        if seed != 0 {
+            seeded_shuffle(&mut alphabet, seed);
+        }
+        shuffle(&mut alphabet);
+        Ok(Self {
+            alphabet,
+            min_length,
+            blocklist: Vec::new(),
+        })
+    }
+
+    /// Avoid IDs containing any of `words`, e.g. profanity
+    ///
+    /// Matching ignores case. Words shorter than 3 characters, or with
+    /// characters outside the alphabet, can never appear and are dropped.
+    /// As in sqids, words of 3 characters only block IDs equal to them, and
+    /// words with digits only block IDs starting or ending with them, so
+    /// that leetspeak filters do not reject too many IDs.
+    pub fn with_blocklist<I>(mut self, words: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let alphabet = self.alphabet.to_ascii_lowercase();
+        self.blocklist = words
+            .into_iter()
+            .map(|word| word.as_ref().to_ascii_lowercase())
+            .filter(|word| {
+                word.len() >= MIN_BLOCKED_WORD_LEN
+                    && word.bytes().all(|byte| alphabet.contains(&byte))
+            })
+            .collect();
+        self.blocklist.sort_unstable();
+        self.blocklist.dedup();
+        self
+    }
+
+    /// The minimum ID length
+    #[inline]
+    pub fn min_length(&self) -> usize {
+        self.min_length
+    }
+
+    /// Encode `nums` into one ID, or an empty string for no numbers
+    ///
+    /// # Panics
+    ///
+    /// If the blocklist rejects every candidate, see [`Self::try_encode`].
+    /// That takes as many blocked IDs for the same numbers as the alphabet
+    /// has characters.
+    pub fn encode(&self, nums: &[u64]) -> String {
+        self.try_encode(nums)
+            .expect("the blocklist rejected every candidate ID")
+    }
+
+    /// Encode `nums`, failing with [`ObfuscateError::AllBlocked`] instead of
+    /// panicking when the blocklist rejects every candidate
+    pub fn try_encode(&self, nums: &[u64]) -> Result<String, ObfuscateError> {
+        if nums.is_empty() {
+            return Ok(String::new());
+        }
+        (0..=self.alphabet.len())
+            .map(|increment| self.encode_with(nums, increment))
+            .find(|id| !self.is_blocked(id))
+            .ok_or(ObfuscateError::AllBlocked)
+    }
+
+    /// Decode an ID back into its numbers
+    ///
+    /// Fails on characters outside the alphabet, on numbers beyond `u64`
+    /// and on strings that decode to numbers whose encoding differs, so
+    /// each list of numbers has exactly one accepted ID. An empty string
+    /// decodes to no numbers.
+    pub fn decode(&self, id: &str) -> Result<Vec<u64>, ObfuscateError> {
+        if id.is_empty() {
+            return Ok(Vec::new());
+        }
+        for (index, ch) in id.char_indices() {
+            if !ch.is_ascii() || !self.alphabet.contains(&(ch as u8)) {
+                return Err(ObfuscateError::InvalidChar { ch, index });
+            }
+        }
+
+        let bytes = id.as_bytes();
+        let offset = self
+            .alphabet
+            .iter()
+            .position(|&byte| byte == bytes[0])
+            .expect("checked above");
+        let mut alphabet = self.alphabet.clone();
+        alphabet.rotate_left(offset);
+        alphabet.reverse();
+
+        let mut nums = Vec::new();
+        let mut rest = &bytes[1..];
+        while !rest.is_empty() {
+            let separator = alphabet[0];
+            let (chunk, tail) = match rest.iter().position(|&byte| byte == separator) {
+                Some(end) => (&rest[..end], Some(&rest[end + 1..])),
+                None => (rest, None),
+            };
+            // Padding starts with the separator, ending the numbers
+            if chunk.is_empty() {
+                break;
+            }
+            nums.push(to_number(chunk, &alphabet[1..])?);
+            match tail {
+                Some(tail) => {
+                    shuffle(&mut alphabet);
+                    rest = tail;
+                }
+                None => break,
+            }
+        }
+
+        if nums.is_empty() || self.try_encode(&nums).ok().as_deref() != Some(id) {
+            return Err(ObfuscateError::NotCanonical);
+        }
+        Ok(nums)
+    }
+
+    fn encode_with(&self, nums: &[u64], increment: usize) -> String {
+        let len = self.alphabet.len();
+        let offset = nums
+            .iter()
+            .enumerate()
+            .fold(nums.len(), |acc, (index, &num)| {
+                usize::from(self.alphabet[(num % len as u64) as usize]) + index + acc
+            });
+        let mut alphabet = self.alphabet.clone();
+        alphabet.rotate_left((offset + increment) % len);
+
+        let mut id = Vec::with_capacity(self.min_length.max(16));
+        id.push(alphabet[0]);
+        alphabet.reverse();
+        for (index, &num) in nums.iter().enumerate() {
+            push_number(&mut id, num, &alphabet[1..]);
+            if index + 1 < nums.len() {
+                id.push(alphabet[0]);
+                shuffle(&mut alphabet);
+            }
+        }
+
+        if id.len() < self.min_length {
+            id.push(alphabet[0]);
+            while id.len() < self.min_length {
+                shuffle(&mut alphabet);
+                let take = (self.min_length - id.len()).min(len);
+                id.extend_from_slice(&alphabet[..take]);
+            }
+        }
+        String::from_utf8(id).expect("the alphabet is ASCII")
+    }
+
+    fn is_blocked(&self, id: &str) -> bool {
+        let id = id.to_ascii_lowercase();
+        self.blocklist.iter().any(|word| {
+            if word.len() > id.len() {
+                false
+            } else if id.len() <= MIN_BLOCKED_WORD_LEN || word.len() <= MIN_BLOCKED_WORD_LEN {
+                id == *word
+            } else if word.bytes().any(|byte| byte.is_ascii_digit()) {
+                id.starts_with(word.as_str()) || id.ends_with(word.as_str())
+            } else {
+                id.contains(word.as_str())
+            }
+        })
+    }
+}
+
+/// The sqids shuffle, deterministic in the alphabet's own characters
+fn shuffle(alphabet: &mut [u8]) {
+    let len = alphabet.len();
+    for (i, j) in (0..len).zip((1..len).rev()) {
+        let r = (i * j + usize::from(alphabet[i]) + usize::from(alphabet[j])) % len;
+        alphabet.swap(i, r);
+    }
+}
+
+/// Fisher-Yates shuffle driven by SplitMix64, giving each seed its own
+/// alphabet order
+fn seeded_shuffle(alphabet: &mut [u8], mut seed: u64) {
+    for i in (1..alphabet.len()).rev() {
+        seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        alphabet.swap(i, (z % (i as u64 + 1)) as usize);
+    }
+}
+
+fn push_number(id: &mut Vec<u8>, mut num: u64, alphabet: &[u8]) {
+    let base = alphabet.len() as u64;
+    let start = id.len();
+    loop {
+        id.push(alphabet[(num % base) as usize]);
+        num /= base;
+        if num == 0 {
+            break;
+        }
+    }
+    id[start..].reverse();
+}
+
+fn to_number(chunk: &[u8], alphabet: &[u8]) -> Result<u64, ObfuscateError> {
+    let base = alphabet.len() as u64;
+    chunk.iter().try_fold(0u64, |acc, byte| {
+        // The separator is not in the digit alphabet
+        let digit = alphabet
+            .iter()
+            .position(|candidate| candidate == byte)
+            .ok_or(ObfuscateError::NotCanonical)?;
+        acc.checked_mul(base)
+            .and_then(|acc| acc.checked_add(digit as u64))
+            .ok_or(ObfuscateError::Overflow)
+    })
+}
+
+impl Default for IdObfuscator {
+    /// The sqids defaults: [`DEFAULT_OBFUSCATE_ALPHABET`], no minimum
+    /// length and no blocklist
+    fn default() -> Self {
+        Self::new(DEFAULT_OBFUSCATE_ALPHABET, 0, 0).expect("the default alphabet is valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::{RngCore, SeedableRng};
+
+    #[test]
+    fn test_sqids_reference_values() {
+        // From the sqids spec's encoding tests
+        let obfuscator = IdObfuscator::default();
+        let singles = ["bM", "Uk", "gb", "Ef", "Vq", "uw", "OI", "AX", "p6", "nJ"];
+        for (num, expected) in (0..).zip(singles) {
+            assert_eq!(obfuscator.encode(&[num]), expected);
+            assert_eq!(obfuscator.decode(expected), Ok(vec![num]));
+        }
+        assert_eq!(obfuscator.encode(&[1, 2, 3]), "86Rf07");
+        assert_eq!(obfuscator.encode(&[]), "");
+        assert_eq!(obfuscator.decode(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_round_trip_random() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3828);
+        let obfuscators = [
+            IdObfuscator::default(),
+            IdObfuscator::new(DEFAULT_OBFUSCATE_ALPHABET, 8, 42).unwrap(),
+            IdObfuscator::new("0123456789abcdef", 20, 7).unwrap(),
+            IdObfuscator::new("abc", 0, 1).unwrap(),
+        ];
+        for obfuscator in &obfuscators {
+            for _ in 0..1_000 {
+                let num = rng.next_u64() >> (rng.next_u32() % 64);
+                let id = obfuscator.encode(&[num]);
+                assert_eq!(obfuscator.decode(&id), Ok(vec![num]), "{id}");
+            }
+            for edge in [0, 1, u64::MAX] {
+                let id = obfuscator.encode(&[edge]);
+                assert_eq!(obfuscator.decode(&id), Ok(vec![edge]), "{id}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiple_numbers() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let obfuscator = IdObfuscator::new(DEFAULT_OBFUSCATE_ALPHABET, 0, 99).unwrap();
+        for count in 1..10 {
+            let nums: Vec<u64> = (0..count).map(|_| rng.next_u64() % 1_000_000).collect();
+            let id = obfuscator.encode(&nums);
+            assert_eq!(obfuscator.decode(&id).unwrap(), nums, "{id}");
+        }
+        let nums = [0, 0, 0, u64::MAX, u64::MAX];
+        assert_eq!(obfuscator.decode(&obfuscator.encode(&nums)).unwrap(), nums);
+    }
+
+    #[test]
+    fn test_min_length_padding() {
+        for min_length in [0, 1, 5, 10, 61, 62, 63, 200, 255] {
+            let obfuscator = IdObfuscator::new(DEFAULT_OBFUSCATE_ALPHABET, min_length, 0).unwrap();
+            for nums in [&[0][..], &[1, 2, 3], &[u64::MAX]] {
+                let id = obfuscator.encode(nums);
+                assert!(id.len() >= min_length, "{id}");
+                assert_eq!(obfuscator.decode(&id).unwrap(), nums, "{id}");
+            }
+        }
+        // Padding does not change IDs already long enough
+        let padded = IdObfuscator::new(DEFAULT_OBFUSCATE_ALPHABET, 6, 0).unwrap();
+        assert_eq!(padded.encode(&[1, 2, 3]), "86Rf07");
+        assert_eq!(padded.encode(&[1]).len(), 6);
+        assert!(
+            IdObfuscator::new(DEFAULT_OBFUSCATE_ALPHABET, 256, 0)
+                .is_err_and(|err| err == ObfuscateError::MinLengthTooLarge { min_length: 256 })
+        );
+    }
+
+    #[test]
+    fn test_seed_changes_encoding() {
+        let plain = IdObfuscator::default();
+        let seeded = IdObfuscator::new(DEFAULT_OBFUSCATE_ALPHABET, 0, 12345).unwrap();
+        let again = IdObfuscator::new(DEFAULT_OBFUSCATE_ALPHABET, 0, 12345).unwrap();
+        let nums: Vec<u64> = (0..100).collect();
+        let plain_ids: Vec<String> = nums.iter().map(|&n| plain.encode(&[n])).collect();
+        let seeded_ids: Vec<String> = nums.iter().map(|&n| seeded.encode(&[n])).collect();
+        assert_ne!(plain_ids, seeded_ids);
+        assert_eq!(seeded_ids[7], again.encode(&[7]));
+    }
+
+    #[test]
+    fn test_blocklist() {
+        // From the sqids spec's blocklist tests
+        let obfuscator = IdObfuscator::default().with_blocklist(["ArUO"]);
+        assert_eq!(obfuscator.decode("aho1e"), Ok(vec![4_572_721]));
+        assert_eq!(obfuscator.encode(&[4_572_721]), "aho1e");
+        assert_eq!(IdObfuscator::default().encode(&[100_000]), "ArUO");
+        assert_eq!(obfuscator.encode(&[100_000]), "QyG4");
+        assert_eq!(obfuscator.decode("QyG4"), Ok(vec![100_000]));
+        // The blocked encoding is no longer canonical
+        assert_eq!(obfuscator.decode("ArUO"), Err(ObfuscateError::NotCanonical));
+
+        // Short words and words outside the alphabet are dropped
+        let filtered = IdObfuscator::new("abcdef", 0, 0)
+            .unwrap()
+            .with_blocklist(["ab", "xyz", "FAB", "fab"]);
+        assert_eq!(filtered.blocklist, ["fab"]);
+    }
+
+    #[test]
+    fn test_all_blocked() {
+        let obfuscator = IdObfuscator::new("abc", 0, 0).unwrap();
+        let blocklist: Vec<String> = (0..=3)
+            .map(|increment| obfuscator.encode_with(&[1_000], increment))
+            .collect();
+        let blocked = obfuscator.with_blocklist(&blocklist);
+        assert_eq!(
+            blocked.try_encode(&[1_000]),
+            Err(ObfuscateError::AllBlocked)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_foreign_characters() {
+        let obfuscator = IdObfuscator::default();
+        assert_eq!(
+            obfuscator.decode("86R-07"),
+            Err(ObfuscateError::InvalidChar { ch: '-', index: 3 })
+        );
+        assert_eq!(
+            obfuscator.decode("86é"),
+            Err(ObfuscateError::InvalidChar { ch: 'é', index: 2 })
+        );
+        let hex = IdObfuscator::new("0123456789abcdef", 0, 0).unwrap();
+        assert!(matches!(
+            hex.decode("xyz"),
+            Err(ObfuscateError::InvalidChar { ch: 'x', index: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        let obfuscator = IdObfuscator::default();
+        // Too many digits for 64 bits
+        assert_eq!(
+            obfuscator.decode(&"b".repeat(40)).unwrap_err(),
+            ObfuscateError::Overflow
+        );
+        // Every string over the alphabet either decodes canonically or fails
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        for _ in 0..2_000 {
+            let len = 1 + rng.next_u32() as usize % 12;
+            let id: String = (0..len)
+                .map(|_| {
+                    char::from(DEFAULT_OBFUSCATE_ALPHABET.as_bytes()[rng.next_u32() as usize % 62])
+                })
+                .collect();
+            if let Ok(nums) = obfuscator.decode(&id) {
+                assert_eq!(obfuscator.encode(&nums), id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_alphabets() {
+        assert_eq!(
+            IdObfuscator::new("ab", 0, 0).unwrap_err(),
+            ObfuscateError::AlphabetTooShort { len: 2 }
+        );
+        assert_eq!(
+            IdObfuscator::new("abca", 0, 0).unwrap_err(),
+            ObfuscateError::DuplicateChar { ch: 'a', index: 3 }
+        );
+        assert_eq!(
+            IdObfuscator::new("abcé", 0, 0).unwrap_err(),
+            ObfuscateError::NonAsciiAlphabet { ch: 'é' }
+        );
+        assert_eq!(IdObfuscator::default().min_length(), 0);
+    }
+}
@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+
+/// Error for a prefix rejected by a [`PrefixPolicy`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PrefixError {
+    #[error("prefix is {len} bytes long, the maximum is {max}")]
+    TooLong { len: usize, max: usize },
+
+    #[error(
+        "prefix contains {ch:?} at byte {index}, allowed are ASCII letters, digits and {allowed:?}"
+    )]
+    InvalidChar {
+        ch: char,
+        index: usize,
+        allowed: String,
+    },
+}
+
+/// Characters and length allowed in a prefix
+///
+/// The default allows ASCII alphanumerics plus `_`, `-` and `:`, up to 32
+/// bytes, which keeps IDs safe in CSV exports and URL paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixPolicy {
+    max_len: usize,
+    extra_chars: Cow<'static, str>,
+}
+
+impl Default for PrefixPolicy {
+    fn default() -> Self {
+        Self {
+            max_len: 32,
+            extra_chars: Cow::Borrowed("_-:"),
+        }
+    }
+}
+
+impl PrefixPolicy {
+    /// Set the maximum prefix length in bytes
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Set the characters allowed besides ASCII alphanumerics
+    pub fn with_extra_chars(mut self, extra_chars: impl Into<String>) -> Self {
+        self.extra_chars = Cow::Owned(extra_chars.into());
+        self
+    }
+
+    /// Check `prefix` against the policy
+    pub fn validate(&self, prefix: &str) -> Result<(), PrefixError> {
+        if prefix.len() > self.max_len {
+            return Err(PrefixError::TooLong {
+                len: prefix.len(),
+                max: self.max_len,
+            });
+        }
+
+        match prefix
+            .char_indices()
+            .find(|(_, ch)| !ch.is_ascii_alphanumeric() && !self.extra_chars.contains(*ch))
+        {
+            Some((index, ch)) => Err(PrefixError::InvalidChar {
+                ch,
+                index,
+                allowed: self.extra_chars.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_accepts() {
+        let policy = PrefixPolicy::default();
+
+        for prefix in ["order_", "user-", "acct:", "A1b2", "", &"x".repeat(32)] {
+            assert_eq!(policy.validate(prefix), Ok(()), "{prefix:?}");
+        }
+    }
+
+    #[test]
+    fn test_default_policy_rejects() {
+        let policy = PrefixPolicy::default();
+
+        assert_eq!(
+            policy.validate("bad prefix\n"),
+            Err(PrefixError::InvalidChar {
+                ch: ' ',
+                index: 3,
+                allowed: "_-:".to_string(),
+            })
+        );
+        assert!(matches!(
+            policy.validate("id,"),
+            Err(PrefixError::InvalidChar { ch: ',', .. })
+        ));
+        assert!(matches!(
+            policy.validate("ü_"),
+            Err(PrefixError::InvalidChar {
+                ch: 'ü',
+                index: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            policy.validate("a/b"),
+            Err(PrefixError::InvalidChar { ch: '/', .. })
+        ));
+        assert_eq!(
+            policy.validate(&"x".repeat(33)),
+            Err(PrefixError::TooLong { len: 33, max: 32 })
+        );
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = PrefixPolicy::default()
+            .with_max_len(8)
+            .with_extra_chars(".");
+
+        assert_eq!(policy.validate("v1.user"), Ok(()));
+        assert!(policy.validate("v1_user").is_err());
+        assert!(policy.validate("v1.user.x").is_err());
+    }
+}
@@ -0,0 +1,326 @@
+use alloc::string::{String, ToString};
+use core::{cmp::Ordering, fmt, hash, marker::PhantomData, str::FromStr};
+
+#[cfg(feature = "std")]
+use crate::IdGenerator;
+
+/// Error for a string that is not a [`PrefixedId`] of the expected kind
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum IdError {
+    #[error("expected prefix {expected:?}, found {found:?}")]
+    WrongPrefix {
+        expected: &'static str,
+        found: String,
+    },
+
+    #[error("ID has nothing after the prefix {prefix:?}")]
+    MissingBody { prefix: &'static str },
+}
+
+/// The namespace of a [`PrefixedId`], usually declared with
+/// [`define_id_prefix!`](crate::define_id_prefix)
+pub trait IdPrefix {
+    /// Text every ID of this kind starts with, e.g. `"cus_"`
+    const PREFIX: &'static str;
+}
+
+/// Declare a marker type implementing [`IdPrefix`]
+///
+/// ```
+/// use gen_id::{PrefixedId, define_id_prefix};
+///
+/// define_id_prefix!(pub Customer, "cus_");
+/// pub type CustomerId = PrefixedId<Customer>;
+///
+/// let id: CustomerId = "cus_V1StGXR8Z5jd".parse()?;
+/// assert_eq!(id.body(), "V1StGXR8Z5jd");
+/// # Ok::<(), gen_id::IdError>(())
+/// ```
+#[macro_export]
+macro_rules! define_id_prefix {
+    ($(#[$meta:meta])* $vis:vis $name:ident, $prefix:expr $(,)?) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+        $vis struct $name;
+
+        impl $crate::IdPrefix for $name {
+            const PREFIX: &'static str = {
+                assert!(!$prefix.is_empty(), "ID prefix must not be empty");
+                $prefix
+            };
+        }
+    };
+}
+
+/// A `String` ID that is known to start with `T`'s prefix, e.g. Stripe-style
+/// `cus_...` and `ord_...`
+///
+/// Customer and order IDs become different types, so one cannot be passed
+/// where the other is expected. The full prefixed text is stored, and is
+/// what `Display`, `AsRef<str>` and serde (with the `serde` feature) use.
+pub struct PrefixedId<T> {
+    id: String,
+    kind: PhantomData<fn() -> T>,
+}
+
+impl<T: IdPrefix> PrefixedId<T> {
+    /// Generate a new ID, `T`'s prefix followed by one ID from `generator`
+    ///
+    /// The generator should have no prefix of its own, or it ends up after
+    /// `T`'s.
+    ///
+    /// ```
+    /// use gen_id::{PrefixedId, UuidFormat, UuidGenerator, define_id_prefix};
+    ///
+    /// define_id_prefix!(Order, "ord_");
+    ///
+    /// let generator = UuidGenerator::v7().with_format(UuidFormat::Base58);
+    /// let id = PrefixedId::<Order>::generate(&generator);
+    /// assert!(id.as_str().starts_with("ord_"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn generate(generator: &impl IdGenerator) -> Self {
+        let body = generator.generate();
+        let mut id = String::with_capacity(T::PREFIX.len() + body.len());
+        id.push_str(T::PREFIX);
+        id.push_str(&body);
+        Self::new_unchecked(id)
+    }
+
+    /// Check that `id` starts with `T`'s prefix and has something after it
+    pub fn parse(id: &str) -> Result<Self, IdError> {
+        match id.strip_prefix(T::PREFIX) {
+            Some("") => Err(IdError::MissingBody { prefix: T::PREFIX }),
+            Some(_) => Ok(Self::new_unchecked(id.to_string())),
+            None => Err(IdError::WrongPrefix {
+                expected: T::PREFIX,
+                found: found_prefix(id, T::PREFIX).to_string(),
+            }),
+        }
+    }
+
+    /// `T`'s prefix
+    #[inline]
+    pub const fn prefix() -> &'static str {
+        T::PREFIX
+    }
+
+    /// The ID after the prefix
+    #[inline]
+    pub fn body(&self) -> &str {
+        &self.id[T::PREFIX.len()..]
+    }
+}
+
+impl<T> PrefixedId<T> {
+    #[inline]
+    fn new_unchecked(id: String) -> Self {
+        Self {
+            id,
+            kind: PhantomData,
+        }
+    }
+
+    /// The full ID, prefix included
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+
+    /// The full ID, prefix included
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.id
+    }
+}
+
+/// The prefix `id` has instead of `expected`, for error messages
+///
+/// Prefixes ending in a separator such as `_` are compared up to the
+/// separator, so `ord_abc` checked against `cus_` reports `ord_`. Others
+/// report as many bytes as `expected` has.
+fn found_prefix<'a>(id: &'a str, expected: &str) -> &'a str {
+    match expected.chars().last() {
+        Some(separator) if !separator.is_alphanumeric() => id
+            .find(separator)
+            .map_or("", |index| &id[..index + separator.len_utf8()]),
+        _ => id.get(..expected.len()).unwrap_or(id),
+    }
+}
+
+// Manual impls, as derives would require `T` to implement each trait too
+
+impl<T> Clone for PrefixedId<T> {
+    fn clone(&self) -> Self {
+        Self::new_unchecked(self.id.clone())
+    }
+}
+
+impl<T> PartialEq for PrefixedId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for PrefixedId<T> {}
+
+impl<T> PartialOrd for PrefixedId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for PrefixedId<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<T> hash::Hash for PrefixedId<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for PrefixedId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PrefixedId").field(&self.id).finish()
+    }
+}
+
+impl<T> fmt::Display for PrefixedId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+impl<T> AsRef<str> for PrefixedId<T> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.id
+    }
+}
+
+impl<T: IdPrefix> FromStr for PrefixedId<T> {
+    type Err = IdError;
+
+    #[inline]
+    fn from_str(id: &str) -> Result<Self, IdError> {
+        Self::parse(id)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for PrefixedId<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.id)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: IdPrefix> serde::Deserialize<'de> for PrefixedId<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Self::parse(&id).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::define_id_prefix!(Customer, "cus_");
+    crate::define_id_prefix!(Order, "ord_");
+    crate::define_id_prefix!(Tenant, "tenant");
+
+    type CustomerId = PrefixedId<Customer>;
+    type OrderId = PrefixedId<Order>;
+
+    #[test]
+    fn test_parse_round_trip() {
+        let id: CustomerId = "cus_V1StGXR8Z5jd".parse().unwrap();
+        assert_eq!(id.as_str(), "cus_V1StGXR8Z5jd");
+        assert_eq!(id.body(), "V1StGXR8Z5jd");
+        assert_eq!(id.to_string(), "cus_V1StGXR8Z5jd");
+        assert_eq!(id.as_ref(), "cus_V1StGXR8Z5jd");
+        assert_eq!(CustomerId::prefix(), "cus_");
+        assert_eq!(format!("{id:?}"), "PrefixedId(\"cus_V1StGXR8Z5jd\")");
+        assert_eq!(id.clone().into_string(), "cus_V1StGXR8Z5jd");
+    }
+
+    #[test]
+    fn test_wrong_prefix_names_both() {
+        let err = CustomerId::parse("ord_V1StGXR8Z5jd").unwrap_err();
+        assert_eq!(
+            err,
+            IdError::WrongPrefix {
+                expected: "cus_",
+                found: "ord_".to_string(),
+            }
+        );
+        assert_eq!(err.to_string(), "expected prefix \"cus_\", found \"ord_\"");
+
+        // No separator at all
+        assert_eq!(
+            OrderId::parse("V1StGXR8Z5jd").unwrap_err().to_string(),
+            "expected prefix \"ord_\", found \"\""
+        );
+        // Prefixes without a separator compare by length
+        assert_eq!(
+            PrefixedId::<Tenant>::parse("tenure42").unwrap_err(),
+            IdError::WrongPrefix {
+                expected: "tenant",
+                found: "tenure".to_string(),
+            }
+        );
+        assert_eq!(
+            PrefixedId::<Tenant>::parse("ten").unwrap_err(),
+            IdError::WrongPrefix {
+                expected: "tenant",
+                found: "ten".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_body() {
+        assert_eq!(
+            CustomerId::parse("cus_"),
+            Err(IdError::MissingBody { prefix: "cus_" })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "nanoid")]
+    fn test_generate() {
+        let generator = crate::NanoIdGenerator::new();
+        let id = OrderId::generate(&generator);
+        assert!(id.as_str().starts_with("ord_"), "{id}");
+        assert_eq!(id.body().len(), 12);
+        assert_eq!(OrderId::parse(id.as_str()), Ok(id));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "uuid"))]
+    fn test_generate_from_uuid() {
+        let id = CustomerId::generate(&crate::UuidGenerator::v7());
+        assert!(crate::parse_uuid(id.body()).is_ok(), "{id}");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let id: CustomerId = "cus_V1StGXR8Z5jd".parse().unwrap();
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, "\"cus_V1StGXR8Z5jd\"");
+        assert_eq!(serde_json::from_str::<CustomerId>(&json).unwrap(), id);
+
+        let err = serde_json::from_str::<OrderId>(&json).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("expected prefix \"ord_\", found \"cus_\""),
+            "{err}"
+        );
+    }
+}
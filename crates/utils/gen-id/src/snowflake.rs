@@ -0,0 +1,366 @@
+use std::{
+    hint,
+    sync::{Arc, Mutex, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::source::{self, Clock};
+
+/// Milliseconds since the generator's epoch, about 69 years
+pub const TIMESTAMP_BITS: u32 = 41;
+/// Machine ID, up to 1024 generators
+pub const MACHINE_ID_BITS: u32 = 10;
+/// Per-millisecond sequence, up to 4096 IDs per millisecond and machine
+pub const SEQUENCE_BITS: u32 = 12;
+
+const MAX_TIMESTAMP: u64 = (1 << TIMESTAMP_BITS) - 1;
+const MAX_SEQUENCE: u16 = (1 << SEQUENCE_BITS) - 1;
+
+/// Error for a [`SnowflakeGenerator`] that cannot be created
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum SnowflakeError {
+    #[error("machine ID {machine_id} exceeds the maximum of {max}")]
+    MachineIdOutOfRange { machine_id: u16, max: u16 },
+
+    #[error("epoch must lie between the Unix epoch and now")]
+    InvalidEpoch,
+}
+
+/// The fields of a Snowflake ID, from [`SnowflakeGenerator::decompose`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SnowflakeParts {
+    /// Unix time in milliseconds, the generator's epoch added back
+    pub timestamp_ms: u64,
+    pub machine_id: u16,
+    pub sequence: u16,
+}
+
+/// Generator of 64-bit Snowflake IDs for stores without 128-bit columns
+///
+/// From the most significant bit: a zero sign bit, 41 bits of milliseconds
+/// since a custom epoch, a 10-bit machine ID and a 12-bit sequence, so IDs
+/// fit an `i64` and sort by time. Each machine must have its own ID.
+///
+/// When the 4096 IDs of a millisecond are used up, the generator spins until
+/// the clock moves on. When the clock goes backwards, it keeps using the
+/// last timestamp, and if that millisecond is used up too it advances one
+/// millisecond past it instead of waiting for the clock to catch up.
+/// Clones share that state, so they never repeat each other's IDs.
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// use gen_id::SnowflakeGenerator;
+///
+/// // 2020-01-01
+/// let epoch = UNIX_EPOCH + Duration::from_millis(1_577_836_800_000);
+/// let generator = SnowflakeGenerator::new(7, epoch)?;
+///
+/// let first = generator.generate();
+/// let second = generator.generate();
+/// assert!(second > first);
+/// assert_eq!(generator.decompose(first).machine_id, 7);
+/// # Ok::<(), gen_id::SnowflakeError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct SnowflakeGenerator {
+    machine_id: u16,
+    epoch_ms: u64,
+    clock: Option<Clock>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    timestamp: u64,
+    sequence: u16,
+}
+
+impl SnowflakeGenerator {
+    /// Largest machine ID that fits the 10 bits
+    pub const MAX_MACHINE_ID: u16 = (1 << MACHINE_ID_BITS) - 1;
+
+    /// Creates a generator for `machine_id`, counting time from `epoch`
+    ///
+    /// Fails when `machine_id` exceeds [`Self::MAX_MACHINE_ID`] or `epoch`
+    /// is before 1970 or in the future.
+    pub fn new(machine_id: u16, epoch: SystemTime) -> Result<Self, SnowflakeError> {
+        if machine_id > Self::MAX_MACHINE_ID {
+            return Err(SnowflakeError::MachineIdOutOfRange {
+                machine_id,
+                max: Self::MAX_MACHINE_ID,
+            });
+        }
+        let epoch_ms = epoch
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| SnowflakeError::InvalidEpoch)?
+            .as_millis();
+        if epoch_ms > u128::from(source::system_millis()) {
+            return Err(SnowflakeError::InvalidEpoch);
+        }
+
+        Ok(Self {
+            machine_id,
+            epoch_ms: epoch_ms as u64,
+            clock: None,
+            state: Arc::default(),
+        })
+    }
+
+    /// Take timestamps from `clock`, in milliseconds since the Unix epoch,
+    /// instead of the system time
+    #[inline]
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Some(Clock::new(clock));
+        self
+    }
+
+    /// The machine ID every generated ID carries
+    #[inline]
+    pub const fn machine_id(&self) -> u16 {
+        self.machine_id
+    }
+
+    /// Generates one ID, greater than every ID this generator and its clones
+    /// returned before
+    ///
+    /// # Panics
+    /// Panics once the 41-bit timestamp runs out, about 69 years after the
+    /// epoch.
+    pub fn generate(&self) -> u64 {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        self.next(&mut state)
+    }
+
+    /// Generates `count` increasing IDs, holding the lock once
+    ///
+    /// # Panics
+    /// Panics like [`Self::generate`].
+    pub fn generate_batch(&self, count: usize) -> Vec<u64> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        (0..count).map(|_| self.next(&mut state)).collect()
+    }
+
+    /// Split `id` into its timestamp, machine ID and sequence
+    ///
+    /// The timestamp is converted back to Unix milliseconds with this
+    /// generator's epoch, so decompose IDs with a generator of the same
+    /// epoch.
+    pub fn decompose(&self, id: u64) -> SnowflakeParts {
+        SnowflakeParts {
+            timestamp_ms: (id >> (MACHINE_ID_BITS + SEQUENCE_BITS)) + self.epoch_ms,
+            machine_id: ((id >> SEQUENCE_BITS) & u64::from(Self::MAX_MACHINE_ID)) as u16,
+            sequence: (id & u64::from(MAX_SEQUENCE)) as u16,
+        }
+    }
+
+    fn next(&self, state: &mut State) -> u64 {
+        // A clock that went backwards reuses the last timestamp
+        let mut now = self.now().max(state.timestamp);
+        if now > state.timestamp {
+            state.sequence = 0;
+        } else if state.sequence < MAX_SEQUENCE {
+            state.sequence += 1;
+        } else {
+            now = self.wait_past(state.timestamp);
+            state.sequence = 0;
+        }
+        assert!(
+            now <= MAX_TIMESTAMP,
+            "Snowflake timestamp exceeds {TIMESTAMP_BITS} bits, the epoch is too old"
+        );
+        state.timestamp = now;
+
+        (now << (MACHINE_ID_BITS + SEQUENCE_BITS))
+            | (u64::from(self.machine_id) << SEQUENCE_BITS)
+            | u64::from(state.sequence)
+    }
+
+    /// Spin until the clock passes `last`, or step past it if the clock is
+    /// behind
+    fn wait_past(&self, last: u64) -> u64 {
+        loop {
+            let now = self.now();
+            if now > last {
+                return now;
+            }
+            if now < last {
+                return last + 1;
+            }
+            hint::spin_loop();
+        }
+    }
+
+    /// Milliseconds since the epoch, 0 before it
+    fn now(&self) -> u64 {
+        let unix_ms = match &self.clock {
+            Some(clock) => clock.now_millis(),
+            None => source::system_millis(),
+        };
+        unix_ms.saturating_sub(self.epoch_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::HashSet,
+        sync::atomic::{AtomicU64, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    const EPOCH_MS: u64 = 1_577_836_800_000;
+
+    fn at_epoch(machine_id: u16) -> SnowflakeGenerator {
+        SnowflakeGenerator::new(machine_id, UNIX_EPOCH + Duration::from_millis(EPOCH_MS)).unwrap()
+    }
+
+    /// A clock reading `times` in turn, then repeating the last one
+    fn scripted_clock(times: Vec<u64>) -> impl Fn() -> u64 + Send + Sync + 'static {
+        let calls = AtomicU64::new(0);
+        move || {
+            let call = calls.fetch_add(1, Ordering::Relaxed) as usize;
+            EPOCH_MS + times[call.min(times.len() - 1)]
+        }
+    }
+
+    #[test]
+    fn test_decompose() {
+        let generator = at_epoch(5).with_clock(|| EPOCH_MS + 1_000);
+        let ids = generator.generate_batch(3);
+        for (sequence, id) in ids.into_iter().enumerate() {
+            assert_eq!(
+                generator.decompose(id),
+                SnowflakeParts {
+                    timestamp_ms: EPOCH_MS + 1_000,
+                    machine_id: 5,
+                    sequence: sequence as u16,
+                }
+            );
+            assert_eq!(id, (1_000 << 22) | (5 << 12) | sequence as u64);
+        }
+
+        let generator = at_epoch(SnowflakeGenerator::MAX_MACHINE_ID);
+        let parts = generator.decompose(generator.generate());
+        assert_eq!(parts.machine_id, 1023);
+        let now = source::system_millis();
+        assert!(parts.timestamp_ms <= now && parts.timestamp_ms + 1_000 > now);
+    }
+
+    #[test]
+    fn test_machine_id_bounds() {
+        assert_eq!(at_epoch(0).machine_id(), 0);
+        assert_eq!(at_epoch(1023).machine_id(), 1023);
+
+        let err = SnowflakeGenerator::new(1024, UNIX_EPOCH).unwrap_err();
+        assert_eq!(
+            err,
+            SnowflakeError::MachineIdOutOfRange {
+                machine_id: 1024,
+                max: 1023
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "machine ID 1024 exceeds the maximum of 1023"
+        );
+        assert!(SnowflakeGenerator::new(u16::MAX, UNIX_EPOCH).is_err());
+    }
+
+    #[test]
+    fn test_epoch_bounds() {
+        let future = SystemTime::now() + Duration::from_secs(3_600);
+        assert_eq!(
+            SnowflakeGenerator::new(1, future).unwrap_err(),
+            SnowflakeError::InvalidEpoch
+        );
+        let before_unix = UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(
+            SnowflakeGenerator::new(1, before_unix).unwrap_err(),
+            SnowflakeError::InvalidEpoch
+        );
+    }
+
+    #[test]
+    fn test_sequence_rollover_waits_for_next_millisecond() {
+        // The clock reads 1000 for the whole first millisecond and some
+        // spins, then moves to 1001
+        let mut times = vec![1_000; 4_096 + 10];
+        times.push(1_001);
+        let generator = at_epoch(1).with_clock(scripted_clock(times));
+
+        let ids = generator.generate_batch(4_097);
+        let last = generator.decompose(ids[4_095]);
+        assert_eq!(
+            (last.timestamp_ms, last.sequence),
+            (EPOCH_MS + 1_000, 4_095)
+        );
+        let rolled = generator.decompose(ids[4_096]);
+        assert_eq!(
+            (rolled.timestamp_ms, rolled.sequence),
+            (EPOCH_MS + 1_001, 0)
+        );
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_clock_regression_reuses_last_timestamp() {
+        let generator = at_epoch(1).with_clock(scripted_clock(vec![2_000, 1_500, 1_500]));
+        let ids = generator.generate_batch(3);
+        for (sequence, &id) in ids.iter().enumerate() {
+            let parts = generator.decompose(id);
+            assert_eq!(parts.timestamp_ms, EPOCH_MS + 2_000);
+            assert_eq!(parts.sequence, sequence as u16);
+        }
+    }
+
+    #[test]
+    fn test_clock_regression_with_exhausted_sequence_steps_ahead() {
+        let generator = at_epoch(1).with_clock(scripted_clock(vec![2_000, 1_500]));
+        let ids = generator.generate_batch(4_097);
+        let parts = generator.decompose(ids[4_096]);
+        assert_eq!((parts.timestamp_ms, parts.sequence), (EPOCH_MS + 2_001, 0));
+    }
+
+    #[test]
+    fn test_before_epoch_saturates() {
+        let generator = at_epoch(1).with_clock(|| EPOCH_MS - 5);
+        assert_eq!(
+            generator.decompose(generator.generate()).timestamp_ms,
+            EPOCH_MS
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds 41 bits")]
+    fn test_timestamp_overflow_panics() {
+        at_epoch(1).with_clock(|| EPOCH_MS + (1 << 41)).generate();
+    }
+
+    #[test]
+    fn test_monotonic_across_threads() {
+        let generator = at_epoch(3);
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let generator = generator.clone();
+                thread::spawn(move || {
+                    (0..20_000)
+                        .map(|_| generator.generate())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut all = HashSet::new();
+        for handle in handles {
+            let ids = handle.join().unwrap();
+            assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+            all.extend(ids);
+        }
+        assert_eq!(all.len(), 8 * 20_000);
+        assert!(all.iter().all(|&id| id < 1 << 63));
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+
+/// Bits of entropy in an ID of `length` characters drawn uniformly from an
+/// alphabet of `alphabet_len`, i.e. `length * log2(alphabet_len)`
+///
+/// Alphabets of fewer than 2 characters carry no information, so they give
+/// 0 bits. A prefix adds nothing and is not counted.
+///
+/// ```
+/// // The reference NanoID: 21 characters of a 64-character alphabet
+/// assert_eq!(gen_id::entropy_bits(64, 21), 126.0);
+/// ```
+pub fn entropy_bits(alphabet_len: usize, length: usize) -> f64 {
+    if alphabet_len < 2 {
+        return 0.0;
+    }
+    length as f64 * (alphabet_len as f64).log2()
+}
+
+/// Summary of a generator's configuration for a security review
+///
+/// `Display` renders one line with the numbers, followed by one indented
+/// line per note:
+///
+/// ```text
+/// UUID v7, 36 characters: 74.0 bits of entropy
+///   note: the first 48 bits are the creation time in milliseconds
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct IdStrengthReport {
+    /// Scheme and variant, e.g. `UUID v7` or `NanoID, 62-character alphabet`
+    pub scheme: String,
+    /// Characters in an ID, without prefix or suffix
+    pub length: usize,
+    /// Unpredictable bits per ID
+    pub entropy_bits: f64,
+    /// What a reviewer should know beyond the numbers, e.g. a RNG that is
+    /// not cryptographically secure
+    pub notes: Vec<String>,
+}
+
+impl IdStrengthReport {
+    pub(crate) fn new(scheme: impl Into<String>, length: usize, entropy_bits: f64) -> Self {
+        Self {
+            scheme: scheme.into(),
+            length,
+            entropy_bits,
+            notes: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+impl fmt::Display for IdStrengthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {} characters: {:.1} bits of entropy",
+            self.scheme, self.length, self.entropy_bits
+        )?;
+        for note in &self.notes {
+            write!(f, "\n  note: {note}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_bits(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 0.01, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_entropy_bits() {
+        assert_eq!(entropy_bits(64, 21), 126.0);
+        assert_eq!(entropy_bits(16, 32), 128.0);
+        assert_eq!(entropy_bits(2, 122), 122.0);
+        assert_bits(entropy_bits(62, 12), 71.45);
+        assert_bits(entropy_bits(36, 24), 124.08);
+        assert_eq!(entropy_bits(1, 100), 0.0);
+        assert_eq!(entropy_bits(0, 100), 0.0);
+        assert_eq!(entropy_bits(62, 0), 0.0);
+    }
+
+    #[test]
+    fn test_display() {
+        let report = IdStrengthReport::new("UUID v7", 36, 74.0)
+            .with_note("the first 48 bits are the creation time in milliseconds");
+        assert_eq!(
+            report.to_string(),
+            "UUID v7, 36 characters: 74.0 bits of entropy\n  \
+             note: the first 48 bits are the creation time in milliseconds"
+        );
+        assert_eq!(
+            IdStrengthReport::new("NanoID, 62-character alphabet", 12, entropy_bits(62, 12))
+                .to_string(),
+            "NanoID, 62-character alphabet, 12 characters: 71.5 bits of entropy"
+        );
+    }
+}
@@ -0,0 +1,477 @@
+use std::{
+    fmt, hint,
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use rand::RngCore;
+use uuid::Uuid;
+
+use crate::{
+    IdGenerator,
+    source::{self, Clock},
+    uuid::{
+        EncodingError,
+        encoding::{self, BASE32_LEN},
+    },
+};
+
+const RANDOM_BITS: u32 = 80;
+const MAX_RANDOM: u128 = (1 << RANDOM_BITS) - 1;
+const MAX_TIMESTAMP: u64 = (1 << 48) - 1;
+
+/// Error for a string that is not a ULID, or a ULID that cannot be made
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum UlidError {
+    #[error("ULIDs are 26 characters long, found {found}")]
+    InvalidLength { found: usize },
+
+    #[error("{ch:?} at index {index} is not in the Crockford base32 alphabet")]
+    InvalidChar { ch: char, index: usize },
+
+    #[error("ULID value does not fit in 128 bits, the first character must be 0-7")]
+    Overflow,
+
+    #[error("timestamp {0} does not fit in 48 bits")]
+    TimestampOutOfRange(u64),
+
+    #[error("random component exhausted within millisecond {timestamp_ms}")]
+    RandomOverflow { timestamp_ms: u64 },
+}
+
+impl From<EncodingError> for UlidError {
+    fn from(err: EncodingError) -> Self {
+        match err {
+            EncodingError::InvalidLength { found, .. } => Self::InvalidLength { found },
+            EncodingError::InvalidChar { ch, index, .. }
+            | EncodingError::MalformedGroup { ch, index, .. } => Self::InvalidChar { ch, index },
+            EncodingError::Overflow { .. } => Self::Overflow,
+        }
+    }
+}
+
+/// A Universally Unique Lexicographically Sortable Identifier
+///
+/// 48 bits of Unix milliseconds followed by 80 random bits, written as 26
+/// Crockford base32 characters without hyphens. The strings sort by time
+/// whatever their case, and [`Ord`] orders by creation.
+///
+/// The 128 bits line up with a UUID, so ULIDs convert to and from
+/// [`Uuid`] losslessly. The result carries no UUID version.
+///
+/// ```
+/// use gen_id::Ulid;
+///
+/// let ulid: Ulid = "01arz3ndektsv4rrffq69g5fav".parse()?;
+/// assert_eq!(ulid.timestamp_ms(), 1_469_922_850_259);
+/// assert_eq!(ulid.to_string(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+/// # Ok::<(), gen_id::UlidError>(())
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Ulid(u128);
+
+impl Ulid {
+    /// Length of the base32 string form
+    pub const ENCODED_LEN: usize = BASE32_LEN;
+
+    /// All zero bits, `00000000000000000000000000`
+    pub const NIL: Self = Self(0);
+
+    /// All one bits, `7ZZZZZZZZZZZZZZZZZZZZZZZZZ`
+    pub const MAX: Self = Self(u128::MAX);
+
+    /// Creates a ULID from Unix milliseconds and the low 80 bits of `random`
+    ///
+    /// Fails for timestamps beyond 48 bits, after the year 10889.
+    pub fn from_parts(timestamp_ms: u64, random: u128) -> Result<Self, UlidError> {
+        if timestamp_ms > MAX_TIMESTAMP {
+            return Err(UlidError::TimestampOutOfRange(timestamp_ms));
+        }
+        Ok(Self::new(timestamp_ms, random))
+    }
+
+    #[inline]
+    fn new(timestamp_ms: u64, random: u128) -> Self {
+        Self(u128::from(timestamp_ms) << RANDOM_BITS | random & MAX_RANDOM)
+    }
+
+    #[inline]
+    pub const fn from_u128(value: u128) -> Self {
+        Self(value)
+    }
+
+    #[inline]
+    pub const fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    /// Creation time in Unix milliseconds
+    #[inline]
+    pub const fn timestamp_ms(&self) -> u64 {
+        (self.0 >> RANDOM_BITS) as u64
+    }
+
+    /// The 80 random bits after the timestamp
+    #[inline]
+    pub const fn random(&self) -> u128 {
+        self.0 & MAX_RANDOM
+    }
+
+    /// The same 128 bits as a UUID
+    #[inline]
+    pub const fn to_uuid(&self) -> Uuid {
+        Uuid::from_u128(self.0)
+    }
+
+    /// Decode 26 Crockford base32 characters, in either case
+    ///
+    /// As in the spec, `I` and `L` read as 1 and `O` as 0.
+    pub fn parse(input: &str) -> Result<Self, UlidError> {
+        Ok(Self(encoding::decode_base32(input)?.as_u128()))
+    }
+}
+
+impl fmt::Display for Ulid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(encoding::encode_base32(
+            &self.to_uuid(),
+            &mut [0; Self::ENCODED_LEN],
+        ))
+    }
+}
+
+impl fmt::Debug for Ulid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ulid({self})")
+    }
+}
+
+impl FromStr for Ulid {
+    type Err = UlidError;
+
+    #[inline]
+    fn from_str(input: &str) -> Result<Self, UlidError> {
+        Self::parse(input)
+    }
+}
+
+impl From<Uuid> for Ulid {
+    #[inline]
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid.as_u128())
+    }
+}
+
+impl From<Ulid> for Uuid {
+    #[inline]
+    fn from(ulid: Ulid) -> Self {
+        ulid.to_uuid()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Ulid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Ulid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        Self::parse(&input).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Generator of [`Ulid`]s, random or monotonic
+///
+/// In monotonic mode a ULID within the same millisecond as the previous one
+/// reuses its timestamp and increments its random part, so IDs from one
+/// generator and its clones strictly increase. A clock that went backwards
+/// is treated as the same millisecond. When the increment would overflow 80
+/// bits, [`Self::try_generate`] fails as the spec requires and
+/// [`Self::generate`] waits for the next millisecond.
+///
+/// ```
+/// use gen_id::UlidGenerator;
+///
+/// let generator = UlidGenerator::new().with_monotonic(true);
+/// let first = generator.generate();
+/// let second = generator.generate();
+/// assert!(second > first);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UlidGenerator {
+    monotonic: bool,
+    clock: Option<Clock>,
+    last: Arc<Mutex<Option<Ulid>>>,
+}
+
+impl UlidGenerator {
+    /// Creates a generator of independent random ULIDs
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Increment the random part within a millisecond instead of drawing a
+    /// new one
+    #[inline]
+    pub fn with_monotonic(mut self, monotonic: bool) -> Self {
+        self.monotonic = monotonic;
+        self
+    }
+
+    /// Take timestamps from `clock`, in milliseconds since the Unix epoch,
+    /// instead of the system time
+    ///
+    /// Values beyond 48 bits are truncated.
+    #[inline]
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Some(Clock::new(clock));
+        self
+    }
+
+    /// Generates one ULID, waiting for the next millisecond if a monotonic
+    /// generator used up the current one
+    pub fn generate(&self) -> Ulid {
+        self.next(true)
+            .expect("waiting for the next millisecond cannot overflow")
+    }
+
+    /// Generates one ULID, failing with [`UlidError::RandomOverflow`] if a
+    /// monotonic generator used up the current millisecond
+    pub fn try_generate(&self) -> Result<Ulid, UlidError> {
+        self.next(false)
+    }
+
+    /// Generates `count` ULIDs, see [`Self::generate`]
+    pub fn generate_batch(&self, count: usize) -> Vec<Ulid> {
+        (0..count).map(|_| self.generate()).collect()
+    }
+
+    fn next(&self, wait: bool) -> Result<Ulid, UlidError> {
+        if !self.monotonic {
+            return Ok(Ulid::new(self.now(), random()));
+        }
+
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = self.now();
+        let ulid = match *last {
+            Some(prev) if now <= prev.timestamp_ms() => {
+                if prev.random() < MAX_RANDOM {
+                    Ulid(prev.0 + 1)
+                } else if wait {
+                    Ulid::new(self.wait_past(prev.timestamp_ms()), random())
+                } else {
+                    return Err(UlidError::RandomOverflow {
+                        timestamp_ms: prev.timestamp_ms(),
+                    });
+                }
+            }
+            _ => Ulid::new(now, random()),
+        };
+        *last = Some(ulid);
+        Ok(ulid)
+    }
+
+    /// Spin until the clock passes `last`, or step past it if the clock is
+    /// behind
+    fn wait_past(&self, last: u64) -> u64 {
+        loop {
+            let now = self.now();
+            if now > last {
+                return now;
+            }
+            if now < last {
+                return last + 1;
+            }
+            hint::spin_loop();
+        }
+    }
+
+    fn now(&self) -> u64 {
+        let millis = match &self.clock {
+            Some(clock) => clock.now_millis(),
+            None => source::system_millis(),
+        };
+        millis & MAX_TIMESTAMP
+    }
+}
+
+fn random() -> u128 {
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes[6..]);
+    u128::from_be_bytes(bytes)
+}
+
+/// Base32 strings, e.g. to switch a tenant from UUIDs to ULIDs
+impl IdGenerator for UlidGenerator {
+    #[inline]
+    fn generate(&self) -> String {
+        UlidGenerator::generate(self).to_string()
+    }
+
+    #[inline]
+    fn generate_batch(&self, count: usize) -> Vec<String> {
+        (0..count).map(|_| IdGenerator::generate(self)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn test_reference_values() {
+        // From the ULID spec's README
+        let ulid = Ulid::parse("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+        assert_eq!(ulid.timestamp_ms(), 1_469_922_850_259);
+        let seeded = Ulid::from_parts(1_469_918_176_385, ulid.random()).unwrap();
+        assert_eq!(seeded.to_string(), "01ARYZ6S41TSV4RRFFQ69G5FAV");
+
+        assert_eq!(Ulid::MAX.to_string(), "7ZZZZZZZZZZZZZZZZZZZZZZZZZ");
+        assert_eq!(Ulid::parse("7ZZZZZZZZZZZZZZZZZZZZZZZZZ"), Ok(Ulid::MAX));
+        assert_eq!(Ulid::MAX.timestamp_ms(), MAX_TIMESTAMP);
+        assert_eq!(Ulid::MAX.random(), MAX_RANDOM);
+        assert_eq!(Ulid::NIL.to_string(), "0".repeat(26));
+    }
+
+    #[test]
+    fn test_parse_is_case_insensitive() {
+        let upper = Ulid::parse("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+        assert_eq!(Ulid::parse("01arz3ndektsv4rrffq69g5fav"), Ok(upper));
+        assert_eq!(Ulid::parse("01ArZ3nDeKtSv4RrFfQ69g5FaV"), Ok(upper));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Ulid::parse("80000000000000000000000000"),
+            Err(UlidError::Overflow)
+        );
+        assert_eq!(
+            Ulid::parse("01ARZ3NDEKTSV4RRFFQ69G5FA"),
+            Err(UlidError::InvalidLength { found: 25 })
+        );
+        assert_eq!(
+            Ulid::parse("01ARZ3NDEKTSV4RRFFQ69G5FAU"),
+            Err(UlidError::InvalidChar { ch: 'U', index: 25 })
+        );
+        assert_eq!(
+            Ulid::from_parts(MAX_TIMESTAMP + 1, 0),
+            Err(UlidError::TimestampOutOfRange(MAX_TIMESTAMP + 1))
+        );
+    }
+
+    #[test]
+    fn test_uuid_round_trip() {
+        let ulid = Ulid::parse("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+        let uuid = Uuid::from(ulid);
+        assert_eq!(uuid.as_u128(), ulid.as_u128());
+        assert_eq!(Ulid::from(uuid), ulid);
+        assert_eq!(Ulid::from(Uuid::max()), Ulid::MAX);
+    }
+
+    #[test]
+    fn test_sorts_across_milliseconds() {
+        let ulids: Vec<Ulid> = (0..100)
+            .map(|millis| Ulid::from_parts(1_700_000_000_000 + millis, random()).unwrap())
+            .collect();
+        assert!(ulids.is_sorted());
+        let strings: Vec<String> = ulids.iter().map(Ulid::to_string).collect();
+        assert!(strings.is_sorted());
+        let lower: Vec<String> = strings.iter().map(|s| s.to_lowercase()).collect();
+        assert!(lower.is_sorted());
+    }
+
+    #[test]
+    fn test_generate_uses_clock() {
+        let generator = UlidGenerator::new().with_clock(|| 1_700_000_000_000);
+        let ulid = generator.generate();
+        assert_eq!(ulid.timestamp_ms(), 1_700_000_000_000);
+        assert_ne!(generator.generate(), ulid);
+
+        let now = source::system_millis();
+        assert!(UlidGenerator::new().generate().timestamp_ms().abs_diff(now) < 1_000);
+    }
+
+    #[test]
+    fn test_monotonic_increments_within_millisecond() {
+        let generator = UlidGenerator::new()
+            .with_monotonic(true)
+            .with_clock(|| 1_000);
+        let ulids = generator.generate_batch(1_000);
+        for pair in ulids.windows(2) {
+            assert_eq!(pair[1].as_u128(), pair[0].as_u128() + 1);
+        }
+        assert!(ulids.iter().all(|ulid| ulid.timestamp_ms() == 1_000));
+    }
+
+    #[test]
+    fn test_monotonic_survives_clock_regression() {
+        let calls = AtomicU64::new(0);
+        let generator = UlidGenerator::new()
+            .with_monotonic(true)
+            .with_clock(move || 2_000 - calls.fetch_add(1, Ordering::Relaxed).min(1) * 500);
+        let first = generator.generate();
+        let second = generator.generate();
+        assert_eq!(second.timestamp_ms(), 2_000);
+        assert_eq!(second.as_u128(), first.as_u128() + 1);
+    }
+
+    #[test]
+    fn test_monotonic_overflow() {
+        let calls = Arc::new(AtomicU64::new(0));
+        let clock_calls = Arc::clone(&calls);
+        let generator = UlidGenerator::new()
+            .with_monotonic(true)
+            .with_clock(move || 1_000 + clock_calls.load(Ordering::Relaxed));
+        *generator.last.lock().unwrap() = Some(Ulid::new(1_000, MAX_RANDOM));
+
+        assert_eq!(
+            generator.try_generate(),
+            Err(UlidError::RandomOverflow {
+                timestamp_ms: 1_000
+            })
+        );
+
+        // generate waits for the clock to reach the next millisecond
+        calls.store(1, Ordering::Relaxed);
+        let next = generator.generate();
+        assert_eq!(next.timestamp_ms(), 1_001);
+        assert!(next > Ulid::new(1_000, MAX_RANDOM));
+    }
+
+    #[test]
+    fn test_monotonic_across_threads() {
+        let generator = UlidGenerator::new().with_monotonic(true);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let generator = generator.clone();
+                std::thread::spawn(move || generator.generate_batch(5_000))
+            })
+            .collect();
+        let mut all = Vec::new();
+        for handle in handles {
+            let ulids = handle.join().unwrap();
+            assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+            all.extend(ulids);
+        }
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), 20_000);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let ulid = Ulid::parse("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+        let json = serde_json::to_string(&ulid).unwrap();
+        assert_eq!(json, "\"01ARZ3NDEKTSV4RRFFQ69G5FAV\"");
+        assert_eq!(serde_json::from_str::<Ulid>(&json).unwrap(), ulid);
+    }
+}
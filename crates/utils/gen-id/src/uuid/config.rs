@@ -0,0 +1,202 @@
+use uuid::Uuid;
+
+use super::{
+    format::{UuidFormat, UuidVersion},
+    generator::{NamedUuidGenerator, UuidGenerator, VersionError},
+};
+
+/// Declarative settings for a [`UuidGenerator`], e.g. from a config file
+///
+/// Missing fields fall back to a v4 generator with standard format and no
+/// prefix or suffix. A v5 config needs a `namespace` and is built with
+/// [`Self::build_named`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[non_exhaustive]
+pub struct UuidGeneratorConfig {
+    pub version: UuidVersion,
+    pub format: UuidFormat,
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    pub namespace: Option<Uuid>,
+}
+
+impl UuidGeneratorConfig {
+    pub fn with_version(mut self, version: UuidVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn with_format(mut self, format: UuidFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = Some(suffix.into());
+        self
+    }
+
+    pub fn with_namespace(mut self, namespace: Uuid) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
+    /// Create a v4 or v7 generator with these settings
+    ///
+    /// Fails with [`VersionError::NameBased`] for v5, see
+    /// [`Self::build_named`].
+    pub fn build(&self) -> Result<UuidGenerator, VersionError> {
+        let mut generator = UuidGenerator::try_new(self.version, self.format)?;
+        if let Some(prefix) = &self.prefix {
+            generator = generator.with_prefix(prefix.clone());
+        }
+        if let Some(suffix) = &self.suffix {
+            generator = generator.with_suffix(suffix.clone());
+        }
+        Ok(generator)
+    }
+
+    /// Create a v5 generator with these settings
+    ///
+    /// Fails for other versions and when no namespace is set.
+    pub fn build_named(&self) -> Result<NamedUuidGenerator, VersionError> {
+        if self.version != UuidVersion::V5 {
+            return Err(VersionError::NotNameBased(self.version));
+        }
+        let namespace = self.namespace.ok_or(VersionError::MissingNamespace)?;
+        let mut generator = NamedUuidGenerator::new(namespace).with_format(self.format);
+        if let Some(prefix) = &self.prefix {
+            generator = generator.with_prefix(prefix.clone());
+        }
+        if let Some(suffix) = &self.suffix {
+            generator = generator.with_suffix(suffix.clone());
+        }
+        Ok(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_applies_settings() {
+        let generator = UuidGeneratorConfig::default()
+            .with_version(UuidVersion::V7)
+            .with_format(UuidFormat::Simple)
+            .with_prefix("trade_")
+            .with_suffix("_sg1")
+            .build()
+            .unwrap();
+        let uuid = generator.generate_uuid();
+
+        assert_eq!(uuid.get_version_num(), 7);
+        assert_eq!(
+            generator.format(&uuid),
+            format!("trade_{}_sg1", uuid.simple())
+        );
+    }
+
+    #[test]
+    fn test_build_v5_with_namespace() {
+        let config = UuidGeneratorConfig::default()
+            .with_version(UuidVersion::V5)
+            .with_namespace(Uuid::NAMESPACE_DNS);
+
+        assert_eq!(
+            config.build_named().unwrap().generate_named(b"python.org"),
+            "886313e1-3b8a-5372-9b90-0c9aee199e5d"
+        );
+        assert_eq!(config.build().unwrap_err(), VersionError::NameBased);
+    }
+
+    #[test]
+    fn test_build_named_needs_v5_and_namespace() {
+        let config = UuidGeneratorConfig::default().with_version(UuidVersion::V5);
+        assert_eq!(
+            config.build_named().unwrap_err(),
+            VersionError::MissingNamespace
+        );
+
+        let config = UuidGeneratorConfig::default().with_namespace(Uuid::NAMESPACE_DNS);
+        assert_eq!(
+            config.build_named().unwrap_err(),
+            VersionError::NotNameBased(UuidVersion::V4)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_enum_serde_names() {
+        let formats = [
+            (UuidFormat::Standard, "standard"),
+            (UuidFormat::Simple, "simple"),
+            (UuidFormat::StandardUppercase, "standard_uppercase"),
+            (UuidFormat::SimpleUppercase, "simple_uppercase"),
+            (UuidFormat::Braced, "braced"),
+            (UuidFormat::BracedUppercase, "braced_uppercase"),
+            (UuidFormat::Urn, "urn"),
+            (UuidFormat::Base32Crockford, "base32_crockford"),
+            (UuidFormat::Base58, "base58"),
+            (UuidFormat::Base62, "base62"),
+        ];
+        for (format, name) in formats {
+            let json = format!("\"{name}\"");
+            assert_eq!(serde_json::to_string(&format).unwrap(), json);
+            assert_eq!(serde_json::from_str::<UuidFormat>(&json).unwrap(), format);
+        }
+
+        let versions = [
+            (UuidVersion::V4, "v4"),
+            (UuidVersion::V7, "v7"),
+            (UuidVersion::V5, "v5"),
+        ];
+        for (version, name) in versions {
+            let json = format!("\"{name}\"");
+            assert_eq!(serde_json::to_string(&version).unwrap(), json);
+            assert_eq!(serde_json::from_str::<UuidVersion>(&json).unwrap(), version);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_unknown_variant_lists_accepted_values() {
+        let err = serde_json::from_str::<UuidFormat>("\"hex\"")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown variant `hex`"), "{err}");
+        assert!(err.contains("`simple_uppercase`"), "{err}");
+
+        let err = serde_json::from_str::<UuidVersion>("\"v1\"")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`v4`, `v7`, `v5`"), "{err}");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_config_deserialize() {
+        let config: UuidGeneratorConfig =
+            serde_json::from_str(r#"{"version": "v7", "prefix": "order_"}"#).unwrap();
+
+        assert_eq!(
+            config,
+            UuidGeneratorConfig::default()
+                .with_version(UuidVersion::V7)
+                .with_prefix("order_")
+        );
+
+        let config: UuidGeneratorConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, UuidGeneratorConfig::default());
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+use sqlx::{Database, Decode, Encode, Type, encode::IsNull, error::BoxDynError};
+use uuid::Uuid;
+
+/// A UUID stored in its 16 raw bytes, for Postgres `uuid` and SQLite
+/// `BLOB` columns
+///
+/// Encodes and decodes like [`Uuid`] in every sqlx backend that supports it,
+/// so a v7 ID takes 16 bytes instead of 36 or more as text. Prefixes are not
+/// stored: they belong to the application's formatting, e.g.
+/// [`UuidGenerator::format`](super::UuidGenerator::format) when rendering
+/// and [`parse_prefixed`](super::parse_prefixed) when reading input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DbUuid(pub Uuid);
+
+impl From<Uuid> for DbUuid {
+    #[inline]
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
+impl From<DbUuid> for Uuid {
+    #[inline]
+    fn from(uuid: DbUuid) -> Self {
+        uuid.0
+    }
+}
+
+/// Hyphenated lowercase, without any prefix
+impl fmt::Display for DbUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<DB: Database> Type<DB> for DbUuid
+where
+    Uuid: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <Uuid as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <Uuid as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database> Encode<'q, DB> for DbUuid
+where
+    Uuid: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.0.encode_by_ref(buf)
+    }
+}
+
+impl<'r, DB: Database> Decode<'r, DB> for DbUuid
+where
+    Uuid: Decode<'r, DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        Uuid::decode(value).map(Self)
+    }
+}
@@ -0,0 +1,543 @@
+use alloc::string::{String, ToString};
+
+use uuid::Uuid;
+
+use super::{format::UuidFormat, parser::ParseError};
+
+/// Crockford's base32 alphabet, without I, L, O and U
+const BASE32_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// Bitcoin's base58 alphabet, without 0, O, I and l
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// Digits, then uppercase, then lowercase, in ASCII order
+pub(crate) const BASE62_ALPHABET: &[u8; 62] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// 26 characters of 5 bits hold 128 bits with 2 to spare
+pub(crate) const BASE32_LEN: usize = 26;
+/// 58^22 is the smallest power of 58 above 2^128
+pub(crate) const BASE58_LEN: usize = 22;
+/// 62^22 is the smallest power of 62 above 2^128
+pub(crate) const BASE62_LEN: usize = 22;
+
+/// Proquint consonants, 4 bits each
+const PROQUINT_CONSONANTS: &[u8; 16] = b"bdfghjklmnprstvz";
+/// Proquint vowels, 2 bits each
+const PROQUINT_VOWELS: &[u8; 4] = b"aiou";
+/// Eight 5-letter quints of 16 bits each, joined by 7 dashes
+pub(crate) const PROQUINT_LEN: usize = 47;
+
+/// Error for a Crockford base32, base58, base62 or proquint string that is
+/// not a UUID
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EncodingError {
+    #[error("{format} UUIDs are {expected} characters long, found {found}")]
+    InvalidLength {
+        format: UuidFormat,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("{ch:?} at index {index} is not in the {format} alphabet")]
+    InvalidChar {
+        format: UuidFormat,
+        ch: char,
+        index: usize,
+    },
+
+    #[error("{format} value does not fit in 128 bits")]
+    Overflow { format: UuidFormat },
+
+    /// A proquint group with a letter or separator out of place, e.g. a
+    /// vowel where a consonant belongs
+    ///
+    /// `group` counts from 0, like `index`.
+    #[error(
+        "{format} group {group} is malformed: expected a {expected} at index {index}, found {ch:?}"
+    )]
+    MalformedGroup {
+        format: UuidFormat,
+        group: usize,
+        index: usize,
+        ch: char,
+        expected: &'static str,
+    },
+}
+
+/// Encode `uuid` as a fixed-width 22-character base62 string
+///
+/// Short enough for URLs and reversible with [`from_short`]. UUIDs with
+/// leading zero bits are left-padded with `0`, never truncated, so every
+/// UUID gives 22 characters and v7 IDs still sort by time.
+pub fn to_short(uuid: &Uuid) -> String {
+    let mut buf = [0u8; BASE62_LEN];
+    encode_base62(uuid, &mut buf).to_string()
+}
+
+/// Decode a 22-character base62 string from [`to_short`]
+///
+/// Any other length returns [`EncodingError::InvalidLength`] and a character
+/// outside `0-9A-Za-z` returns [`EncodingError::InvalidChar`], both wrapped
+/// in [`ParseError::Encoding`].
+pub fn from_short(input: &str) -> Result<Uuid, ParseError> {
+    Ok(decode_base62(input)?)
+}
+
+/// Encode `uuid` as eight pronounceable proquint groups joined by dashes
+///
+/// Each group of consonant, vowel, consonant, vowel, consonant spells 16
+/// bits, most significant first, as in the
+/// [proquint spec](https://arxiv.org/html/0901.4016), so IDs can be read
+/// aloud and typed back without hex mistakes.
+///
+/// ```
+/// use gen_id::{Uuid, from_proquint, to_proquint};
+///
+/// let uuid = Uuid::from_u128(0x7f00_0001_3f54_dcc1_3f76_0723_8c62_c18d);
+/// let proquint = to_proquint(&uuid);
+/// assert_eq!(proquint, "lusab-babad-gutih-tugad-gutuk-bisog-mudof-sakat");
+/// assert_eq!(from_proquint(&proquint.to_uppercase())?, uuid);
+/// # Ok::<(), gen_id::ParseError>(())
+/// ```
+pub fn to_proquint(uuid: &Uuid) -> String {
+    let mut buf = [0u8; PROQUINT_LEN];
+    encode_proquint(uuid, &mut buf).to_string()
+}
+
+/// Decode 47 characters from [`to_proquint`], in either case
+///
+/// Any other length returns [`EncodingError::InvalidLength`]. A letter or
+/// dash out of place returns [`EncodingError::MalformedGroup`] with the
+/// group and index. Both are wrapped in [`ParseError::Encoding`].
+pub fn from_proquint(input: &str) -> Result<Uuid, ParseError> {
+    Ok(decode_proquint(input)?)
+}
+
+/// Write `uuid` as 26 uppercase Crockford base32 characters
+///
+/// Fixed width with the alphabet in ASCII order, so encoded v7 UUIDs sort
+/// like the UUIDs themselves.
+#[inline]
+pub(crate) fn encode_base32<'a>(uuid: &Uuid, buf: &'a mut [u8]) -> &'a str {
+    encode_radix(uuid, BASE32_ALPHABET, &mut buf[..BASE32_LEN])
+}
+
+/// Write `uuid` as 22 base58 characters, left-padded with `1` (zero)
+#[inline]
+pub(crate) fn encode_base58<'a>(uuid: &Uuid, buf: &'a mut [u8]) -> &'a str {
+    encode_radix(uuid, BASE58_ALPHABET, &mut buf[..BASE58_LEN])
+}
+
+/// Write `uuid` as 22 base62 characters, left-padded with `0`
+#[inline]
+pub(crate) fn encode_base62<'a>(uuid: &Uuid, buf: &'a mut [u8]) -> &'a str {
+    encode_radix(uuid, BASE62_ALPHABET, &mut buf[..BASE62_LEN])
+}
+
+/// Fill all of `buf` with the digits of `uuid`, most significant first
+fn encode_radix<'a>(uuid: &Uuid, alphabet: &[u8], buf: &'a mut [u8]) -> &'a str {
+    let radix = alphabet.len() as u128;
+    let mut value = uuid.as_u128();
+    for slot in buf.iter_mut().rev() {
+        *slot = alphabet[(value % radix) as usize];
+        value /= radix;
+    }
+    core::str::from_utf8(buf).expect("the alphabet is ASCII")
+}
+
+/// Write `uuid` as 47 lowercase proquint characters
+pub(crate) fn encode_proquint<'a>(uuid: &Uuid, buf: &'a mut [u8]) -> &'a str {
+    let buf = &mut buf[..PROQUINT_LEN];
+    for (group, pair) in uuid.as_bytes().chunks_exact(2).enumerate() {
+        let word = usize::from(u16::from_be_bytes([pair[0], pair[1]]));
+        let start = group * 6;
+        if group > 0 {
+            buf[start - 1] = b'-';
+        }
+        buf[start..start + 5].copy_from_slice(&[
+            PROQUINT_CONSONANTS[word >> 12],
+            PROQUINT_VOWELS[word >> 10 & 0x3],
+            PROQUINT_CONSONANTS[word >> 6 & 0xf],
+            PROQUINT_VOWELS[word >> 4 & 0x3],
+            PROQUINT_CONSONANTS[word & 0xf],
+        ]);
+    }
+    core::str::from_utf8(buf).expect("proquints are ASCII")
+}
+
+/// Decode a proquint, case-insensitive
+pub(crate) fn decode_proquint(input: &str) -> Result<Uuid, EncodingError> {
+    let format = UuidFormat::Proquint;
+    check_len(format, input, PROQUINT_LEN)?;
+
+    let mut value: u128 = 0;
+    for (position, (index, ch)) in input.char_indices().enumerate() {
+        let lower = ch.to_ascii_lowercase();
+        let (alphabet, expected): (&[u8], _) = match position % 6 {
+            5 if ch == '-' => continue,
+            5 => (&[], "dash"),
+            0 | 2 | 4 => (PROQUINT_CONSONANTS, "consonant"),
+            _ => (PROQUINT_VOWELS, "vowel"),
+        };
+        let digit = alphabet
+            .iter()
+            .position(|&c| char::from(c) == lower)
+            .ok_or(EncodingError::MalformedGroup {
+                format,
+                group: position / 6,
+                index,
+                ch,
+                expected,
+            })?;
+        value = value << alphabet.len().trailing_zeros() | digit as u128;
+    }
+    Ok(Uuid::from_u128(value))
+}
+
+/// Decode Crockford base32, case-insensitive and reading `I`/`L` as 1 and
+/// `O` as 0
+pub(crate) fn decode_base32(input: &str) -> Result<Uuid, EncodingError> {
+    let format = UuidFormat::Base32Crockford;
+    check_len(format, input, BASE32_LEN)?;
+
+    let mut value: u128 = 0;
+    for (index, ch) in input.char_indices() {
+        let digit = match ch.to_ascii_uppercase() {
+            'O' => 0,
+            'I' | 'L' => 1,
+            upper => BASE32_ALPHABET
+                .iter()
+                .position(|&c| char::from(c) == upper)
+                .ok_or(EncodingError::InvalidChar { format, ch, index })?,
+        };
+        if value >> 123 != 0 {
+            return Err(EncodingError::Overflow { format });
+        }
+        value = value << 5 | digit as u128;
+    }
+    Ok(Uuid::from_u128(value))
+}
+
+/// Decode fixed-width base58
+#[inline]
+pub(crate) fn decode_base58(input: &str) -> Result<Uuid, EncodingError> {
+    decode_radix(UuidFormat::Base58, BASE58_ALPHABET, BASE58_LEN, input)
+}
+
+/// Decode fixed-width base62, which is case-sensitive
+#[inline]
+pub(crate) fn decode_base62(input: &str) -> Result<Uuid, EncodingError> {
+    decode_radix(UuidFormat::Base62, BASE62_ALPHABET, BASE62_LEN, input)
+}
+
+fn decode_radix(
+    format: UuidFormat,
+    alphabet: &[u8],
+    len: usize,
+    input: &str,
+) -> Result<Uuid, EncodingError> {
+    check_len(format, input, len)?;
+
+    let radix = alphabet.len() as u128;
+    let mut value: u128 = 0;
+    for (index, ch) in input.char_indices() {
+        let digit = alphabet
+            .iter()
+            .position(|&c| char::from(c) == ch)
+            .ok_or(EncodingError::InvalidChar { format, ch, index })?;
+        value = value
+            .checked_mul(radix)
+            .and_then(|value| value.checked_add(digit as u128))
+            .ok_or(EncodingError::Overflow { format })?;
+    }
+    Ok(Uuid::from_u128(value))
+}
+
+fn check_len(format: UuidFormat, input: &str, expected: usize) -> Result<(), EncodingError> {
+    // Characters rather than bytes, so a multi-byte character is reported as
+    // such instead of as a length mismatch
+    let found = input.chars().count();
+    if found != expected {
+        return Err(EncodingError::InvalidLength {
+            format,
+            expected,
+            found,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(format: UuidFormat, uuid: &Uuid) -> String {
+        let mut buf = [0u8; BASE32_LEN];
+        match format {
+            UuidFormat::Base32Crockford => encode_base32(uuid, &mut buf).to_string(),
+            _ => encode_base58(uuid, &mut buf).to_string(),
+        }
+    }
+
+    #[test]
+    fn test_known_values() {
+        let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let cases = [
+            (uuid, "2N1T201RMV87AAE5J4CSAM8000", "BWBeN28Vb7cMEx7Ym8AUzs"),
+            (
+                Uuid::nil(),
+                "00000000000000000000000000",
+                "1111111111111111111111",
+            ),
+            (
+                Uuid::max(),
+                "7ZZZZZZZZZZZZZZZZZZZZZZZZZ",
+                "YcVfxkQb6JRzqk5kF2tNLv",
+            ),
+        ];
+
+        for (uuid, base32, base58) in cases {
+            assert_eq!(encode(UuidFormat::Base32Crockford, &uuid), base32);
+            assert_eq!(encode(UuidFormat::Base58, &uuid), base58);
+            assert_eq!(decode_base32(base32), Ok(uuid));
+            assert_eq!(decode_base58(base58), Ok(uuid));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_round_trip_random() {
+        for _ in 0..1_000 {
+            let uuid = Uuid::new_v4();
+            assert_eq!(
+                decode_base32(&encode(UuidFormat::Base32Crockford, &uuid)),
+                Ok(uuid)
+            );
+            assert_eq!(decode_base58(&encode(UuidFormat::Base58, &uuid)), Ok(uuid));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_short_round_trip() {
+        let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(to_short(&uuid), "2aUyqjCzEIiEcYMKj7TZtw");
+        assert_eq!(to_short(&Uuid::nil()), "0".repeat(22));
+        assert_eq!(to_short(&Uuid::max()), "7n42DGM5Tflk9n8mt7Fhc7");
+
+        for _ in 0..5_000 {
+            let uuid = Uuid::new_v4();
+            let short = to_short(&uuid);
+            assert_eq!(short.len(), 22, "{uuid}");
+            assert_eq!(from_short(&short).unwrap(), uuid);
+        }
+
+        // Leading zero bits pad instead of shortening the string
+        for value in [0, 1, 61, 62, u64::MAX as u128, 1 << 100] {
+            let uuid = Uuid::from_u128(value);
+            let short = to_short(&uuid);
+            assert_eq!(short.len(), 22, "{value}");
+            assert_eq!(from_short(&short).unwrap(), uuid);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_short_sorts_like_uuid() {
+        let mut uuids: Vec<Uuid> = (0..1_000).map(|_| Uuid::new_v4()).collect();
+        uuids.sort();
+        let shorts: Vec<String> = uuids.iter().map(to_short).collect();
+        assert!(shorts.is_sorted());
+    }
+
+    #[test]
+    fn test_from_short_errors() {
+        let err = from_short("2aUyqjCzEIiEcYMKj7TZt").unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::Encoding(EncodingError::InvalidLength {
+                format: UuidFormat::Base62,
+                expected: 22,
+                found: 21,
+            })
+        ));
+        assert!(matches!(
+            from_short("2aUyqjCzEIiEcYMKj7TZt-").unwrap_err(),
+            ParseError::Encoding(EncodingError::InvalidChar {
+                ch: '-',
+                index: 21,
+                ..
+            })
+        ));
+        assert!(matches!(
+            from_short("zzzzzzzzzzzzzzzzzzzzzz").unwrap_err(),
+            ParseError::Encoding(EncodingError::Overflow { .. })
+        ));
+        assert!(from_short("550e8400-e29b-41d4-a716-446655440000").is_err());
+    }
+
+    #[test]
+    fn test_base32_aliases() {
+        let uuid = decode_base32("2N1T201RMV87AAE5J4CSAM8000").unwrap();
+        assert_eq!(decode_base32("2n1t201rmv87aae5j4csam8000"), Ok(uuid));
+        assert_eq!(decode_base32("2NIT2O1RMV87AAE5J4CSAM8OOO"), Ok(uuid));
+        assert_eq!(decode_base32("2NlT201RMV87AAE5J4CSAM8000"), Ok(uuid));
+    }
+
+    #[test]
+    fn test_rejects_characters_outside_alphabet() {
+        assert_eq!(
+            decode_base32("2N1T201RMV87AAE5J4CSAM800U"),
+            Err(EncodingError::InvalidChar {
+                format: UuidFormat::Base32Crockford,
+                ch: 'U',
+                index: 25,
+            })
+        );
+        for (ch, index) in [('0', 0), ('O', 3), ('I', 5), ('l', 21), ('-', 10)] {
+            let mut input = b"BWBeN28Vb7cMEx7Ym8AUzs".to_vec();
+            input[index] = ch as u8;
+            assert_eq!(
+                decode_base58(std::str::from_utf8(&input).unwrap()),
+                Err(EncodingError::InvalidChar {
+                    format: UuidFormat::Base58,
+                    ch,
+                    index,
+                })
+            );
+        }
+        assert!(matches!(
+            decode_base58("BWBeN28Vb7cMEx7Ym8AUzé"),
+            Err(EncodingError::InvalidChar { ch: 'é', .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_wrong_length_and_overflow() {
+        assert_eq!(
+            decode_base58("BWBeN28Vb7cMEx7Ym8AUz"),
+            Err(EncodingError::InvalidLength {
+                format: UuidFormat::Base58,
+                expected: 22,
+                found: 21,
+            })
+        );
+        assert_eq!(
+            decode_base32("80000000000000000000000000"),
+            Err(EncodingError::Overflow {
+                format: UuidFormat::Base32Crockford
+            })
+        );
+        assert_eq!(
+            decode_base58("zzzzzzzzzzzzzzzzzzzzzz"),
+            Err(EncodingError::Overflow {
+                format: UuidFormat::Base58
+            })
+        );
+    }
+
+    /// IPv4 examples from the proquint spec, each address two quints
+    const PROQUINT_SPEC: [([u8; 4], &str); 12] = [
+        ([127, 0, 0, 1], "lusab-babad"),
+        ([63, 84, 220, 193], "gutih-tugad"),
+        ([63, 118, 7, 35], "gutuk-bisog"),
+        ([140, 98, 193, 141], "mudof-sakat"),
+        ([64, 255, 6, 200], "haguz-biram"),
+        ([128, 30, 52, 45], "mabiv-gibot"),
+        ([147, 67, 119, 2], "natag-lisaf"),
+        ([212, 58, 253, 68], "tibup-zujah"),
+        ([216, 35, 68, 215], "tobog-higil"),
+        ([216, 68, 232, 21], "todah-vobij"),
+        ([198, 81, 129, 136], "sinid-makam"),
+        ([12, 110, 110, 204], "budov-kuras"),
+    ];
+
+    #[test]
+    fn test_proquint_spec_vectors() {
+        // Four addresses to a UUID, in the spec's order
+        for chunk in PROQUINT_SPEC.chunks_exact(4) {
+            let bytes: Vec<u8> = chunk.iter().flat_map(|(ip, _)| *ip).collect();
+            let uuid = Uuid::from_slice(&bytes).unwrap();
+            let expected = chunk
+                .iter()
+                .map(|(_, quints)| *quints)
+                .collect::<Vec<_>>()
+                .join("-");
+            assert_eq!(to_proquint(&uuid), expected);
+            assert_eq!(from_proquint(&expected).unwrap(), uuid);
+        }
+
+        let nil = ["babab"; 8].join("-");
+        let max = ["zuzuz"; 8].join("-");
+        assert_eq!(to_proquint(&Uuid::nil()), nil);
+        assert_eq!(to_proquint(&Uuid::max()), max);
+        assert_eq!(from_proquint(&nil).unwrap(), Uuid::nil());
+        assert_eq!(from_proquint(&max.to_uppercase()).unwrap(), Uuid::max());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_proquint_round_trip_random() {
+        let mut uuids = Vec::new();
+        for _ in 0..1_000 {
+            let uuid = Uuid::new_v4();
+            let proquint = to_proquint(&uuid);
+            assert_eq!(proquint.len(), PROQUINT_LEN);
+            assert_eq!(from_proquint(&proquint).unwrap(), uuid);
+            assert_eq!(from_proquint(&proquint.to_uppercase()).unwrap(), uuid);
+            uuids.push(uuid);
+        }
+
+        // The alphabets are in ASCII order, so proquints sort like UUIDs
+        uuids.sort();
+        let proquints: Vec<String> = uuids.iter().map(to_proquint).collect();
+        assert!(proquints.is_sorted());
+    }
+
+    #[test]
+    fn test_proquint_errors() {
+        let valid = "lusab-babad-gutih-tugad-gutuk-bisog-mudof-sakat";
+        assert_eq!(
+            decode_proquint(&valid[..41]),
+            Err(EncodingError::InvalidLength {
+                format: UuidFormat::Proquint,
+                expected: 47,
+                found: 41,
+            })
+        );
+
+        let cases = [
+            // Vowel where a consonant belongs, in the first group
+            (0, 'a', 0, "consonant"),
+            // Consonant where a vowel belongs, in the third group
+            (13, 'b', 2, "vowel"),
+            // Letter outside both alphabets
+            (20, 'c', 3, "consonant"),
+            // Separator other than a dash
+            (23, '_', 3, "dash"),
+            // Dash inside a group
+            (46, '-', 7, "consonant"),
+        ];
+        for (index, ch, group, expected) in cases {
+            let mut input = valid.as_bytes().to_vec();
+            input[index] = ch as u8;
+            let input = std::str::from_utf8(&input).unwrap();
+            assert_eq!(
+                decode_proquint(input),
+                Err(EncodingError::MalformedGroup {
+                    format: UuidFormat::Proquint,
+                    group,
+                    index,
+                    ch,
+                    expected,
+                }),
+                "{input}"
+            );
+        }
+
+        let err = from_proquint("lusab_babad-gutih-tugad-gutuk-bisog-mudof-sakat").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "proquint group 0 is malformed: expected a dash at index 5, found '_'"
+        );
+    }
+}
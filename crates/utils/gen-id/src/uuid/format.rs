@@ -0,0 +1,222 @@
+use alloc::string::{String, ToString};
+
+use uuid::Uuid;
+
+use super::encoding;
+
+/// Format for UUID output
+///
+/// Serialized in snake_case, e.g. `standard` or `simple_uppercase`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum UuidFormat {
+    /// Standard format with hyphens: 550e8400-e29b-41d4-a716-446655440000
+    #[default]
+    Standard,
+    /// Simple format without hyphens: 550e8400e29b41d4a716446655440000
+    Simple,
+    /// Standard format with hyphens, uppercase: 550E8400-E29B-41D4-A716-446655440000
+    StandardUppercase,
+    /// Simple format without hyphens, uppercase: 550E8400E29B41D4A716446655440000
+    SimpleUppercase,
+    /// Standard format in braces: {550e8400-e29b-41d4-a716-446655440000}
+    Braced,
+    /// Standard format in braces, uppercase: {550E8400-E29B-41D4-A716-446655440000}
+    BracedUppercase,
+    /// URN format: urn:uuid:550e8400-e29b-41d4-a716-446655440000
+    Urn,
+    /// Crockford base32, 26 uppercase characters: 2N1T201RMV87AAE5J4CSAM8000
+    ///
+    /// Fixed width, so v7 UUIDs still sort by time as strings.
+    Base32Crockford,
+    /// Base58, 22 characters padded with `1`: BWBeN28Vb7cMEx7Ym8AUzs
+    ///
+    /// Fixed width, so v7 UUIDs still sort by time as strings.
+    Base58,
+    /// Short base62 form from [`to_short`](super::to_short), 22 characters
+    /// padded with `0`: 2aUyqjCzEIiEcYMKj7TZtw
+    ///
+    /// The same length as base58, so the generic parsers cannot tell them
+    /// apart. Decode it with [`from_short`](super::from_short) or a
+    /// generator's `parse`.
+    Base62,
+    /// Eight pronounceable groups from [`to_proquint`](super::to_proquint),
+    /// 47 characters: jihav-mibab-vapir-halih-pisik-hidok-jijah-babab
+    ///
+    /// For IDs read aloud, e.g. during incidents. Fixed width with the
+    /// letters in ASCII order, so v7 UUIDs still sort by time as strings.
+    Proquint,
+}
+
+/// UUID version
+///
+/// Serialized as `v4`, `v7` or `v5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum UuidVersion {
+    /// Random UUID (version 4)
+    #[default]
+    V4,
+    /// Timestamp-based sortable UUID (version 7)
+    V7,
+    /// Name-based UUID using SHA-1 (version 5), generated with
+    /// `NamedUuidGenerator::generate_named`
+    V5,
+}
+
+/// Longest formatted UUID, a proquint
+pub(crate) const MAX_LEN: usize = encoding::PROQUINT_LEN;
+
+/// Format `uuid` as `format`, without prefix or suffix
+///
+/// Needs only `alloc`, unlike the generator.
+#[inline]
+pub fn format_uuid(uuid: &Uuid, format: UuidFormat) -> String {
+    let mut buf = [0u8; MAX_LEN];
+    encode(uuid, format, &mut buf).to_string()
+}
+
+/// Encode into a stack buffer large enough for the longest format
+///
+/// With `simd` the hex formats go through uuid-simd, which writes the same
+/// bytes as the scalar encoders.
+#[inline]
+pub(crate) fn encode<'a>(uuid: &Uuid, format: UuidFormat, buf: &'a mut [u8; MAX_LEN]) -> &'a str {
+    #[cfg(feature = "simd")]
+    {
+        encode_simd(uuid, format, buf)
+    }
+    #[cfg(not(feature = "simd"))]
+    {
+        encode_scalar(uuid, format, buf)
+    }
+}
+
+#[cfg(feature = "simd")]
+#[inline]
+fn encode_simd<'a>(uuid: &Uuid, format: UuidFormat, buf: &'a mut [u8; MAX_LEN]) -> &'a str {
+    use uuid::fmt::Urn;
+    use uuid_simd::{AsOut, AsciiCase};
+
+    let case = match format {
+        UuidFormat::StandardUppercase
+        | UuidFormat::SimpleUppercase
+        | UuidFormat::BracedUppercase => AsciiCase::Upper,
+        _ => AsciiCase::Lower,
+    };
+    let src = uuid.as_bytes();
+    let hyphenated = |dst: &mut [u8]| {
+        let dst: &mut [u8; 36] = dst.try_into().expect("36 byte window");
+        let _ = uuid_simd::format_hyphenated(src, dst.as_out(), case);
+    };
+
+    let len = match format {
+        UuidFormat::Standard | UuidFormat::StandardUppercase => {
+            hyphenated(&mut buf[..36]);
+            36
+        }
+        UuidFormat::Simple | UuidFormat::SimpleUppercase => {
+            let dst: &mut [u8; 32] = (&mut buf[..32]).try_into().expect("32 byte window");
+            let _ = uuid_simd::format_simple(src, dst.as_out(), case);
+            32
+        }
+        UuidFormat::Braced | UuidFormat::BracedUppercase => {
+            buf[0] = b'{';
+            hyphenated(&mut buf[1..37]);
+            buf[37] = b'}';
+            38
+        }
+        UuidFormat::Urn => {
+            buf[..9].copy_from_slice(b"urn:uuid:");
+            hyphenated(&mut buf[9..Urn::LENGTH]);
+            Urn::LENGTH
+        }
+        UuidFormat::Base32Crockford
+        | UuidFormat::Base58
+        | UuidFormat::Base62
+        | UuidFormat::Proquint => {
+            return encode_scalar(uuid, format, buf);
+        }
+    };
+    core::str::from_utf8(&buf[..len]).expect("hex output is ASCII")
+}
+
+#[inline]
+pub(crate) fn encode_scalar<'a>(
+    uuid: &Uuid,
+    format: UuidFormat,
+    buf: &'a mut [u8; MAX_LEN],
+) -> &'a str {
+    match format {
+        UuidFormat::Standard => uuid.hyphenated().encode_lower(buf),
+        UuidFormat::Simple => uuid.simple().encode_lower(buf),
+        UuidFormat::StandardUppercase => uuid.hyphenated().encode_upper(buf),
+        UuidFormat::SimpleUppercase => uuid.simple().encode_upper(buf),
+        UuidFormat::Braced => uuid.braced().encode_lower(buf),
+        UuidFormat::BracedUppercase => uuid.braced().encode_upper(buf),
+        UuidFormat::Urn => uuid.urn().encode_lower(buf),
+        UuidFormat::Base32Crockford => encoding::encode_base32(uuid, buf),
+        UuidFormat::Base58 => encoding::encode_base58(uuid, buf),
+        UuidFormat::Base62 => encoding::encode_base62(uuid, buf),
+        UuidFormat::Proquint => encoding::encode_proquint(uuid, buf),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uuid::names::FORMATS;
+
+    #[test]
+    fn test_format_uuid_every_format() {
+        let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        for (_, format) in FORMATS {
+            let formatted = format_uuid(&uuid, format);
+            let expected = match format {
+                UuidFormat::Standard => "550e8400-e29b-41d4-a716-446655440000",
+                UuidFormat::Simple => "550e8400e29b41d4a716446655440000",
+                UuidFormat::StandardUppercase => "550E8400-E29B-41D4-A716-446655440000",
+                UuidFormat::SimpleUppercase => "550E8400E29B41D4A716446655440000",
+                UuidFormat::Braced => "{550e8400-e29b-41d4-a716-446655440000}",
+                UuidFormat::BracedUppercase => "{550E8400-E29B-41D4-A716-446655440000}",
+                UuidFormat::Urn => "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+                UuidFormat::Base32Crockford => "2N1T201RMV87AAE5J4CSAM8000",
+                UuidFormat::Base58 => "BWBeN28Vb7cMEx7Ym8AUzs",
+                UuidFormat::Base62 => "2aUyqjCzEIiEcYMKj7TZtw",
+                UuidFormat::Proquint => "jihav-mibab-vapir-halih-pisik-hidok-jijah-babab",
+            };
+            assert_eq!(formatted, expected, "{format}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "simd")]
+    fn test_simd_matches_scalar() {
+        let mut bytes = [0u8; 16];
+        for seed in 0u32..4096 {
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = (seed.wrapping_mul(2_654_435_761) >> (i % 4 * 8)) as u8 ^ (i as u8 * 17);
+            }
+            let uuid = Uuid::from_bytes(bytes);
+            for uuid in [uuid, Uuid::nil(), Uuid::max()] {
+                for (_, format) in FORMATS {
+                    let mut simd = [0u8; MAX_LEN];
+                    let mut scalar = [0u8; MAX_LEN];
+                    assert_eq!(
+                        encode_simd(&uuid, format, &mut simd).as_bytes(),
+                        encode_scalar(&uuid, format, &mut scalar).as_bytes(),
+                        "{uuid} as {format}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,28 +1,84 @@
-use uuid::Uuid;
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rand_core::{CryptoRng, RngCore};
+use uuid::{Builder, Uuid};
+
+use super::{
+    encoding::{self, EncodingError},
+    format::{self, UuidFormat, UuidVersion},
+    monotonic::MonotonicV7,
+    random::{RandomSource, SeededRng},
+};
+
+use crate::{
+    IdStrengthReport,
+    prefix::{PrefixError, PrefixPolicy},
+    source::{self, Clock},
+};
 
 #[cfg(feature = "custom-uuid")]
-use super::metadata::{ClientMetadata, encode_os_metadata, hash_to_u16, hash_to_u32};
-
-/// Format for UUID output
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum UuidFormat {
-    /// Standard format with hyphens: 550e8400-e29b-41d4-a716-446655440000
-    Standard,
-    /// Simple format without hyphens: 550e8400e29b41d4a716446655440000
-    Simple,
-    /// Standard format with hyphens, uppercase: 550E8400-E29B-41D4-A716-446655440000
-    StandardUppercase,
-    /// Simple format without hyphens, uppercase: 550E8400E29B41D4A716446655440000
-    SimpleUppercase,
+use super::{
+    metadata::{ClientMetadata, EncodedMetadata, set_checksum, set_marker},
+    monotonic::MetadataTail,
+};
+
+/// Largest millisecond timestamp a UUID v7 can hold (48 bits)
+const MAX_V7_MILLIS: u64 = (1 << 48) - 1;
+
+/// Longest wait for the clock to leave a millisecond whose metadata tails
+/// are used up, after which the timestamp moves on without it
+#[cfg(all(feature = "custom-uuid", not(target_arch = "wasm32")))]
+const TAIL_WAIT: std::time::Duration = std::time::Duration::from_millis(2);
+
+/// Error for timestamps a UUID v7 cannot represent
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TimestampError {
+    #[error("timestamp is {0:?} before the Unix epoch")]
+    BeforeEpoch(std::time::Duration),
+
+    #[error("timestamp of {0}ms since the Unix epoch exceeds the 48-bit UUID v7 range")]
+    OutOfRange(u128),
 }
 
-/// UUID version
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum UuidVersion {
-    /// Random UUID (version 4)
-    V4,
-    /// Timestamp-based sortable UUID (version 7)
-    V7,
+/// Why an input does not match a [`UuidGenerator`]'s configuration
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MatchError {
+    #[error("expected prefix `{0}`")]
+    MissingPrefix(String),
+
+    #[error("expected suffix `{0}`")]
+    MissingSuffix(String),
+
+    #[error("invalid UUID: {0}")]
+    InvalidUuid(uuid::Error),
+
+    #[error("invalid UUID: {0}")]
+    InvalidEncoding(EncodingError),
+
+    #[error("UUID is not in {expected:?} format")]
+    FormatMismatch { expected: UuidFormat },
+
+    #[error("expected a {expected:?} UUID, found version {found}")]
+    VersionMismatch { expected: UuidVersion, found: usize },
+}
+
+/// Why a generator cannot be created for a [`UuidVersion`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum VersionError {
+    #[error("v5 UUIDs are derived from a name, create a NamedUuidGenerator")]
+    NameBased,
+
+    #[error("{0:?} UUIDs are not derived from a name")]
+    NotNameBased(UuidVersion),
+
+    #[error("a v5 generator needs a namespace")]
+    MissingNamespace,
 }
 
 /// UUID generator with various formatting options
@@ -31,15 +87,56 @@ pub struct UuidGenerator {
     version: UuidVersion,
     format: UuidFormat,
     prefix: Option<String>,
+    suffix: Option<String>,
+    monotonic: Option<Arc<MonotonicV7>>,
+    rng: Option<Arc<RandomSource>>,
+    clock: Option<Clock>,
+    clock_guard: Option<Arc<AtomicU64>>,
+    #[cfg(feature = "custom-uuid")]
+    metadata_v8: bool,
+    /// Tail of a generator with its own clock, `None` for the process-wide
+    /// tails
+    #[cfg(feature = "custom-uuid")]
+    metadata_tail: Option<Arc<MetadataTail>>,
 }
 
 impl UuidGenerator {
     /// Create a new UUID generator with specified version and format
+    ///
+    /// # Panics
+    /// Panics on [`UuidVersion::V5`], which needs a namespace and a name per
+    /// ID. Use [`Self::v5`], or [`Self::try_new`] for versions read at runtime.
     pub fn new(version: UuidVersion, format: UuidFormat) -> Self {
+        match Self::try_new(version, format) {
+            Ok(generator) => generator,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Create a new UUID generator, or an error for [`UuidVersion::V5`]
+    pub fn try_new(version: UuidVersion, format: UuidFormat) -> Result<Self, VersionError> {
+        if version == UuidVersion::V5 {
+            return Err(VersionError::NameBased);
+        }
+        Ok(Self::with_version(version, format))
+    }
+
+    /// The generator behind both public constructors and
+    /// [`NamedUuidGenerator`], which is the only one holding a v5 version
+    fn with_version(version: UuidVersion, format: UuidFormat) -> Self {
         Self {
             version,
             format,
             prefix: None,
+            suffix: None,
+            monotonic: None,
+            rng: None,
+            clock: None,
+            clock_guard: None,
+            #[cfg(feature = "custom-uuid")]
+            metadata_v8: false,
+            #[cfg(feature = "custom-uuid")]
+            metadata_tail: None,
         }
     }
 
@@ -55,6 +152,76 @@ impl UuidGenerator {
         Self::new(UuidVersion::V7, UuidFormat::Standard)
     }
 
+    /// Create a UUID v5 generator for the given namespace with standard format
+    ///
+    /// See [`NamedUuidGenerator`].
+    #[inline]
+    pub fn v5(namespace: Uuid) -> NamedUuidGenerator {
+        NamedUuidGenerator::new(namespace)
+    }
+
+    /// Create a UUID v4 generator with a deterministic seeded RNG
+    ///
+    /// See [`Self::with_seed`].
+    #[inline]
+    pub fn v4_seeded(seed: u64) -> Self {
+        Self::v4().with_seed(seed)
+    }
+
+    /// Use a deterministic RNG so the same seed produces the same sequence
+    ///
+    /// Meant for reproducible tests: anyone who knows the seed can produce
+    /// every ID. The RNG is ChaCha8 seeded through rand_core's
+    /// `seed_from_u64`, as in
+    #[cfg_attr(
+        feature = "nanoid",
+        doc = "[`NanoIdGenerator::seeded`](crate::NanoIdGenerator::seeded), so the"
+    )]
+    #[cfg_attr(not(feature = "nanoid"), doc = "`NanoIdGenerator::seeded`, so the")]
+    /// sequence is the same on every platform and release. Clones share the
+    /// RNG and continue the same sequence. Combine with
+    /// [`Self::with_clock`] to make v7 UUIDs fully reproducible.
+    #[inline]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Some(Arc::new(RandomSource::Seeded(Box::new(SeededRng::new(
+            seed,
+        )))));
+        self
+    }
+
+    /// Draw random bytes from `rng` instead of the OS RNG, e.g. an audited
+    /// DRBG
+    ///
+    /// v4 UUIDs take all their random bits from it and v7 UUIDs their random
+    /// tail, still stamped by the system clock (or [`Self::with_clock`]).
+    /// Clones share the RNG.
+    ///
+    /// The RNG sits behind a mutex, so every ID takes a lock and threads
+    /// generating at the same time wait on each other. Batch methods draw
+    /// the whole batch under one lock. Without an injected RNG, generation
+    /// stays on the lock-free OS RNG path.
+    #[inline]
+    pub fn with_rng<R: RngCore + CryptoRng + Send + 'static>(mut self, rng: R) -> Self {
+        self.rng = Some(Arc::new(RandomSource::Injected(Mutex::new(Box::new(rng)))));
+        self
+    }
+
+    /// Take v7 timestamps from `clock`, in milliseconds since the Unix epoch,
+    /// instead of the system time
+    ///
+    /// Values beyond the 48-bit v7 range are truncated. Metadata UUIDs then
+    /// count with a tail of this generator and its clones instead of the
+    /// process-wide one, so the same seed and clock give the same IDs.
+    #[inline]
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Some(Clock::new(clock));
+        #[cfg(feature = "custom-uuid")]
+        {
+            self.metadata_tail = Some(Arc::default());
+        }
+        self
+    }
+
     /// Set the output format
     #[inline]
     pub fn with_format(mut self, format: UuidFormat) -> Self {
@@ -62,13 +229,84 @@ impl UuidGenerator {
         self
     }
 
+    /// Guarantee strictly increasing v7 UUIDs, even within one millisecond
+    ///
+    /// A counter in the random bits orders UUIDs generated in the same
+    /// millisecond (RFC 9562 section 6.2). Clones of the generator share the
+    /// counter, so ordering also holds across threads using clones. Has no
+    /// effect on other versions. Metadata UUIDs count with their own 12-bit
+    /// tail instead, see `generate_with_metadata` (`custom-uuid` feature).
+    #[inline]
+    pub fn with_monotonic(mut self, monotonic: bool) -> Self {
+        self.monotonic = monotonic.then(|| Arc::new(MonotonicV7::default()));
+        self
+    }
+
+    /// Never let v7 timestamps go backwards when the clock does
+    ///
+    /// The generator remembers the latest timestamp it used and keeps using
+    /// it while the clock is behind, e.g. after NTP steps it back, until real
+    /// time catches up. Clones share it, so this holds across threads. IDs in
+    /// the held millisecond are only ordered with [`Self::with_monotonic`],
+    /// which also holds the timestamp on its own. Explicit timestamps from
+    /// [`Self::generate_at`] are not affected.
+    #[inline]
+    pub fn with_clock_guard(mut self, clock_guard: bool) -> Self {
+        self.clock_guard = clock_guard.then(|| Arc::new(AtomicU64::new(0)));
+        self
+    }
+
+    /// Emit metadata UUIDs as version 8 instead of version 7
+    ///
+    /// The metadata layout replaces random bits, so RFC 9562 validators see a
+    /// v7 metadata UUID as a v7 with a misleading layout. Version 8 is the
+    /// RFC's version for custom layouts. The timestamp stays in the first 48
+    /// bits, so v7 and v8 IDs sort together. [`extract_metadata`] decodes both.
+    ///
+    /// [`extract_metadata`]: crate::extract_metadata
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    pub fn with_metadata_v8(mut self, metadata_v8: bool) -> Self {
+        self.metadata_v8 = metadata_v8;
+        self
+    }
+
     /// Set a prefix for the generated UUIDs
+    ///
+    /// The prefix is not validated, prefer [`Self::try_with_prefix`] for
+    /// prefixes that are not compile-time constants. An empty prefix means
+    /// no prefix.
     #[inline]
     pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
-        self.prefix = Some(prefix.into());
+        let prefix = prefix.into();
+        self.prefix = (!prefix.is_empty()).then_some(prefix);
         self
     }
 
+    /// Set a prefix after checking it against the default [`PrefixPolicy`]:
+    /// ASCII alphanumerics plus `_`, `-` and `:`, at most 32 bytes
+    ///
+    /// An empty prefix means no prefix.
+    #[inline]
+    pub fn try_with_prefix(self, prefix: impl Into<String>) -> Result<Self, PrefixError> {
+        self.try_with_prefix_policy(prefix, &PrefixPolicy::default())
+    }
+
+    /// Set a prefix after checking it against `policy`
+    #[inline]
+    pub fn try_with_prefix_policy(
+        self,
+        prefix: impl Into<String>,
+        policy: &PrefixPolicy,
+    ) -> Result<Self, PrefixError> {
+        let prefix = prefix.into();
+        policy.validate(&prefix)?;
+        Ok(self.with_prefix(prefix))
+    }
+
     /// Remove the prefix
     #[inline]
     pub fn without_prefix(mut self) -> Self {
@@ -76,15 +314,147 @@ impl UuidGenerator {
         self
     }
 
+    /// Set a suffix appended after the formatted UUID, e.g. a region tag
+    #[inline]
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = Some(suffix.into());
+        self
+    }
+
+    /// Remove the suffix
+    #[inline]
+    pub fn without_suffix(mut self) -> Self {
+        self.suffix = None;
+        self
+    }
+
     /// Generate a single UUID
     #[inline]
     pub fn generate(&self) -> String {
-        let uuid = match self.version {
-            UuidVersion::V4 => Uuid::new_v4(),
-            UuidVersion::V7 => Uuid::now_v7(),
+        self.format(&self.generate_uuid())
+    }
+
+    /// Generate a single UUID as its 16 bytes, skipping formatting
+    ///
+    /// The prefix and format are ignored.
+    #[inline]
+    pub fn generate_bytes(&self) -> [u8; 16] {
+        self.generate_uuid().into_bytes()
+    }
+
+    /// Generate a single UUID without formatting it
+    #[inline]
+    pub fn generate_uuid(&self) -> Uuid {
+        match self.version {
+            UuidVersion::V4 => match &self.rng {
+                Some(rng) => self.uuid_from_random(rng.next_bytes()),
+                None => Uuid::new_v4(),
+            },
+            UuidVersion::V7 => self.new_v7(),
+            UuidVersion::V5 => unreachable!("v5 generators are only built by NamedUuidGenerator"),
+        }
+    }
+
+    #[inline]
+    fn new_v7(&self) -> Uuid {
+        match (&self.monotonic, &self.rng, &self.clock, &self.clock_guard) {
+            (None, None, None, None) => Uuid::now_v7(),
+            _ => self.v7_from(self.random_bytes()),
+        }
+    }
+
+    /// A v4 or v7 UUID whose random bits come from `random`
+    #[inline]
+    fn uuid_from_random(&self, random: [u8; 16]) -> Uuid {
+        match self.version {
+            UuidVersion::V4 => Builder::from_random_bytes(random).into_uuid(),
+            UuidVersion::V7 => self.v7_from(random),
+            UuidVersion::V5 => unreachable!("v5 generators are only built by NamedUuidGenerator"),
+        }
+    }
+
+    #[inline]
+    fn v7_from(&self, random: [u8; 16]) -> Uuid {
+        let millis = self.now_millis();
+        match &self.monotonic {
+            Some(monotonic) => monotonic.next_at(millis, random),
+            None => v7_with_random(millis, random),
+        }
+    }
+
+    #[inline]
+    fn v7_at(&self, millis: u64) -> Uuid {
+        v7_with_random(millis, self.random_bytes())
+    }
+
+    /// Call `f` with `count` new UUIDs, drawing all random bytes from an
+    /// injected or seeded RNG under a single lock
+    #[inline]
+    fn for_each_uuid(&self, count: usize, mut f: impl FnMut(Uuid)) {
+        match &self.rng {
+            Some(rng) => {
+                let mut random = vec![[0u8; 16]; count];
+                rng.fill(&mut random);
+                for bytes in random {
+                    f(self.uuid_from_random(bytes));
+                }
+            }
+            None => self.iter_uuids().take(count).for_each(f),
+        }
+    }
+
+    #[inline]
+    fn now_millis(&self) -> u64 {
+        let now = match &self.clock {
+            Some(clock) => clock.now_millis(),
+            None => source::system_millis(),
         };
+        self.guard_millis(now)
+    }
+
+    /// `millis`, or the latest timestamp used when the clock guard is on and
+    /// `millis` is behind it
+    #[inline]
+    fn guard_millis(&self, millis: u64) -> u64 {
+        match &self.clock_guard {
+            Some(latest) => millis.max(latest.fetch_max(millis, Ordering::Relaxed)),
+            None => millis,
+        }
+    }
+
+    #[inline]
+    fn random_bytes(&self) -> [u8; 16] {
+        match &self.rng {
+            Some(rng) => rng.next_bytes(),
+            None => *Uuid::new_v4().as_bytes(),
+        }
+    }
+
+    /// Generate a UUID v7 for the given time instead of now
+    ///
+    /// The timestamp is truncated to milliseconds and the remaining bits are
+    /// random. This always produces a v7 UUID, whatever the generator's
+    /// version, and does not use the monotonic counter.
+    #[inline]
+    pub fn generate_at(&self, timestamp: SystemTime) -> Result<String, TimestampError> {
+        Ok(self.format(&self.generate_uuid_at(timestamp)?))
+    }
+
+    /// Generate a UUID v7 for the given time without formatting it
+    ///
+    /// See [`Self::generate_at`].
+    #[inline]
+    pub fn generate_uuid_at(&self, timestamp: SystemTime) -> Result<Uuid, TimestampError> {
+        let since_epoch = timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| TimestampError::BeforeEpoch(err.duration()))?;
+
+        let millis = since_epoch.as_millis();
+        if millis > u128::from(MAX_V7_MILLIS) {
+            return Err(TimestampError::OutOfRange(millis));
+        }
 
-        self.format_uuid(&uuid)
+        Ok(self.v7_at(millis as u64))
     }
 
     /// Generate a UUID v7 with embedded client metadata
@@ -94,53 +464,186 @@ impl UuidGenerator {
     ///
     /// # Structure
     /// - Bytes 0-5: Timestamp (milliseconds) - preserved for sorting
-    /// - Byte 6: Version (0x7X) where X contains 4 bits of OS type
-    /// - Byte 7: OS version (4 bits major, 4 bits minor)
-    /// - Byte 8: Variant bits (preserved)
+    /// - Byte 6: Version (0x7X, or 0x8X with [`Self::with_metadata_v8`])
+    ///   where X holds the low 3 OS type bits and the top major version bit
+    /// - Byte 7: The rest of the OS version, 4 bits of major and 4 of minor
+    /// - Byte 8: Variant (2 bits), metadata marker `1011` (4 bits) checked by
+    ///   [`extract_metadata`], schema version (2 bits, currently 1)
     /// - Byte 9: Hostname hash (8 bits)
-    /// - Bytes 10-13: Extended hash (user agent + hostname)
-    /// - Bytes 14-15: Random bits for collision resistance
+    /// - Bytes 10-13: Top OS type bit, 12 bits of app version (presence bit,
+    ///   6 bits major, 5 bits minor), then a 19-bit extended hash (hostname +
+    ///   user agent + device ID)
+    /// - Bytes 14-15: A 12-bit tail keeping IDs distinct, then a CRC-4
+    ///   checksum of the other 124 bits checked by [`extract_metadata`]
+    ///
+    /// A plain v7 UUID passes the marker, schema version and checksum checks
+    /// by chance 1 time in 1024 (1/16 × 1/4 × 1/16).
+    ///
+    /// The tail is a counter shared by every generator in the process for
+    /// the same metadata. Within a millisecond it counts up from a random
+    /// start in its lower half, so IDs never collide and strictly increase
+    /// for the same metadata, even from generators built per request. After
+    /// at least 2048 IDs in one millisecond the next one moves on to the
+    /// following millisecond. Generators with [`Self::with_clock`] count with
+    /// their own tail, shared by clones only. Separate processes only differ
+    /// by the 11 random start bits. Reusing one generator also saves a lookup
+    /// of the shared tail per batch.
+    ///
+    /// [`extract_metadata`]: crate::extract_metadata
     ///
     /// # Availability
     /// This method is only available when the `custom-uuid` feature is enabled.
     #[inline]
     #[cfg(feature = "custom-uuid")]
     pub fn generate_with_metadata(&self, metadata: &ClientMetadata) -> String {
-        // Start with a v7 UUID to get the timestamp
-        let uuid = Uuid::now_v7();
-        let mut bytes = *uuid.as_bytes();
+        self.format(&self.generate_uuid_with_metadata(metadata))
+    }
 
-        // Encode OS metadata (4 bits type + 8 bits version)
-        let os_encoded = encode_os_metadata(metadata.os_type, metadata.os_version);
+    /// Generate a UUID v7 with embedded client metadata without formatting it
+    ///
+    /// See [`Self::generate_with_metadata`] for the layout.
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    pub fn generate_uuid_with_metadata(&self, metadata: &ClientMetadata) -> Uuid {
+        let encoded = metadata.encode();
+        self.generate_uuid_encoded(&self.metadata_tail(&encoded), &encoded)
+    }
 
-        // Inject OS type into byte 6 (preserve version bits 0x7X)
-        bytes[6] = 0x70 | ((os_encoded >> 8) as u8 & 0x0F);
+    /// This generator's own tail, or the process-wide one for `encoded`
+    #[cfg(feature = "custom-uuid")]
+    fn metadata_tail(&self, encoded: &EncodedMetadata) -> Arc<MetadataTail> {
+        match &self.metadata_tail {
+            Some(tail) => Arc::clone(tail),
+            None => MetadataTail::shared(encoded, source::system_millis()),
+        }
+    }
 
-        // Inject OS version into byte 7
-        bytes[7] = os_encoded as u8;
+    /// Take the next tail from `tail`. When a millisecond's tails run out it
+    /// waits for the next millisecond. A custom clock may never advance, so
+    /// with one the timestamp steps past the last one used instead.
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    fn generate_uuid_encoded(&self, tail: &MetadataTail, encoded: &EncodedMetadata) -> Uuid {
+        let random = self.random_bytes();
+        let start = u16::from_be_bytes([random[14], random[15]]);
+        let mut now = self.now_millis();
+        let (millis, tail) = loop {
+            match tail.next_at(now, start) {
+                Ok(slot) => break slot,
+                Err(exhausted) => now = self.next_millis(exhausted),
+            }
+        };
+        embed_metadata(
+            v7_with_random(millis, random),
+            encoded,
+            self.metadata_version(),
+            tail,
+        )
+    }
 
-        // Byte 8 is preserved for variant bits (already set by Uuid::now_v7)
+    /// Strictly increasing metadata UUIDs for a batch
+    ///
+    /// Bytes 6-13 are the same for every ID of a batch, so the tail counter
+    /// orders them.
+    #[cfg(feature = "custom-uuid")]
+    fn metadata_batch<'a>(
+        &'a self,
+        count: usize,
+        encoded: &'a EncodedMetadata,
+    ) -> impl Iterator<Item = Uuid> + 'a {
+        let tail = self.metadata_tail(encoded);
+        (0..count).map(move |_| self.generate_uuid_encoded(&tail, encoded))
+    }
 
-        // Inject hostname hash into byte 9
-        let hostname_hash = hash_to_u16(&metadata.hostname);
-        bytes[9] = (hostname_hash & 0xFF) as u8;
+    /// First millisecond after `millis`, whose tails are used up
+    ///
+    /// Sleeps in short steps for the system clock to pass `millis`, for at
+    /// most [`TAIL_WAIT`]. A clock that is stuck or behind, e.g. after
+    /// stepping back, would never get there, so the timestamp then moves one
+    /// millisecond ahead of it, as the monotonic v7 counter does.
+    #[cfg(feature = "custom-uuid")]
+    fn next_millis(&self, millis: u64) -> u64 {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.clock.is_none() {
+            let deadline = std::time::Instant::now() + TAIL_WAIT;
+            while std::time::Instant::now() < deadline {
+                let now = self.now_millis();
+                if now > millis {
+                    return now;
+                }
+                std::thread::sleep(std::time::Duration::from_micros(100));
+            }
+        }
+        self.guard_millis(millis + 1)
+    }
 
-        // Create extended hash from hostname + user agent
-        let extended_input = match &metadata.user_agent {
-            Some(ua) => format!("{}{}", metadata.hostname, ua),
-            None => metadata.hostname.clone(),
-        };
-        let extended_hash = hash_to_u32(&extended_input);
-        bytes[10..14].copy_from_slice(&extended_hash.to_be_bytes());
+    /// Generate a UUID v7 with embedded client metadata as its 16 bytes, e.g.
+    /// for a `BINARY(16)` column
+    ///
+    /// The prefix and format are ignored. See [`Self::generate_with_metadata`]
+    /// for the layout.
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    pub fn generate_bytes_with_metadata(&self, metadata: &ClientMetadata) -> [u8; 16] {
+        self.generate_uuid_with_metadata(metadata).into_bytes()
+    }
+
+    /// Generate a UUID v7 with embedded client metadata for the given time
+    ///
+    /// See [`Self::generate_with_metadata`] and [`Self::generate_at`].
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    pub fn generate_with_metadata_at(
+        &self,
+        metadata: &ClientMetadata,
+        timestamp: SystemTime,
+    ) -> Result<String, TimestampError> {
+        Ok(self.format(&self.generate_uuid_with_metadata_at(metadata, timestamp)?))
+    }
 
-        // Bytes 14-15 remain random from the original UUID v7 for collision resistance
+    /// Generate a UUID v7 with embedded client metadata for the given time
+    /// without formatting it
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    pub fn generate_uuid_with_metadata_at(
+        &self,
+        metadata: &ClientMetadata,
+        timestamp: SystemTime,
+    ) -> Result<Uuid, TimestampError> {
+        let uuid = self.generate_uuid_at(timestamp)?;
+        let tail = u16::from_be_bytes([uuid.as_bytes()[14], uuid.as_bytes()[15]]) >> 4;
+        Ok(embed_metadata(
+            uuid,
+            &metadata.encode(),
+            self.metadata_version(),
+            tail,
+        ))
+    }
 
-        let custom_uuid = Uuid::from_bytes(bytes);
-        self.format_uuid(&custom_uuid)
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    fn metadata_version(&self) -> u8 {
+        if self.metadata_v8 { 8 } else { 7 }
     }
 
     /// Generate a batch of UUIDs with metadata
     ///
+    /// The metadata is encoded and hashed once for the whole batch. The batch
+    /// is strictly increasing, as the tail counter of
+    /// [`Self::generate_with_metadata`] orders IDs within a millisecond.
+    ///
     /// # Availability
     /// This method is only available when the `custom-uuid` feature is enabled.
     #[inline]
@@ -150,165 +653,1618 @@ impl UuidGenerator {
         count: usize,
         metadata: &ClientMetadata,
     ) -> Vec<String> {
-        (0..count)
-            .map(|_| self.generate_with_metadata(metadata))
+        self.generate_uuid_batch_with_metadata(count, metadata)
+            .iter()
+            .map(|uuid| self.format(uuid))
             .collect()
     }
 
+    /// Generate a batch of UUIDs with metadata without formatting them
+    ///
+    /// The same IDs as [`Self::generate_batch_with_metadata`], e.g. to bind
+    /// as native UUIDs in a bulk insert. The prefix and format do not apply.
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    pub fn generate_uuid_batch_with_metadata(
+        &self,
+        count: usize,
+        metadata: &ClientMetadata,
+    ) -> Vec<Uuid> {
+        let encoded = metadata.encode();
+        self.metadata_batch(count, &encoded).collect()
+    }
+
+    /// Generate a batch of UUIDs with metadata into `out`, reusing its capacity
+    ///
+    /// Strictly increasing like [`Self::generate_batch_with_metadata`]. See
+    /// [`Self::generate_batch_into`].
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[inline]
+    #[cfg(feature = "custom-uuid")]
+    pub fn generate_batch_with_metadata_into(
+        &self,
+        count: usize,
+        metadata: &ClientMetadata,
+        out: &mut Vec<String>,
+    ) {
+        let encoded = metadata.encode();
+        out.clear();
+        out.extend(
+            self.metadata_batch(count, &encoded)
+                .map(|uuid| self.format(&uuid)),
+        );
+    }
+
     /// Generate a batch of UUIDs
+    ///
+    /// Each ID is encoded straight into its own exactly sized string, with
+    /// no intermediate `Vec<Uuid>`.
     #[inline]
     pub fn generate_batch(&self, count: usize) -> Vec<String> {
-        (0..count).map(|_| self.generate()).collect()
+        let mut out = Vec::new();
+        self.generate_batch_into(count, &mut out);
+        out
     }
 
+    /// Generate a batch of UUIDs into `out`, replacing its contents
+    ///
+    /// The vector is cleared and refilled, so its capacity is reused and no
+    /// reallocation happens when it already holds `count` entries.
     #[inline]
-    fn format_uuid(&self, uuid: &Uuid) -> String {
-        let formatted = match self.format {
-            UuidFormat::Standard => uuid.hyphenated().to_string(),
-            UuidFormat::Simple => uuid.simple().to_string(),
-            UuidFormat::StandardUppercase => uuid.hyphenated().to_string().to_uppercase(),
-            UuidFormat::SimpleUppercase => uuid.simple().to_string().to_uppercase(),
-        };
+    pub fn generate_batch_into(&self, count: usize, out: &mut Vec<String>) {
+        out.clear();
+        out.reserve(count);
+        self.for_each_uuid(count, |uuid| out.push(self.format(&uuid)));
+    }
+
+    /// Append a batch of UUIDs to `out`, separated by `separator`
+    ///
+    /// Builds bulk values such as `'a','b','c'` for SQL without a `String`
+    /// per UUID. Nothing is written before the first UUID, so pass a cleared
+    /// buffer or push a separator first when appending to existing content.
+    #[inline]
+    pub fn generate_batch_joined_into(&self, count: usize, separator: &str, out: &mut String) {
+        let mut first = true;
+        self.for_each_uuid(count, |uuid| {
+            if !first {
+                out.push_str(separator);
+            }
+            first = false;
+            self.format_into(&uuid, out);
+        });
+    }
+
+    /// Generate a batch of UUIDs without formatting them
+    ///
+    /// The same IDs as [`Self::generate_batch`], e.g. to bind as native UUIDs
+    /// in a bulk insert. The prefix and format do not apply.
+    #[inline]
+    pub fn generate_uuid_batch(&self, count: usize) -> Vec<Uuid> {
+        let mut uuids = Vec::with_capacity(count);
+        self.for_each_uuid(count, |uuid| uuids.push(uuid));
+        uuids
+    }
+
+    /// Generate a batch of UUIDs on the rayon thread pool
+    ///
+    /// Output length and uniqueness match [`Self::generate_batch`]. Plain v7
+    /// UUIDs carry valid timestamps but are not ordered across threads; in
+    /// monotonic mode the batch is sorted, so it is strictly increasing. With
+    /// a seed the set of IDs is reproducible but their positions are not.
+    ///
+    /// # Availability
+    /// This method is only available when the `parallel` feature is enabled.
+    #[cfg(feature = "parallel")]
+    pub fn generate_batch_parallel(&self, count: usize) -> Vec<String> {
+        use rayon::prelude::*;
+
+        self.generate_uuid_batch_parallel(count)
+            .par_iter()
+            .map(|uuid| self.format(uuid))
+            .collect()
+    }
+
+    /// Generate a batch of unformatted UUIDs on the rayon thread pool
+    ///
+    /// See [`Self::generate_batch_parallel`].
+    ///
+    /// # Availability
+    /// This method is only available when the `parallel` feature is enabled.
+    #[cfg(feature = "parallel")]
+    pub fn generate_uuid_batch_parallel(&self, count: usize) -> Vec<Uuid> {
+        use rayon::prelude::*;
+
+        let mut uuids: Vec<Uuid> = (0..count)
+            .into_par_iter()
+            .map(|_| self.generate_uuid())
+            .collect();
+
+        // The shared counter orders UUIDs by generation, not by index
+        if self.monotonic.is_some() && self.version == UuidVersion::V7 {
+            uuids.par_sort_unstable();
+        }
+
+        uuids
+    }
+
+    /// Endless iterator of formatted UUIDs, generated lazily
+    ///
+    /// Each item comes from [`Self::generate`], so v7 ordering is the same
+    /// as calling it in a loop.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        std::iter::repeat_with(|| self.generate())
+    }
+
+    /// Endless iterator of unformatted UUIDs, generated lazily
+    #[inline]
+    pub fn iter_uuids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        std::iter::repeat_with(|| self.generate_uuid())
+    }
+
+    /// Format a UUID with this generator's format, prefix and suffix
+    #[inline]
+    pub fn format(&self, uuid: &Uuid) -> String {
+        let mut buf = [0u8; format::MAX_LEN];
+        let encoded = self.encode(uuid, &mut buf);
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+        let suffix = self.suffix.as_deref().unwrap_or_default();
+
+        // Single allocation sized for prefix, UUID and suffix
+        let mut out = String::with_capacity(prefix.len() + encoded.len() + suffix.len());
+        out.push_str(prefix);
+        out.push_str(encoded);
+        out.push_str(suffix);
+        out
+    }
+
+    /// Append a UUID with this generator's format, prefix and suffix to `out`
+    #[inline]
+    pub fn format_into(&self, uuid: &Uuid, out: &mut String) {
+        let mut buf = [0u8; format::MAX_LEN];
+        if let Some(prefix) = &self.prefix {
+            out.push_str(prefix);
+        }
+        out.push_str(self.encode(uuid, &mut buf));
+        if let Some(suffix) = &self.suffix {
+            out.push_str(suffix);
+        }
+    }
+
+    /// Whether `input` could have been produced by this generator
+    ///
+    /// See [`Self::parse`].
+    #[inline]
+    pub fn matches(&self, input: &str) -> bool {
+        self.parse(input).is_ok()
+    }
 
-        match &self.prefix {
-            Some(prefix) => format!("{}{}", prefix, formatted),
-            None => formatted,
+    /// Parse an ID produced by a generator with this configuration
+    ///
+    /// Checks, in order, the prefix, the suffix, that the UUID parses, that
+    /// it is spelled exactly as this generator's format (hyphens, case,
+    /// braces) and that its version matches. V5 namespaces cannot be checked
+    /// without the name.
+    pub fn parse(&self, input: &str) -> Result<Uuid, MatchError> {
+        let mut body = input;
+        if let Some(prefix) = &self.prefix {
+            body = body
+                .strip_prefix(prefix.as_str())
+                .ok_or_else(|| MatchError::MissingPrefix(prefix.clone()))?;
+        }
+        if let Some(suffix) = &self.suffix {
+            body = body
+                .strip_suffix(suffix.as_str())
+                .ok_or_else(|| MatchError::MissingSuffix(suffix.clone()))?;
         }
+
+        let uuid = match self.format {
+            UuidFormat::Base32Crockford => {
+                encoding::decode_base32(body).map_err(MatchError::InvalidEncoding)?
+            }
+            UuidFormat::Base58 => {
+                encoding::decode_base58(body).map_err(MatchError::InvalidEncoding)?
+            }
+            UuidFormat::Base62 => {
+                encoding::decode_base62(body).map_err(MatchError::InvalidEncoding)?
+            }
+            UuidFormat::Proquint => {
+                encoding::decode_proquint(body).map_err(MatchError::InvalidEncoding)?
+            }
+            _ => Uuid::try_parse(body).map_err(MatchError::InvalidUuid)?,
+        };
+
+        let mut buf = [0u8; format::MAX_LEN];
+        if self.encode(&uuid, &mut buf) != body {
+            return Err(MatchError::FormatMismatch {
+                expected: self.format,
+            });
+        }
+
+        let expected = match self.version {
+            UuidVersion::V4 => 4,
+            UuidVersion::V7 => 7,
+            UuidVersion::V5 => 5,
+        };
+        let found = uuid.get_version_num();
+        #[cfg(feature = "custom-uuid")]
+        let expected_metadata = self.version == UuidVersion::V7 && self.metadata_v8 && found == 8;
+        #[cfg(not(feature = "custom-uuid"))]
+        let expected_metadata = false;
+        if found != expected && !expected_metadata {
+            return Err(MatchError::VersionMismatch {
+                expected: self.version,
+                found,
+            });
+        }
+
+        Ok(uuid)
+    }
+
+    /// Unpredictable bits in each ID from [`Self::generate`]
+    ///
+    /// 122 for v4 and 74 for v7, what is left after the version, variant and
+    /// timestamp bits. A monotonic v7 counter follows from the previous ID,
+    /// leaving the last 32 random bits. Seeded generators have none, as
+    /// every ID follows from the seed.
+    pub fn entropy_bits(&self) -> f64 {
+        if self.is_seeded() {
+            return 0.0;
+        }
+        match self.version {
+            UuidVersion::V4 => 122.0,
+            UuidVersion::V7 if self.monotonic.is_some() => 32.0,
+            UuidVersion::V7 => 74.0,
+            UuidVersion::V5 => 0.0,
+        }
+    }
+
+    /// [`Self::entropy_bits`] with the scheme, length and caveats, for
+    /// review documents
+    ///
+    /// ```
+    /// use gen_id::UuidGenerator;
+    ///
+    /// let report = UuidGenerator::v4().strength_report();
+    /// assert_eq!(report.to_string(), "UUID v4, 36 characters: 122.0 bits of entropy");
+    /// ```
+    pub fn strength_report(&self) -> IdStrengthReport {
+        let scheme = match self.version {
+            UuidVersion::V4 => "UUID v4",
+            UuidVersion::V7 => "UUID v7",
+            UuidVersion::V5 => "UUID v5",
+        };
+        let mut report = IdStrengthReport::new(scheme, self.body_len(), self.entropy_bits());
+        if self.version == UuidVersion::V7 {
+            report = report.with_note("the first 48 bits are the creation time in milliseconds");
+            if self.monotonic.is_some() {
+                report = report.with_note(
+                    "a 42-bit counter orders IDs within a millisecond, \
+                     only the last 32 bits are fresh per ID",
+                );
+            }
+        }
+        if self.version == UuidVersion::V5 {
+            report = report.with_note("derived from the namespace and name, nothing is random");
+        } else if self.is_seeded() {
+            report = report.with_note("seeded ChaCha8 RNG, every ID follows from the seed");
+        }
+        report
+    }
+
+    /// Unpredictable bits in each ID from [`Self::generate_with_metadata`]
+    ///
+    /// The OS, hostname, app version and checksum fields take the place of
+    /// random bits, leaving the 12 tail bits. Those hold a counter starting
+    /// from 11 random bits in each millisecond, counted for the first ID.
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[cfg(feature = "custom-uuid")]
+    pub fn metadata_entropy_bits(&self) -> f64 {
+        if self.is_seeded() { 0.0 } else { 11.0 }
+    }
+
+    /// [`Self::strength_report`] for [`Self::generate_with_metadata`]
+    ///
+    /// # Availability
+    /// This method is only available when the `custom-uuid` feature is enabled.
+    #[cfg(feature = "custom-uuid")]
+    pub fn metadata_strength_report(&self) -> IdStrengthReport {
+        let scheme = if self.metadata_v8 {
+            "UUID v8 with metadata"
+        } else {
+            "UUID v7 with metadata"
+        };
+        let mut report =
+            IdStrengthReport::new(scheme, self.body_len(), self.metadata_entropy_bits())
+                .with_note("the first 48 bits are the creation time in milliseconds")
+                .with_note("OS, hostname hash, app version and checksum fill 62 bits");
+        if self.is_seeded() {
+            report = report.with_note("seeded ChaCha8 RNG, every ID follows from the seed");
+        }
+        report.with_note("the 12 tail bits count up from a random start each millisecond")
+    }
+
+    #[inline]
+    fn is_seeded(&self) -> bool {
+        matches!(self.rng.as_deref(), Some(RandomSource::Seeded(_)))
+    }
+
+    /// Characters in a formatted UUID, without prefix or suffix
+    #[inline]
+    fn body_len(&self) -> usize {
+        self.encode(&Uuid::max(), &mut [0u8; format::MAX_LEN]).len()
+    }
+
+    /// Encode into a stack buffer large enough for the longest format
+    #[inline]
+    fn encode<'a>(&self, uuid: &Uuid, buf: &'a mut [u8; format::MAX_LEN]) -> &'a str {
+        format::encode(uuid, self.format, buf)
+    }
+}
+
+impl Default for UuidGenerator {
+    fn default() -> Self {
+        Self::v4()
+    }
+}
+
+/// UUID v5 generator, deriving each ID from a namespace and a name
+///
+/// The same namespace and name always produce the same UUID, formatted and
+/// prefixed like [`UuidGenerator`] output. There is no random or time-based
+/// `generate`, every ID needs a name.
+#[derive(Debug, Clone)]
+pub struct NamedUuidGenerator {
+    namespace: Uuid,
+    inner: UuidGenerator,
+}
+
+impl NamedUuidGenerator {
+    /// Create a generator hashing names into `namespace`, with standard
+    /// format
+    pub fn new(namespace: Uuid) -> Self {
+        Self {
+            namespace,
+            inner: UuidGenerator::with_version(UuidVersion::V5, UuidFormat::Standard),
+        }
+    }
+
+    /// The namespace names are hashed into
+    #[inline]
+    pub fn namespace(&self) -> Uuid {
+        self.namespace
+    }
+
+    /// Set the output format
+    #[inline]
+    pub fn with_format(mut self, format: UuidFormat) -> Self {
+        self.inner = self.inner.with_format(format);
+        self
+    }
+
+    /// Set a prefix, see [`UuidGenerator::with_prefix`]
+    #[inline]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.inner = self.inner.with_prefix(prefix);
+        self
+    }
+
+    /// Set a prefix after checking it, see [`UuidGenerator::try_with_prefix`]
+    #[inline]
+    pub fn try_with_prefix(mut self, prefix: impl Into<String>) -> Result<Self, PrefixError> {
+        self.inner = self.inner.try_with_prefix(prefix)?;
+        Ok(self)
+    }
+
+    /// Set a suffix appended after the formatted UUID
+    #[inline]
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.inner = self.inner.with_suffix(suffix);
+        self
+    }
+
+    /// Generate the UUID v5 for `name`
+    #[inline]
+    pub fn generate_named(&self, name: &[u8]) -> String {
+        self.format(&self.generate_named_uuid(name))
+    }
+
+    /// Generate the UUID v5 for `name` without formatting it
+    #[inline]
+    pub fn generate_named_uuid(&self, name: &[u8]) -> Uuid {
+        Uuid::new_v5(&self.namespace, name)
+    }
+
+    /// Generate a batch of UUID v5, one per name
+    #[inline]
+    pub fn generate_named_batch<N: AsRef<[u8]>>(&self, names: &[N]) -> Vec<String> {
+        names
+            .iter()
+            .map(|name| self.generate_named(name.as_ref()))
+            .collect()
+    }
+
+    /// Format a UUID with this generator's format, prefix and suffix
+    #[inline]
+    pub fn format(&self, uuid: &Uuid) -> String {
+        self.inner.format(uuid)
+    }
+
+    /// Whether `input` could have been produced by this generator
+    #[inline]
+    pub fn matches(&self, input: &str) -> bool {
+        self.inner.matches(input)
+    }
+
+    /// Parse an ID produced by a generator with this configuration
+    ///
+    /// See [`UuidGenerator::parse`]. The namespace cannot be checked without
+    /// the name.
+    #[inline]
+    pub fn parse(&self, input: &str) -> Result<Uuid, MatchError> {
+        self.inner.parse(input)
+    }
+
+    /// Always 0, every ID follows from the namespace and name
+    #[inline]
+    pub fn entropy_bits(&self) -> f64 {
+        0.0
+    }
+
+    /// See [`UuidGenerator::strength_report`]
+    #[inline]
+    pub fn strength_report(&self) -> IdStrengthReport {
+        self.inner.strength_report()
+    }
+}
+
+#[inline]
+fn v7_with_random(millis: u64, random: [u8; 16]) -> Uuid {
+    Builder::from_unix_timestamp_millis(millis, random[..10].try_into().unwrap()).into_uuid()
+}
+
+/// Overwrite the random bits of a v7 UUID with client metadata, keeping its
+/// timestamp, setting `version` (7 or 8) and putting the 12-bit `tail` in
+/// bytes 14-15. See [`UuidGenerator::generate_with_metadata`] for the layout.
+#[cfg(feature = "custom-uuid")]
+fn embed_metadata(uuid: Uuid, encoded: &EncodedMetadata, version: u8, tail: u16) -> Uuid {
+    let mut bytes = *uuid.as_bytes();
+
+    // OS metadata (4 bits type + 9 bits version)
+    let os_encoded = encoded.os_encoded;
+
+    // Inject OS type into byte 6 below the version bits (0x7X or 0x8X)
+    bytes[6] = (version << 4) | ((os_encoded >> 8) as u8 & 0x0F);
+
+    // Inject OS version into byte 7
+    bytes[7] = os_encoded as u8;
+
+    // Mark byte 8 and its schema version below the variant bits (already set
+    // on the v7 UUID)
+    set_marker(&mut bytes);
+
+    // Inject hostname hash into byte 9
+    bytes[9] = encoded.hostname_byte;
+
+    // Extended hash from hostname + user agent + device ID. The top bit
+    // carries the top OS type bit and the next 12 the app version, leaving 19
+    // bits of hash
+    let os_type_high = u32::from(os_encoded >> 12) << 31;
+    let app_version = u32::from(encoded.app_version) << 19;
+    bytes[10..14]
+        .copy_from_slice(&(os_type_high | app_version | encoded.extended_hash).to_be_bytes());
+
+    // Bytes 14-15 keep 12 bits for collision resistance, then the checksum
+    bytes[14..].copy_from_slice(&(tail << 4).to_be_bytes());
+    set_checksum(&mut bytes);
+
+    Uuid::from_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uuid::{
+        extract_v7_timestamp, from_proquint, from_short, parse_encoded, parse_uuid, strip_affixes,
+    };
+
+    #[test]
+    fn test_entropy_bits() {
+        use rand_chacha::ChaCha8Rng;
+        use rand_core::SeedableRng;
+
+        assert_eq!(UuidGenerator::v4().entropy_bits(), 122.0);
+        assert_eq!(UuidGenerator::v7().entropy_bits(), 74.0);
+        assert_eq!(
+            UuidGenerator::v7().with_monotonic(true).entropy_bits(),
+            32.0
+        );
+        assert_eq!(UuidGenerator::v5(Uuid::nil()).entropy_bits(), 0.0);
+        assert_eq!(UuidGenerator::v4_seeded(1).entropy_bits(), 0.0);
+        // An injected RNG must be a CryptoRng, so it keeps the full count
+        let injected = UuidGenerator::v4().with_rng(ChaCha8Rng::seed_from_u64(0));
+        assert_eq!(injected.entropy_bits(), 122.0);
+    }
+
+    #[test]
+    fn test_strength_report() {
+        let report = UuidGenerator::v4()
+            .with_format(UuidFormat::Base58)
+            .with_prefix("usr_")
+            .strength_report();
+        assert_eq!(report.scheme, "UUID v4");
+        assert_eq!(report.length, 22);
+        assert_eq!(report.entropy_bits, 122.0);
+        assert!(report.notes.is_empty());
+
+        let report = UuidGenerator::v7().with_monotonic(true).strength_report();
+        assert_eq!(
+            report.to_string(),
+            "UUID v7, 36 characters: 32.0 bits of entropy\n  \
+             note: the first 48 bits are the creation time in milliseconds\n  \
+             note: a 42-bit counter orders IDs within a millisecond, \
+             only the last 32 bits are fresh per ID"
+        );
+        assert_eq!(UuidGenerator::v4_seeded(1).strength_report().notes.len(), 1);
+        assert_eq!(
+            UuidGenerator::v7()
+                .with_format(UuidFormat::Urn)
+                .strength_report()
+                .length,
+            45
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_entropy_bits() {
+        assert_eq!(UuidGenerator::v7().metadata_entropy_bits(), 11.0);
+        assert_eq!(
+            UuidGenerator::v7()
+                .with_monotonic(true)
+                .metadata_entropy_bits(),
+            11.0
+        );
+        let report = UuidGenerator::v7()
+            .with_metadata_v8(true)
+            .metadata_strength_report();
+        assert_eq!(report.scheme, "UUID v8 with metadata");
+        assert_eq!(report.entropy_bits, 11.0);
+        assert_eq!(report.notes.len(), 3);
+    }
+
+    #[test]
+    fn test_v4_generation() {
+        let generator = UuidGenerator::v4();
+        let uuid = generator.generate();
+
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(uuid.chars().filter(|&c| c == '-').count(), 4);
+        assert!(parse_uuid(&uuid).is_ok());
+    }
+
+    #[test]
+    fn test_v7_generation() {
+        let generator = UuidGenerator::v7();
+        let uuid = generator.generate();
+
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(uuid.chars().filter(|&c| c == '-').count(), 4);
+        assert!(parse_uuid(&uuid).is_ok());
+    }
+
+    #[test]
+    fn test_v7_sortability() {
+        let generator = UuidGenerator::v7();
+        let mut uuids = Vec::new();
+
+        for _ in 0..5 {
+            uuids.push(generator.generate());
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        let mut sorted = uuids.clone();
+        sorted.sort();
+        assert_eq!(
+            uuids, sorted,
+            "UUID v7 should be sortable lexicographically"
+        );
+    }
+
+    #[test]
+    fn test_typed_generation() {
+        let v4 = UuidGenerator::v4().generate_uuid();
+        assert_eq!(v4.get_version_num(), 4);
+
+        let v7 = UuidGenerator::v7().generate_uuid();
+        assert_eq!(v7.get_version_num(), 7);
+        assert!(v7.get_timestamp().is_some());
+    }
+
+    #[test]
+    fn test_typed_batch_generation() {
+        let batch = UuidGenerator::v7().generate_uuid_batch(10);
+
+        assert_eq!(batch.len(), 10);
+        assert!(batch.iter().all(|uuid| uuid.get_version_num() == 7));
+
+        let unique: std::collections::HashSet<_> = batch.iter().collect();
+        assert_eq!(unique.len(), 10);
+    }
+
+    #[test]
+    fn test_format_matches_generate() {
+        let generator = UuidGenerator::v4()
+            .with_format(UuidFormat::SimpleUppercase)
+            .with_prefix("user_");
+        let uuid = generator.generate_uuid();
+        let formatted = generator.format(&uuid);
+
+        assert_eq!(
+            formatted,
+            format!("user_{}", uuid.simple().to_string().to_uppercase())
+        );
+        assert_eq!(parse_uuid(&formatted[5..]).unwrap(), uuid);
+
+        let named = UuidGenerator::v5(Uuid::NAMESPACE_DNS).with_prefix("user_");
+        assert_eq!(
+            named.generate_named(b"x"),
+            named.format(&named.generate_named_uuid(b"x"))
+        );
+    }
+
+    #[test]
+    fn test_generate_at_uses_given_timestamp() {
+        let at = UNIX_EPOCH + std::time::Duration::from_millis(1_600_000_000_123);
+        let generator = UuidGenerator::v4().with_prefix("trade_");
+
+        let uuid = generator.generate_uuid_at(at).unwrap();
+        assert_eq!(uuid.get_version_num(), 7);
+        assert_eq!(
+            uuid.get_timestamp().unwrap().to_unix(),
+            (1_600_000_000, 123_000_000)
+        );
+        assert_ne!(uuid, generator.generate_uuid_at(at).unwrap());
+
+        let formatted = generator.generate_at(at).unwrap();
+        assert!(formatted.starts_with("trade_"));
+        assert_eq!(
+            parse_uuid(&formatted[6..]).unwrap().get_timestamp(),
+            uuid.get_timestamp()
+        );
+    }
+
+    #[test]
+    fn test_generate_at_rejects_unrepresentable_timestamps() {
+        let generator = UuidGenerator::v7();
+
+        let before = UNIX_EPOCH - std::time::Duration::from_secs(1);
+        assert_eq!(
+            generator.generate_at(before),
+            Err(TimestampError::BeforeEpoch(std::time::Duration::from_secs(
+                1
+            )))
+        );
+
+        let max = UNIX_EPOCH + std::time::Duration::from_millis(MAX_V7_MILLIS);
+        assert!(generator.generate_at(max).is_ok());
+        assert_eq!(
+            generator.generate_at(max + std::time::Duration::from_millis(1)),
+            Err(TimestampError::OutOfRange(u128::from(MAX_V7_MILLIS) + 1))
+        );
+    }
+
+    /// Counts up one byte at a time, standing in for an audited DRBG
+    struct CountingRng(u8);
+
+    impl RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_be_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_be_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                *byte = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+        }
+    }
+
+    impl CryptoRng for CountingRng {}
+
+    fn counting_bytes(start: u8) -> [u8; 16] {
+        std::array::from_fn(|i| start.wrapping_add(i as u8))
+    }
+
+    #[test]
+    fn test_injected_rng_v4() {
+        let generator = UuidGenerator::v4().with_rng(CountingRng(0));
+
+        assert_eq!(
+            generator.generate_uuid(),
+            Builder::from_random_bytes(counting_bytes(0)).into_uuid()
+        );
+        // Clones continue the same sequence
+        assert_eq!(
+            generator.clone().generate_uuid(),
+            Builder::from_random_bytes(counting_bytes(16)).into_uuid()
+        );
+    }
+
+    #[test]
+    fn test_injected_rng_v7_keeps_real_clock() {
+        let generator = UuidGenerator::v7().with_rng(CountingRng(100));
+
+        let before = SystemTime::now();
+        let uuid = generator.generate_uuid();
+        let after = SystemTime::now();
+
+        let millis = extract_v7_timestamp(&uuid).unwrap();
+        let to_millis = |t: SystemTime| t.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        assert!((to_millis(before)..=to_millis(after)).contains(&millis));
+        assert_eq!(uuid, v7_with_random(millis, counting_bytes(100)));
+    }
+
+    #[test]
+    fn test_injected_rng_batch_matches_single_calls() {
+        for version in [UuidVersion::V4, UuidVersion::V7] {
+            let batched = UuidGenerator::new(version, UuidFormat::Standard)
+                .with_clock(|| 1_000)
+                .with_rng(CountingRng(7));
+            let single = UuidGenerator::new(version, UuidFormat::Standard)
+                .with_clock(|| 1_000)
+                .with_rng(CountingRng(7));
+
+            let expected: Vec<Uuid> = (0..20).map(|_| single.generate_uuid()).collect();
+            assert_eq!(batched.generate_uuid_batch(20), expected, "{version:?}");
+
+            let strings: Vec<String> = expected[..5].iter().map(|u| single.format(u)).collect();
+            let mut out = Vec::new();
+            UuidGenerator::new(version, UuidFormat::Standard)
+                .with_clock(|| 1_000)
+                .with_rng(CountingRng(7))
+                .generate_batch_into(5, &mut out);
+            assert_eq!(out, strings, "{version:?}");
+        }
+    }
+
+    #[test]
+    fn test_seeded_v4_is_reproducible() {
+        let first = UuidGenerator::v4_seeded(42).generate_batch(5);
+        let second = UuidGenerator::v4_seeded(42).generate_batch(5);
+
+        assert_eq!(first, second);
+        assert_ne!(first, UuidGenerator::v4_seeded(43).generate_batch(5));
+        for uuid in &first {
+            assert_eq!(parse_uuid(uuid).unwrap().get_version_num(), 4);
+        }
+    }
+
+    #[test]
+    fn test_seeded_v7_with_frozen_clock() {
+        let generator = || {
+            UuidGenerator::v7()
+                .with_seed(7)
+                .with_clock(|| 1_700_000_000_000)
+        };
+        let uuids = generator().generate_uuid_batch(3);
+
+        assert_eq!(uuids, generator().generate_uuid_batch(3));
+        for uuid in &uuids {
+            assert_eq!(uuid.get_version_num(), 7);
+            assert_eq!(uuid.get_timestamp().unwrap().to_unix(), (1_700_000_000, 0));
+        }
+    }
+
+    #[test]
+    fn test_stepping_clock_with_monotonic_mode() {
+        let generator = || {
+            let tick = std::sync::atomic::AtomicU64::new(1_700_000_000_000);
+            UuidGenerator::v7()
+                .with_seed(1)
+                .with_monotonic(true)
+                .with_clock(move || tick.fetch_add(1, std::sync::atomic::Ordering::Relaxed) / 2)
+        };
+        let uuids = generator().generate_uuid_batch(10);
+
+        assert_eq!(uuids, generator().generate_uuid_batch(10));
+        assert!(uuids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    /// A clock replaying `millis`, then staying at the last value
+    fn replay_clock(millis: &'static [u64]) -> impl Fn() -> u64 + Send + Sync + 'static {
+        let index = std::sync::atomic::AtomicUsize::new(0);
+        move || millis[index.fetch_add(1, Ordering::Relaxed).min(millis.len() - 1)]
+    }
+
+    const BACKWARDS: &[u64] = &[1_000, 1_005, 980, 990, 1_004, 1_010, 950, 1_011];
+
+    #[test]
+    fn test_clock_guard_holds_timestamp() {
+        let generator = UuidGenerator::v7()
+            .with_clock(replay_clock(BACKWARDS))
+            .with_clock_guard(true);
+        let timestamps: Vec<_> = (0..BACKWARDS.len())
+            .map(|_| extract_v7_timestamp(&generator.generate_uuid()).unwrap())
+            .collect();
+
+        assert_eq!(
+            timestamps,
+            [1_000, 1_005, 1_005, 1_005, 1_005, 1_010, 1_010, 1_011]
+        );
+
+        // Without the guard the timestamps follow the clock
+        let unguarded = UuidGenerator::v7().with_clock(replay_clock(BACKWARDS));
+        assert_eq!(
+            extract_v7_timestamp(&unguarded.generate_uuid()),
+            Some(1_000)
+        );
+        unguarded.generate_uuid();
+        assert_eq!(extract_v7_timestamp(&unguarded.generate_uuid()), Some(980));
+    }
+
+    #[test]
+    fn test_clock_guard_with_monotonic_never_decreases() {
+        let generator = UuidGenerator::v7()
+            .with_clock(replay_clock(BACKWARDS))
+            .with_clock_guard(true)
+            .with_monotonic(true);
+        let clone = generator.clone();
+
+        // Clones share the guard, alternate between them
+        let uuids: Vec<_> = (0..BACKWARDS.len())
+            .map(|i| {
+                if i % 2 == 0 {
+                    generator.generate_uuid()
+                } else {
+                    clone.generate_uuid()
+                }
+            })
+            .collect();
+        assert!(uuids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_clock_guard_across_threads() {
+        let generator = UuidGenerator::v7().with_clock_guard(true);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let generator = generator.clone();
+                std::thread::spawn(move || {
+                    let timestamps: Vec<_> = (0..1_000)
+                        .map(|_| extract_v7_timestamp(&generator.generate_uuid()).unwrap())
+                        .collect();
+                    timestamps.windows(2).all(|pair| pair[0] <= pair[1])
+                })
+            })
+            .collect();
+        assert!(handles.into_iter().all(|handle| handle.join().unwrap()));
+    }
+
+    #[test]
+    fn test_uuid_batch_is_unique() {
+        for generator in [UuidGenerator::v4(), UuidGenerator::v7()] {
+            let batch = generator.generate_uuid_batch(100_000);
+            let unique: std::collections::HashSet<_> = batch.iter().collect();
+            assert_eq!(unique.len(), 100_000);
+        }
+    }
+
+    #[test]
+    fn test_uuid_batch_matches_string_batch() {
+        let generator = || {
+            UuidGenerator::v7()
+                .with_seed(3)
+                .with_clock(|| 1_700_000_000_000)
+                .with_prefix("evt_")
+                .with_format(UuidFormat::SimpleUppercase)
+        };
+        let uuids = generator().generate_uuid_batch(1_000);
+        let strings = generator().generate_batch(1_000);
+
+        let formatter = generator();
+        assert_eq!(
+            uuids
+                .iter()
+                .map(|uuid| formatter.format(uuid))
+                .collect::<Vec<_>>(),
+            strings
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_uuid_batch_with_metadata() {
+        use crate::uuid::{ClientMetadata, OsType, extract_metadata};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let batch = UuidGenerator::v7().generate_uuid_batch_with_metadata(100_000, &metadata);
+        let unique: std::collections::HashSet<_> = batch.iter().collect();
+        assert_eq!(unique.len(), 100_000);
+        assert!(batch.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(batch.iter().all(|uuid| extract_metadata(uuid).is_some()));
+
+        let generator = || {
+            UuidGenerator::v7()
+                .with_seed(5)
+                .with_clock(|| 1_700_000_000_000)
+                .with_prefix("evt_")
+        };
+        let uuids = generator().generate_uuid_batch_with_metadata(5_000, &metadata);
+        let strings = generator().generate_batch_with_metadata(5_000, &metadata);
+        let formatter = generator();
+        assert_eq!(
+            uuids
+                .iter()
+                .map(|uuid| formatter.format(uuid))
+                .collect::<Vec<_>>(),
+            strings
+        );
+    }
+
+    #[test]
+    fn test_v5_generation() {
+        let generator = UuidGenerator::v5(Uuid::NAMESPACE_DNS);
+        let uuid = generator.generate_named(b"python.org");
+
+        assert_eq!(uuid, "886313e1-3b8a-5372-9b90-0c9aee199e5d");
+        assert_eq!(uuid, generator.generate_named(b"python.org"));
+        assert_ne!(uuid, generator.generate_named(b"rust-lang.org"));
+        assert_eq!(parse_uuid(&uuid).unwrap().get_version_num(), 5);
+    }
+
+    #[test]
+    fn test_v5_namespaces_differ() {
+        let dns = UuidGenerator::v5(Uuid::NAMESPACE_DNS);
+        let url = UuidGenerator::v5(Uuid::NAMESPACE_URL);
+
+        assert_ne!(
+            dns.generate_named(b"BTC-USD:42"),
+            url.generate_named(b"BTC-USD:42")
+        );
+    }
+
+    #[test]
+    fn test_v5_with_prefix_and_format() {
+        let generator = UuidGenerator::v5(Uuid::NAMESPACE_OID)
+            .with_format(UuidFormat::SimpleUppercase)
+            .with_prefix("acct_");
+        let uuid = generator.generate_named(b"BTC-USD:42");
+
+        assert!(uuid.starts_with("acct_"));
+        assert_eq!(uuid.len(), 37); // "acct_" (5) + simple UUID (32)
+
+        let expected = Uuid::new_v5(&Uuid::NAMESPACE_OID, b"BTC-USD:42");
+        assert_eq!(parse_uuid(&uuid[5..]).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_v5_named_batch() {
+        let generator = UuidGenerator::v5(Uuid::NAMESPACE_DNS);
+        let batch = generator.generate_named_batch(&["a", "b", "a"]);
+
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch[0], batch[2]);
+        assert_ne!(batch[0], batch[1]);
+        assert_eq!(batch[1], generator.generate_named(b"b"));
+    }
+
+    #[test]
+    fn test_try_new_rejects_v5() {
+        assert_eq!(
+            UuidGenerator::try_new(UuidVersion::V5, UuidFormat::Standard).unwrap_err(),
+            VersionError::NameBased
+        );
+        assert!(UuidGenerator::try_new(UuidVersion::V7, UuidFormat::Simple).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "NamedUuidGenerator")]
+    fn test_new_v5_panics() {
+        UuidGenerator::new(UuidVersion::V5, UuidFormat::Standard);
+    }
+
+    #[test]
+    fn test_v7_monotonic_ordering() {
+        let generator = UuidGenerator::v7().with_monotonic(true);
+        let uuids = generator.generate_uuid_batch(100_000);
+
+        assert!(uuids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(uuids.iter().all(|uuid| uuid.get_version_num() == 7));
+
+        let formatted = generator.generate_batch(1_000);
+        assert!(formatted.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_v7_monotonic_shared_across_clones() {
+        let generator = UuidGenerator::v7().with_monotonic(true);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let generator = generator.clone();
+                std::thread::spawn(move || generator.generate_uuid_batch(10_000))
+            })
+            .collect();
+
+        let mut all = Vec::new();
+        for handle in handles {
+            let uuids = handle.join().unwrap();
+            assert!(uuids.windows(2).all(|pair| pair[0] < pair[1]));
+            all.extend(uuids);
+        }
+
+        let unique: std::collections::HashSet<_> = all.iter().collect();
+        assert_eq!(unique.len(), 40_000);
+    }
+
+    #[test]
+    fn test_simple_format() {
+        let generator = UuidGenerator::v4().with_format(UuidFormat::Simple);
+        let uuid = generator.generate();
+
+        assert_eq!(uuid.len(), 32);
+        assert!(!uuid.contains('-'));
+        assert!(uuid.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_standard_uppercase_format() {
+        let generator = UuidGenerator::v4().with_format(UuidFormat::StandardUppercase);
+        let uuid = generator.generate();
+
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(uuid.chars().filter(|&c| c == '-').count(), 4);
+        assert!(
+            uuid.chars()
+                .filter(|c| c.is_alphabetic())
+                .all(|c| c.is_uppercase())
+        );
+    }
+
+    #[test]
+    fn test_simple_uppercase_format() {
+        let generator = UuidGenerator::v4().with_format(UuidFormat::SimpleUppercase);
+        let uuid = generator.generate();
+
+        assert_eq!(uuid.len(), 32);
+        assert!(!uuid.contains('-'));
+        assert!(
+            uuid.chars()
+                .filter(|c| c.is_alphabetic())
+                .all(|c| c.is_uppercase())
+        );
+    }
+
+    #[test]
+    fn test_braced_format() {
+        let generator = UuidGenerator::v4().with_format(UuidFormat::Braced);
+        let uuid = generator.generate();
+
+        assert_eq!(uuid.len(), 38);
+        assert!(uuid.starts_with('{') && uuid.ends_with('}'));
+        assert!(!uuid.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(parse_uuid(&uuid).is_ok());
+    }
+
+    #[test]
+    fn test_braced_uppercase_format() {
+        let generator = UuidGenerator::v4().with_format(UuidFormat::BracedUppercase);
+        let uuid = generator.generate();
+
+        assert_eq!(uuid.len(), 38);
+        assert!(uuid.starts_with('{') && uuid.ends_with('}'));
+        assert!(
+            uuid.chars()
+                .filter(|c| c.is_alphabetic())
+                .all(|c| c.is_uppercase())
+        );
+        assert!(parse_uuid(&uuid).is_ok());
+    }
+
+    #[test]
+    fn test_urn_format() {
+        let generator = UuidGenerator::v7().with_format(UuidFormat::Urn);
+        let uuid = generator.generate();
+
+        assert_eq!(uuid.len(), 45);
+        assert!(uuid.starts_with("urn:uuid:"));
+        assert!(parse_uuid(&uuid).is_ok());
+    }
+
+    #[test]
+    fn test_braced_and_urn_with_prefix() {
+        let cases = [
+            (UuidFormat::Braced, "{", 38),
+            (UuidFormat::BracedUppercase, "{", 38),
+            (UuidFormat::Urn, "urn:uuid:", 45),
+        ];
+
+        for (format, marker, len) in cases {
+            let generator = UuidGenerator::v4().with_format(format).with_prefix("user_");
+            let uuid = generator.generate();
+
+            assert_eq!(uuid.len(), 5 + len, "{format:?}");
+            assert!(uuid[5..].starts_with(marker), "{format:?}");
+            assert!(parse_uuid(&uuid[5..]).is_ok(), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_with_prefix() {
+        let generator = UuidGenerator::v4().with_prefix("user_");
+        let uuid = generator.generate();
+
+        assert!(uuid.starts_with("user_"));
+        assert_eq!(uuid.len(), 41); // "user_" (5) + standard UUID (36)
+
+        let uuid_part = &uuid[5..];
+        assert!(parse_uuid(uuid_part).is_ok());
+    }
+
+    #[test]
+    fn test_with_prefix_simple_format() {
+        let generator = UuidGenerator::v4()
+            .with_format(UuidFormat::Simple)
+            .with_prefix("id_");
+        let uuid = generator.generate();
+
+        assert!(uuid.starts_with("id_"));
+        assert_eq!(uuid.len(), 35); // "id_" (3) + simple UUID (32)
+    }
+
+    #[test]
+    fn test_with_suffix() {
+        let generator = UuidGenerator::v4()
+            .with_prefix("order_")
+            .with_suffix("_sg1");
+        let id = generator.generate();
+
+        assert!(id.starts_with("order_") && id.ends_with("_sg1"));
+        assert_eq!(id.len(), 46); // "order_" (6) + UUID (36) + "_sg1" (4)
+
+        let uuid = parse_uuid(strip_affixes(&id, "order_", "_sg1").unwrap()).unwrap();
+        assert_eq!(generator.format(&uuid), id);
+
+        let mut out = String::new();
+        generator.format_into(&uuid, &mut out);
+        assert_eq!(out, id);
+
+        assert_eq!(generator.without_suffix().format(&uuid).len(), 42);
+    }
+
+    #[test]
+    fn test_suffix_in_batches() {
+        let generator = UuidGenerator::v7()
+            .with_format(UuidFormat::Simple)
+            .with_suffix("_eu");
+
+        for id in generator.generate_batch(5) {
+            assert!(id.ends_with("_eu"));
+            assert!(parse_uuid(strip_affixes(&id, "", "_eu").unwrap()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_try_with_prefix() {
+        let generator = UuidGenerator::v4().try_with_prefix("order:").unwrap();
+        assert!(generator.generate().starts_with("order:"));
+
+        assert!(matches!(
+            UuidGenerator::v4().try_with_prefix("bad prefix\n"),
+            Err(PrefixError::InvalidChar { ch: ' ', .. })
+        ));
+        assert!(matches!(
+            UuidGenerator::v4().try_with_prefix("x".repeat(33)),
+            Err(PrefixError::TooLong { len: 33, max: 32 })
+        ));
+
+        let policy = PrefixPolicy::default().with_extra_chars("/");
+        assert!(
+            UuidGenerator::v4()
+                .try_with_prefix_policy("tenant/", &policy)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_empty_prefix_is_no_prefix() {
+        let generator = UuidGenerator::v4().try_with_prefix("").unwrap();
+        assert_eq!(generator.generate().len(), 36);
+
+        let generator = UuidGenerator::v4().with_prefix("");
+        assert_eq!(generator.generate().len(), 36);
+    }
+
+    #[test]
+    fn test_parse_round_trips_own_output() {
+        let generator = UuidGenerator::v7()
+            .with_format(UuidFormat::Simple)
+            .with_prefix("trade_")
+            .with_suffix("_sg1");
+        let uuid = generator.generate_uuid();
+        let id = generator.format(&uuid);
+
+        assert_eq!(generator.parse(&id), Ok(uuid));
+        assert!(generator.matches(&id));
+
+        let named = UuidGenerator::v5(Uuid::NAMESPACE_DNS).with_format(UuidFormat::Urn);
+        assert!(named.matches(&named.generate_named(b"x")));
+    }
+
+    #[test]
+    fn test_parse_rejects_mismatches() {
+        let generator = UuidGenerator::v7()
+            .with_format(UuidFormat::Simple)
+            .with_prefix("trade_");
+        let uuid = generator.generate_uuid();
+
+        assert_eq!(
+            generator.parse(&format!("order_{}", uuid.simple())),
+            Err(MatchError::MissingPrefix("trade_".to_string()))
+        );
+        assert_eq!(
+            generator.parse(&format!("trade_{}", uuid.hyphenated())),
+            Err(MatchError::FormatMismatch {
+                expected: UuidFormat::Simple
+            })
+        );
+        assert_eq!(
+            generator.parse(&format!(
+                "trade_{}",
+                uuid.simple().to_string().to_uppercase()
+            )),
+            Err(MatchError::FormatMismatch {
+                expected: UuidFormat::Simple
+            })
+        );
+        assert_eq!(
+            generator.parse(&format!("trade_{}", Uuid::new_v4().simple())),
+            Err(MatchError::VersionMismatch {
+                expected: UuidVersion::V7,
+                found: 4
+            })
+        );
+        assert!(matches!(
+            generator.parse("trade_not-a-uuid"),
+            Err(MatchError::InvalidUuid(_))
+        ));
+        assert_eq!(
+            generator
+                .with_suffix("_sg1")
+                .parse(&format!("trade_{}", uuid.simple())),
+            Err(MatchError::MissingSuffix("_sg1".to_string()))
+        );
     }
-}
 
-impl Default for UuidGenerator {
-    fn default() -> Self {
-        Self::v4()
+    #[test]
+    fn test_parse_with_hex_prefix() {
+        // The prefix itself is valid hex, so it must be stripped exactly
+        let generator = UuidGenerator::v4()
+            .with_format(UuidFormat::Simple)
+            .with_prefix("abc");
+        // Fixed so "ab" + UUID never happens to start with "abc"
+        let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+
+        assert_eq!(generator.parse(&generator.format(&uuid)), Ok(uuid));
+        assert!(matches!(
+            generator.parse(&format!("ab{}", uuid.simple())),
+            Err(MatchError::MissingPrefix(_))
+        ));
+
+        let unprefixed = UuidGenerator::v4().with_format(UuidFormat::Simple);
+        assert!(matches!(
+            unprefixed.parse(&format!("abc{}", uuid.simple())),
+            Err(MatchError::InvalidUuid(_))
+        ));
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::uuid::parse_uuid;
 
     #[test]
-    fn test_v4_generation() {
-        let generator = UuidGenerator::v4();
+    fn test_without_prefix() {
+        let generator = UuidGenerator::v4().with_prefix("test_").without_prefix();
         let uuid = generator.generate();
 
+        assert!(!uuid.starts_with("test_"));
         assert_eq!(uuid.len(), 36);
-        assert_eq!(uuid.chars().filter(|&c| c == '-').count(), 4);
-        assert!(parse_uuid(&uuid).is_ok());
     }
 
     #[test]
-    fn test_v7_generation() {
-        let generator = UuidGenerator::v7();
-        let uuid = generator.generate();
+    fn test_batch_generation() {
+        let generator = UuidGenerator::v4();
+        let batch = generator.generate_batch(10);
 
-        assert_eq!(uuid.len(), 36);
-        assert_eq!(uuid.chars().filter(|&c| c == '-').count(), 4);
-        assert!(parse_uuid(&uuid).is_ok());
+        assert_eq!(batch.len(), 10);
+
+        for uuid in &batch {
+            assert!(parse_uuid(uuid).is_ok());
+        }
+
+        let unique: std::collections::HashSet<_> = batch.iter().collect();
+        assert_eq!(unique.len(), 10);
     }
 
     #[test]
-    fn test_v7_sortability() {
-        let generator = UuidGenerator::v7();
-        let mut uuids = Vec::new();
+    fn test_iter_respects_format_and_prefix() {
+        let generator = UuidGenerator::v4()
+            .with_format(UuidFormat::Simple)
+            .with_prefix("id_");
+        let ids: Vec<_> = generator.iter().take(3).collect();
 
-        for _ in 0..5 {
-            uuids.push(generator.generate());
-            std::thread::sleep(std::time::Duration::from_millis(1));
+        assert_eq!(ids.len(), 3);
+        for id in &ids {
+            assert!(id.starts_with("id_"));
+            assert_eq!(id.len(), 35);
         }
 
-        let mut sorted = uuids.clone();
-        sorted.sort();
-        assert_eq!(
-            uuids, sorted,
-            "UUID v7 should be sortable lexicographically"
-        );
+        let unique: std::collections::HashSet<_> = generator.iter().take(10).collect();
+        assert_eq!(unique.len(), 10);
     }
 
     #[test]
-    fn test_simple_format() {
-        let generator = UuidGenerator::v4().with_format(UuidFormat::Simple);
-        let uuid = generator.generate();
+    fn test_iter_uuids_matches_seeded_batch() {
+        let streamed: Vec<_> = UuidGenerator::v4_seeded(9).iter_uuids().take(4).collect();
 
-        assert_eq!(uuid.len(), 32);
-        assert!(!uuid.contains('-'));
-        assert!(uuid.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(streamed, UuidGenerator::v4_seeded(9).generate_uuid_batch(4));
     }
 
     #[test]
-    fn test_standard_uppercase_format() {
-        let generator = UuidGenerator::v4().with_format(UuidFormat::StandardUppercase);
-        let uuid = generator.generate();
+    fn test_iter_preserves_monotonic_order() {
+        let generator = UuidGenerator::v7().with_monotonic(true);
+        let ids: Vec<_> = generator.iter().take(1_000).collect();
 
-        assert_eq!(uuid.len(), 36);
-        assert_eq!(uuid.chars().filter(|&c| c == '-').count(), 4);
-        assert!(
-            uuid.chars()
-                .filter(|c| c.is_alphabetic())
-                .all(|c| c.is_uppercase())
-        );
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
     }
 
     #[test]
-    fn test_simple_uppercase_format() {
-        let generator = UuidGenerator::v4().with_format(UuidFormat::SimpleUppercase);
-        let uuid = generator.generate();
+    fn test_batch_into_reuses_capacity() {
+        let generator = UuidGenerator::v4().with_prefix("order_");
+        let mut out = Vec::with_capacity(64);
+        let buffer = out.as_ptr();
+
+        generator.generate_batch_into(64, &mut out);
+        assert_eq!(out.len(), 64);
+        generator.generate_batch_into(32, &mut out);
+        assert_eq!(out.len(), 32);
+
+        assert_eq!(out.as_ptr(), buffer);
+        assert_eq!(out.capacity(), 64);
+        assert!(out.iter().all(|id| id.starts_with("order_")));
+    }
 
-        assert_eq!(uuid.len(), 32);
-        assert!(!uuid.contains('-'));
-        assert!(
-            uuid.chars()
-                .filter(|c| c.is_alphabetic())
-                .all(|c| c.is_uppercase())
+    #[test]
+    fn test_batch_joined_into() {
+        let generator = UuidGenerator::v4_seeded(3).with_prefix("o_");
+        let mut out = String::with_capacity(1024);
+        let buffer = out.as_ptr();
+
+        generator.generate_batch_joined_into(3, ",", &mut out);
+
+        assert_eq!(out.as_ptr(), buffer);
+        assert_eq!(
+            out,
+            UuidGenerator::v4_seeded(3)
+                .with_prefix("o_")
+                .generate_batch(3)
+                .join(",")
         );
     }
 
     #[test]
-    fn test_with_prefix() {
-        let generator = UuidGenerator::v4().with_prefix("user_");
-        let uuid = generator.generate();
+    fn test_format_output_per_variant() {
+        let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let cases = [
+            (UuidFormat::Standard, "550e8400-e29b-41d4-a716-446655440000"),
+            (UuidFormat::Simple, "550e8400e29b41d4a716446655440000"),
+            (
+                UuidFormat::StandardUppercase,
+                "550E8400-E29B-41D4-A716-446655440000",
+            ),
+            (
+                UuidFormat::SimpleUppercase,
+                "550E8400E29B41D4A716446655440000",
+            ),
+            (UuidFormat::Braced, "{550e8400-e29b-41d4-a716-446655440000}"),
+            (
+                UuidFormat::BracedUppercase,
+                "{550E8400-E29B-41D4-A716-446655440000}",
+            ),
+            (
+                UuidFormat::Urn,
+                "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+            ),
+            (UuidFormat::Base32Crockford, "2N1T201RMV87AAE5J4CSAM8000"),
+            (UuidFormat::Base58, "BWBeN28Vb7cMEx7Ym8AUzs"),
+            (UuidFormat::Base62, "2aUyqjCzEIiEcYMKj7TZtw"),
+            (
+                UuidFormat::Proquint,
+                "jihav-mibab-vapir-halih-pisik-hidok-jijah-babab",
+            ),
+        ];
 
-        assert!(uuid.starts_with("user_"));
-        assert_eq!(uuid.len(), 41); // "user_" (5) + standard UUID (36)
+        for (format, expected) in cases {
+            let generator = UuidGenerator::v4().with_format(format);
+            assert_eq!(generator.format(&uuid), expected);
 
-        let uuid_part = &uuid[5..];
-        assert!(parse_uuid(uuid_part).is_ok());
+            let prefixed = generator.with_prefix("ord_").format(&uuid);
+            assert_eq!(prefixed, format!("ord_{expected}"));
+            assert_eq!(prefixed.capacity(), prefixed.len());
+        }
     }
 
     #[test]
-    fn test_with_prefix_simple_format() {
-        let generator = UuidGenerator::v4()
-            .with_format(UuidFormat::Simple)
-            .with_prefix("id_");
-        let uuid = generator.generate();
+    fn test_encoded_formats_with_prefix_round_trip() {
+        for format in [
+            UuidFormat::Base32Crockford,
+            UuidFormat::Base58,
+            UuidFormat::Base62,
+            UuidFormat::Proquint,
+        ] {
+            let v4 = UuidGenerator::v4()
+                .with_format(format)
+                .with_prefix("ord_")
+                .with_suffix("_x");
+            let generator = UuidGenerator::v7()
+                .with_format(format)
+                .with_prefix("ord_")
+                .with_suffix("_x");
+
+            for _ in 0..100 {
+                let uuid = Uuid::new_v4();
+                let id = v4.format(&uuid);
+
+                assert_eq!(v4.parse(&id), Ok(uuid));
+                assert_eq!(
+                    generator.parse(&id),
+                    Err(MatchError::VersionMismatch {
+                        expected: UuidVersion::V7,
+                        found: 4,
+                    })
+                );
+                let body = strip_affixes(&id, "ord_", "_x").unwrap();
+                let decoded = match format {
+                    UuidFormat::Base62 => from_short(body).unwrap(),
+                    UuidFormat::Proquint => from_proquint(body).unwrap(),
+                    _ => parse_encoded(body).unwrap(),
+                };
+                assert_eq!(decoded, uuid);
+            }
 
-        assert!(uuid.starts_with("id_"));
-        assert_eq!(uuid.len(), 35); // "id_" (3) + simple UUID (32)
+            let id = generator.generate();
+            assert!(generator.matches(&id), "{id}");
+            assert!(!generator.matches(&id.replace("ord_", "usr_")), "{id}");
+        }
     }
 
     #[test]
-    fn test_without_prefix() {
-        let generator = UuidGenerator::v4().with_prefix("test_").without_prefix();
-        let uuid = generator.generate();
+    fn test_encoded_format_parse_errors() {
+        let base32 = UuidGenerator::v4().with_format(UuidFormat::Base32Crockford);
+        assert!(matches!(
+            base32.parse("2N1T201RMV87AAE5J4CSAM800U"),
+            Err(MatchError::InvalidEncoding(EncodingError::InvalidChar {
+                ch: 'U',
+                ..
+            }))
+        ));
+        assert_eq!(
+            base32.parse("2n1t201rmv87aae5j4csam8000"),
+            Err(MatchError::FormatMismatch {
+                expected: UuidFormat::Base32Crockford
+            })
+        );
 
-        assert!(!uuid.starts_with("test_"));
-        assert_eq!(uuid.len(), 36);
+        let base58 = UuidGenerator::v4().with_format(UuidFormat::Base58);
+        assert!(matches!(
+            base58.parse("BWBeN28Vb7cMEx7Ym8AUz0"),
+            Err(MatchError::InvalidEncoding(EncodingError::InvalidChar {
+                ch: '0',
+                ..
+            }))
+        ));
+        assert!(matches!(
+            base58.parse("550e8400-e29b-41d4-a716-446655440000"),
+            Err(MatchError::InvalidEncoding(
+                EncodingError::InvalidLength { .. }
+            ))
+        ));
     }
 
     #[test]
-    fn test_batch_generation() {
-        let generator = UuidGenerator::v4();
-        let batch = generator.generate_batch(10);
+    fn test_proquint_with_prefix() {
+        let generator = UuidGenerator::v7()
+            .with_format(UuidFormat::Proquint)
+            .with_prefix("srv_");
+        let id = generator.generate();
+        assert_eq!(id.len(), 4 + 47);
+        assert!(id.starts_with("srv_"), "{id}");
+        let uuid = generator.parse(&id).unwrap();
+        assert_eq!(uuid.get_version_num(), 7);
+        // Spelled exactly as generated, though from_proquint takes either case
+        let upper = format!("srv_{}", id[4..].to_uppercase());
+        assert_eq!(
+            generator.parse(&upper),
+            Err(MatchError::FormatMismatch {
+                expected: UuidFormat::Proquint
+            })
+        );
+        assert_eq!(from_proquint(&upper[4..]).unwrap(), uuid);
+
+        let malformed = id.replacen('-', "_", 1);
+        assert!(matches!(
+            generator.parse(&malformed),
+            Err(MatchError::InvalidEncoding(EncodingError::MalformedGroup {
+                group: 0,
+                index: 5,
+                ..
+            }))
+        ));
+    }
 
-        assert_eq!(batch.len(), 10);
+    #[test]
+    fn test_format_into_matches_format() {
+        let formats = [
+            UuidFormat::Standard,
+            UuidFormat::Simple,
+            UuidFormat::StandardUppercase,
+            UuidFormat::SimpleUppercase,
+            UuidFormat::Braced,
+            UuidFormat::BracedUppercase,
+            UuidFormat::Urn,
+            UuidFormat::Base32Crockford,
+            UuidFormat::Base58,
+            UuidFormat::Base62,
+        ];
+        let uuid = UuidGenerator::v4().generate_uuid();
 
-        for uuid in &batch {
-            assert!(parse_uuid(uuid).is_ok());
+        for format in formats {
+            let generator = UuidGenerator::v4().with_format(format).with_prefix("p_");
+            let mut out = String::new();
+            generator.format_into(&uuid, &mut out);
+
+            assert_eq!(out, generator.format(&uuid), "{format:?}");
         }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallel_batch_v4() {
+        let batch = UuidGenerator::v4().generate_batch_parallel(1_000_000);
+
+        assert_eq!(batch.len(), 1_000_000);
+        assert!(batch.iter().all(|uuid| parse_uuid(uuid).is_ok()));
 
         let unique: std::collections::HashSet<_> = batch.iter().collect();
-        assert_eq!(unique.len(), 10);
+        assert_eq!(unique.len(), 1_000_000);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallel_batch_v7() {
+        let plain = UuidGenerator::v7()
+            .with_prefix("t_")
+            .generate_batch_parallel(10_000);
+        assert!(plain.iter().all(|uuid| {
+            let uuid = parse_uuid(&uuid[2..]).unwrap();
+            uuid.get_version_num() == 7 && uuid.get_timestamp().is_some()
+        }));
+
+        let monotonic = UuidGenerator::v7()
+            .with_monotonic(true)
+            .generate_uuid_batch_parallel(100_000);
+        assert!(monotonic.windows(2).all(|pair| pair[0] < pair[1]));
     }
 
     #[test]
@@ -362,6 +2318,13 @@ mod tests {
             UuidFormat::Simple,
             UuidFormat::StandardUppercase,
             UuidFormat::SimpleUppercase,
+            UuidFormat::Braced,
+            UuidFormat::BracedUppercase,
+            UuidFormat::Urn,
+            UuidFormat::Base32Crockford,
+            UuidFormat::Base58,
+            UuidFormat::Base62,
+            UuidFormat::Proquint,
         ];
 
         let versions = [UuidVersion::V4, UuidVersion::V7];
@@ -396,7 +2359,34 @@ mod tests {
                                 .all(|c| c.is_uppercase())
                         );
                     }
+                    UuidFormat::Braced | UuidFormat::BracedUppercase => {
+                        assert_eq!(uuid.len(), 38);
+                        assert!(uuid.starts_with('{') && uuid.ends_with('}'));
+                    }
+                    UuidFormat::Urn => {
+                        assert_eq!(uuid.len(), 45);
+                        assert!(uuid.starts_with("urn:uuid:"));
+                    }
+                    UuidFormat::Base32Crockford => {
+                        assert_eq!(uuid.len(), 26);
+                        assert!(uuid.chars().all(|c| c.is_ascii_alphanumeric()));
+                    }
+                    UuidFormat::Base58 | UuidFormat::Base62 => {
+                        assert_eq!(uuid.len(), 22);
+                        assert!(uuid.chars().all(|c| c.is_ascii_alphanumeric()));
+                    }
+                    UuidFormat::Proquint => {
+                        assert_eq!(uuid.len(), 47);
+                        assert_eq!(uuid.chars().filter(|&c| c == '-').count(), 7);
+                    }
+                }
+                if *format != UuidFormat::Base62 {
+                    assert!(parse_encoded(&uuid).is_ok());
                 }
+                assert_eq!(
+                    generator.parse(&uuid).map(|u| generator.format(&u)),
+                    Ok(uuid)
+                );
             }
         }
     }
@@ -471,6 +2461,26 @@ mod tests {
         assert_eq!(unique.len(), 5);
     }
 
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_batch_into() {
+        use crate::uuid::{ClientMetadata, OsType, parse_uuid_with_metadata};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let generator = UuidGenerator::v7();
+        let mut out = Vec::with_capacity(8);
+        let buffer = out.as_ptr();
+
+        generator.generate_batch_with_metadata_into(8, &metadata, &mut out);
+
+        assert_eq!(out.len(), 8);
+        assert_eq!(out.as_ptr(), buffer);
+        for uuid in &out {
+            let (_, extracted) = parse_uuid_with_metadata(uuid).unwrap();
+            assert_eq!(extracted.unwrap().os_type, OsType::Linux);
+        }
+    }
+
     #[test]
     #[cfg(feature = "custom-uuid")]
     fn test_metadata_sortability() {
@@ -490,6 +2500,266 @@ mod tests {
         assert_eq!(uuids, sorted, "Metadata UUIDs should maintain sortability");
     }
 
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_typed_metadata_generation() {
+        use crate::uuid::{ClientMetadata, OsType, extract_metadata};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let generator = UuidGenerator::v7().with_prefix("trade_");
+
+        let uuid = generator.generate_uuid_with_metadata(&metadata);
+        assert_eq!(uuid.get_version_num(), 7);
+
+        let extracted = extract_metadata(&uuid).unwrap();
+        assert_eq!(extracted.os_type, OsType::Linux);
+        assert_eq!(extracted.os_version, (6, 1));
+        assert!(generator.format(&uuid).starts_with("trade_"));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_monotonic_metadata_batch_is_unique() {
+        use crate::uuid::{ClientMetadata, OsType, extract_metadata};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let generator = UuidGenerator::v7()
+            .with_clock(|| 1_000)
+            .with_monotonic(true);
+
+        let batch: Vec<_> = (0..4096)
+            .map(|_| generator.generate_uuid_with_metadata(&metadata))
+            .collect();
+        let unique: std::collections::HashSet<_> = batch.iter().collect();
+        assert_eq!(unique.len(), 4096);
+        assert!(batch.iter().all(|uuid| extract_metadata(uuid).is_some()));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_single_metadata_calls_share_tail_counter() {
+        use crate::uuid::{ClientMetadata, OsType};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let generator = UuidGenerator::v7().with_clock(|| 1_000);
+        let clone = generator.clone();
+
+        let ids: Vec<_> = (0..3000)
+            .map(|i| {
+                let source = if i % 2 == 0 { &generator } else { &clone };
+                source.generate_uuid_with_metadata(&metadata)
+            })
+            .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_separate_generators_share_metadata_tail() {
+        use crate::uuid::{ClientMetadata, OsType};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let long_lived = UuidGenerator::v7();
+
+        // Generators built per call, as in a request handler, interleaved
+        // with a long-lived one
+        let ids: Vec<_> = (0..3000)
+            .map(|i| match i % 3 {
+                0 => long_lived.generate_uuid_with_metadata(&metadata),
+                _ => UuidGenerator::v7().generate_uuid_with_metadata(&metadata),
+            })
+            .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_tail_overflow_behind_clock_moves_on() {
+        use crate::uuid::{ClientMetadata, OsType};
+
+        // Metadata of its own, as the tail is shared with every test
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "tail-overflow-01");
+        let generator = UuidGenerator::v7().with_clock_guard(true);
+        // As if the clock stepped back an hour after this millisecond's
+        // tails were used up
+        let ahead = source::system_millis() + 3_600_000;
+        let tail = generator.metadata_tail(&metadata.encode());
+        while tail.next_at(ahead, 0).is_ok() {}
+
+        let start = std::time::Instant::now();
+        let uuid = generator.generate_uuid_with_metadata(&metadata);
+
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+        assert_eq!(extract_v7_timestamp(&uuid), Some(ahead + 1));
+        assert_eq!(generator.now_millis(), ahead + 1);
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_batch_is_strictly_increasing() {
+        use crate::uuid::{ClientMetadata, OsType};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let batch = UuidGenerator::v7().generate_batch_with_metadata(200_000, &metadata);
+        assert!(batch.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let mut out = Vec::new();
+        UuidGenerator::v7()
+            .with_metadata_v8(true)
+            .generate_batch_with_metadata_into(10_000, &metadata, &mut out);
+        assert!(out.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_batch_with_frozen_clock_steps_timestamp() {
+        use crate::uuid::{ClientMetadata, OsType, extract_metadata, parse_uuid};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let batch = UuidGenerator::v7()
+            .with_clock(|| 1_000)
+            .generate_batch_with_metadata(10_000, &metadata);
+        assert!(batch.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let uuids: Vec<_> = batch.iter().map(|id| parse_uuid(id).unwrap()).collect();
+        let first = extract_metadata(&uuids[0]).unwrap();
+        let last = extract_metadata(&uuids[uuids.len() - 1]).unwrap();
+        assert_eq!(first.timestamp_ms, 1_000);
+        // At least 2048 IDs fit in each millisecond
+        assert!((1_001..=1_004).contains(&last.timestamp_ms));
+    }
+
+    #[test]
+    fn test_generate_bytes() {
+        let generator = UuidGenerator::v7()
+            .with_prefix("order_")
+            .with_format(UuidFormat::SimpleUppercase);
+        let bytes = generator.generate_bytes();
+        assert_eq!(Uuid::from_bytes(bytes).get_version_num(), 7);
+
+        let seeded = || UuidGenerator::v4_seeded(7).with_prefix("order_");
+        let id = seeded().generate();
+        assert_eq!(
+            strip_affixes(&id, "order_", ""),
+            Some(
+                Uuid::from_bytes(seeded().generate_bytes())
+                    .to_string()
+                    .as_str()
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_generate_bytes_with_metadata_matches_string_path() {
+        use crate::uuid::{
+            ClientMetadata, OsType, extract_metadata, extract_metadata_from_bytes,
+            parse_prefixed_with_metadata,
+        };
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01")
+            .with_user_agent("ua")
+            .with_app_version(3, 2);
+        let generator = || {
+            UuidGenerator::v7()
+                .with_seed(11)
+                .with_clock(|| 1_700_000_000_000)
+                .with_prefix("evt_")
+        };
+
+        let bytes = generator().generate_bytes_with_metadata(&metadata);
+        let (uuid, from_string) =
+            parse_prefixed_with_metadata("evt_", &generator().generate_with_metadata(&metadata))
+                .unwrap();
+
+        assert_eq!(uuid.into_bytes(), bytes);
+        assert!(from_string.is_some());
+        assert_eq!(extract_metadata_from_bytes(&bytes), from_string);
+        assert_eq!(extract_metadata(&Uuid::from_bytes(bytes)), from_string);
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_v8_round_trip() {
+        use crate::uuid::{ClientMetadata, OsType, extract_metadata, parse_uuid_with_metadata};
+
+        let metadata = ClientMetadata::new(OsType::MacOS, (14, 5), "server-01");
+        let legacy = UuidGenerator::v7().with_clock(|| 1_000);
+        let v8 = UuidGenerator::v7()
+            .with_clock(|| 2_000)
+            .with_metadata_v8(true);
+
+        let old = legacy.generate_uuid_with_metadata(&metadata);
+        let new = v8.generate_uuid_with_metadata(&metadata);
+        assert_eq!(old.get_version_num(), 7);
+        assert_eq!(new.get_version_num(), 8);
+        assert_eq!(new.get_variant(), uuid::Variant::RFC4122);
+        assert!(new > old);
+
+        let old_extracted = extract_metadata(&old).unwrap();
+        let new_extracted = extract_metadata(&new).unwrap();
+        assert_eq!(old_extracted.timestamp_ms, 1_000);
+        assert_eq!(new_extracted.timestamp_ms, 2_000);
+        for extracted in [&old_extracted, &new_extracted] {
+            assert_eq!(extracted.os_type, OsType::MacOS);
+            assert_eq!(extracted.os_version, (14, 5));
+        }
+
+        let id = v8.generate_with_metadata(&metadata);
+        assert!(v8.matches(&id));
+        assert!(!legacy.matches(&id));
+        assert!(v8.matches(&v8.generate()));
+        let (_, extracted) = parse_uuid_with_metadata(&id).unwrap();
+        assert_eq!(
+            extracted.unwrap().hostname_hash,
+            old_extracted.hostname_hash
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_generation_at() {
+        use crate::uuid::{ClientMetadata, OsType, parse_uuid_with_metadata};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "backfill");
+        let at = UNIX_EPOCH + std::time::Duration::from_millis(1_500_000_000_000);
+        let generator = UuidGenerator::v7();
+
+        let uuid = generator.generate_with_metadata_at(&metadata, at).unwrap();
+        let (parsed, extracted) = parse_uuid_with_metadata(&uuid).unwrap();
+
+        assert_eq!(
+            parsed.get_timestamp().unwrap().to_unix(),
+            (1_500_000_000, 0)
+        );
+        assert_eq!(extracted.unwrap().os_type, OsType::Linux);
+        assert!(
+            generator
+                .generate_with_metadata_at(
+                    &metadata,
+                    UNIX_EPOCH - std::time::Duration::from_millis(1)
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_with_suffix() {
+        use crate::uuid::{ClientMetadata, OsType, parse_uuid_with_metadata};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let generator = UuidGenerator::v7()
+            .with_prefix("trade_")
+            .with_suffix("_sg1");
+
+        for id in generator.generate_batch_with_metadata(3, &metadata) {
+            let uuid_part = strip_affixes(&id, "trade_", "_sg1").unwrap();
+            let (_, extracted) = parse_uuid_with_metadata(uuid_part).unwrap();
+            assert_eq!(extracted.unwrap().os_type, OsType::Linux);
+        }
+    }
+
     #[test]
     #[cfg(feature = "custom-uuid")]
     fn test_metadata_with_user_agent() {
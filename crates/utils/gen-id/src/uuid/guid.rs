@@ -0,0 +1,105 @@
+use uuid::Uuid;
+
+use super::parser::{
+    InvalidInputReason, ParseError, is_valid_uuid_hyphenated, parse_uuid, truncate_input,
+};
+
+/// The 16 bytes of `uuid` in Microsoft GUID order
+///
+/// Windows stores the first three fields (`Data1`, `Data2`, `Data3`) little
+/// endian and the last eight bytes as they are, e.g. `GUID` structs,
+/// `System.Guid.ToByteArray` and SQL Server `uniqueidentifier`. Use this
+/// when handing raw bytes to such a service, not [`Uuid::as_bytes`].
+#[inline]
+pub fn to_guid_le(uuid: &Uuid) -> [u8; 16] {
+    uuid.to_bytes_le()
+}
+
+/// Read 16 bytes in Microsoft GUID order, the inverse of [`to_guid_le`]
+///
+/// The result is in RFC 9562 byte order like every other [`Uuid`], so
+/// formatting, timestamps and metadata extraction work on it unchanged.
+#[inline]
+pub fn from_guid_le(bytes: [u8; 16]) -> Uuid {
+    Uuid::from_bytes_le(bytes)
+}
+
+/// Parse a GUID in the registry format, e.g.
+/// `{6B29FC40-CA47-1067-B31D-00DD010662DA}`
+///
+/// The text form of a GUID is already in RFC order, so this needs no byte
+/// swapping; it only insists on the braces and hyphens, in either case,
+/// with surrounding whitespace ignored. Anything else returns
+/// [`ParseError::InvalidInput`].
+pub fn parse_guid_registry(input: &str) -> Result<Uuid, ParseError> {
+    let guid = input.trim_ascii();
+    match guid.strip_prefix('{').and_then(|g| g.strip_suffix('}')) {
+        Some(inner) if is_valid_uuid_hyphenated(inner) => parse_uuid(inner),
+        _ => Err(ParseError::InvalidInput {
+            input: truncate_input(guid),
+            reason: InvalidInputReason::NotRegistryGuid,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `GUID` structure docs: `Data1` 0x6B29FC40, `Data2` 0xCA47, `Data3`
+    /// 0x1067 and `Data4` B3 1D 00 DD 01 06 62 DA, laid out little endian
+    const WIN32_GUID: &str = "{6B29FC40-CA47-1067-B31D-00DD010662DA}";
+    const WIN32_BYTES: [u8; 16] = [
+        0x40, 0xFC, 0x29, 0x6B, 0x47, 0xCA, 0x67, 0x10, 0xB3, 0x1D, 0x00, 0xDD, 0x01, 0x06, 0x62,
+        0xDA,
+    ];
+
+    #[test]
+    fn test_win32_guid_bytes() {
+        let uuid = parse_guid_registry(WIN32_GUID).unwrap();
+        assert_eq!(uuid.as_fields().0, 0x6B29_FC40);
+        assert_eq!(to_guid_le(&uuid), WIN32_BYTES);
+        assert_eq!(from_guid_le(WIN32_BYTES), uuid);
+        assert_ne!(*uuid.as_bytes(), WIN32_BYTES);
+    }
+
+    /// `new Guid(0xa, 0xb, 0xc, new byte[] { 0, 1, 2, 3, 4, 5, 6, 7 })` from
+    /// the .NET `Guid` constructor docs, which prints
+    /// `0000000a-000b-000c-0001-020304050607`
+    #[test]
+    fn test_dotnet_guid_bytes() {
+        let bytes = [0x0a, 0, 0, 0, 0x0b, 0, 0x0c, 0, 0, 1, 2, 3, 4, 5, 6, 7];
+        let uuid = from_guid_le(bytes);
+        assert_eq!(
+            uuid,
+            parse_guid_registry("{0000000a-000b-000c-0001-020304050607}").unwrap()
+        );
+        assert_eq!(to_guid_le(&uuid), bytes);
+    }
+
+    #[test]
+    fn test_parse_guid_registry_forms() {
+        let uuid = parse_guid_registry(WIN32_GUID).unwrap();
+        assert_eq!(
+            parse_guid_registry(" {6b29fc40-ca47-1067-b31d-00dd010662da}\n").unwrap(),
+            uuid
+        );
+
+        for input in [
+            "6B29FC40-CA47-1067-B31D-00DD010662DA",
+            "{6B29FC40CA471067B31D00DD010662DA}",
+            "{6B29FC40-CA47-1067-B31D-00DD010662DA",
+            "urn:uuid:6B29FC40-CA47-1067-B31D-00DD010662DA",
+            "{6B29FC40-CA47-1067-B31D-00DD010662DG}",
+        ] {
+            let err = parse_guid_registry(input).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "invalid UUID `{input}`: expected a registry GUID like \
+                     {{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}}"
+                )
+            );
+        }
+    }
+}
@@ -1,13 +1,35 @@
-use uuid::Uuid;
+use alloc::string::String;
+#[cfg(feature = "custom-uuid")]
+use std::sync::OnceLock;
+#[cfg(feature = "std")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use uuid::{Uuid, Variant};
+
+use super::timestamp::timestamp_prefix;
 
 /// Operating system type for metadata encoding
+///
+/// The discriminants are the stored 4-bit codes and never change. Serialized
+/// in lowercase, e.g. `macos` or `freebsd`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum OsType {
+    /// Unrecognised OS, or a code this build does not know
+    Unknown = 0,
     Linux = 1,
     Windows = 2,
     MacOS = 3,
     Android = 4,
     IOS = 5,
+    FreeBsd = 6,
+    OpenBsd = 7,
+    /// WebAssembly build, typically running in a browser
+    Wasm = 8,
 }
 
 impl OsType {
@@ -37,58 +59,159 @@ impl OsType {
         OsType::IOS
     }
 
+    #[cfg(target_os = "freebsd")]
+    pub fn current() -> Self {
+        OsType::FreeBsd
+    }
+
+    #[cfg(target_os = "openbsd")]
+    pub fn current() -> Self {
+        OsType::OpenBsd
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn current() -> Self {
+        OsType::Wasm
+    }
+
     #[cfg(not(any(
         target_os = "linux",
         target_os = "windows",
         target_os = "macos",
         target_os = "android",
         target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_arch = "wasm32",
     )))]
     pub fn current() -> Self {
-        // Fallback to Linux for other Unix-like systems
-        OsType::Linux
+        OsType::Unknown
     }
 
-    /// Encode as 3-bit value
+    /// Encode as 4-bit value
     pub(crate) fn encode(self) -> u8 {
         self as u8
     }
 
-    /// Decode from 3-bit value
+    /// Decode from 4-bit value
     pub(crate) fn decode(value: u8) -> Self {
-        match value & 0x07 {
+        match value & 0x0F {
             1 => OsType::Linux,
             2 => OsType::Windows,
             3 => OsType::MacOS,
             4 => OsType::Android,
             5 => OsType::IOS,
-            _ => OsType::Linux, // Default fallback
+            6 => OsType::FreeBsd,
+            7 => OsType::OpenBsd,
+            8 => OsType::Wasm,
+            _ => OsType::Unknown,
+        }
+    }
+}
+
+/// Hash used for the hostname byte and the extended hash
+///
+/// Not recorded in the UUID, so comparing a hostname or extended hash with
+/// an extracted UUID needs the setting the writer used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum HashKind {
+    /// djb2, the default, which earlier IDs were written with
+    #[default]
+    Djb2,
+    /// 32-bit FNV-1a, spreading short, similar hostnames more evenly
+    Fnv1a,
+}
+
+impl HashKind {
+    #[inline]
+    pub(crate) fn hash_u32(self, input: &str) -> u32 {
+        self.hash_parts(&[input])
+    }
+
+    /// Hash the concatenation of `parts` without building it
+    #[inline]
+    pub(crate) fn hash_parts(self, parts: &[&str]) -> u32 {
+        let bytes = parts.iter().flat_map(|part| part.bytes());
+        match self {
+            HashKind::Djb2 => djb2(bytes),
+            HashKind::Fnv1a => fnv1a(bytes),
         }
     }
+
+    /// The 32-bit hash folded to 16 bits
+    #[inline]
+    pub(crate) fn hash_u16(self, input: &str) -> u16 {
+        let hash = self.hash_u32(input);
+        (hash ^ (hash >> 16)) as u16
+    }
 }
 
 /// Client metadata to embed in UUID
+///
+/// With the `serde` feature versions serialize as `[major, minor]` arrays.
+/// Deserializing rejects versions that do not fit the UUID layout instead of
+/// truncating them, and the optional fields may be omitted.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClientMetadata {
     /// Operating system type
     pub os_type: OsType,
     /// OS version encoded as (major, minor)
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "de::os_version"))]
     pub os_version: (u8, u8),
     /// Hostname or machine identifier
     pub hostname: String,
     /// Optional user agent string
     pub user_agent: Option<String>,
+    /// Optional client release as (major, minor)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "de::app_version")
+    )]
+    pub app_version: Option<(u8, u8)>,
+    /// Optional identifier telling apart instances on the same host
+    pub device_id: Option<String>,
+    /// Hash for the hostname byte and the extended hash
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hash: HashKind,
 }
 
 impl ClientMetadata {
-    /// Create metadata from current system
+    /// Metadata for the current system, read once per process
+    ///
+    /// Cheap to call per request. Later calls clone the process-wide
+    /// snapshot taken by the first, see [`cached`](Self::cached), so a
+    /// hostname or OS version change after that is not seen. Use
+    /// [`from_system_uncached`](Self::from_system_uncached) to read the
+    /// system again.
     #[cfg(feature = "custom-uuid")]
     #[inline]
     pub fn from_system() -> Self {
-        use sysinfo::System;
+        Self::cached().clone()
+    }
 
-        let mut sys = System::new_all();
-        sys.refresh_all();
+    /// Shared snapshot of the current system, computed on first use and
+    /// kept for the life of the process
+    #[cfg(feature = "custom-uuid")]
+    pub fn cached() -> &'static Self {
+        static CACHED: OnceLock<ClientMetadata> = OnceLock::new();
+        CACHED.get_or_init(Self::from_system_uncached)
+    }
+
+    /// Read the current system again, for callers that expect the hostname
+    /// or OS version to change while running
+    ///
+    /// Only queries the OS version and hostname, without enumerating
+    /// processes or disks.
+    #[cfg(all(feature = "custom-uuid", not(target_arch = "wasm32")))]
+    #[inline]
+    pub fn from_system_uncached() -> Self {
+        use sysinfo::System;
 
         let os_type = OsType::current();
 
@@ -107,42 +230,58 @@ impl ClientMetadata {
             os_version,
             hostname,
             user_agent: None,
+            app_version: None,
+            device_id: None,
+            hash: HashKind::Djb2,
         }
     }
 
-    /// Create metadata from current system (fallback without sysinfo)
-    #[cfg(not(feature = "custom-uuid"))]
+    /// Read the current system again
+    ///
+    /// A browser exposes neither hostname nor OS version, so this is
+    /// [`OsType::Wasm`] with hostname `unknown`. Browser callers get more out
+    /// of [`from_user_agent`](Self::from_user_agent) with `navigator.userAgent`
+    /// and the page origin as hostname.
+    #[cfg(all(feature = "custom-uuid", target_arch = "wasm32"))]
     #[inline]
-    pub fn from_system() -> Self {
-        Self {
-            os_type: OsType::current(),
-            os_version: Self::detect_os_version(),
-            hostname: Self::detect_hostname(),
-            user_agent: None,
-        }
+    pub fn from_system_uncached() -> Self {
+        Self::new(OsType::current(), Self::detect_os_version(), "unknown")
+    }
+
+    /// Metadata for the OS named in a browser user agent
+    ///
+    /// Recognizes the Chrome, Safari and Firefox formats on Windows, macOS,
+    /// iOS, Android, Linux and the BSDs, falling back to [`OsType::Unknown`].
+    /// The user agent is kept, so it feeds the extended hash, and the hostname
+    /// is `unknown` until set with [`with_hostname`](Self::with_hostname).
+    pub fn from_user_agent(user_agent: &str) -> Self {
+        let (os_type, os_version) = super::user_agent::parse_os(user_agent);
+        Self::new(os_type, os_version, "unknown").with_user_agent(user_agent)
     }
 
     /// Parse OS version string into (major, minor) tuple
-    #[cfg(feature = "custom-uuid")]
-    #[inline]
-    fn parse_os_version(version_str: &str, _os_type: OsType) -> (u8, u8) {
-        // Try to extract major.minor from version string
-        let parts: Vec<&str> = version_str.split('.').collect();
-
-        if parts.len() >= 2 {
-            let major = parts[0].parse::<u8>().unwrap_or(0);
-            let minor = parts[1].parse::<u8>().unwrap_or(0);
-
-            // Clamp to 5 bits major (0-31), 4 bits minor (0-15)
-            (major.min(31), minor.min(15))
-        } else if let Some(first) = parts.first() {
-            // Try to extract just major version
-            let major = first.parse::<u8>().unwrap_or(0);
-            (major.min(31), 0)
-        } else {
-            // Fallback to defaults
-            Self::detect_os_version()
-        }
+    ///
+    /// Windows 10.0 builds from 22000 on are Windows 11, and Darwin kernel
+    /// versions become macOS marketing versions. Other OSes keep the first
+    /// two numbers, e.g. the Linux kernel major.minor.
+    #[cfg(all(feature = "custom-uuid", not(target_arch = "wasm32")))]
+    fn parse_os_version(version_str: &str, os_type: OsType) -> (u8, u8) {
+        let mut parts = version_str.trim().split('.').map(leading_number);
+        let Some(major) = parts.next().flatten() else {
+            return Self::detect_os_version();
+        };
+        let minor = parts.next().flatten().unwrap_or(0);
+        let build = parts.next().flatten();
+
+        let (major, minor) = match os_type {
+            // Windows 11 kept version 10.0 and only raised the build number
+            OsType::Windows if major == 10 && build.is_some_and(|build| build >= 22000) => {
+                (11, minor)
+            }
+            OsType::MacOS => darwin_to_macos(major, minor),
+            _ => (major, minor),
+        };
+        saturate_os_version(major, minor)
     }
 
     /// Create metadata with custom values
@@ -152,9 +291,19 @@ impl ClientMetadata {
             os_version,
             hostname: hostname.into(),
             user_agent: None,
+            app_version: None,
+            device_id: None,
+            hash: HashKind::Djb2,
         }
     }
 
+    /// Set the hostname, or another identifier such as a page origin
+    #[inline]
+    pub fn with_hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.hostname = hostname.into();
+        self
+    }
+
     /// Set user agent
     #[inline]
     pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
@@ -162,6 +311,67 @@ impl ClientMetadata {
         self
     }
 
+    /// Set an identifier for this instance, such as a device or install ID
+    ///
+    /// It is folded into the extended hash, so two instances on one host get
+    /// different hashes.
+    #[inline]
+    pub fn with_device_id(mut self, device_id: impl Into<String>) -> Self {
+        self.device_id = Some(device_id.into());
+        self
+    }
+
+    /// Use the current process ID as the device ID, e.g. `pid:4242`
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn with_pid(self) -> Self {
+        self.with_device_id(format!("pid:{}", std::process::id()))
+    }
+
+    /// Choose the hash for the hostname byte and the extended hash
+    ///
+    /// Readers comparing hashes with extracted metadata must use the same
+    /// kind, it is not stored in the UUID.
+    #[inline]
+    pub fn with_hash(mut self, hash: HashKind) -> Self {
+        self.hash = hash;
+        self
+    }
+
+    /// Hash of hostname, user agent and device ID for bytes 10-13
+    ///
+    /// The device ID follows a `\x1f` separator, so metadata without one
+    /// hashes as it did before the field existed.
+    pub(crate) fn extended_hash(&self) -> u32 {
+        let hostname = self.hostname.as_str();
+        let user_agent = self.user_agent.as_deref().unwrap_or_default();
+        match &self.device_id {
+            Some(device_id) => self
+                .hash
+                .hash_parts(&[hostname, user_agent, "\x1f", device_id]),
+            None => self.hash.hash_parts(&[hostname, user_agent]),
+        }
+    }
+
+    /// Compute the embedded fields once, for reuse across a batch
+    pub(crate) fn encode(&self) -> EncodedMetadata {
+        EncodedMetadata {
+            os_encoded: encode_os_metadata(self.os_type, self.os_version),
+            hostname_byte: self.hash.hash_u16(&self.hostname) as u8,
+            extended_hash: self.extended_hash() & 0x7_FFFF,
+            app_version: encode_app_version(self.app_version),
+        }
+    }
+
+    /// Set the client release, clamped to a 6-bit major (0-63) and 5-bit
+    /// minor (0-31) when embedded
+    #[inline]
+    pub fn with_app_version(mut self, major: u8, minor: u8) -> Self {
+        self.app_version = Some((major, minor));
+        self
+    }
+
+    #[cfg(feature = "custom-uuid")]
     #[inline]
     fn detect_os_version() -> (u8, u8) {
         // Simplified version detection - could be enhanced with system calls
@@ -185,46 +395,182 @@ impl ClientMetadata {
     }
 }
 
+/// Leading decimal digits of a version component, e.g. 8 in `8-generic`
+#[cfg(all(feature = "custom-uuid", not(target_arch = "wasm32")))]
+fn leading_number(part: &str) -> Option<u32> {
+    let end = part
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(part.len());
+    (end > 0).then(|| part[..end].parse().unwrap_or(u32::MAX))
+}
+
+/// Map a Darwin kernel version to the macOS marketing version
+///
+/// No macOS release is numbered 16 to 25, so those majors are taken as
+/// Darwin 16 (macOS 10.12) to Darwin 25 (macOS 26).
+#[cfg(all(feature = "custom-uuid", not(target_arch = "wasm32")))]
+fn darwin_to_macos(major: u32, minor: u32) -> (u32, u32) {
+    match major {
+        16..=19 => (10, major - 4),
+        20..=24 => (major - 9, minor),
+        25 => (26, minor),
+        _ => (major, minor),
+    }
+}
+
+/// Clamp an OS version to the 5-bit major and 4-bit minor of the layout
+pub(crate) fn saturate_os_version(major: u32, minor: u32) -> (u8, u8) {
+    let saturated = (major.min(31) as u8, minor.min(15) as u8);
+    if (u32::from(saturated.0), u32::from(saturated.1)) != (major, minor) {
+        tracing::debug!(
+            major,
+            minor,
+            "OS version does not fit the metadata layout, saturating to {}.{}",
+            saturated.0,
+            saturated.1
+        );
+    }
+    saturated
+}
+
+/// [`ClientMetadata`] fields as embedded, independent of the UUID they go in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct EncodedMetadata {
+    /// 13-bit OS type and version
+    pub(crate) os_encoded: u16,
+    /// Byte 9
+    pub(crate) hostname_byte: u8,
+    /// 19-bit extended hash
+    pub(crate) extended_hash: u32,
+    /// 12-bit app version
+    pub(crate) app_version: u16,
+}
+
+/// Bounds checks for deserialized [`ClientMetadata`] versions
+#[cfg(feature = "serde")]
+mod de {
+    use serde::{Deserialize, Deserializer, de::Error};
+
+    fn bounded<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        field: &str,
+        max: (u8, u8),
+    ) -> Result<(u8, u8), D::Error> {
+        let (major, minor) = <(u8, u8)>::deserialize(deserializer)?;
+        if major > max.0 || minor > max.1 {
+            return Err(D::Error::custom(format_args!(
+                "{field} [{major}, {minor}] out of range, the maximum is [{}, {}]",
+                max.0, max.1
+            )));
+        }
+        Ok((major, minor))
+    }
+
+    /// 5-bit major, 4-bit minor
+    pub(super) fn os_version<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<(u8, u8), D::Error> {
+        bounded(deserializer, "os_version", (31, 15))
+    }
+
+    /// 6-bit major, 5-bit minor
+    pub(super) fn app_version<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<(u8, u8)>, D::Error> {
+        #[derive(Deserialize)]
+        struct AppVersion(#[serde(deserialize_with = "app_version_bounds")] (u8, u8));
+
+        fn app_version_bounds<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<(u8, u8), D::Error> {
+            bounded(deserializer, "app_version", (63, 31))
+        }
+
+        Ok(Option::<AppVersion>::deserialize(deserializer)?.map(|version| version.0))
+    }
+}
+
 /// Metadata extracted from a UUID
+///
+/// With the `serde` feature the timestamp serializes as integer milliseconds
+/// and the OS version as a `[major, minor]` array.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtractedMetadata {
+    /// Layout the metadata was decoded with, 0 for IDs read by
+    /// [`extract_metadata_legacy`]
+    pub schema_version: u8,
     pub timestamp_ms: u64,
     pub os_type: OsType,
     pub os_version: (u8, u8),
     pub hostname_hash: u8,
     pub extended_hash: u32,
+    /// Client release, `None` when not set or read by
+    /// [`extract_metadata_legacy`]
+    pub app_version: Option<(u8, u8)>,
 }
 
-/// Hash a string to 16 bits using a simple hash function
-#[inline]
-pub(crate) fn hash_to_u16(input: &str) -> u16 {
-    let mut hash: u32 = 5381;
-    for byte in input.bytes() {
-        hash = ((hash << 5).wrapping_add(hash)).wrapping_add(byte as u32);
+impl ExtractedMetadata {
+    /// Generation time as a [`SystemTime`]
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn timestamp(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.timestamp_ms)
+    }
+
+    /// Generation time in UTC
+    ///
+    /// The 48-bit timestamp reaches past year 9999, which `time` cannot
+    /// represent, so such timestamps saturate to its latest date.
+    #[cfg(feature = "time")]
+    pub fn timestamp_offsetdatetime(&self) -> time::OffsetDateTime {
+        let nanos = i128::from(self.timestamp_ms) * 1_000_000;
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos)
+            .unwrap_or(time::PrimitiveDateTime::MAX.assume_utc())
+    }
+
+    /// Time elapsed since generation
+    ///
+    /// Zero for a timestamp in the future, e.g. from a client with a skewed
+    /// clock.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn age(&self) -> Duration {
+        let now = crate::source::system_millis();
+        Duration::from_millis(now.saturating_sub(self.timestamp_ms))
     }
-    (hash ^ (hash >> 16)) as u16
 }
 
-/// Hash a string to 32 bits
+/// Hash bytes to 32 bits with djb2
 #[inline]
-pub(crate) fn hash_to_u32(input: &str) -> u32 {
-    let mut hash: u32 = 5381;
-    for byte in input.bytes() {
-        hash = ((hash << 5).wrapping_add(hash)).wrapping_add(byte as u32);
-    }
-    hash
+fn djb2(bytes: impl Iterator<Item = u8>) -> u32 {
+    bytes.fold(5381, |hash: u32, byte| {
+        ((hash << 5).wrapping_add(hash)).wrapping_add(byte as u32)
+    })
 }
 
-/// Encode OS metadata into 12 bits (3 bits type + 5 bits major + 4 bits minor)
+/// Hash bytes to 32 bits with FNV-1a
+#[inline]
+fn fnv1a(bytes: impl Iterator<Item = u8>) -> u32 {
+    bytes.fold(0x811C_9DC5, |hash: u32, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Encode OS metadata into 13 bits (4 bits type + 5 bits major + 4 bits minor)
+///
+/// The low 12 bits go in bytes 6-7 and the top type bit in byte 10. Larger
+/// versions saturate.
 #[inline]
 pub(crate) fn encode_os_metadata(os_type: OsType, os_version: (u8, u8)) -> u16 {
+    let (major, minor) = saturate_os_version(os_version.0.into(), os_version.1.into());
     let type_bits = (os_type.encode() as u16) << 9;
-    let major_bits = ((os_version.0 & 0x1F) as u16) << 4;
-    let minor_bits = (os_version.1 & 0x0F) as u16;
+    let major_bits = u16::from(major) << 4;
+    let minor_bits = u16::from(minor);
     type_bits | major_bits | minor_bits
 }
 
-/// Decode OS metadata from 12 bits (3 bits type + 5 bits major + 4 bits minor)
+/// Decode OS metadata from 13 bits (4 bits type + 5 bits major + 4 bits minor)
 #[inline]
 pub(crate) fn decode_os_metadata(encoded: u16) -> (OsType, (u8, u8)) {
     let os_type = OsType::decode((encoded >> 9) as u8);
@@ -233,54 +579,204 @@ pub(crate) fn decode_os_metadata(encoded: u16) -> (OsType, (u8, u8)) {
     (os_type, (major, minor))
 }
 
-/// Extract metadata from a UUID v7 with embedded metadata
+/// Encode an app version into 12 bits (1 presence bit + 6 bits major + 5
+/// bits minor), saturating larger versions
+#[inline]
+pub(crate) fn encode_app_version(app_version: Option<(u8, u8)>) -> u16 {
+    app_version.map_or(0, |(major, minor)| {
+        1 << 11 | u16::from(major.min(63)) << 5 | u16::from(minor.min(31))
+    })
+}
+
+/// Decode an app version from 12 bits (1 presence bit + 6 bits major + 5
+/// bits minor)
+#[inline]
+pub(crate) fn decode_app_version(encoded: u16) -> Option<(u8, u8)> {
+    let major = ((encoded >> 5) & 0x3F) as u8;
+    let minor = (encoded & 0x1F) as u8;
+    (encoded & (1 << 11) != 0).then_some((major, minor))
+}
+
+/// Tag in bits 2-5 of byte 8, below the variant, marking a metadata UUID
+const METADATA_MARKER: u8 = 0b1011;
+
+/// Layout written by [`set_marker`], stored in the low 2 bits of byte 8
+///
+/// Only version 1 is in use, the others are left for later layouts.
+pub(crate) const SCHEMA_VERSION: u8 = 1;
+
+/// Error from [`try_extract_metadata`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum MetadataError {
+    #[error("UUID carries no embedded metadata")]
+    NotMetadata,
+
+    #[error("unknown metadata schema version {version}")]
+    UnknownSchema { version: u8 },
+
+    #[error("metadata checksum mismatch, the UUID was altered or truncated")]
+    ChecksumMismatch,
+}
+
+/// Write the metadata marker and current schema version into byte 8,
+/// keeping the variant bits
+#[inline]
+pub(crate) fn set_marker(bytes: &mut [u8; 16]) {
+    bytes[8] = (bytes[8] & 0b1100_0000) | (METADATA_MARKER << 2) | SCHEMA_VERSION;
+}
+
+#[inline]
+fn has_marker(bytes: &[u8; 16]) -> bool {
+    (bytes[8] >> 2) & 0x0F == METADATA_MARKER
+}
+
+/// CRC-4 (polynomial `x^4 + x + 1`) of every bit except the checksum nibble
+///
+/// A CRC of degree 4 detects every error burst up to 4 bits long, so any
+/// single changed hex digit is caught.
+fn checksum(bytes: &[u8; 16]) -> u8 {
+    let mut crc = 0u8;
+    for (index, byte) in bytes.iter().enumerate() {
+        let bits = if index == 15 { 4 } else { 8 };
+        for bit in (8 - bits..8).rev() {
+            let feedback = ((crc >> 3) ^ (byte >> bit)) & 1;
+            crc = (crc << 1) & 0x0F;
+            if feedback == 1 {
+                crc ^= 0b0011;
+            }
+        }
+    }
+    crc
+}
+
+/// Write the checksum into the low nibble of byte 15
+#[inline]
+pub(crate) fn set_checksum(bytes: &mut [u8; 16]) {
+    bytes[15] = (bytes[15] & 0xF0) | checksum(bytes);
+}
+
+/// Extract metadata from a UUID v7 or v8 with embedded metadata
+///
+/// Returns `None` unless the UUID carries the metadata marker, so plain v7
+/// UUIDs are not decoded as noise, for a checksum mismatch, and for schema
+/// versions this build does not know. IDs generated before the marker existed need
+/// [`extract_metadata_legacy`].
 #[inline]
 pub fn extract_metadata(uuid: &Uuid) -> Option<ExtractedMetadata> {
+    try_extract_metadata(uuid).ok()
+}
+
+/// Extract metadata from the 16 bytes of a UUID, e.g. read from a
+/// `BINARY(16)` column
+///
+/// The same as [`extract_metadata`] without building the [`Uuid`] first.
+/// The bytes must be in RFC order; GUID bytes from a Windows service go
+/// through [`from_guid_le`](super::from_guid_le) and [`extract_metadata`].
+#[inline]
+pub fn extract_metadata_from_bytes(bytes: &[u8; 16]) -> Option<ExtractedMetadata> {
+    extract_metadata(&Uuid::from_bytes(*bytes))
+}
+
+/// Extract metadata, telling a UUID without metadata apart from one written
+/// with a newer schema
+#[inline]
+pub fn try_extract_metadata(uuid: &Uuid) -> Result<ExtractedMetadata, MetadataError> {
+    if !is_metadata_version(uuid) || !has_marker(uuid.as_bytes()) {
+        return Err(MetadataError::NotMetadata);
+    }
+
     let bytes = uuid.as_bytes();
+    if bytes[15] & 0x0F != checksum(bytes) {
+        return Err(MetadataError::ChecksumMismatch);
+    }
 
-    // Check if it's a v7 UUID
-    if (bytes[6] >> 4) != 0x07 {
-        return None;
+    match bytes[8] & 0b11 {
+        SCHEMA_VERSION => Ok(decode(uuid)),
+        version => Err(MetadataError::UnknownSchema { version }),
     }
+}
 
-    // Extract timestamp (bytes 0-5)
-    let timestamp_ms = u64::from_be_bytes([
-        0, 0, bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5],
-    ]);
+/// Extract metadata without checking the marker or checksum
+///
+/// For IDs generated before either was added, decoded with their layout: a
+/// 3-bit OS type and a 32-bit extended hash, without app version. They are
+/// reported with `schema_version` 0. Every v7 or v8 UUID with the RFC
+/// variant decodes, so the random bits of a plain v7 UUID come back as
+/// metadata. Returns `None` for other versions and variants, including the
+/// nil and max UUIDs.
+#[inline]
+pub fn extract_metadata_legacy(uuid: &Uuid) -> Option<ExtractedMetadata> {
+    is_metadata_version(uuid).then(|| decode_unmarked(uuid))
+}
 
-    // Extract OS metadata from bytes 6-7
-    let os_encoded = ((bytes[6] as u16 & 0x0F) << 8) | (bytes[7] as u16);
-    let (os_type, os_version) = decode_os_metadata(os_encoded);
+/// Metadata UUIDs are v8, or v7 when generated without v8 mode, with the
+/// RFC variant bits `10` in byte 8
+///
+/// The variant check also rules out the nil and max UUIDs.
+#[inline]
+fn is_metadata_version(uuid: &Uuid) -> bool {
+    uuid.get_variant() == Variant::RFC4122 && matches!(uuid.get_version_num(), 7 | 8)
+}
 
-    // Extract hostname hash from byte 9
-    let hostname_hash = bytes[9];
+fn decode(uuid: &Uuid) -> ExtractedMetadata {
+    let bytes = uuid.as_bytes();
 
-    // Extract extended hash from bytes 10-13
-    let mut extended_hash_bytes = [0u8; 4];
-    extended_hash_bytes.copy_from_slice(&bytes[10..14]);
-    let extended_hash = u32::from_be_bytes(extended_hash_bytes);
+    // Bytes 10-13 hold the top OS type bit, 12 bits of app version and the
+    // 19-bit extended hash
+    let packed = u32::from_be_bytes([bytes[10], bytes[11], bytes[12], bytes[13]]);
+    let os_type_high = (packed >> 31) as u16;
+    let (os_type, os_version) = decode_os_metadata((os_type_high << 12) | os_low_bits(bytes));
 
-    Some(ExtractedMetadata {
-        timestamp_ms,
+    ExtractedMetadata {
+        schema_version: SCHEMA_VERSION,
+        timestamp_ms: timestamp_prefix(uuid),
         os_type,
         os_version,
-        hostname_hash,
-        extended_hash,
-    })
+        hostname_hash: bytes[9],
+        extended_hash: packed & 0x7_FFFF,
+        app_version: decode_app_version((packed >> 19) as u16),
+    }
+}
+
+/// Decode the layout from before the marker, with the whole of bytes 10-13
+/// as extended hash
+fn decode_unmarked(uuid: &Uuid) -> ExtractedMetadata {
+    let bytes = uuid.as_bytes();
+    let (os_type, os_version) = decode_os_metadata(os_low_bits(bytes));
+
+    ExtractedMetadata {
+        schema_version: 0,
+        timestamp_ms: timestamp_prefix(uuid),
+        os_type,
+        os_version,
+        hostname_hash: bytes[9],
+        extended_hash: u32::from_be_bytes([bytes[10], bytes[11], bytes[12], bytes[13]]),
+        app_version: None,
+    }
 }
 
-#[cfg(test)]
+/// OS metadata below the version nibble in bytes 6-7
+#[inline]
+fn os_low_bits(bytes: &[u8; 16]) -> u16 {
+    (u16::from(bytes[6] & 0x0F) << 8) | u16::from(bytes[7])
+}
+
+#[cfg(all(test, feature = "custom-uuid"))]
 mod tests {
     use super::*;
 
     #[test]
     fn test_os_type_encoding_decoding() {
         let test_cases = [
+            OsType::Unknown,
             OsType::Linux,
             OsType::Windows,
             OsType::MacOS,
             OsType::Android,
             OsType::IOS,
+            OsType::FreeBsd,
+            OsType::OpenBsd,
+            OsType::Wasm,
         ];
 
         for os_type in &test_cases {
@@ -290,6 +786,369 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_extract_metadata_skips_nil_and_max() {
+        assert_eq!(extract_metadata(&Uuid::nil()), None);
+        assert_eq!(extract_metadata(&Uuid::max()), None);
+        assert_eq!(extract_metadata_legacy(&Uuid::nil()), None);
+        assert_eq!(extract_metadata_legacy(&Uuid::max()), None);
+    }
+
+    #[test]
+    fn test_extract_metadata_after_guid_round_trip() {
+        use crate::uuid::{UuidGenerator, from_guid_le, to_guid_le};
+
+        let metadata = ClientMetadata::new(OsType::Windows, (11, 0), "win-host");
+        let uuid = UuidGenerator::v7()
+            .with_seed(7)
+            .with_clock(|| 1_700_000_000_000)
+            .generate_uuid_with_metadata(&metadata);
+        let guid_bytes = to_guid_le(&uuid);
+
+        let extracted = extract_metadata(&from_guid_le(guid_bytes)).unwrap();
+        assert_eq!(extracted.os_type, OsType::Windows);
+        assert_eq!(extracted.timestamp_ms, 1_700_000_000_000);
+        assert_eq!(Some(extracted), extract_metadata(&uuid));
+
+        // Raw GUID-order bytes put the version nibble elsewhere
+        assert_eq!(extract_metadata_from_bytes(&guid_bytes), None);
+    }
+
+    #[test]
+    fn test_extract_metadata_requires_marker() {
+        use crate::uuid::UuidGenerator;
+
+        let metadata = ClientMetadata::new(OsType::Android, (13, 0), "pixel");
+        let uuid = UuidGenerator::v7().generate_uuid_with_metadata(&metadata);
+        assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+        assert_eq!(extract_metadata(&uuid).unwrap().os_type, OsType::Android);
+        assert_eq!(
+            extract_metadata_legacy(&uuid).unwrap().os_type,
+            OsType::Android
+        );
+
+        // An ID from before the marker: same layout, byte 8 left random
+        let mut bytes = *uuid.as_bytes();
+        bytes[8] = 0x80;
+        let legacy = Uuid::from_bytes(bytes);
+        assert_eq!(extract_metadata(&legacy), None);
+        assert_eq!(
+            extract_metadata_legacy(&legacy).unwrap().os_type,
+            OsType::Android
+        );
+    }
+
+    #[test]
+    fn test_unknown_schema_version() {
+        use crate::uuid::UuidGenerator;
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let uuid = UuidGenerator::v7().generate_uuid_with_metadata(&metadata);
+        assert_eq!(
+            try_extract_metadata(&uuid).unwrap().schema_version,
+            SCHEMA_VERSION
+        );
+
+        // Only version 1 is in use
+        let mut bytes = *uuid.as_bytes();
+        bytes[8] &= !0b11;
+        set_checksum(&mut bytes);
+        let bumped = Uuid::from_bytes(bytes);
+
+        assert_eq!(
+            try_extract_metadata(&bumped),
+            Err(MetadataError::UnknownSchema { version: 0 })
+        );
+        assert_eq!(extract_metadata(&bumped), None);
+
+        assert_eq!(
+            try_extract_metadata(&Uuid::new_v4()),
+            Err(MetadataError::NotMetadata)
+        );
+    }
+
+    #[test]
+    fn test_checksum_detects_any_changed_hex_digit() {
+        use crate::uuid::UuidGenerator;
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let id = UuidGenerator::v7()
+            .with_metadata_v8(true)
+            .generate_uuid_with_metadata(&metadata)
+            .simple()
+            .to_string();
+
+        for index in 0..id.len() {
+            for digit in "0123456789abcdef".chars() {
+                if id[index..].starts_with(digit) {
+                    continue;
+                }
+                let mut edited = id.clone();
+                edited.replace_range(index..=index, digit.encode_utf8(&mut [0; 4]));
+
+                let uuid = Uuid::parse_str(&edited).unwrap();
+                assert!(extract_metadata(&uuid).is_none(), "{edited}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        use crate::uuid::UuidGenerator;
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let uuid = UuidGenerator::v7().generate_uuid_with_metadata(&metadata);
+
+        let mut bytes = *uuid.as_bytes();
+        bytes[12] ^= 0x01;
+        let corrupted = Uuid::from_bytes(bytes);
+
+        assert_eq!(
+            try_extract_metadata(&corrupted),
+            Err(MetadataError::ChecksumMismatch)
+        );
+        assert!(extract_metadata_legacy(&corrupted).is_some());
+    }
+
+    #[test]
+    fn test_os_type_codes_are_stable() {
+        assert_eq!(OsType::Linux.encode(), 1);
+        assert_eq!(OsType::Windows.encode(), 2);
+        assert_eq!(OsType::MacOS.encode(), 3);
+        assert_eq!(OsType::Android.encode(), 4);
+        assert_eq!(OsType::IOS.encode(), 5);
+
+        for unassigned in 9..16 {
+            assert_eq!(OsType::decode(unassigned), OsType::Unknown);
+        }
+    }
+
+    #[test]
+    fn test_new_os_types_round_trip() {
+        use crate::uuid::UuidGenerator;
+
+        for os_type in [OsType::FreeBsd, OsType::OpenBsd, OsType::Wasm] {
+            let metadata = ClientMetadata::new(os_type, (14, 1), "client");
+            let uuid = UuidGenerator::v7().generate_uuid_with_metadata(&metadata);
+            let extracted = extract_metadata(&uuid).unwrap();
+
+            assert_eq!(extracted.os_type, os_type);
+            assert_eq!(extracted.os_version, (14, 1));
+            assert_eq!(
+                extracted.extended_hash,
+                HashKind::Djb2.hash_u32("client") & 0x7_FFFF
+            );
+        }
+    }
+
+    #[test]
+    fn test_legacy_layout_decodes_three_type_bits() {
+        use crate::uuid::UuidGenerator;
+
+        let metadata = ClientMetadata::new(OsType::IOS, (17, 2), "iphone").with_app_version(4, 12);
+        let uuid = UuidGenerator::v7().generate_uuid_with_metadata(&metadata);
+
+        // The unmarked layout had a 3-bit type and a full 32-bit extended hash
+        let mut bytes = *uuid.as_bytes();
+        bytes[8] = 0x80;
+        bytes[10] |= 0x80;
+        let extracted = extract_metadata_legacy(&Uuid::from_bytes(bytes)).unwrap();
+
+        assert_eq!(extracted.schema_version, 0);
+        assert_eq!(extracted.os_type, OsType::IOS);
+        assert_eq!(extracted.os_version, (17, 2));
+        assert_eq!(extracted.extended_hash >> 31, 1);
+        assert_eq!(extracted.app_version, None);
+    }
+
+    fn extracted_at(timestamp_ms: u64) -> ExtractedMetadata {
+        ExtractedMetadata {
+            schema_version: 1,
+            timestamp_ms,
+            os_type: OsType::Linux,
+            os_version: (6, 1),
+            hostname_hash: 0,
+            extended_hash: 0,
+            app_version: None,
+        }
+    }
+
+    #[test]
+    fn test_extracted_metadata_timestamp_and_age() {
+        use crate::uuid::UuidGenerator;
+
+        let uuid = UuidGenerator::v7()
+            .with_clock(|| 1_700_000_000_123)
+            .generate_uuid_with_metadata(&ClientMetadata::new(OsType::Linux, (6, 1), "host"));
+        let extracted = extract_metadata(&uuid).unwrap();
+        assert_eq!(
+            extracted.timestamp(),
+            UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)
+        );
+        assert!(extracted.age() > Duration::from_secs(3600));
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        assert!(extracted_at(now - 5_000).age() >= Duration::from_secs(5));
+
+        // Clock skew puts the timestamp ahead of us
+        assert_eq!(extracted_at(now + 3_600_000).age(), Duration::ZERO);
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_extracted_metadata_offsetdatetime() {
+        let datetime = extracted_at(1_700_000_000_123).timestamp_offsetdatetime();
+        assert_eq!(datetime.unix_timestamp(), 1_700_000_000);
+        assert_eq!(datetime.millisecond(), 123);
+        assert_eq!(datetime.offset(), time::UtcOffset::UTC);
+
+        let latest = extracted_at((1 << 48) - 1).timestamp_offsetdatetime();
+        assert_eq!(latest, time::PrimitiveDateTime::MAX.assume_utc());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_extracted_metadata_serde() {
+        let extracted = ExtractedMetadata {
+            schema_version: 1,
+            timestamp_ms: 1_700_000_000_000,
+            os_type: OsType::MacOS,
+            os_version: (14, 5),
+            hostname_hash: 7,
+            extended_hash: 42,
+            app_version: Some((4, 12)),
+        };
+
+        let json = serde_json::to_string(&extracted).unwrap();
+        assert_eq!(
+            json,
+            r#"{"schema_version":1,"timestamp_ms":1700000000000,"os_type":"macos","os_version":[14,5],"hostname_hash":7,"extended_hash":42,"app_version":[4,12]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ExtractedMetadata>(&json).unwrap(),
+            extracted
+        );
+        assert!(serde_json::from_str::<OsType>("\"beos\"").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_client_metadata_serde() {
+        let metadata = ClientMetadata::new(OsType::Android, (14, 2), "pixel")
+            .with_user_agent("shell/2.1")
+            .with_app_version(2, 1);
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert_eq!(
+            json,
+            r#"{"os_type":"android","os_version":[14,2],"hostname":"pixel","user_agent":"shell/2.1","app_version":[2,1],"device_id":null,"hash":"djb2"}"#
+        );
+        let back: ClientMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.os_type, OsType::Android);
+        assert_eq!(back.os_version, (14, 2));
+        assert_eq!(back.user_agent.as_deref(), Some("shell/2.1"));
+        assert_eq!(back.app_version, Some((2, 1)));
+
+        // Optional fields may be left out
+        let minimal: ClientMetadata =
+            serde_json::from_str(r#"{"os_type":"ios","os_version":[17,4],"hostname":"phone"}"#)
+                .unwrap();
+        assert_eq!(minimal.user_agent, None);
+        assert_eq!(minimal.app_version, None);
+        assert_eq!(minimal.device_id, None);
+        assert_eq!(minimal.hash, HashKind::Djb2);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_client_metadata_serde_rejects_out_of_range() {
+        let err = serde_json::from_str::<ClientMetadata>(
+            r#"{"os_type":"linux","os_version":[32,0],"hostname":"h"}"#,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("os_version [32, 0] out of range, the maximum is [31, 15]"),
+            "{err}"
+        );
+        assert!(
+            serde_json::from_str::<ClientMetadata>(
+                r#"{"os_type":"linux","os_version":[6,16],"hostname":"h"}"#
+            )
+            .is_err()
+        );
+
+        let err = serde_json::from_str::<ClientMetadata>(
+            r#"{"os_type":"linux","os_version":[6,1],"hostname":"h","app_version":[64,0]}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("app_version [64, 0]"), "{err}");
+
+        // The largest values that fit are accepted
+        let max: ClientMetadata = serde_json::from_str(
+            r#"{"os_type":"linux","os_version":[31,15],"hostname":"h","app_version":[63,31]}"#,
+        )
+        .unwrap();
+        assert_eq!(max.app_version, Some((63, 31)));
+    }
+
+    #[test]
+    fn test_app_version_round_trip() {
+        use crate::uuid::UuidGenerator;
+
+        let generator = UuidGenerator::v7();
+        let metadata = ClientMetadata::new(OsType::Wasm, (1, 0), "browser")
+            .with_user_agent("Mozilla/5.0")
+            .with_app_version(4, 12);
+
+        let extracted =
+            extract_metadata(&generator.generate_uuid_with_metadata(&metadata)).unwrap();
+        assert_eq!(extracted.app_version, Some((4, 12)));
+        assert_eq!(extracted.os_type, OsType::Wasm);
+        assert_eq!(
+            extracted.extended_hash,
+            HashKind::Djb2.hash_u32("browserMozilla/5.0") & 0x7_FFFF
+        );
+
+        let unset = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let extracted = extract_metadata(&generator.generate_uuid_with_metadata(&unset)).unwrap();
+        assert_eq!(extracted.app_version, None);
+
+        let large = unset.with_app_version(200, 40);
+        let extracted = extract_metadata(&generator.generate_uuid_with_metadata(&large)).unwrap();
+        assert_eq!(extracted.app_version, Some((63, 31)));
+    }
+
+    #[test]
+    fn test_extract_metadata_checks_variant_bits() {
+        use crate::uuid::UuidGenerator;
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        for generator in [
+            UuidGenerator::v7(),
+            UuidGenerator::v7().with_metadata_v8(true),
+        ] {
+            let bytes = *generator.generate_uuid_with_metadata(&metadata).as_bytes();
+            assert!(extract_metadata(&Uuid::from_bytes(bytes)).is_some());
+
+            // NCS, Microsoft and future variants, with a matching checksum so
+            // only the variant is wrong
+            for variant in [0b00, 0b01, 0b11] {
+                let mut bytes = bytes;
+                bytes[8] = (bytes[8] & 0x3F) | (variant << 6);
+                set_checksum(&mut bytes);
+
+                let uuid = Uuid::from_bytes(bytes);
+                assert_eq!(extract_metadata(&uuid), None, "{uuid}");
+                assert_eq!(try_extract_metadata(&uuid), Err(MetadataError::NotMetadata));
+                assert_eq!(extract_metadata_legacy(&uuid), None);
+            }
+        }
+    }
+
     #[test]
     fn test_client_metadata_from_system() {
         let metadata = ClientMetadata::from_system();
@@ -301,6 +1160,55 @@ mod tests {
         assert!(!metadata.hostname.is_empty());
     }
 
+    #[test]
+    fn test_client_metadata_cached_once() {
+        let first = ClientMetadata::cached();
+        assert!(std::ptr::eq(first, ClientMetadata::cached()));
+
+        let fresh = ClientMetadata::from_system_uncached();
+        assert_eq!(first.os_type, fresh.os_type);
+        assert_eq!(first.hostname, fresh.hostname);
+        assert_eq!(ClientMetadata::from_system().hostname, first.hostname);
+    }
+
+    #[test]
+    fn test_client_metadata_from_user_agent() {
+        let user_agent = "Mozilla/5.0 (Macintosh; Intel Mac OS X 14.4; rv:125.0) Gecko/20100101 \
+                          Firefox/125.0";
+        let metadata =
+            ClientMetadata::from_user_agent(user_agent).with_hostname("https://app.example");
+
+        assert_eq!(metadata.os_type, OsType::MacOS);
+        assert_eq!(metadata.os_version, (14, 4));
+        assert_eq!(metadata.hostname, "https://app.example");
+        assert_eq!(metadata.user_agent.as_deref(), Some(user_agent));
+    }
+
+    #[test]
+    fn test_device_id_changes_extended_hash() {
+        use crate::uuid::UuidGenerator;
+
+        let first = ClientMetadata::new(OsType::Linux, (6, 1), "server-01").with_device_id("a");
+        let second = first.clone().with_device_id("b");
+        assert_ne!(first.extended_hash(), second.extended_hash());
+
+        let generator = UuidGenerator::v7();
+        let first = extract_metadata(&generator.generate_uuid_with_metadata(&first)).unwrap();
+        let second = extract_metadata(&generator.generate_uuid_with_metadata(&second)).unwrap();
+        assert_ne!(first.extended_hash, second.extended_hash);
+        assert_eq!(first.hostname_hash, second.hostname_hash);
+
+        // Without a device ID the hash is unchanged
+        let plain = ClientMetadata::new(OsType::Linux, (6, 1), "server-01").with_user_agent("ua");
+        assert_eq!(
+            plain.extended_hash(),
+            HashKind::Djb2.hash_u32("server-01ua")
+        );
+
+        let pid = plain.with_pid();
+        assert_eq!(pid.device_id, Some(format!("pid:{}", std::process::id())));
+    }
+
     #[test]
     fn test_metadata_different_hostnames() {
         use crate::uuid::{UuidGenerator, parse_uuid_with_metadata};
@@ -341,38 +1249,190 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_os_metadata_bit_layout() {
+        // 3-bit type, 5-bit major, 4-bit minor: iOS 17.2 is 101 10001 0010
+        assert_eq!(encode_os_metadata(OsType::IOS, (17, 2)), 0xB12);
+        assert_eq!(decode_os_metadata(0xB12), (OsType::IOS, (17, 2)));
+
+        let metadata = ClientMetadata::new(OsType::IOS, (17, 2), "iphone");
+        let uuid = crate::UuidGenerator::v7().generate_uuid_with_metadata(&metadata);
+        let bytes = uuid.as_bytes();
+        assert_eq!(bytes[6] & 0x0F, 0b1011);
+        assert_eq!(bytes[7], 0b0001_0010);
+        assert_eq!(crate::extract_metadata(&uuid).unwrap().os_version, (17, 2));
+    }
+
     #[test]
     fn test_hash_consistency() {
         let input = "test-hostname";
 
         // Hash should be consistent
-        let hash1 = hash_to_u16(input);
-        let hash2 = hash_to_u16(input);
+        let hash1 = HashKind::Djb2.hash_u16(input);
+        let hash2 = HashKind::Djb2.hash_u16(input);
         assert_eq!(hash1, hash2);
 
-        let hash1_32 = hash_to_u32(input);
-        let hash2_32 = hash_to_u32(input);
+        let hash1_32 = HashKind::Djb2.hash_u32(input);
+        let hash2_32 = HashKind::Djb2.hash_u32(input);
         assert_eq!(hash1_32, hash2_32);
     }
 
+    #[test]
+    fn test_fnv1a_known_values() {
+        assert_eq!(HashKind::Fnv1a.hash_u32(""), 0x811C_9DC5);
+        assert_eq!(HashKind::Fnv1a.hash_u32("a"), 0xE40C_292C);
+        assert_eq!(HashKind::Fnv1a.hash_u32("foobar"), 0xBF9C_F968);
+    }
+
+    #[test]
+    fn test_hash_kind_is_used_for_both_hashes() {
+        use crate::uuid::UuidGenerator;
+
+        let djb2 = ClientMetadata::new(OsType::Linux, (6, 1), "web-01");
+        let fnv1a = djb2.clone().with_hash(HashKind::Fnv1a);
+        let generator = UuidGenerator::v7();
+
+        let extracted = extract_metadata(&generator.generate_uuid_with_metadata(&djb2)).unwrap();
+        assert_eq!(
+            extracted.hostname_hash,
+            HashKind::Djb2.hash_u16("web-01") as u8
+        );
+        assert_eq!(
+            extracted.extended_hash,
+            HashKind::Djb2.hash_u32("web-01") & 0x7_FFFF
+        );
+
+        let extracted = extract_metadata(&generator.generate_uuid_with_metadata(&fnv1a)).unwrap();
+        let fnv = HashKind::Fnv1a.hash_u32("web-01");
+        assert_eq!(extracted.hostname_hash, (fnv ^ (fnv >> 16)) as u8);
+        assert_eq!(extracted.extended_hash, fnv & 0x7_FFFF);
+    }
+
+    #[test]
+    fn test_fnv1a_spreads_hostnames_better() {
+        let mut hostnames = Vec::new();
+        for prefix in ["h", "web", "db", "node", "srv"] {
+            hostnames.extend((1..600).map(|i| format!("{prefix}{i}")));
+        }
+        for role in ["web", "api", "db", "cache", "worker", "k8s-node"] {
+            for env in ["prod", "staging", "dev"] {
+                for region in ["us-east-1", "eu-west-1", "ap-south-1"] {
+                    hostnames.extend((1..13).map(|i| format!("{role}-{env}-{region}-{i:02}")));
+                }
+            }
+        }
+
+        // Distinct 19-bit extended hashes, and chi-squared of their top 8
+        // bits, where about 255 is uniform
+        let spread = |hash: HashKind| {
+            let hashes: Vec<u32> = hostnames
+                .iter()
+                .map(|hostname| hash.hash_u32(hostname) & 0x7_FFFF)
+                .collect();
+            let distinct = hashes
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len();
+
+            let mut buckets = [0u32; 256];
+            for hash in &hashes {
+                buckets[(hash >> 11) as usize] += 1;
+            }
+            let expected = hashes.len() as f64 / 256.0;
+            let chi_squared: f64 = buckets
+                .iter()
+                .map(|&count| (f64::from(count) - expected).powi(2) / expected)
+                .sum();
+            (distinct, chi_squared)
+        };
+
+        let (djb2_distinct, djb2_chi) = spread(HashKind::Djb2);
+        let (fnv1a_distinct, fnv1a_chi) = spread(HashKind::Fnv1a);
+        assert!(
+            fnv1a_distinct > djb2_distinct,
+            "{fnv1a_distinct} <= {djb2_distinct}"
+        );
+        assert!(fnv1a_chi * 10.0 < djb2_chi, "{fnv1a_chi} vs {djb2_chi}");
+    }
+
+    #[test]
+    fn test_hash_parts_matches_concatenation() {
+        for hash in [HashKind::Djb2, HashKind::Fnv1a] {
+            assert_eq!(
+                hash.hash_parts(&["web-01", "", "Mozilla/5.0"]),
+                hash.hash_u32("web-01Mozilla/5.0")
+            );
+        }
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "web-01")
+            .with_user_agent("ua")
+            .with_device_id("pid:1");
+        assert_eq!(
+            metadata.extended_hash(),
+            HashKind::Djb2.hash_u32("web-01ua\x1fpid:1")
+        );
+    }
+
     #[test]
     fn test_hash_different_inputs() {
-        let hash1 = hash_to_u16("host-001");
-        let hash2 = hash_to_u16("host-002");
+        let hash1 = HashKind::Djb2.hash_u16("host-001");
+        let hash2 = HashKind::Djb2.hash_u16("host-002");
 
         // Different inputs should (likely) produce different hashes
         assert_ne!(hash1, hash2);
     }
 
+    #[test]
+    fn test_parse_os_version() {
+        let cases = [
+            // Windows 11 still reports 10.0, the build tells them apart
+            (OsType::Windows, "10.0.22631", (11, 0)),
+            (OsType::Windows, "10.0.22000", (11, 0)),
+            (OsType::Windows, "10.0.19045", (10, 0)),
+            (OsType::Windows, "10.0", (10, 0)),
+            (OsType::Windows, "6.1.7601", (6, 1)),
+            (OsType::Windows, "11 (22631)", (11, 0)),
+            // Marketing versions pass through, Darwin versions are mapped
+            (OsType::MacOS, "14.4.1", (14, 4)),
+            (OsType::MacOS, "26.0", (26, 0)),
+            (OsType::MacOS, "23.4.0", (14, 4)),
+            (OsType::MacOS, "20.6.0", (11, 6)),
+            (OsType::MacOS, "25.0.0", (26, 0)),
+            (OsType::MacOS, "19.6.0", (10, 15)),
+            // Linux keeps the kernel major.minor
+            (OsType::Linux, "6.8.0-45-generic", (6, 8)),
+            (OsType::Linux, "5.15.153.1-microsoft-standard-WSL2", (5, 15)),
+            (OsType::Linux, "4.19.16", (4, 15)),
+            (OsType::Android, "14", (14, 0)),
+            (OsType::IOS, "17.4", (17, 4)),
+            // Overflowing majors saturate instead of wrapping
+            (OsType::Linux, "40", (31, 0)),
+            (OsType::FreeBsd, "14.1-RELEASE", (14, 1)),
+        ];
+
+        for (os_type, input, expected) in cases {
+            assert_eq!(
+                ClientMetadata::parse_os_version(input, os_type),
+                expected,
+                "{os_type} {input}"
+            );
+        }
+        assert_eq!(
+            ClientMetadata::parse_os_version("unknown", OsType::Linux),
+            ClientMetadata::detect_os_version()
+        );
+    }
+
     #[test]
     fn test_version_clamping() {
-        // Test that versions exceeding bit limits are properly masked
+        // Versions exceeding bit limits saturate rather than wrap
         // Major: 5-bit (0-31), Minor: 4-bit (0-15)
         let test_cases = [
-            ((31, 15), (31, 15)), // Max values for 5-bit major, 4-bit minor
-            ((32, 0), (0, 0)),    // 32 & 0x1F = 0 (overflow)
-            ((17, 2), (17, 2)),   // Valid iOS 17.2
-            ((25, 16), (25, 0)),  // Minor overflow: 16 & 0x0F = 0
+            ((31, 15), (31, 15)),   // Max values for 5-bit major, 4-bit minor
+            ((32, 0), (31, 0)),     // Major overflow
+            ((17, 2), (17, 2)),     // Valid iOS 17.2
+            ((25, 16), (25, 15)),   // Minor overflow
+            ((255, 255), (31, 15)), // Both overflow
         ];
 
         for (input_version, expected_version) in &test_cases {
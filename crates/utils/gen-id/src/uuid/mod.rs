@@ -1,14 +1,61 @@
-mod generator;
+pub(crate) mod encoding;
+mod format;
+mod guid;
+mod names;
 mod parser;
+mod sentinel;
+mod timestamp;
+
+#[cfg(feature = "std")]
+mod config;
+#[cfg(feature = "sqlx")]
+mod db;
+#[cfg(feature = "std")]
+mod generator;
+#[cfg(feature = "std")]
+mod monotonic;
+#[cfg(feature = "std")]
+mod random;
 
-#[cfg(feature = "custom-uuid")]
+#[cfg(feature = "metadata")]
+// The encoding half is only used by the generator
+#[cfg_attr(not(feature = "custom-uuid"), allow(dead_code))]
 mod metadata;
+#[cfg(feature = "metadata")]
+mod user_agent;
+
+pub use encoding::{EncodingError, from_proquint, from_short, to_proquint, to_short};
+pub use format::{UuidFormat, UuidVersion, format_uuid};
+pub use guid::{from_guid_le, parse_guid_registry, to_guid_le};
+pub use names::UnknownVariantError;
+pub use parser::{
+    BatchParseError, InvalidInputReason, ParseError, clean_uuid_input, from_slice, is_valid_uuid,
+    is_valid_uuid_hyphenated, is_valid_uuid_simple, parse_encoded, parse_prefixed, parse_uuid,
+    parse_uuid_batch, parse_uuid_batch_strict, parse_uuid_strict, reformat, reformat_prefixed,
+    strip_affixes,
+};
+pub use sentinel::{is_max, is_nil, max, nil};
+pub use timestamp::{
+    extract_timestamp_any, extract_v7_timestamp, sort_by_timestamp, timestamp_key, uuid_version,
+    v7_timestamp_ms,
+};
 
-pub use generator::{UuidFormat, UuidGenerator, UuidVersion};
-pub use parser::{ParseError, parse_uuid};
+#[cfg(feature = "std")]
+pub use crate::prefix::{PrefixError, PrefixPolicy};
+#[cfg(feature = "std")]
+pub use config::UuidGeneratorConfig;
+#[cfg(feature = "sqlx")]
+pub use db::DbUuid;
+#[cfg(feature = "std")]
+pub use generator::{MatchError, NamedUuidGenerator, TimestampError, UuidGenerator, VersionError};
 
-#[cfg(feature = "custom-uuid")]
-pub use parser::parse_uuid_with_metadata;
+#[cfg(feature = "metadata")]
+pub use parser::{
+    parse_prefixed_with_metadata, parse_uuid_batch_with_metadata, parse_uuid_with_metadata,
+};
 
-#[cfg(feature = "custom-uuid")]
-pub use metadata::{ClientMetadata, ExtractedMetadata, OsType, extract_metadata};
+#[cfg(feature = "metadata")]
+pub use metadata::{
+    ClientMetadata, ExtractedMetadata, HashKind, MetadataError, OsType, extract_metadata,
+    extract_metadata_from_bytes, extract_metadata_legacy, try_extract_metadata,
+};
@@ -0,0 +1,218 @@
+use std::sync::{Mutex, PoisonError};
+#[cfg(feature = "custom-uuid")]
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+use uuid::{Builder, Uuid};
+
+#[cfg(feature = "custom-uuid")]
+use super::metadata::EncodedMetadata;
+
+/// Width of the counter spread over `rand_a` (12 bits) and the top of
+/// `rand_b` (30 bits), RFC 9562 section 6.2 method 1
+const COUNTER_BITS: u32 = 42;
+const COUNTER_MAX: u64 = (1 << COUNTER_BITS) - 1;
+
+/// Monotonic UUID v7 source shared by clones of a generator
+///
+/// Within one millisecond the counter increments, so consecutive UUIDs
+/// strictly increase. A new millisecond reseeds the counter with random bits,
+/// leaving the top bit clear so it cannot overflow right away. On overflow,
+/// or when the clock goes backwards, the timestamp is advanced past the last
+/// one used instead of waiting for the clock.
+#[derive(Debug, Default)]
+pub(crate) struct MonotonicV7 {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    millis: u64,
+    counter: u64,
+}
+
+impl MonotonicV7 {
+    pub(crate) fn next_at(&self, now_millis: u64, random: [u8; 16]) -> Uuid {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if now_millis > state.millis {
+            state.millis = now_millis;
+            state.counter = seed_counter(&random);
+        } else if state.counter < COUNTER_MAX {
+            state.counter += 1;
+        } else {
+            state.millis += 1;
+            state.counter = seed_counter(&random);
+        }
+
+        encode(state.millis, state.counter, &random)
+    }
+}
+
+/// Largest 12-bit tail of a metadata UUID
+#[cfg(feature = "custom-uuid")]
+const TAIL_MAX: u64 = 0x0FFF;
+
+/// Shared tails kept before idle ones are pruned
+#[cfg(feature = "custom-uuid")]
+const SHARED_TAILS_MAX: usize = 1024;
+
+/// Tail counters of generators on the system clock, one per metadata
+#[cfg(feature = "custom-uuid")]
+static SHARED_TAILS: OnceLock<Mutex<HashMap<EncodedMetadata, Arc<MetadataTail>>>> = OnceLock::new();
+
+/// Tail counter for metadata UUIDs
+///
+/// Metadata leaves 12 bits for uniqueness, too few to leave to chance.
+/// Within one millisecond the tail counts up from a random start in its
+/// lower half, so IDs with the same metadata are distinct and strictly
+/// increase. A clock going backwards keeps counting in the latest
+/// millisecond used.
+#[cfg(feature = "custom-uuid")]
+#[derive(Debug, Default)]
+pub(crate) struct MetadataTail {
+    state: Mutex<State>,
+}
+
+#[cfg(feature = "custom-uuid")]
+impl MetadataTail {
+    /// The process-wide tail for `encoded`, shared by every generator on the
+    /// system clock so separately built ones keep counting instead of
+    /// colliding
+    ///
+    /// Past [`SHARED_TAILS_MAX`] entries, tails nobody holds and last used
+    /// before `now_millis` are dropped. Their next ID would start a new
+    /// millisecond from a random point anyway.
+    pub(crate) fn shared(encoded: &EncodedMetadata, now_millis: u64) -> Arc<Self> {
+        let tails = SHARED_TAILS.get_or_init(Default::default);
+        let mut tails = tails.lock().unwrap_or_else(PoisonError::into_inner);
+        if tails.len() >= SHARED_TAILS_MAX && !tails.contains_key(encoded) {
+            tails.retain(|_, tail| Arc::strong_count(tail) > 1 || tail.millis() >= now_millis);
+        }
+        Arc::clone(tails.entry(*encoded).or_default())
+    }
+
+    fn millis(&self) -> u64 {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .millis
+    }
+
+    /// Millisecond and tail for the next ID at `now_millis`, or the
+    /// millisecond whose tails are used up
+    pub(crate) fn next_at(&self, now_millis: u64, random: u16) -> Result<(u64, u16), u64> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if now_millis > state.millis {
+            state.millis = now_millis;
+            state.counter = u64::from(random) & (TAIL_MAX >> 1);
+        } else if state.counter < TAIL_MAX {
+            state.counter += 1;
+        } else {
+            return Err(state.millis);
+        }
+
+        Ok((state.millis, state.counter as u16))
+    }
+}
+
+fn seed_counter(random: &[u8; 16]) -> u64 {
+    let seed = u64::from_be_bytes(random[..8].try_into().unwrap());
+    seed & (COUNTER_MAX >> 1)
+}
+
+fn encode(millis: u64, counter: u64, random: &[u8; 16]) -> Uuid {
+    // Version and variant bits are applied by the builder, the counter fills
+    // the bits around them and the last 32 bits stay random
+    let mut bytes = [0u8; 10];
+    bytes[0] = (counter >> 38) as u8 & 0x0F;
+    bytes[1] = (counter >> 30) as u8;
+    bytes[2] = (counter >> 24) as u8 & 0x3F;
+    bytes[3] = (counter >> 16) as u8;
+    bytes[4] = (counter >> 8) as u8;
+    bytes[5] = counter as u8;
+    bytes[6..].copy_from_slice(&random[12..]);
+
+    Builder::from_unix_timestamp_millis(millis, &bytes).into_uuid()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp_millis(uuid: &Uuid) -> u64 {
+        let (secs, nanos) = uuid.get_timestamp().unwrap().to_unix();
+        secs * 1000 + u64::from(nanos) / 1_000_000
+    }
+
+    #[test]
+    fn test_counter_increments_within_millisecond() {
+        let source = MonotonicV7::default();
+        let first = source.next_at(1_000, [0xFF; 16]);
+        let second = source.next_at(1_000, [0x00; 16]);
+
+        assert!(second > first);
+        assert_eq!(first.get_version_num(), 7);
+        assert_eq!(timestamp_millis(&second), 1_000);
+    }
+
+    #[test]
+    fn test_counter_overflow_advances_timestamp() {
+        let source = MonotonicV7::default();
+        source.next_at(1_000, [0; 16]);
+        source.state.lock().unwrap().counter = COUNTER_MAX;
+
+        let at_max = encode(1_000, COUNTER_MAX, &[0xFF; 16]);
+        let overflowed = source.next_at(1_000, [0; 16]);
+
+        assert!(overflowed > at_max);
+        assert_eq!(timestamp_millis(&overflowed), 1_001);
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_metadata_tail_counts_within_millisecond() {
+        let tail = MetadataTail::default();
+        let (millis, first) = tail.next_at(1_000, 0xFFFF).unwrap();
+        assert_eq!(millis, 1_000);
+        assert_eq!(first, 0x07FF);
+
+        // Backwards clock keeps counting in the latest millisecond
+        assert_eq!(tail.next_at(900, 0).unwrap(), (1_000, 0x0800));
+
+        tail.state.lock().unwrap().counter = TAIL_MAX;
+        assert_eq!(tail.next_at(1_000, 0), Err(1_000));
+        assert_eq!(tail.next_at(1_001, 0x0123).unwrap(), (1_001, 0x0123));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_shared_metadata_tail_is_keyed_by_metadata() {
+        use crate::uuid::{ClientMetadata, OsType};
+
+        let one = ClientMetadata::new(OsType::Linux, (6, 1), "shared-tail-01").encode();
+        let two = ClientMetadata::new(OsType::Linux, (6, 1), "shared-tail-02").encode();
+
+        assert!(Arc::ptr_eq(
+            &MetadataTail::shared(&one, 0),
+            &MetadataTail::shared(&one, 0)
+        ));
+        assert!(!Arc::ptr_eq(
+            &MetadataTail::shared(&one, 0),
+            &MetadataTail::shared(&two, 0)
+        ));
+    }
+
+    #[test]
+    fn test_clock_going_backwards_keeps_order() {
+        let source = MonotonicV7::default();
+        let first = source.next_at(2_000, [0; 16]);
+        let second = source.next_at(1_500, [0; 16]);
+
+        assert!(second > first);
+        assert_eq!(timestamp_millis(&second), 2_000);
+    }
+}
@@ -0,0 +1,206 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
+
+use super::format::{UuidFormat, UuidVersion};
+#[cfg(feature = "metadata")]
+use super::metadata::OsType;
+
+pub(crate) const FORMATS: [(&str, UuidFormat); 11] = [
+    ("standard", UuidFormat::Standard),
+    ("simple", UuidFormat::Simple),
+    ("standard_uppercase", UuidFormat::StandardUppercase),
+    ("simple_uppercase", UuidFormat::SimpleUppercase),
+    ("braced", UuidFormat::Braced),
+    ("braced_uppercase", UuidFormat::BracedUppercase),
+    ("urn", UuidFormat::Urn),
+    ("base32_crockford", UuidFormat::Base32Crockford),
+    ("base58", UuidFormat::Base58),
+    ("base62", UuidFormat::Base62),
+    ("proquint", UuidFormat::Proquint),
+];
+
+const VERSIONS: [(&str, UuidVersion); 3] = [
+    ("v4", UuidVersion::V4),
+    ("v7", UuidVersion::V7),
+    ("v5", UuidVersion::V5),
+];
+
+#[cfg(feature = "metadata")]
+const OS_TYPES: [(&str, OsType); 9] = [
+    ("unknown", OsType::Unknown),
+    ("linux", OsType::Linux),
+    ("windows", OsType::Windows),
+    ("macos", OsType::MacOS),
+    ("android", OsType::Android),
+    ("ios", OsType::IOS),
+    ("freebsd", OsType::FreeBsd),
+    ("openbsd", OsType::OpenBsd),
+    ("wasm", OsType::Wasm),
+];
+
+/// Error for a string that names no [`UuidFormat`], [`UuidVersion`] or OS type
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown UUID {kind} `{input}`, expected one of: {}", .expected.join(", "))]
+pub struct UnknownVariantError {
+    kind: &'static str,
+    input: String,
+    expected: Vec<&'static str>,
+}
+
+fn parse_name<T: Copy>(
+    kind: &'static str,
+    names: &[(&'static str, T)],
+    input: &str,
+) -> Result<T, UnknownVariantError> {
+    let normalized = input.trim().to_ascii_lowercase().replace('-', "_");
+    names
+        .iter()
+        .find(|(name, _)| *name == normalized)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| UnknownVariantError {
+            kind,
+            input: input.to_string(),
+            expected: names.iter().map(|(name, _)| *name).collect(),
+        })
+}
+
+fn name_of<T: PartialEq>(names: &[(&'static str, T)], value: &T) -> &'static str {
+    names
+        .iter()
+        .find(|(_, v)| v == value)
+        .map(|(name, _)| *name)
+        .expect("every variant has a name")
+}
+
+impl UuidFormat {
+    /// Canonical snake_case name, the same as the serde name
+    pub fn as_str(&self) -> &'static str {
+        name_of(&FORMATS, self)
+    }
+}
+
+impl UuidVersion {
+    /// Canonical name such as `v7`, the same as the serde name
+    pub fn as_str(&self) -> &'static str {
+        name_of(&VERSIONS, self)
+    }
+}
+
+#[cfg(feature = "metadata")]
+impl OsType {
+    /// Lowercase name such as `macos`, the same as the serde name
+    pub fn as_str(&self) -> &'static str {
+        name_of(&OS_TYPES, self)
+    }
+}
+
+impl fmt::Display for UuidFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for UuidVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "metadata")]
+impl fmt::Display for OsType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Case-insensitive, accepting both `simple-uppercase` and `simple_uppercase`
+impl FromStr for UuidFormat {
+    type Err = UnknownVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_name("format", &FORMATS, s)
+    }
+}
+
+/// Case-insensitive, e.g. `v7` or `V7`
+impl FromStr for UuidVersion {
+    type Err = UnknownVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_name("version", &VERSIONS, s)
+    }
+}
+
+/// Case-insensitive, e.g. `macos` or `macOS`
+#[cfg(feature = "metadata")]
+impl FromStr for OsType {
+    type Err = UnknownVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_name("OS type", &OS_TYPES, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_round_trip() {
+        for (_, format) in FORMATS {
+            assert_eq!(format.to_string().parse::<UuidFormat>(), Ok(format));
+        }
+    }
+
+    #[test]
+    fn test_version_round_trip() {
+        for (_, version) in VERSIONS {
+            assert_eq!(version.to_string().parse::<UuidVersion>(), Ok(version));
+        }
+    }
+
+    #[test]
+    fn test_parse_accepts_case_and_separators() {
+        for input in ["simple-uppercase", "SIMPLE_UPPERCASE", "Simple-Uppercase"] {
+            assert_eq!(input.parse(), Ok(UuidFormat::SimpleUppercase), "{input}");
+        }
+        assert_eq!("V7".parse(), Ok(UuidVersion::V7));
+    }
+
+    #[test]
+    fn test_parse_error_lists_options() {
+        let err = "hex".parse::<UuidFormat>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown UUID format `hex`, expected one of: standard, simple, standard_uppercase, \
+             simple_uppercase, braced, braced_uppercase, urn, base32_crockford, base58, base62, \
+             proquint"
+        );
+
+        let err = "v1".parse::<UuidVersion>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown UUID version `v1`, expected one of: v4, v7, v5"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "metadata")]
+    fn test_os_type_names() {
+        for (_, os_type) in OS_TYPES {
+            assert_eq!(os_type.to_string().parse::<OsType>(), Ok(os_type));
+        }
+        assert_eq!("macOS".parse(), Ok(OsType::MacOS));
+        assert_eq!("IOS".parse(), Ok(OsType::IOS));
+
+        let err = "beos".parse::<OsType>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown UUID OS type `beos`, expected one of: unknown, linux, windows, macos, \
+             android, ios, freebsd, openbsd, wasm"
+        );
+    }
+}
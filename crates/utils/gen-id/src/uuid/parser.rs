@@ -1,7 +1,17 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
 use uuid::Uuid;
 
-#[cfg(feature = "custom-uuid")]
+#[cfg(feature = "metadata")]
 use super::metadata::{ExtractedMetadata, extract_metadata};
+use super::{
+    encoding::{self, BASE32_LEN, BASE58_LEN, EncodingError, PROQUINT_LEN},
+    format::{self, UuidFormat, format_uuid},
+};
 
 /// Error type for UUID parsing
 #[derive(Debug, thiserror::Error)]
@@ -13,6 +23,53 @@ pub enum ParseError {
     #[cfg(not(feature = "simd"))]
     #[error("UUID parse error: {0}")]
     Standard(#[from] uuid::Error),
+
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+
+    #[error("expected prefix `{expected}`, found `{found}`")]
+    PrefixMismatch { expected: String, found: String },
+
+    #[error("unsupported UUID version {version}, expected 4, 5, 7 or 8")]
+    UnsupportedVersion { version: u8 },
+
+    #[error("expected 16 UUID bytes, found {found}")]
+    InvalidByteLength { found: usize },
+
+    /// The input is recognisably not a bare UUID, e.g. `order_0194e7...`
+    ///
+    /// `input` is echoed back for triage, cut to 64 characters.
+    #[error("invalid UUID `{input}`: {reason}")]
+    InvalidInput {
+        input: String,
+        reason: InvalidInputReason,
+    },
+}
+
+/// Why [`ParseError::InvalidInput`] rejected an input
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum InvalidInputReason {
+    /// A UUID follows a `_` or `:` separator
+    #[error("input appears to have a prefix '{prefix}'; strip it or use parse_prefixed")]
+    Prefixed { prefix: String },
+
+    /// Not `{...}` around a hyphenated UUID, see
+    /// [`parse_guid_registry`](super::parse_guid_registry)
+    #[error("expected a registry GUID like {{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}}")]
+    NotRegistryGuid,
+}
+
+/// Longest input echoed in [`ParseError::InvalidInput`]
+const MAX_ECHOED_CHARS: usize = 64;
+
+/// Error from [`parse_uuid_batch_strict`] for the first invalid input
+#[derive(Debug, thiserror::Error)]
+#[error("invalid UUID at index {index}: {source}")]
+pub struct BatchParseError {
+    pub index: usize,
+    #[source]
+    pub source: ParseError,
 }
 
 /// Parse a UUID string and extract embedded metadata if present
@@ -20,57 +77,374 @@ pub enum ParseError {
 /// # Availability
 /// This function is only available when the `custom-uuid` feature is enabled.
 #[inline]
-#[cfg(feature = "custom-uuid")]
+#[cfg(feature = "metadata")]
 pub fn parse_uuid_with_metadata(
     input: &str,
 ) -> Result<(Uuid, Option<ExtractedMetadata>), ParseError> {
+    let uuid = parse_clean(input)?;
+    let metadata = extract_metadata(&uuid);
+    Ok((uuid, metadata))
+}
+
+/// Parse a UUID string, rejecting versions this crate does not generate
+///
+/// Like [`parse_uuid`], but a well-formed UUID whose version is not 4, 5, 7
+/// or 8 returns [`ParseError::UnsupportedVersion`], so random hex blobs and
+/// the nil UUID are caught early.
+#[inline]
+pub fn parse_uuid_strict(input: &str) -> Result<Uuid, ParseError> {
+    let uuid = parse_clean(input)?;
+    match uuid.get_version_num() {
+        4 | 5 | 7 | 8 => Ok(uuid),
+        version => Err(ParseError::UnsupportedVersion {
+            version: version as u8,
+        }),
+    }
+}
+
+/// Parse a UUID in any [`UuidFormat`], including the base32, base58 and
+/// proquint ones
+///
+/// Hex forms are cleaned like [`parse_uuid`]. A 26-character input is read
+/// as Crockford base32, a 22-character one as base58 and a 47-character one
+/// as a proquint, the only lengths those encodings produce. Base62 has the same length as base58, so short
+/// IDs need [`from_short`](super::from_short) instead.
+#[inline]
+pub fn parse_encoded(input: &str) -> Result<Uuid, ParseError> {
+    parse_clean(input)
+}
+
+/// Parse an ID that must start with `prefix`, e.g. `user_550e8400-...`
+///
+/// The prefix is verified and stripped before the usual cleaning and
+/// parsing. A missing or different prefix returns
+/// [`ParseError::PrefixMismatch`].
+#[inline]
+pub fn parse_prefixed(prefix: &str, input: &str) -> Result<Uuid, ParseError> {
+    parse_clean(strip_expected_prefix(prefix, input)?)
+}
+
+/// Convert a UUID string to `target`, e.g. simple to hyphenated uppercase
+///
+/// Accepts every form [`parse_uuid`] accepts and allocates only the output.
+#[inline]
+pub fn reformat(input: &str, target: UuidFormat) -> Result<String, ParseError> {
+    let uuid = parse_clean(input)?;
+    Ok(format_uuid(&uuid, target))
+}
+
+/// Convert a prefixed ID to `target`, keeping the prefix
+///
+/// The prefix is checked like [`parse_prefixed`], so `user_<simple>`
+/// becomes `user_<target>`.
+#[inline]
+pub fn reformat_prefixed(
+    prefix: &str,
+    input: &str,
+    target: UuidFormat,
+) -> Result<String, ParseError> {
+    let uuid = parse_prefixed(prefix, input)?;
+
+    let mut buf = [0u8; format::MAX_LEN];
+    let encoded = format::encode(&uuid, target, &mut buf);
+    let mut out = String::with_capacity(prefix.len() + encoded.len());
+    out.push_str(prefix);
+    out.push_str(encoded);
+    Ok(out)
+}
+
+/// Parse a prefixed ID and extract embedded metadata if present
+///
+/// See [`parse_prefixed`].
+///
+/// # Availability
+/// This function is only available when the `custom-uuid` feature is enabled.
+#[inline]
+#[cfg(feature = "metadata")]
+pub fn parse_prefixed_with_metadata(
+    prefix: &str,
+    input: &str,
+) -> Result<(Uuid, Option<ExtractedMetadata>), ParseError> {
+    parse_uuid_with_metadata(strip_expected_prefix(prefix, input)?)
+}
+
+/// Parse many UUID strings, one result per input in order
+///
+/// Inputs are cleaned like [`parse_uuid`]. The output is allocated once
+/// from the iterator's size hint.
+#[inline]
+pub fn parse_uuid_batch<'a>(
+    inputs: impl IntoIterator<Item = &'a str>,
+) -> Vec<Result<Uuid, ParseError>> {
+    inputs.into_iter().map(parse_clean).collect()
+}
+
+/// Parse many UUID strings, stopping at the first invalid one
+#[inline]
+pub fn parse_uuid_batch_strict<'a>(
+    inputs: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<Uuid>, BatchParseError> {
+    inputs
+        .into_iter()
+        .enumerate()
+        .map(|(index, input)| {
+            parse_clean(input).map_err(|source| BatchParseError { index, source })
+        })
+        .collect()
+}
+
+/// Parse many UUID strings and extract embedded metadata in one pass
+///
+/// # Availability
+/// This function is only available when the `custom-uuid` feature is enabled.
+#[inline]
+#[cfg(feature = "metadata")]
+pub fn parse_uuid_batch_with_metadata<'a>(
+    inputs: impl IntoIterator<Item = &'a str>,
+) -> Vec<Result<(Uuid, Option<ExtractedMetadata>), ParseError>> {
+    inputs.into_iter().map(parse_uuid_with_metadata).collect()
+}
+
+fn strip_expected_prefix<'a>(prefix: &str, input: &'a str) -> Result<&'a str, ParseError> {
+    input
+        .strip_prefix(prefix)
+        .ok_or_else(|| ParseError::PrefixMismatch {
+            expected: prefix.to_string(),
+            found: input.chars().take(prefix.chars().count()).collect(),
+        })
+}
+
+#[inline]
+pub(crate) fn parse_clean(input: &str) -> Result<Uuid, ParseError> {
     let clean_input = clean_uuid_input(input);
+    match clean_input.len() {
+        BASE32_LEN => return Ok(encoding::decode_base32(clean_input)?),
+        BASE58_LEN => return Ok(encoding::decode_base58(clean_input)?),
+        PROQUINT_LEN => return Ok(encoding::decode_proquint(clean_input)?),
+        _ => {}
+    }
 
+    parse_hex(clean_input)
+}
+
+/// Parse a cleaned hex UUID, explaining inputs that look prefixed
+#[inline]
+fn parse_hex(clean_input: &str) -> Result<Uuid, ParseError> {
     #[cfg(feature = "simd")]
-    let uuid = {
+    let result = {
         use uuid_simd::UuidExt;
-        Uuid::parse(clean_input.as_bytes()).map_err(ParseError::Simd)?
+        Uuid::parse(clean_input.as_bytes()).map_err(ParseError::Simd)
     };
 
     #[cfg(not(feature = "simd"))]
-    let uuid = Uuid::parse_str(clean_input).map_err(ParseError::Standard)?;
+    let result = Uuid::parse_str(clean_input).map_err(ParseError::Standard);
 
-    let metadata = extract_metadata(&uuid);
-    Ok((uuid, metadata))
+    result.map_err(|err| match detect_prefix(clean_input) {
+        Some(prefix) => ParseError::InvalidInput {
+            input: truncate_input(clean_input),
+            reason: InvalidInputReason::Prefixed {
+                prefix: prefix.to_string(),
+            },
+        },
+        None => err,
+    })
+}
+
+/// The prefix up to and including a `_` or `:` that a simple or hyphenated
+/// UUID follows, e.g. `order_` in `order_0194e7...`
+fn detect_prefix(input: &str) -> Option<&str> {
+    [36, 32].into_iter().find_map(|len| {
+        let split = input.len().checked_sub(len)?;
+        let (prefix, uuid) = (input.get(..split)?, input.get(split..)?);
+        let is_uuid = if len == 36 {
+            is_hyphenated(uuid.as_bytes())
+        } else {
+            is_hex(uuid.as_bytes())
+        };
+        (prefix.len() > 1 && prefix.ends_with(['_', ':']) && is_uuid).then_some(prefix)
+    })
+}
+
+pub(crate) fn truncate_input(input: &str) -> String {
+    match input.char_indices().nth(MAX_ECHOED_CHARS) {
+        Some((end, _)) => format!("{}...", &input[..end]),
+        None => input.to_string(),
+    }
+}
+
+/// Parse the UUID at the end of `input`, ignoring any unknown prefix
+///
+/// Tries the proquint, URN, braced, hyphenated, simple, base32 and base58
+/// lengths, longest first, so hex-looking prefixes cannot bleed into the
+/// UUID.
+pub(crate) fn parse_trailing(input: &str) -> Option<Uuid> {
+    let input = input.trim_ascii();
+    [PROQUINT_LEN, 45, 38, 36, 32, BASE32_LEN, BASE58_LEN]
+        .into_iter()
+        .find_map(|len| {
+            let start = input.len().checked_sub(len)?;
+            parse_clean(input.get(start..)?).ok()
+        })
 }
 
-/// Parse a UUID string using uuid-simd (legacy function, kept for backwards compatibility)
+/// Parse a hex UUID string, using uuid-simd with the `simd` feature
+///
+/// Input is cleaned with [`clean_uuid_input`] first. A UUID behind a
+/// prefix such as `order_` returns [`ParseError::InvalidInput`] naming the
+/// prefix; [`parse_prefixed`] strips a known one.
 #[inline]
-#[cfg(feature = "simd")]
-pub fn parse_uuid(input: &str) -> Result<Uuid, uuid_simd::Error> {
-    use uuid_simd::UuidExt;
+pub fn parse_uuid(input: &str) -> Result<Uuid, ParseError> {
+    parse_hex(clean_uuid_input(input))
+}
 
-    let clean_input = clean_uuid_input(input);
+/// Read a UUID from 16 bytes in RFC order, e.g. a `BYTEA` or `BLOB` column
+///
+/// Any other length returns [`ParseError::InvalidByteLength`]. GUID bytes
+/// from Windows need [`from_guid_le`](super::from_guid_le) instead.
+#[inline]
+pub fn from_slice(bytes: &[u8]) -> Result<Uuid, ParseError> {
+    let bytes = <[u8; 16]>::try_from(bytes)
+        .map_err(|_| ParseError::InvalidByteLength { found: bytes.len() })?;
+    Ok(Uuid::from_bytes(bytes))
+}
 
-    Uuid::parse(clean_input.as_bytes())
+/// Strip a known prefix and suffix from a generated ID, returning the UUID
+/// part for [`parse_uuid`]
+///
+/// Returns `None` when either is missing. Pass `""` for an absent prefix
+/// or suffix.
+#[inline]
+pub fn strip_affixes<'a>(input: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    input.strip_prefix(prefix)?.strip_suffix(suffix)
 }
 
-/// Parse a UUID string using standard parser (legacy function, kept for backwards compatibility)
+/// Check whether [`parse_uuid`] would accept `input`, without parsing
+///
+/// After the same cleaning as [`parse_uuid`], accepts the simple,
+/// hyphenated, braced and `urn:uuid:` forms in either case. Nothing is
+/// allocated.
 #[inline]
-#[cfg(not(feature = "simd"))]
-pub fn parse_uuid(input: &str) -> Result<Uuid, uuid::Error> {
-    let clean_input = clean_uuid_input(input);
+pub fn is_valid_uuid(input: &str) -> bool {
+    let input = clean_uuid_input(input).as_bytes();
+    match input.len() {
+        32 => is_hex(input),
+        36 => is_hyphenated(input),
+        38 => input[0] == b'{' && input[37] == b'}' && is_hyphenated(&input[1..37]),
+        45 => input.strip_prefix(b"urn:uuid:").is_some_and(is_hyphenated),
+        _ => false,
+    }
+}
+
+/// Check for exactly 32 hex digits, with no prefix, braces or whitespace
+#[inline]
+pub fn is_valid_uuid_simple(input: &str) -> bool {
+    input.len() == 32 && is_hex(input.as_bytes())
+}
 
-    Uuid::parse_str(clean_input)
+/// Check for exactly the 8-4-4-4-12 hyphenated form, with no prefix, braces
+/// or whitespace
+#[inline]
+pub fn is_valid_uuid_hyphenated(input: &str) -> bool {
+    is_hyphenated(input.as_bytes())
+}
+
+#[inline]
+fn is_hex(input: &[u8]) -> bool {
+    input.iter().all(u8::is_ascii_hexdigit)
 }
 
+#[inline]
+fn is_hyphenated(input: &[u8]) -> bool {
+    input.len() == 36
+        && input.iter().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+/// Strip surrounding ASCII whitespace, a `urn:uuid:` or `uuid:` scheme in
+/// any case, and a matching pair of braces
+///
+/// An unpaired brace is left in place so parsing rejects it.
 #[inline]
 pub fn clean_uuid_input(input: &str) -> &str {
-    input
-        .trim_start_matches("urn:uuid:")
-        .trim_start_matches("uuid:")
-        .trim_start_matches('{')
-        .trim_end_matches('}')
+    let input = input.trim_ascii();
+    let input = strip_prefix_ignore_case(input, "urn:uuid:")
+        .or_else(|| strip_prefix_ignore_case(input, "uuid:"))
+        .unwrap_or(input);
+
+    match input.strip_prefix('{') {
+        Some(inner) => inner.strip_suffix('}').unwrap_or(input),
+        None => input,
+    }
 }
 
-#[cfg(test)]
+#[inline]
+fn strip_prefix_ignore_case<'a>(input: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = input.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &input[prefix.len()..])
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
+    use crate::uuid::{UuidGenerator, UuidVersion};
+
+    #[test]
+    fn test_parse_uuid_strict_versions() {
+        for generator in [
+            UuidGenerator::v4(),
+            UuidGenerator::v7(),
+            UuidGenerator::new(UuidVersion::V7, UuidFormat::Urn),
+        ] {
+            assert!(parse_uuid_strict(&generator.generate()).is_ok());
+        }
+        let v5 = UuidGenerator::v5(Uuid::NAMESPACE_DNS).generate_named(b"example.com");
+        assert!(parse_uuid_strict(&v5).is_ok());
+
+        for (input, expected) in [
+            // v1
+            ("c232ab00-9414-11ec-b3c8-9f6bdeced846", 1),
+            // Random hex with version nibble 0xc
+            ("0123456789abcdef0123456789abcdef", 12),
+            ("00000000-0000-0000-0000-000000000000", 0),
+        ] {
+            assert!(
+                matches!(
+                    parse_uuid_strict(input),
+                    Err(ParseError::UnsupportedVersion { version }) if version == expected
+                ),
+                "{input}"
+            );
+        }
+        assert!(matches!(
+            parse_uuid_strict("not-a-uuid"),
+            Err(err) if !matches!(err, ParseError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_parse_uuid_with_metadata_wrong_variant() {
+        use crate::uuid::{ClientMetadata, OsType};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let id = UuidGenerator::v7().generate_with_metadata(&metadata);
+        assert!(parse_uuid_with_metadata(&id).unwrap().1.is_some());
+
+        // Byte 8 starts at index 19 of the hyphenated form, clear its top bit
+        // so the variant bits become 00
+        let mut corrupted = id.into_bytes();
+        let nibble =
+            u8::from_str_radix(std::str::from_utf8(&corrupted[19..20]).unwrap(), 16).unwrap();
+        corrupted[19] = format!("{:x}", nibble & 0x7).as_bytes()[0];
+        let corrupted = String::from_utf8(corrupted).unwrap();
+
+        let (uuid, extracted) = parse_uuid_with_metadata(&corrupted).unwrap();
+        assert_ne!(uuid.get_variant(), uuid::Variant::RFC4122);
+        assert_eq!(extracted, None);
+    }
 
     #[test]
     fn test_parse_uuid_standard() {
@@ -107,6 +481,318 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_strip_affixes() {
+        let id = "order_550e8400-e29b-41d4-a716-446655440000_sg1";
+
+        let uuid_part = strip_affixes(id, "order_", "_sg1").unwrap();
+        assert!(parse_uuid(uuid_part).is_ok());
+        assert_eq!(strip_affixes(id, "user_", "_sg1"), None);
+        assert_eq!(strip_affixes(id, "order_", "_us1"), None);
+    }
+
+    #[test]
+    fn test_parse_prefixed() {
+        let uuid = parse_prefixed("user_", "user_550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(
+            uuid,
+            parse_uuid("550e8400-e29b-41d4-a716-446655440000").unwrap()
+        );
+
+        assert!(parse_prefixed("user_", "user_{550e8400-e29b-41d4-a716-446655440000}").is_ok());
+        assert!(parse_prefixed("", "550e8400e29b41d4a716446655440000").is_ok());
+    }
+
+    #[test]
+    fn test_parse_encoded_forms() {
+        let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        for input in [
+            "2N1T201RMV87AAE5J4CSAM8000",
+            " 2n1t201rmv87aae5j4csam8000 ",
+            "BWBeN28Vb7cMEx7Ym8AUzs",
+            "{550e8400-e29b-41d4-a716-446655440000}",
+        ] {
+            assert_eq!(parse_encoded(input).unwrap(), uuid, "{input}");
+        }
+
+        assert!(matches!(
+            parse_encoded("BWBeN28Vb7cMEx7Ym8AUzO"),
+            Err(ParseError::Encoding(EncodingError::InvalidChar {
+                ch: 'O',
+                index: 21,
+                ..
+            }))
+        ));
+        assert!(parse_uuid("2N1T201RMV87AAE5J4CSAM8000").is_err());
+    }
+
+    #[test]
+    fn test_reformat_every_combination() {
+        use crate::uuid::names::FORMATS;
+
+        let uuid = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        // Base62 reads as base58 here, see `parse_encoded`
+        for (_, source) in FORMATS
+            .into_iter()
+            .filter(|(_, f)| *f != UuidFormat::Base62)
+        {
+            let input = UuidGenerator::new(UuidVersion::V4, source).format(&uuid);
+            for (_, target) in FORMATS {
+                let expected = UuidGenerator::new(UuidVersion::V4, target).format(&uuid);
+                assert_eq!(
+                    reformat(&input, target).unwrap(),
+                    expected,
+                    "{source} -> {target}"
+                );
+            }
+        }
+
+        assert_eq!(
+            reformat(
+                "URN:UUID:550E8400-E29B-41D4-A716-446655440000",
+                UuidFormat::Simple
+            )
+            .unwrap(),
+            "550e8400e29b41d4a716446655440000"
+        );
+        assert!(reformat("not-a-uuid", UuidFormat::Standard).is_err());
+    }
+
+    #[test]
+    fn test_reformat_prefixed() {
+        assert_eq!(
+            reformat_prefixed(
+                "user_",
+                "user_550e8400e29b41d4a716446655440000",
+                UuidFormat::StandardUppercase
+            )
+            .unwrap(),
+            "user_550E8400-E29B-41D4-A716-446655440000"
+        );
+        assert!(matches!(
+            reformat_prefixed(
+                "user_",
+                "acct_550e8400e29b41d4a716446655440000",
+                UuidFormat::Standard
+            ),
+            Err(ParseError::PrefixMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_prefixed_mismatch() {
+        let err = parse_prefixed("user_", "acct_550e8400-e29b-41d4-a716-446655440000").unwrap_err();
+        assert!(matches!(
+            &err,
+            ParseError::PrefixMismatch { expected, found } if expected == "user_" && found == "acct_"
+        ));
+        assert_eq!(err.to_string(), "expected prefix `user_`, found `acct_`");
+
+        let err = parse_prefixed("user_", "550e8400-e29b-41d4-a716-446655440000").unwrap_err();
+        assert!(matches!(err, ParseError::PrefixMismatch { found, .. } if found == "550e8"));
+
+        assert!(matches!(
+            parse_prefixed("user_", "user_not-a-uuid"),
+            Err(err) if !matches!(err, ParseError::PrefixMismatch { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_parse_prefixed_with_metadata() {
+        use crate::uuid::{ClientMetadata, OsType, UuidGenerator};
+
+        let metadata = ClientMetadata::new(OsType::Linux, (6, 1), "server-01");
+        let uuid = UuidGenerator::v7()
+            .with_prefix("trade_")
+            .generate_with_metadata(&metadata);
+
+        let (_, extracted) = parse_prefixed_with_metadata("trade_", &uuid).unwrap();
+        assert_eq!(extracted.unwrap().os_type, OsType::Linux);
+        assert!(matches!(
+            parse_prefixed_with_metadata("order_", &uuid),
+            Err(ParseError::PrefixMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_uuid_uppercase_urn() {
+        let expected = parse_uuid("550e8400-e29b-41d4-a716-446655440000").unwrap();
+
+        for input in [
+            "URN:UUID:550E8400-E29B-41D4-A716-446655440000",
+            "Urn:Uuid:550e8400-e29b-41d4-a716-446655440000",
+            "UUID:550e8400-e29b-41d4-a716-446655440000",
+        ] {
+            assert_eq!(parse_uuid(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_uuid_trims_whitespace() {
+        for input in [
+            "550e8400-e29b-41d4-a716-446655440000\n",
+            "  550e8400-e29b-41d4-a716-446655440000\r\n",
+            "\t{550e8400-e29b-41d4-a716-446655440000} ",
+            " urn:uuid:550e8400e29b41d4a716446655440000\n",
+        ] {
+            assert!(parse_uuid(input).is_ok(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_uuid_rejects_mismatched_braces() {
+        for input in [
+            "550e8400-e29b-41d4-a716-446655440000}",
+            "{550e8400-e29b-41d4-a716-446655440000",
+            "{550e8400-e29b-41d4-a716-446655440000}}",
+        ] {
+            assert!(parse_uuid(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_clean_uuid_input() {
+        let uuid = "550e8400-e29b-41d4-a716-446655440000";
+
+        assert_eq!(clean_uuid_input(&format!("URN:UUID:{{{uuid}}}\n")), uuid);
+        assert_eq!(clean_uuid_input(&format!("{uuid}}}")), format!("{uuid}}}"));
+        assert_eq!(clean_uuid_input("urn:uu"), "urn:uu");
+    }
+
+    #[test]
+    fn test_parse_uuid_batch() {
+        let inputs = [
+            "550e8400-e29b-41d4-a716-446655440000",
+            "not-a-uuid",
+            "URN:UUID:550E8400E29B41D4A716446655440000\n",
+        ];
+        let results = parse_uuid_batch(inputs);
+
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_err());
+        assert_eq!(results[0].as_ref().unwrap(), results[2].as_ref().unwrap());
+    }
+
+    #[test]
+    fn test_parse_uuid_batch_strict() {
+        let lines =
+            "550e8400-e29b-41d4-a716-446655440000\n{550e8400-e29b-41d4-a716-446655440000}\n";
+        assert_eq!(parse_uuid_batch_strict(lines.lines()).unwrap().len(), 2);
+
+        let err = parse_uuid_batch_strict([
+            "550e8400-e29b-41d4-a716-446655440000",
+            "550e8400-e29b-41d4-a716-446655440000",
+            "550e8400-e29b-41d4-a716-44665544000",
+            "also bad",
+        ])
+        .unwrap_err();
+        assert_eq!(err.index, 2);
+        assert!(err.to_string().starts_with("invalid UUID at index 2: "));
+    }
+
+    #[test]
+    #[cfg(feature = "custom-uuid")]
+    fn test_parse_uuid_batch_with_metadata() {
+        use crate::uuid::{ClientMetadata, OsType, UuidGenerator};
+
+        let metadata = ClientMetadata::new(OsType::MacOS, (14, 5), "laptop");
+        let ids = UuidGenerator::v7().generate_batch_with_metadata(3, &metadata);
+        let results = parse_uuid_batch_with_metadata(ids.iter().map(String::as_str));
+
+        assert_eq!(results.len(), 3);
+        for result in results {
+            let (_, extracted) = result.unwrap();
+            assert_eq!(extracted.unwrap().os_type, OsType::MacOS);
+        }
+    }
+
+    #[test]
+    fn test_is_valid_uuid_agrees_with_parse_uuid() {
+        use crate::uuid::{UuidFormat, UuidGenerator};
+
+        let formats = [
+            UuidFormat::Standard,
+            UuidFormat::Simple,
+            UuidFormat::StandardUppercase,
+            UuidFormat::SimpleUppercase,
+            UuidFormat::Braced,
+            UuidFormat::BracedUppercase,
+            UuidFormat::Urn,
+        ];
+        let mut corpus = vec![
+            String::new(),
+            "{}".to_string(),
+            "urn:uuid:".to_string(),
+            "{{550e8400-e29b-41d4-a716-446655440000}}".to_string(),
+            "urn:uuid:{550e8400-e29b-41d4-a716-446655440000}".to_string(),
+            "urn:uuid:550e8400e29b41d4a716446655440000".to_string(),
+            "550e8400-e29b41d4-a716-4466554400000".to_string(),
+        ];
+        for format in formats {
+            let generator = UuidGenerator::v4_seeded(11).with_format(format);
+            corpus.extend(generator.generate_batch(3));
+        }
+
+        // Single-character substitutions, truncations and extensions of
+        // every valid input
+        let mut mutated = Vec::new();
+        for valid in &corpus {
+            for i in 0..valid.len() {
+                for replacement in ["g", "-", "{", "}", " ", "A", "0", "é"] {
+                    if valid.is_char_boundary(i) && valid.is_char_boundary(i + 1) {
+                        let mut candidate = valid.clone();
+                        candidate.replace_range(i..i + 1, replacement);
+                        mutated.push(candidate);
+                    }
+                }
+                mutated.push(valid[..i].to_string());
+            }
+            mutated.push(format!("{valid}0"));
+            mutated.push(format!(" {valid}\n"));
+            mutated.push(format!("URN:UUID:{valid}"));
+        }
+        corpus.extend(mutated);
+
+        for input in &corpus {
+            assert_eq!(is_valid_uuid(input), parse_uuid(input).is_ok(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_strict_validity_checks() {
+        assert!(is_valid_uuid_simple("550e8400e29b41d4a716446655440000"));
+        assert!(!is_valid_uuid_simple(
+            "550e8400-e29b-41d4-a716-446655440000"
+        ));
+        assert!(!is_valid_uuid_simple(" 550e8400e29b41d4a716446655440000"));
+
+        assert!(is_valid_uuid_hyphenated(
+            "550E8400-E29B-41D4-A716-446655440000"
+        ));
+        assert!(!is_valid_uuid_hyphenated(
+            "{550e8400-e29b-41d4-a716-446655440000}"
+        ));
+        assert!(!is_valid_uuid_hyphenated(
+            "550e8400e-29b-41d4-a716-446655440000"
+        ));
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let uuid = parse_uuid("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(from_slice(uuid.as_bytes()).unwrap(), uuid);
+
+        for len in [0, 15, 17, 36] {
+            let err = from_slice(&vec![0; len]).unwrap_err();
+            assert!(matches!(err, ParseError::InvalidByteLength { found } if found == len));
+            assert_eq!(
+                err.to_string(),
+                format!("expected 16 UUID bytes, found {len}")
+            );
+        }
+    }
+
     #[test]
     fn test_parse_invalid_uuid() {
         let uuid_str = "invalid-uuid-string";
@@ -114,16 +800,82 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_prefixed_input_names_prefix() {
+        for (input, prefix) in [
+            ("order_0194e7a3c5f27c1e8b3d4f5a6b7c8d9e", "order_"),
+            ("order_0194e7a3-c5f2-7c1e-8b3d-4f5a6b7c8d9e", "order_"),
+            (" user:0194e7a3-c5f2-7c1e-8b3d-4f5a6b7c8d9e\n", "user:"),
+            ("tenant:a_0194e7a3c5f27c1e8b3d4f5a6b7c8d9e", "tenant:a_"),
+        ] {
+            let expected = format!(
+                "invalid UUID `{}`: input appears to have a prefix '{prefix}'; strip it or use \
+                 parse_prefixed",
+                input.trim()
+            );
+            let err = parse_uuid(input).unwrap_err();
+            assert!(
+                matches!(
+                    &err,
+                    ParseError::InvalidInput {
+                        reason: InvalidInputReason::Prefixed { prefix: found },
+                        ..
+                    } if found == prefix
+                ),
+                "{err:?}"
+            );
+            assert_eq!(err.to_string(), expected);
+            assert_eq!(parse_encoded(input).unwrap_err().to_string(), expected);
+            assert_eq!(
+                parse_uuid_batch([input])[0]
+                    .as_ref()
+                    .unwrap_err()
+                    .to_string(),
+                expected
+            );
+        }
+
+        let id = format!("{}_0194e7a3c5f27c1e8b3d4f5a6b7c8d9e", "a".repeat(100));
+        assert_eq!(
+            parse_uuid(&id).unwrap_err().to_string(),
+            format!(
+                "invalid UUID `{}...`: input appears to have a prefix '{}_'; strip it or use \
+                 parse_prefixed",
+                "a".repeat(64),
+                "a".repeat(100)
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_other_invalid_input_keeps_parser_error() {
+        for input in [
+            "_0194e7a3c5f27c1e8b3d4f5a6b7c8d9e",
+            "order-0194e7a3c5f27c1e8b3d4f5a6b7c8d9e",
+            "order_0194e7a3c5f27c1e8b3d4f5a6b7c8d9g",
+            "order_",
+        ] {
+            let err = parse_uuid(input).unwrap_err();
+            assert!(!matches!(err, ParseError::InvalidInput { .. }), "{input}");
+        }
+    }
+
     #[test]
     #[cfg(feature = "custom-uuid")]
     fn test_extract_metadata_from_standard_v7() {
         use crate::uuid::UuidGenerator;
 
+        // A v7 UUID whose byte 8 lacks the metadata marker
         let generator = UuidGenerator::v7();
-        let uuid = generator.generate();
+        let mut bytes = *generator.generate_uuid().as_bytes();
+        bytes[8] = 0x80 | (bytes[8] & 0x03);
+        let uuid = Uuid::from_bytes(bytes).to_string();
 
         let (_, extracted) = parse_uuid_with_metadata(&uuid).unwrap();
-        assert!(extracted.is_some(), "v7 UUID should have metadata");
+        assert!(
+            extracted.is_none(),
+            "plain v7 UUID should not have metadata"
+        );
     }
 
     #[test]
@@ -0,0 +1,93 @@
+use alloc::string::String;
+
+use uuid::Uuid;
+
+use super::{
+    format::{UuidFormat, format_uuid},
+    parser::parse_trailing,
+};
+
+/// The nil UUID (all zeros) in `format`
+///
+/// Useful as a placeholder where a column or field needs a UUID-shaped value.
+#[inline]
+pub fn nil(format: UuidFormat) -> String {
+    format_uuid(&Uuid::nil(), format)
+}
+
+/// The max UUID (all ones) in `format`
+#[inline]
+pub fn max(format: UuidFormat) -> String {
+    format_uuid(&Uuid::max(), format)
+}
+
+/// Check whether `input` is the nil UUID in any supported format
+///
+/// A prefix such as `user_` before the UUID is ignored.
+#[inline]
+pub fn is_nil(input: &str) -> bool {
+    parse_trailing(input).is_some_and(|uuid| uuid.is_nil())
+}
+
+/// Check whether `input` is the max UUID in any supported format
+///
+/// A prefix such as `user_` before the UUID is ignored.
+#[inline]
+pub fn is_max(input: &str) -> bool {
+    parse_trailing(input).is_some_and(|uuid| uuid.is_max())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uuid::names::FORMATS;
+
+    #[test]
+    fn test_nil_and_max_strings() {
+        assert_eq!(
+            nil(UuidFormat::Standard),
+            "00000000-0000-0000-0000-000000000000"
+        );
+        assert_eq!(
+            max(UuidFormat::BracedUppercase),
+            "{FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF}"
+        );
+        assert_eq!(max(UuidFormat::Simple), "f".repeat(32));
+    }
+
+    #[test]
+    fn test_is_nil_and_is_max_in_every_format() {
+        // Base62 reads as base58 when parsed without its format
+        for (_, format) in FORMATS
+            .into_iter()
+            .filter(|(_, f)| *f != UuidFormat::Base62)
+        {
+            let nil = nil(format);
+            let max = max(format);
+
+            for prefix in ["", "user_", "cafe"] {
+                let nil = format!("{prefix}{nil}");
+                let max = format!("{prefix}{max}");
+                assert!(is_nil(&nil), "{nil}");
+                assert!(!is_max(&nil), "{nil}");
+                assert!(is_max(&max), "{max}");
+                assert!(!is_nil(&max), "{max}");
+            }
+        }
+        assert!(is_max("URN:UUID:FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_is_nil_rejects_other_input() {
+        use crate::uuid::UuidGenerator;
+
+        let generator = UuidGenerator::v4();
+
+        assert!(!is_nil(&generator.generate()));
+        assert!(!is_max(&generator.generate()));
+        assert!(!is_nil(""));
+        assert!(!is_nil("0000"));
+        assert!(!is_nil("00000000-0000-0000-0000-00000000000"));
+    }
+}
@@ -0,0 +1,311 @@
+use alloc::{string::String, vec::Vec};
+use core::mem;
+
+use uuid::Uuid;
+
+use super::parser::{BatchParseError, ParseError, parse_clean, parse_trailing};
+
+/// Unix timestamp in milliseconds from a v7 UUID, `None` for other versions
+///
+/// Reads the 48-bit big-endian prefix directly, so it works for any v7 UUID
+/// without the `custom-uuid` feature.
+#[inline]
+pub fn extract_v7_timestamp(uuid: &Uuid) -> Option<u64> {
+    (uuid.get_version_num() == 7).then(|| timestamp_prefix(uuid))
+}
+
+/// Unix timestamp in milliseconds from a v1, v6 or v7 UUID
+///
+/// v1 and v6 count 100ns ticks since the Gregorian calendar reform
+/// (1582-10-15), which are converted and truncated to milliseconds so legacy
+/// IDs compare directly with v7 ones. Returns `None` for other versions and
+/// for v1/v6 timestamps before the Unix epoch.
+#[inline]
+pub fn extract_timestamp_any(uuid: &Uuid) -> Option<u64> {
+    match uuid.get_version_num() {
+        1 | 6 => gregorian_ticks(uuid)
+            .checked_sub(GREGORIAN_TO_UNIX_TICKS)
+            .map(|ticks| ticks / TICKS_PER_MILLI),
+        7 => Some(timestamp_prefix(uuid)),
+        _ => None,
+    }
+}
+
+/// Version number of the UUID in `input`, e.g. 1 for a legacy v1 ID
+///
+/// Inputs are cleaned like [`parse_uuid`](super::parse_uuid). Any version is
+/// returned, including 0 for the nil UUID.
+#[inline]
+pub fn uuid_version(input: &str) -> Result<u8, ParseError> {
+    parse_clean(input).map(|uuid| uuid.get_version_num() as u8)
+}
+
+/// Parse `input` and return its v7 timestamp in milliseconds
+///
+/// Inputs are cleaned like [`parse_uuid`](super::parse_uuid). A valid UUID
+/// of another version returns `Ok(None)`.
+#[inline]
+pub fn v7_timestamp_ms(input: &str) -> Result<Option<u64>, ParseError> {
+    parse_clean(input).map(|uuid| extract_v7_timestamp(&uuid))
+}
+
+/// Sort key ordering IDs by their timestamp, for `sort_by_key`
+///
+/// Any prefix before the UUID is ignored, whatever its length. Timestamps
+/// come from [`extract_timestamp_any`], so legacy v1/v6 IDs interleave with
+/// v7 ones. IDs without a timestamp get `u64::MAX` and so sort last.
+/// Malformed input is an error rather than a silently misplaced key.
+#[inline]
+pub fn timestamp_key(input: &str) -> Result<u64, ParseError> {
+    let uuid = parse_unprefixed(input)?;
+    Ok(extract_timestamp_any(&uuid).unwrap_or(u64::MAX))
+}
+
+/// Sort prefixed IDs chronologically by their v1, v6 or v7 timestamp
+///
+/// Ties within a millisecond are broken by the UUID itself, which keeps
+/// monotonic generators in generation order. IDs without a timestamp go
+/// last, ordered by their full string. On a malformed ID nothing is
+/// reordered and its index is returned.
+pub fn sort_by_timestamp(ids: &mut [String]) -> Result<(), BatchParseError> {
+    let mut keys = ids
+        .iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let uuid = parse_unprefixed(id).map_err(|source| BatchParseError { index, source })?;
+            let timestamp = extract_timestamp_any(&uuid);
+            Ok((
+                timestamp.unwrap_or(u64::MAX),
+                timestamp.map(|_| uuid),
+                index,
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    keys.sort_by(|a, b| {
+        (a.0, a.1)
+            .cmp(&(b.0, b.1))
+            .then_with(|| ids[a.2].cmp(&ids[b.2]))
+    });
+
+    let sorted: Vec<String> = keys
+        .iter()
+        .map(|&(_, _, index)| mem::take(&mut ids[index]))
+        .collect();
+    for (slot, id) in ids.iter_mut().zip(sorted) {
+        *slot = id;
+    }
+    Ok(())
+}
+
+fn parse_unprefixed(input: &str) -> Result<Uuid, ParseError> {
+    parse_trailing(input).map_or_else(|| parse_clean(input), Ok)
+}
+
+/// 100ns ticks from 1582-10-15 to 1970-01-01
+const GREGORIAN_TO_UNIX_TICKS: u64 = 0x01B2_1DD2_1381_4000;
+const TICKS_PER_MILLI: u64 = 10_000;
+
+/// The 60-bit tick count of a v1 or v6 UUID
+///
+/// v1 stores it low field first (`time_low`, `time_mid`, `time_hi`), v6
+/// high field first so the bytes sort by time.
+fn gregorian_ticks(uuid: &Uuid) -> u64 {
+    let bytes = uuid.as_bytes();
+    let time_low = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64;
+    let time_mid = u16::from_be_bytes([bytes[4], bytes[5]]) as u64;
+    let time_hi = (u16::from_be_bytes([bytes[6], bytes[7]]) & 0x0FFF) as u64;
+
+    if uuid.get_version_num() == 6 {
+        time_low << 28 | time_mid << 12 | time_hi
+    } else {
+        time_hi << 48 | time_mid << 32 | time_low
+    }
+}
+
+/// The 48-bit millisecond prefix shared by v7 and the metadata layout
+#[inline]
+pub(crate) fn timestamp_prefix(uuid: &Uuid) -> u64 {
+    let bytes = uuid.as_bytes();
+    u64::from_be_bytes([
+        0, 0, bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5],
+    ])
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use uuid::Builder;
+
+    use super::*;
+    use crate::uuid::{UuidFormat, UuidGenerator};
+
+    const NODE: [u8; 6] = [1, 2, 3, 4, 5, 6];
+
+    fn ticks_at(millis: u64) -> u64 {
+        millis * TICKS_PER_MILLI + GREGORIAN_TO_UNIX_TICKS
+    }
+
+    fn v1_at(millis: u64) -> Uuid {
+        Builder::from_gregorian_timestamp(ticks_at(millis), 7, &NODE).into_uuid()
+    }
+
+    fn v6_at(millis: u64) -> Uuid {
+        Builder::from_sorted_gregorian_timestamp(ticks_at(millis), 7, &NODE).into_uuid()
+    }
+
+    fn now_millis() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    #[test]
+    fn test_extract_v7_timestamp_close_to_now() {
+        let before = now_millis();
+        let uuid = UuidGenerator::v7().generate_uuid();
+        let after = now_millis();
+
+        let timestamp = extract_v7_timestamp(&uuid).unwrap();
+        assert!(
+            (before..=after).contains(&timestamp),
+            "{before} <= {timestamp} <= {after}"
+        );
+    }
+
+    #[test]
+    fn test_extract_v7_timestamp_other_versions() {
+        assert_eq!(extract_v7_timestamp(&Uuid::new_v4()), None);
+        assert_eq!(extract_v7_timestamp(&Uuid::nil()), None);
+        assert_eq!(extract_v7_timestamp(&Uuid::max()), None);
+    }
+
+    #[test]
+    fn test_v7_timestamp_ms_from_string() {
+        let before = now_millis();
+        let id = UuidGenerator::v7().generate();
+        let after = now_millis();
+
+        let timestamp = v7_timestamp_ms(&id).unwrap().unwrap();
+        assert!((before..=after).contains(&timestamp));
+
+        assert_eq!(
+            v7_timestamp_ms(&UuidGenerator::v4().generate()).unwrap(),
+            None
+        );
+        assert!(v7_timestamp_ms("not-a-uuid").is_err());
+    }
+
+    #[test]
+    fn test_extract_timestamp_any_versions() {
+        let millis = 1_700_000_000_123;
+        let v7 = UuidGenerator::v7()
+            .with_clock(move || millis)
+            .generate_uuid();
+
+        assert_eq!(extract_timestamp_any(&v1_at(millis)), Some(millis));
+        assert_eq!(extract_timestamp_any(&v6_at(millis)), Some(millis));
+        assert_eq!(extract_timestamp_any(&v7), Some(millis));
+        assert_eq!(extract_timestamp_any(&Uuid::new_v4()), None);
+        assert_eq!(extract_timestamp_any(&Uuid::nil()), None);
+    }
+
+    #[test]
+    fn test_extract_timestamp_any_matches_uuid_crate() {
+        for uuid in [v1_at(1_234_567_890_999), v6_at(86_400_000), v1_at(0)] {
+            let (seconds, nanos) = uuid.get_timestamp().unwrap().to_unix();
+            let expected = seconds * 1_000 + nanos as u64 / 1_000_000;
+            assert_eq!(extract_timestamp_any(&uuid), Some(expected), "{uuid}");
+        }
+
+        // Sub-millisecond ticks truncate
+        let uuid = Builder::from_gregorian_timestamp(ticks_at(5) + 9_999, 0, &NODE).into_uuid();
+        assert_eq!(extract_timestamp_any(&uuid), Some(5));
+    }
+
+    #[test]
+    fn test_extract_timestamp_any_before_unix_epoch() {
+        let uuid =
+            Builder::from_gregorian_timestamp(GREGORIAN_TO_UNIX_TICKS - 1, 0, &NODE).into_uuid();
+        assert_eq!(extract_timestamp_any(&uuid), None);
+    }
+
+    #[test]
+    fn test_uuid_version() {
+        assert_eq!(uuid_version(&v1_at(0).to_string()).unwrap(), 1);
+        assert_eq!(uuid_version(&v6_at(0).simple().to_string()).unwrap(), 6);
+        assert_eq!(uuid_version(&UuidGenerator::v7().generate()).unwrap(), 7);
+        assert_eq!(uuid_version(&format!("{{{}}}", Uuid::new_v4())).unwrap(), 4);
+        assert_eq!(uuid_version(&Uuid::nil().to_string()).unwrap(), 0);
+        assert!(uuid_version("not-a-uuid").is_err());
+    }
+
+    fn v7_at(millis: u64, prefix: &str, format: UuidFormat) -> String {
+        UuidGenerator::v7()
+            .with_clock(move || millis)
+            .with_format(format)
+            .with_prefix(prefix)
+            .generate()
+    }
+
+    #[test]
+    fn test_timestamp_key_ignores_prefix() {
+        let id = v7_at(1_000, "order_item_", UuidFormat::Simple);
+        assert_eq!(timestamp_key(&id).unwrap(), 1_000);
+        assert_eq!(
+            timestamp_key(&UuidGenerator::v4().with_prefix("u_").generate()).unwrap(),
+            u64::MAX
+        );
+        assert!(timestamp_key("u_not-a-uuid").is_err());
+    }
+
+    #[test]
+    fn test_sort_by_timestamp_mixed_prefixes_and_formats() {
+        let first = v7_at(1_000, "order_item_", UuidFormat::Simple);
+        let second = v7_at(2_000, "u_", UuidFormat::Standard);
+        let third = v7_at(3_000, "", UuidFormat::BracedUppercase);
+        let v4 = UuidGenerator::v4().with_prefix("a_").generate();
+
+        let mut ids = vec![v4.clone(), third.clone(), first.clone(), second.clone()];
+        sort_by_timestamp(&mut ids).unwrap();
+
+        assert_eq!(ids, [first, second, third, v4]);
+    }
+
+    #[test]
+    fn test_sort_by_timestamp_interleaves_legacy_versions() {
+        let v1 = format!("u_{}", v1_at(1_500));
+        let v6 = format!("u_{}", v6_at(2_500));
+        let first = v7_at(1_000, "u_", UuidFormat::Standard);
+        let second = v7_at(2_000, "u_", UuidFormat::Standard);
+        let v4 = UuidGenerator::v4().with_prefix("u_").generate();
+
+        let mut ids = vec![
+            v4.clone(),
+            v6.clone(),
+            second.clone(),
+            v1.clone(),
+            first.clone(),
+        ];
+        sort_by_timestamp(&mut ids).unwrap();
+
+        assert_eq!(ids, [first, v1.clone(), second, v6, v4]);
+        assert_eq!(timestamp_key(&v1).unwrap(), 1_500);
+    }
+
+    #[test]
+    fn test_sort_by_timestamp_reports_malformed() {
+        let mut ids = vec![
+            v7_at(2_000, "u_", UuidFormat::Standard),
+            "u_garbage".to_string(),
+            v7_at(1_000, "u_", UuidFormat::Standard),
+        ];
+        let before = ids.clone();
+
+        let err = sort_by_timestamp(&mut ids).unwrap_err();
+        assert_eq!(err.index, 1);
+        assert_eq!(ids, before);
+    }
+}
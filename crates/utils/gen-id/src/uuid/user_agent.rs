@@ -0,0 +1,132 @@
+use super::metadata::{OsType, saturate_os_version};
+
+/// OS type and version from a browser user agent string
+///
+/// Handles the formats used by Chrome, Safari and Firefox. Versions saturate
+/// at the 5-bit major and 4-bit minor of the UUID layout, and are `(0, 0)`
+/// when the user agent names the OS without a version.
+pub(crate) fn parse_os(user_agent: &str) -> (OsType, (u8, u8)) {
+    // iOS user agents also say "like Mac OS X" and Android ones "Linux", so
+    // they are checked first
+    if let Some(rest) = after(user_agent, "iPhone OS ").or_else(|| after(user_agent, "CPU OS ")) {
+        return (OsType::IOS, version(rest));
+    }
+    if let Some(rest) = after(user_agent, "Android") {
+        return (OsType::Android, version(rest.trim_start()));
+    }
+    if let Some(rest) = after(user_agent, "Windows NT ") {
+        return (OsType::Windows, version(rest));
+    }
+    if let Some(rest) = after(user_agent, "Mac OS X") {
+        return (OsType::MacOS, version(rest.trim_start()));
+    }
+
+    let os_type = if user_agent.contains("FreeBSD") {
+        OsType::FreeBsd
+    } else if user_agent.contains("OpenBSD") {
+        OsType::OpenBsd
+    } else if user_agent.contains("Linux") || user_agent.contains("CrOS") {
+        OsType::Linux
+    } else {
+        OsType::Unknown
+    };
+    (os_type, (0, 0))
+}
+
+fn after<'a>(haystack: &'a str, needle: &str) -> Option<&'a str> {
+    haystack
+        .find(needle)
+        .map(|index| &haystack[index + needle.len()..])
+}
+
+/// Leading `major.minor` or `major_minor`, saturated to the layout bounds
+fn version(input: &str) -> (u8, u8) {
+    let mut parts = input
+        .split(|ch: char| !ch.is_ascii_digit())
+        .take(2)
+        .map(|part| part.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    saturate_os_version(major, minor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_os() {
+        let cases = [
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/124.0.0.0 Safari/537.36",
+                OsType::Windows,
+                (10, 0),
+            ),
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, \
+                 like Gecko) Version/17.4 Safari/605.1.15",
+                OsType::MacOS,
+                (10, 15),
+            ),
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 14.4; rv:125.0) Gecko/20100101 \
+                 Firefox/125.0",
+                OsType::MacOS,
+                (14, 4),
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4_1 like Mac OS X) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+                OsType::IOS,
+                (17, 4),
+            ),
+            (
+                "Mozilla/5.0 (iPad; CPU OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like \
+                 Gecko) CriOS/124.0.6367.88 Mobile/15E148 Safari/604.1",
+                OsType::IOS,
+                (16, 6),
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/124.0.0.0 Mobile Safari/537.36",
+                OsType::Android,
+                (14, 0),
+            ),
+            (
+                "Mozilla/5.0 (Android 13; Mobile; rv:125.0) Gecko/125.0 Firefox/125.0",
+                OsType::Android,
+                (13, 0),
+            ),
+            (
+                "Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0",
+                OsType::Linux,
+                (0, 0),
+            ),
+            (
+                "Mozilla/5.0 (X11; CrOS x86_64 14541.0.0) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/124.0.0.0 Safari/537.36",
+                OsType::Linux,
+                (0, 0),
+            ),
+            (
+                "Mozilla/5.0 (X11; FreeBSD amd64; rv:125.0) Gecko/20100101 Firefox/125.0",
+                OsType::FreeBsd,
+                (0, 0),
+            ),
+            ("curl/8.5.0", OsType::Unknown, (0, 0)),
+            ("", OsType::Unknown, (0, 0)),
+        ];
+
+        for (user_agent, os_type, os_version) in cases {
+            assert_eq!(parse_os(user_agent), (os_type, os_version), "{user_agent}");
+        }
+    }
+
+    #[test]
+    fn test_version_saturates() {
+        assert_eq!(version("99_99"), (31, 15));
+        assert_eq!(version("12"), (12, 0));
+        assert_eq!(version("x"), (0, 0));
+    }
+}
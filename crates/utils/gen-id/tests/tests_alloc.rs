@@ -0,0 +1,71 @@
+//! Counts heap allocations per thread, in its own test binary so the global
+//! allocator does not wrap the unit tests:
+//!
+//! ```sh
+//! cargo test -p gen-id --test tests_alloc
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use gen_id::{AlphabetPreset, NanoIdGenerator, RngMode};
+use rand_chacha::ChaCha8Rng;
+use rand_core::SeedableRng;
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Allocations `f` makes on this thread
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn test_generate_fixed_does_not_allocate() {
+    let generators = [
+        NanoIdGenerator::new(),
+        NanoIdGenerator::with_preset(AlphabetPreset::UrlSafe).with_rng_mode(RngMode::Fast),
+        NanoIdGenerator::with_alphabet("ACGT").unwrap(),
+        NanoIdGenerator::new().with_rng(ChaCha8Rng::seed_from_u64(7)),
+    ];
+    for generator in &generators {
+        // The first draw sets up the thread-local RNG
+        generator.generate_fixed::<21>();
+
+        let count = allocations(|| {
+            for _ in 0..1_000 {
+                std::hint::black_box(generator.generate_fixed::<21>());
+            }
+        });
+        assert_eq!(count, 0, "{generator:?}");
+    }
+}
+
+#[test]
+fn test_generate_allocates() {
+    // Checks the counter itself, the heap API allocates each ID
+    let generator = NanoIdGenerator::new();
+    generator.generate();
+    assert_eq!(allocations(|| drop(generator.generate())), 1);
+}
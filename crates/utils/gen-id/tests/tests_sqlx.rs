@@ -0,0 +1,56 @@
+use gen_id::{DbUuid, UuidFormat, UuidGenerator, from_slice, parse_prefixed};
+use sqlx::{Row, SqlitePool};
+
+async fn memory_pool() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::query("CREATE TABLE orders (id BLOB PRIMARY KEY NOT NULL)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn test_sqlite_round_trip() {
+    let pool = memory_pool().await;
+    let generator = UuidGenerator::v7().with_prefix("order_");
+    let uuid = generator.generate_uuid();
+
+    sqlx::query("INSERT INTO orders (id) VALUES (?)")
+        .bind(DbUuid(uuid))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let stored: DbUuid = sqlx::query_scalar("SELECT id FROM orders")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, DbUuid(uuid));
+
+    // The prefix lives in the application layer only
+    let id = generator.format(&stored.into());
+    assert_eq!(parse_prefixed("order_", &id).unwrap(), uuid);
+}
+
+#[tokio::test]
+async fn test_sqlite_stores_16_bytes() {
+    let pool = memory_pool().await;
+    let uuid = UuidGenerator::v7()
+        .with_format(UuidFormat::Simple)
+        .generate_uuid();
+
+    sqlx::query("INSERT INTO orders (id) VALUES (?)")
+        .bind(DbUuid(uuid))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let row = sqlx::query("SELECT id, length(id) AS len FROM orders")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<i64, _>("len"), 16);
+    let bytes: Vec<u8> = row.get("id");
+    assert_eq!(from_slice(&bytes).unwrap(), uuid);
+}
@@ -0,0 +1,51 @@
+//! Runs on `wasm32-unknown-unknown` under `wasm-bindgen-test-runner`:
+//!
+//! ```sh
+//! cargo test -p gen-id --target wasm32-unknown-unknown --features wasm,nanoid --test tests_wasm
+//! ```
+#![cfg(target_arch = "wasm32")]
+
+use gen_id::{UuidFormat, UuidGenerator, extract_v7_timestamp, parse_uuid};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_generate_and_parse() {
+    for generator in [UuidGenerator::v4(), UuidGenerator::v7()] {
+        let id = generator.generate();
+        assert_eq!(parse_uuid(&id).unwrap().to_string(), id);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_generate_batch() {
+    let generator = UuidGenerator::v7().with_format(UuidFormat::Simple);
+    let batch = generator.generate_batch(100);
+    assert_eq!(batch.len(), 100);
+    for pair in batch.windows(2) {
+        assert_ne!(pair[0], pair[1]);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_v7_uses_browser_clock() {
+    let now = js_sys::Date::now() as u64;
+    for generator in [
+        UuidGenerator::v7(),
+        UuidGenerator::v7().with_monotonic(true),
+    ] {
+        let uuid = generator.generate_uuid();
+        let millis = extract_v7_timestamp(&uuid).unwrap();
+        assert!(millis.abs_diff(now) < 60_000, "{millis} vs {now}");
+    }
+}
+
+#[wasm_bindgen_test]
+#[cfg(feature = "nanoid")]
+fn test_nanoid() {
+    let generator = gen_id::NanoIdGenerator::new();
+    assert_eq!(generator.generate().len(), 12);
+    assert_eq!(
+        generator.generate_batch_with(10, Some("n_"), None).len(),
+        10
+    );
+}
@@ -1,9 +1,23 @@
-use std::{io::Read, path::Path, sync::Arc};
+use std::{collections::HashMap, io::Read, net::SocketAddr, path::Path, sync::Arc};
 
 #[cfg(feature = "tracing")]
-use crate::middleware::tracing_middleware;
+use crate::middleware::{
+    tracing::{ConnectTimingLayer, ConnectionTimingMiddleware},
+    tracing_middleware,
+};
+use crate::{
+    dns::{FALLBACK_DELAY, IpPreference, PreferenceResolver},
+    error::ConfigError,
+    middleware::{
+        headers::DefaultHeadersMiddleware,
+        retry::{ErrorClassifier, RetryMiddleware},
+        url_guard::{GuardResolver, UrlGuard, UrlGuardConfig, UrlGuardMiddleware},
+    },
+    scrub::Scrubber,
+    shared::{DeriveOptions, SharedClient},
+};
 use reqwest::Client;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Extension};
 use rustls::{ClientConfig, RootCertStore};
 use rustls_pki_types::CertificateDer;
 use zeroize::Zeroize;
@@ -42,10 +56,31 @@ pub struct HttpClientBuilderConfig {
     pub timeout: Option<std::time::Duration>,
     pub connect_timeout: Option<std::time::Duration>,
     pub max_idle_per_host: Option<usize>,
+    /// `SO_KEEPALIVE` idle time for new sockets, keeping NAT mappings of
+    /// pooled connections alive. `None` keeps reqwest's default of 15s.
+    /// Must be longer than zero.
+    pub tcp_keepalive: Option<std::time::Duration>,
+    /// Address family tried first for dual-stack hosts. Anything but
+    /// [`IpPreference::Auto`] replaces reqwest's resolver with the system
+    /// resolver plus reordering, and also reorders `dns_overrides`. The other
+    /// family is only tried if `connect_timeout` outlasts reqwest's fixed
+    /// 300ms fallback delay.
+    pub ip_preference: Option<IpPreference>,
     pub default_headers: Option<reqwest::header::HeaderMap>,
     pub compressions: Option<Vec<CompressionType>>,
     pub retry_enabled: Option<bool>,
     pub max_retries: Option<u32>,
+    /// Decides which failures are retried, replacing [`DefaultClassifier`].
+    ///
+    /// [`DefaultClassifier`]: crate::middleware::DefaultClassifier
+    pub retry_classifier: Option<Arc<dyn ErrorClassifier>>,
+    /// Reject requests to disallowed hosts and private networks.
+    pub url_guard: Option<UrlGuardConfig>,
+    /// Static host to address mappings, used by both reqwest and the URL guard.
+    pub dns_overrides: Option<HashMap<String, Vec<SocketAddr>>>,
+    /// Query parameter and header names redacted from spans, replacing
+    /// [`DEFAULT_SENSITIVE_NAMES`](crate::scrub::DEFAULT_SENSITIVE_NAMES).
+    pub sensitive_names: Option<Vec<String>>,
 }
 
 impl Default for HttpClientBuilderConfig {
@@ -54,6 +89,8 @@ impl Default for HttpClientBuilderConfig {
             timeout: Some(std::time::Duration::from_secs(10)),
             connect_timeout: Some(std::time::Duration::from_secs(5)),
             max_idle_per_host: Some(8),
+            tcp_keepalive: None,
+            ip_preference: None,
             default_headers: Some({
                 let mut headers = reqwest::header::HeaderMap::new();
                 headers.insert(
@@ -65,13 +102,43 @@ impl Default for HttpClientBuilderConfig {
             compressions: Some(vec![CompressionType::Gzip]),
             retry_enabled: Some(true),
             max_retries: Some(3),
+            retry_classifier: None,
+            url_guard: None,
+            dns_overrides: None,
+            sensitive_names: None,
         }
     }
 }
+
+impl HttpClientBuilderConfig {
+    /// Check for option combinations the client cannot honour.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.tcp_keepalive.is_some_and(|idle| idle.is_zero()) {
+            return Err(ConfigError::ZeroKeepalive);
+        }
+
+        let ip_preference = self.ip_preference.unwrap_or_default();
+        if let Some(connect_timeout) = self.connect_timeout
+            && ip_preference != IpPreference::Auto
+            && connect_timeout <= FALLBACK_DELAY
+        {
+            return Err(ConfigError::NoFamilyFallback {
+                connect_timeout,
+                ip_preference,
+            });
+        }
+
+        Ok(())
+    }
+}
+
 pub struct HttpClientBuilder {
     base_config: HttpClientBuilderConfig,
     middleware: Vec<Arc<dyn reqwest_middleware::Middleware>>,
     tls_config: Option<ClientConfig>,
+    url_guard: Option<Arc<UrlGuard>>,
+    #[cfg(feature = "tracing")]
+    connect_timing: bool,
 }
 
 impl HttpClientBuilder {
@@ -82,31 +149,46 @@ impl HttpClientBuilder {
             merged.timeout = custom.timeout;
             merged.connect_timeout = custom.connect_timeout;
             merged.max_idle_per_host = custom.max_idle_per_host;
+            merged.tcp_keepalive = custom.tcp_keepalive;
+            merged.ip_preference = custom.ip_preference;
             merged.default_headers = custom.default_headers;
             merged.compressions = custom.compressions;
             merged.retry_enabled = custom.retry_enabled;
             merged.max_retries = custom.max_retries;
+            merged.retry_classifier = custom.retry_classifier;
+            merged.url_guard = custom.url_guard;
+            merged.dns_overrides = custom.dns_overrides;
+            merged.sensitive_names = custom.sensitive_names;
         }
 
-        let mut middleware = Vec::new();
-
-        // Add retry middleware if enabled
-        if matches!(merged.retry_enabled, Some(true)) {
-            middleware.push(Arc::new(crate::middleware::retry::retry_middleware(
-                merged.max_retries.unwrap_or(3),
-            )) as Arc<dyn reqwest_middleware::Middleware>);
-        }
+        let url_guard = merged.url_guard.clone().map(|config| {
+            Arc::new(UrlGuard::new(
+                config,
+                merged.dns_overrides.clone().unwrap_or_default(),
+                merged.ip_preference.unwrap_or_default(),
+            ))
+        });
 
         Self {
             base_config: merged,
-            middleware,
+            middleware: Vec::new(),
             tls_config: None,
+            url_guard,
+            #[cfg(feature = "tracing")]
+            connect_timing: false,
         }
     }
 
+    /// Add the tracing middleware along with per-request connection timing.
+    ///
+    /// Spans get `http.ttfb_ms`, `http.conn.reused` and, for new connections,
+    /// `http.connect_ms`. The same values are attached to each response as a
+    /// [`RequestTiming`](crate::middleware::RequestTiming) extension.
     #[cfg(feature = "tracing")]
     pub fn with_tracing(mut self) -> Self {
         self.middleware.push(Arc::new(tracing_middleware()));
+        self.middleware.push(Arc::new(ConnectionTimingMiddleware));
+        self.connect_timing = true;
         self
     }
 
@@ -170,7 +252,80 @@ impl HttpClientBuilder {
         self.with_pinned_certs(all_certs)
     }
 
+    /// Build the client. Unsupported option combinations are logged as a
+    /// warning with the `tracing` feature; use [`Self::try_build`] to reject
+    /// them instead.
     pub fn build(self) -> ClientWithMiddleware {
+        self.build_with_terminal(None)
+    }
+
+    /// Build the client after [`HttpClientBuilderConfig::validate`] passes.
+    pub fn try_build(self) -> Result<ClientWithMiddleware, ConfigError> {
+        self.base_config.validate()?;
+        Ok(self.build())
+    }
+
+    /// Build a client whose requests are answered by `mock` instead of the
+    /// network. The mock sits below all middleware and honours the
+    /// configured timeout and default headers.
+    #[cfg(feature = "test-util")]
+    pub fn build_with_mock(self, mock: crate::mock::MockTransport) -> ClientWithMiddleware {
+        let terminal = mock.into_middleware(
+            self.base_config.timeout,
+            self.base_config.default_headers.clone(),
+        );
+        self.build_with_terminal(Some(Arc::new(terminal)))
+    }
+
+    /// Build the reqwest client once and return a [`SharedClient`] that
+    /// derives variant clients sharing its connection pool.
+    ///
+    /// Default headers are applied by middleware in derived clients so each
+    /// variant can change them.
+    pub fn build_shared(mut self) -> SharedClient {
+        let template = DeriveOptions {
+            default_headers: self.base_config.default_headers.take().unwrap_or_default(),
+            retry_enabled: matches!(self.base_config.retry_enabled, Some(true)),
+            max_retries: self.base_config.max_retries.unwrap_or(3),
+            retry_classifier: self.base_config.retry_classifier.clone(),
+            middleware: std::mem::take(&mut self.middleware),
+        };
+        let url_guard = self.url_guard.clone().map(UrlGuardMiddleware::from_guard);
+        let sensitive_names = self.base_config.sensitive_names.clone();
+
+        SharedClient::new(self.build_client(), template, url_guard, sensitive_names)
+    }
+
+    fn build_with_terminal(
+        mut self,
+        terminal: Option<Arc<dyn reqwest_middleware::Middleware>>,
+    ) -> ClientWithMiddleware {
+        let retry = retry_middleware(
+            matches!(self.base_config.retry_enabled, Some(true)),
+            self.base_config.max_retries.unwrap_or(3),
+            self.base_config.retry_classifier.clone(),
+        );
+        let url_guard = self.url_guard.clone().map(UrlGuardMiddleware::from_guard);
+        let sensitive_names = self.base_config.sensitive_names.clone();
+        let mut middleware = std::mem::take(&mut self.middleware);
+        middleware.extend(terminal);
+
+        middleware_stack(
+            self.build_client(),
+            retry,
+            url_guard,
+            None,
+            middleware,
+            sensitive_names,
+        )
+    }
+
+    fn build_client(self) -> Client {
+        #[cfg(feature = "tracing")]
+        if let Err(err) = self.base_config.validate() {
+            tracing::warn!(error = %err, "unsupported HTTP client configuration");
+        }
+
         let mut base = Client::builder();
 
         // Apply base configuration
@@ -209,21 +364,89 @@ impl HttpClientBuilder {
             }
         }
 
+        if let Some(tcp_keepalive) = self.base_config.tcp_keepalive {
+            base = base.tcp_keepalive(tcp_keepalive);
+        }
+
+        let ip_preference = self.base_config.ip_preference.unwrap_or_default();
+        if let Some(guard) = self.url_guard {
+            // The guard resolver applies the DNS overrides and IP preference
+            // itself, as reqwest's overrides would skip it
+            base = base
+                .dns_resolver(Arc::new(GuardResolver::new(guard.clone())))
+                .redirect(guard.redirect_policy());
+        } else {
+            if ip_preference != IpPreference::Auto {
+                base = base.dns_resolver(Arc::new(PreferenceResolver::new(ip_preference)));
+            }
+
+            if let Some(overrides) = &self.base_config.dns_overrides {
+                for (host, addrs) in overrides {
+                    let mut addrs = addrs.clone();
+                    ip_preference.order(&mut addrs);
+                    base = base.resolve_to_addrs(host, &addrs);
+                }
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        if self.connect_timing {
+            base = base.connector_layer(ConnectTimingLayer);
+        }
+
         // Apply TLS config if present
         if let Some(tls_config) = self.tls_config {
             base = base.use_preconfigured_tls(tls_config);
         }
 
-        let client = base.build().unwrap_or_else(|_| {
+        base.build().unwrap_or_else(|_| {
             panic!("reqwest client builder failed - this should be unreachable in reqwest 0.12+")
-        });
+        })
+    }
+}
 
-        // Build middleware chain
-        let mut builder = ClientBuilder::new(client);
-        for middleware in self.middleware {
-            builder = builder.with_arc(middleware);
-        }
+pub(crate) fn retry_middleware(
+    enabled: bool,
+    max_retries: u32,
+    classifier: Option<Arc<dyn ErrorClassifier>>,
+) -> Option<RetryMiddleware> {
+    if !enabled {
+        return None;
+    }
+
+    let retry = RetryMiddleware::new(max_retries);
+    Some(match classifier {
+        Some(classifier) => retry.with_classifier(classifier),
+        None => retry,
+    })
+}
 
-        builder.build()
+/// Assemble the middleware chain: retries outermost, then the URL guard,
+/// injected default headers and the remaining middleware in order.
+pub(crate) fn middleware_stack(
+    client: Client,
+    retry: Option<RetryMiddleware>,
+    url_guard: Option<UrlGuardMiddleware>,
+    default_headers: Option<DefaultHeadersMiddleware>,
+    middleware: Vec<Arc<dyn reqwest_middleware::Middleware>>,
+    sensitive_names: Option<Vec<String>>,
+) -> ClientWithMiddleware {
+    let mut builder = ClientBuilder::new(client);
+    if let Some(names) = sensitive_names {
+        builder = builder.with_init(Extension(Scrubber::new(names)));
+    }
+    if let Some(retry) = retry {
+        builder = builder.with(retry);
+    }
+    if let Some(url_guard) = url_guard {
+        builder = builder.with(url_guard);
     }
+    if let Some(default_headers) = default_headers {
+        builder = builder.with(default_headers);
+    }
+    for middleware in middleware {
+        builder = builder.with_arc(middleware);
+    }
+
+    builder.build()
 }
@@ -0,0 +1,61 @@
+//! Address family preference for dual-stack hosts.
+//!
+//! reqwest races the first resolved address family against the other one
+//! (happy eyeballs), starting the fallback after a fixed 300ms that it does
+//! not expose. [`IpPreference`] decides which family goes first by
+//! reordering resolved addresses, which is all reqwest lets us influence.
+
+use std::{net::SocketAddr, time::Duration};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// Delay before reqwest starts connecting to the other address family.
+pub(crate) const FALLBACK_DELAY: Duration = Duration::from_millis(300);
+
+/// Which address family to try first when a host resolves to both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IpPreference {
+    /// Keep the resolver's order.
+    #[default]
+    Auto,
+    PreferIpv4,
+    PreferIpv6,
+}
+
+impl IpPreference {
+    /// Move addresses of the preferred family to the front, keeping the
+    /// relative order within each family.
+    pub fn order(self, addrs: &mut [SocketAddr]) {
+        match self {
+            Self::Auto => {}
+            Self::PreferIpv4 => addrs.sort_by_key(|a| !a.is_ipv4()),
+            Self::PreferIpv6 => addrs.sort_by_key(|a| !a.is_ipv6()),
+        }
+    }
+}
+
+/// System resolver applying an [`IpPreference`] to its results.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PreferenceResolver {
+    preference: IpPreference,
+}
+
+impl PreferenceResolver {
+    pub(crate) fn new(preference: IpPreference) -> Self {
+        Self { preference }
+    }
+}
+
+impl Resolve for PreferenceResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let preference = self.preference;
+        Box::pin(async move {
+            // Port 0 is replaced by reqwest with the URL's port
+            let mut addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            preference.order(&mut addrs);
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
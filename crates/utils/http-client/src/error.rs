@@ -0,0 +1,103 @@
+use std::{net::IpAddr, time::Duration};
+
+use reqwest::{StatusCode, Url};
+use thiserror::Error;
+
+use crate::{dns::IpPreference, scrub::Scrubber};
+
+/// Error returned by the higher-level helpers built on top of the client.
+///
+/// URLs are scrubbed with [`Scrubber::default`] in messages and in wrapped
+/// reqwest errors, so the error can be logged as is.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum HttpError {
+    #[error("request failed")]
+    #[non_exhaustive]
+    Request {
+        #[source]
+        source: reqwest_middleware::Error,
+    },
+
+    #[error("unexpected status {status} from {}", scrubbed(.url))]
+    #[non_exhaustive]
+    Status { status: StatusCode, url: Url },
+
+    #[error("failed to decode response body from {}", scrubbed(.url))]
+    #[non_exhaustive]
+    Decode {
+        url: Url,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("request to {} cannot be cloned for the next page", scrubbed(.url))]
+    #[non_exhaustive]
+    NotCloneable { url: Url },
+
+    #[error("pagination loop detected: {} was already requested", scrubbed(.url))]
+    #[non_exhaustive]
+    PaginationLoop { url: Url },
+}
+
+impl From<reqwest_middleware::Error> for HttpError {
+    fn from(mut source: reqwest_middleware::Error) -> Self {
+        Scrubber::default().middleware_error(&mut source);
+        Self::Request { source }
+    }
+}
+
+fn scrubbed(url: &Url) -> Url {
+    Scrubber::default().url(url)
+}
+
+/// Builder option combinations the client cannot honour, returned by
+/// [`HttpClientBuilder::try_build`](crate::HttpClientBuilder::try_build).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum ConfigError {
+    #[error("tcp_keepalive must be longer than zero")]
+    ZeroKeepalive,
+
+    #[error(
+        "connect_timeout of {connect_timeout:?} expires before the other address family \
+         is tried, so ip_preference {ip_preference:?} cannot fall back"
+    )]
+    #[non_exhaustive]
+    NoFamilyFallback {
+        connect_timeout: Duration,
+        ip_preference: IpPreference,
+    },
+}
+
+/// Request refused by [`UrlGuardMiddleware`](crate::middleware::UrlGuardMiddleware)
+/// before any connection was attempted.
+#[derive(Debug, Error)]
+#[error("blocked request to {}: {reason}", scrubbed(.url))]
+#[non_exhaustive]
+pub struct BlockedDestination {
+    pub url: Url,
+    pub reason: BlockReason,
+}
+
+impl BlockedDestination {
+    pub fn new(url: Url, reason: BlockReason) -> Self {
+        Self { url, reason }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum BlockReason {
+    #[error("host {0} is not in the allowlist")]
+    HostNotAllowed(String),
+
+    #[error("address {0} is in a denied network")]
+    DeniedAddress(IpAddr),
+
+    #[error("URL has no host")]
+    MissingHost,
+
+    #[error("host {0} could not be resolved")]
+    UnresolvedHost(String),
+}
@@ -1,6 +1,18 @@
 pub mod builder;
+pub mod dns;
+pub mod error;
 pub mod middleware;
+#[cfg(feature = "test-util")]
+pub mod mock;
+pub mod pagination;
+pub mod scrub;
+pub mod shared;
 pub use builder::HttpClientBuilder;
+pub use dns::IpPreference;
+pub use error::{BlockReason, BlockedDestination, ConfigError, HttpError};
+pub use pagination::{Page, Paginator, paginate};
+pub use scrub::Scrubber;
+pub use shared::{DeriveOptions, SharedClient};
 
 // Re-exports
 pub use reqwest_middleware::ClientWithMiddleware;
@@ -0,0 +1,36 @@
+use http::Extensions;
+use reqwest::{Request, Response, header::HeaderMap};
+use reqwest_middleware::{Middleware, Next, Result};
+
+/// Adds headers a request does not already carry, like
+/// `reqwest::ClientBuilder::default_headers` but per middleware stack.
+#[derive(Debug, Clone)]
+pub struct DefaultHeadersMiddleware {
+    headers: HeaderMap,
+}
+
+impl DefaultHeadersMiddleware {
+    pub fn new(headers: HeaderMap) -> Self {
+        Self { headers }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for DefaultHeadersMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        for name in self.headers.keys() {
+            if !req.headers().contains_key(name) {
+                for value in self.headers.get_all(name) {
+                    req.headers_mut().append(name, value.clone());
+                }
+            }
+        }
+
+        next.run(req, extensions).await
+    }
+}
@@ -1,7 +1,40 @@
 #[cfg(feature = "tracing")]
 pub mod tracing;
 #[cfg(feature = "tracing")]
-pub use tracing::tracing_middleware;
+pub use tracing::{RequestTiming, tracing_middleware};
+
+pub mod headers;
+pub use headers::DefaultHeadersMiddleware;
 
 pub mod retry;
-pub use retry::default_retry_policy;
+pub use retry::{
+    DefaultClassifier, ErrorClassifier, RetryMiddleware, Retryable, default_retry_policy,
+};
+
+pub mod throttle;
+pub use throttle::{ThrottleBypass, ThrottleHandle, ThrottleMiddleware, ThrottlePolicy};
+
+pub mod url_guard;
+pub use url_guard::{GuardStrictness, IpCidr, UrlGuardConfig, UrlGuardMiddleware};
+
+#[cfg(feature = "vcr")]
+pub mod vcr;
+#[cfg(feature = "vcr")]
+pub use vcr::VcrMiddleware;
+
+use reqwest::{Response, ResponseBuilderExt};
+
+/// Start a response with the same head (status, version, headers, URL and
+/// extensions) as `res`, for middleware that swaps out the body.
+pub(crate) fn response_builder(res: &Response) -> http::response::Builder {
+    let mut builder = http::Response::builder()
+        .status(res.status())
+        .version(res.version());
+    if let Some(extensions) = builder.extensions_mut() {
+        *extensions = res.extensions().clone();
+    }
+    if let Some(headers) = builder.headers_mut() {
+        *headers = res.headers().clone();
+    }
+    builder.url(res.url().clone())
+}
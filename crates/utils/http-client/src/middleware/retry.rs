@@ -1,13 +1,273 @@
-use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use std::{
+    error::Error as StdError,
+    fmt, io,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
+use http::Extensions;
+use reqwest::{
+    Request, Response, StatusCode,
+    header::{HeaderMap, RETRY_AFTER},
+};
+use reqwest_middleware::{Error, Middleware, Next, Result};
+use reqwest_retry::{
+    RetryDecision, RetryError, RetryPolicy, RetryTransientMiddleware, default_on_request_failure,
+    policies::ExponentialBackoff,
+};
+
+use crate::error::BlockedDestination;
+
+const MIN_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+/// Upper bound for both backoff and `Retry-After` delays. Longer rate-limit
+/// delays end the retry loop instead of blocking the caller.
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Retry decision for a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Retryable {
+    /// Retry after the policy's backoff.
+    Transient,
+    /// Give up and return the outcome as is.
+    Fatal,
+    /// Retry after the given delay, typically from a `Retry-After` header.
+    RateLimited(Duration),
+}
+
+/// Decides whether an attempt's outcome should be retried.
+///
+/// Returning `None` means the attempt succeeded. Implemented for closures
+/// with the same signature as [`ErrorClassifier::classify`], which can fall
+/// back to [`DefaultClassifier`] for cases they do not handle.
+pub trait ErrorClassifier: Send + Sync + 'static {
+    fn classify(&self, outcome: &Result<Response>) -> Option<Retryable>;
+}
+
+impl<F> ErrorClassifier for F
+where
+    F: Fn(&Result<Response>) -> Option<Retryable> + Send + Sync + 'static,
+{
+    fn classify(&self, outcome: &Result<Response>) -> Option<Retryable> {
+        self(outcome)
+    }
+}
+
+impl fmt::Debug for dyn ErrorClassifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ErrorClassifier")
+    }
+}
+
+/// Classifier used unless the builder config sets `retry_classifier`.
+///
+/// - Responses: 408, 429 and 5xx are transient; 429 and 503 with a
+///   `Retry-After` header in seconds are rate limited.
+/// - Transient errors: timeouts, refused, reset or aborted connections,
+///   other connect errors including DNS failures, and connections cut while
+///   reading a body. The system resolver reports temporary and permanent
+///   lookup failures with the same error kind, so unknown hosts are retried
+///   too.
+/// - Fatal errors: TLS failures such as certificate validation, destinations
+///   blocked by the URL guard, request build errors and redirect errors.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultClassifier;
+
+impl ErrorClassifier for DefaultClassifier {
+    fn classify(&self, outcome: &Result<Response>) -> Option<Retryable> {
+        match outcome {
+            Ok(res) => classify_status(res.status(), res.headers()),
+            Err(err) => Some(classify_error(err)),
+        }
+    }
+}
+
+fn classify_status(status: StatusCode, headers: &HeaderMap) -> Option<Retryable> {
+    let retryable = status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS;
+    if !retryable {
+        return None;
+    }
+
+    let rate_limited =
+        status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE;
+    match retry_after(headers) {
+        Some(delay) if rate_limited => Some(Retryable::RateLimited(delay)),
+        _ => Some(Retryable::Transient),
+    }
+}
+
+/// Parse a `Retry-After` header given in seconds. HTTP dates are ignored
+/// and fall back to the regular backoff.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs = headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+fn classify_error(err: &Error) -> Retryable {
+    let root: &(dyn StdError + 'static) = match err {
+        Error::Reqwest(e) if e.is_builder() || e.is_redirect() => return Retryable::Fatal,
+        Error::Reqwest(e) => e,
+        Error::Middleware(e) => e.as_ref(),
+    };
+
+    // TLS errors and guard blocks from the resolver are checked first as
+    // they surface as connect errors
+    let mut io_kind = None;
+    let mut source = Some(root);
+    while let Some(err) = source {
+        if err.is::<rustls::Error>() || err.is::<BlockedDestination>() {
+            return Retryable::Fatal;
+        }
+
+        source = err.source();
+        if let Some(io) = err.downcast_ref::<io::Error>() {
+            io_kind.get_or_insert(io.kind());
+            // `io::Error::source` skips the wrapped error itself
+            if let Some(inner) = io.get_ref() {
+                source = Some(inner as &(dyn StdError + 'static));
+            }
+        }
+    }
+
+    if matches!(
+        io_kind,
+        Some(
+            io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::NotConnected
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::UnexpectedEof
+                | io::ErrorKind::TimedOut
+        )
+    ) {
+        return Retryable::Transient;
+    }
+
+    match err {
+        Error::Reqwest(e) if e.is_body() => Retryable::Transient,
+        _ => match default_on_request_failure(err) {
+            Some(reqwest_retry::Retryable::Transient) => Retryable::Transient,
+            _ => Retryable::Fatal,
+        },
+    }
+}
+
+/// Retries transient failures with exponential backoff, as decided by an
+/// [`ErrorClassifier`].
+///
+/// Failures are wrapped in [`RetryError`] like `reqwest-retry` does.
+#[derive(Debug, Clone)]
+pub struct RetryMiddleware {
+    policy: ExponentialBackoff,
+    classifier: Arc<dyn ErrorClassifier>,
+}
+
+impl RetryMiddleware {
+    /// Retries up to `max_retries` times with [`DefaultClassifier`].
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            policy: ExponentialBackoff::builder()
+                .retry_bounds(MIN_RETRY_INTERVAL, MAX_RETRY_INTERVAL)
+                .build_with_max_retries(max_retries),
+            classifier: Arc::new(DefaultClassifier),
+        }
+    }
+
+    pub fn with_classifier(mut self, classifier: Arc<dyn ErrorClassifier>) -> Self {
+        self.classifier = classifier;
+        self
+    }
+
+    fn delay(
+        &self,
+        retryable: Retryable,
+        start: SystemTime,
+        n_past_retries: u32,
+    ) -> Option<Duration> {
+        let RetryDecision::Retry { execute_after } =
+            self.policy.should_retry(start, n_past_retries)
+        else {
+            return None;
+        };
+
+        match retryable {
+            Retryable::Transient => Some(
+                execute_after
+                    .duration_since(SystemTime::now())
+                    .unwrap_or_default(),
+            ),
+            Retryable::RateLimited(delay) if delay <= MAX_RETRY_INTERVAL => Some(delay),
+            Retryable::RateLimited(_) | Retryable::Fatal => None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for RetryMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let start = SystemTime::now();
+        let mut n_past_retries = 0;
+
+        loop {
+            let attempt = req.try_clone().ok_or_else(|| {
+                Error::middleware(io::Error::other(
+                    "request is not cloneable, was it given a streaming body?",
+                ))
+            })?;
+            let outcome = next.clone().run(attempt, extensions).await;
+
+            let delay = self
+                .classifier
+                .classify(&outcome)
+                .and_then(|retryable| self.delay(retryable, start, n_past_retries));
+            if let Some(delay) = delay {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    "Retry attempt #{}. Sleeping {:?} before the next attempt",
+                    n_past_retries,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                n_past_retries += 1;
+                continue;
+            }
+
+            return outcome.map_err(|err| {
+                let err = if n_past_retries > 0 {
+                    RetryError::WithRetries {
+                        retries: n_past_retries,
+                        err,
+                    }
+                } else {
+                    RetryError::Error(err)
+                };
+                Error::Middleware(err.into())
+            });
+        }
+    }
+}
+
+/// `reqwest-retry` middleware with the same backoff bounds but its own
+/// transient error detection. Use [`RetryMiddleware`] for the classifier
+/// based retries the builder installs.
 pub fn retry_middleware(max_retries: u32) -> RetryTransientMiddleware<ExponentialBackoff> {
     let retry_policy = ExponentialBackoff::builder()
-        .retry_bounds(
-            std::time::Duration::from_millis(100),
-            std::time::Duration::from_secs(30),
-        )
+        .retry_bounds(MIN_RETRY_INTERVAL, MAX_RETRY_INTERVAL)
         .build_with_max_retries(max_retries);
-
     RetryTransientMiddleware::new_with_policy(retry_policy)
 }
 
@@ -0,0 +1,286 @@
+//! Bandwidth throttling for background transfers.
+//!
+//! A single [`ThrottleMiddleware`] paces every request that passes through
+//! it with shared token buckets, so the limit applies to the whole client
+//! rather than to each request.
+
+use std::{
+    pin::pin,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use futures_util::{StreamExt, future, stream};
+use http::{
+    Extensions,
+    header::{CONTENT_LENGTH, HeaderValue},
+};
+use reqwest::{Body, Request, Response};
+use reqwest_middleware::{Middleware, Next, Result};
+use tokio::sync::Notify;
+
+/// Size of the chunks buffered request bodies are split into for pacing.
+const UPLOAD_CHUNK_BYTES: usize = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ThrottlePolicy {
+    /// Download limit in bytes per second, `None` for unlimited. `Some(0)`
+    /// pauses downloads until the rate is raised.
+    pub max_bytes_per_sec_down: Option<u64>,
+    /// Upload limit in bytes per second, `None` for unlimited. `Some(0)`
+    /// pauses uploads until the rate is raised.
+    pub max_bytes_per_sec_up: Option<u64>,
+    /// Bytes that may be transferred without waiting after an idle period.
+    pub burst: u64,
+}
+
+impl ThrottlePolicy {
+    pub fn new(
+        max_bytes_per_sec_down: Option<u64>,
+        max_bytes_per_sec_up: Option<u64>,
+        burst: u64,
+    ) -> Self {
+        Self {
+            max_bytes_per_sec_down,
+            max_bytes_per_sec_up,
+            burst,
+        }
+    }
+}
+
+/// Insert into a request's extensions to skip throttling for that request.
+///
+/// ```ignore
+/// client.get(url).with_extension(ThrottleBypass).send().await?;
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThrottleBypass;
+
+/// Token bucket kept as running totals, so a waiter can tell when the bytes
+/// it reserved are covered even after the rate changes.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: Option<u64>,
+    burst: u64,
+    /// Tokens added so far, including the initial burst
+    credited: f64,
+    /// Tokens reserved so far
+    debited: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: Option<u64>, burst: u64) -> Self {
+        let mut bucket = Self {
+            rate,
+            burst,
+            credited: burst as f64,
+            debited: 0.0,
+            last_refill: Instant::now(),
+        };
+        bucket.pause_if_zero();
+        bucket
+    }
+
+    /// A zero rate holds everything, including the unused burst
+    fn pause_if_zero(&mut self) {
+        if self.rate == Some(0) {
+            self.credited = self.credited.min(self.debited);
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if let Some(rate) = self.rate {
+            let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+            self.credited =
+                (self.credited + elapsed * rate as f64).min(self.debited + self.burst as f64);
+        }
+        self.last_refill = now;
+    }
+
+    /// Take `bytes` from the bucket, returning a ticket to wait on with
+    /// [`wait`](Self::wait), or `None` when unlimited. The bucket may go into
+    /// debt, which makes later callers wait for earlier ones.
+    fn reserve(&mut self, bytes: usize) -> Option<f64> {
+        self.rate?;
+        self.refill(Instant::now());
+        self.debited += bytes as f64;
+        Some(self.debited)
+    }
+
+    /// How long the holder of `ticket` must still wait at the current rate,
+    /// or `None` while paused, until the rate changes.
+    fn wait(&mut self, ticket: f64) -> Option<Duration> {
+        let Some(rate) = self.rate else {
+            return Some(Duration::ZERO);
+        };
+
+        self.refill(Instant::now());
+        let deficit = ticket - self.credited;
+        if deficit <= 0.0 {
+            Some(Duration::ZERO)
+        } else if rate == 0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(deficit / rate as f64))
+        }
+    }
+
+    fn set_rate(&mut self, rate: Option<u64>) {
+        self.refill(Instant::now());
+        self.rate = rate;
+        if rate.is_none() {
+            // Forgive outstanding debt, so waiters go right away
+            self.credited = self.debited + self.burst as f64;
+        }
+        self.pause_if_zero();
+    }
+}
+
+#[derive(Debug)]
+struct ThrottleState {
+    down: Mutex<TokenBucket>,
+    up: Mutex<TokenBucket>,
+    /// Wakes sleeping transfers to recompute their wait after a rate change
+    rate_changed: Notify,
+}
+
+impl ThrottleState {
+    fn bucket(bucket: &Mutex<TokenBucket>) -> MutexGuard<'_, TokenBucket> {
+        bucket.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn pace(&self, bucket: &Mutex<TokenBucket>, bytes: usize) {
+        let Some(ticket) = Self::bucket(bucket).reserve(bytes) else {
+            return;
+        };
+
+        loop {
+            // Registered before reading the rate, so a change in between
+            // still wakes this waiter
+            let mut rate_changed = pin!(self.rate_changed.notified());
+            rate_changed.as_mut().enable();
+
+            let wait = Self::bucket(bucket).wait(ticket);
+            match wait {
+                Some(wait) if wait.is_zero() => return,
+                Some(wait) => {
+                    future::select(pin!(tokio::time::sleep(wait)), rate_changed).await;
+                }
+                None => rate_changed.await,
+            }
+        }
+    }
+}
+
+/// Runtime control over a [`ThrottleMiddleware`]'s limits.
+#[derive(Debug, Clone)]
+pub struct ThrottleHandle {
+    state: Arc<ThrottleState>,
+}
+
+impl ThrottleHandle {
+    /// Change both limits. Transfers in flight pick up the new rates right
+    /// away, including ones waiting for their next chunk. `Some(0)` pauses
+    /// that direction until a later call raises the rate.
+    pub fn set_rate(&self, max_bytes_per_sec_down: Option<u64>, max_bytes_per_sec_up: Option<u64>) {
+        ThrottleState::bucket(&self.state.down).set_rate(max_bytes_per_sec_down);
+        ThrottleState::bucket(&self.state.up).set_rate(max_bytes_per_sec_up);
+        self.state.rate_changed.notify_waiters();
+    }
+}
+
+/// Paces request and response bodies with token buckets shared by every
+/// request sent through this middleware.
+///
+/// Only buffered request bodies, whose length is known, are throttled on
+/// upload and keep their `Content-Length`; streaming bodies pass through
+/// unchanged.
+#[derive(Debug, Clone)]
+pub struct ThrottleMiddleware {
+    state: Arc<ThrottleState>,
+}
+
+impl ThrottleMiddleware {
+    pub fn new(policy: ThrottlePolicy) -> Self {
+        Self {
+            state: Arc::new(ThrottleState {
+                down: Mutex::new(TokenBucket::new(
+                    policy.max_bytes_per_sec_down,
+                    policy.burst,
+                )),
+                up: Mutex::new(TokenBucket::new(policy.max_bytes_per_sec_up, policy.burst)),
+                rate_changed: Notify::new(),
+            }),
+        }
+    }
+
+    pub fn handle(&self) -> ThrottleHandle {
+        ThrottleHandle {
+            state: self.state.clone(),
+        }
+    }
+
+    fn throttle_upload(&self, req: &mut Request) {
+        let Some(bytes) = req.body().and_then(|b| b.as_bytes()).map(<[u8]>::to_vec) else {
+            return;
+        };
+
+        let state = self.state.clone();
+        let chunks: Vec<Vec<u8>> = bytes
+            .chunks(UPLOAD_CHUNK_BYTES)
+            .map(<[u8]>::to_vec)
+            .collect();
+        let paced = stream::iter(chunks).then(move |chunk| {
+            let state = state.clone();
+            async move {
+                state.pace(&state.up, chunk.len()).await;
+                Ok::<_, std::io::Error>(chunk)
+            }
+        });
+
+        // A stream body has no length of its own, and would otherwise be
+        // sent chunked
+        req.headers_mut()
+            .insert(CONTENT_LENGTH, HeaderValue::from(bytes.len()));
+        *req.body_mut() = Some(Body::wrap_stream(paced));
+    }
+
+    fn throttle_download(&self, res: Response) -> Result<Response> {
+        let builder = super::response_builder(&res);
+        let state = self.state.clone();
+        let paced = res.bytes_stream().then(move |chunk| {
+            let state = state.clone();
+            async move {
+                if let Ok(bytes) = &chunk {
+                    state.pace(&state.down, bytes.len()).await;
+                }
+                chunk
+            }
+        });
+
+        let res = builder
+            .body(Body::wrap_stream(paced))
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+        Ok(Response::from(res))
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for ThrottleMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        if extensions.get::<ThrottleBypass>().is_some() {
+            return next.run(req, extensions).await;
+        }
+
+        self.throttle_upload(&mut req);
+        let res = next.run(req, extensions).await?;
+        self.throttle_download(res)
+    }
+}
@@ -1,14 +1,41 @@
 use http::Extensions;
 use opentelemetry::trace::Status;
 use reqwest::{Request, Response};
-use reqwest_middleware::Result;
+use reqwest_middleware::{Middleware, Next, Result};
 use reqwest_tracing::{
-    ReqwestOtelSpanBackend, TracingMiddleware, default_on_request_end, reqwest_otel_span,
+    ERROR_CAUSE_CHAIN, ERROR_MESSAGE, OTEL_STATUS_CODE, ReqwestOtelSpanBackend, TracingMiddleware,
+    default_on_request_success, reqwest_otel_span,
 };
-use std::time::Instant;
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::{Layer, Service};
 use tracing::Span;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
+use crate::scrub::Scrubber;
+
+tokio::task_local! {
+    static CONNECT: Cell<ConnectObservation>;
+}
+
+/// What the connector layer saw of the current request attempt.
+#[derive(Debug, Clone, Copy, Default)]
+enum ConnectObservation {
+    /// No connection was opened, so a pooled one served the request.
+    #[default]
+    None,
+    /// A connection was started but finished elsewhere or failed, e.g.
+    /// hyper kept driving it on a background task after a pooled connection
+    /// won the race.
+    Started,
+    Finished(Duration),
+}
+
 pub struct TimeTrace;
 
 impl ReqwestOtelSpanBackend for TimeTrace {
@@ -16,7 +43,7 @@ impl ReqwestOtelSpanBackend for TimeTrace {
         // record start time
         extension.insert(Instant::now());
 
-        let url = req.url();
+        let url = scrubber(extension).url(req.url());
         let host = url.host_str().unwrap_or_default();
         let full_url = url.as_str();
         let target = url.path().to_string();
@@ -30,7 +57,10 @@ impl ReqwestOtelSpanBackend for TimeTrace {
             time_elapsed = tracing::field::Empty,
             request_id = tracing::field::Empty,
             retry_count = tracing::field::Empty,
-            http.status_code.string = tracing::field::Empty
+            http.status_code.string = tracing::field::Empty,
+            http.ttfb_ms = tracing::field::Empty,
+            http.connect_ms = tracing::field::Empty,
+            http.conn.reused = tracing::field::Empty
         )
     }
 
@@ -40,9 +70,9 @@ impl ReqwestOtelSpanBackend for TimeTrace {
             .map(|s| s.elapsed().as_millis() as i64)
             .unwrap_or_default();
 
-        default_on_request_end(span, outcome);
-
         if let Ok(res) = outcome {
+            default_on_request_success(span, res);
+
             let status = res.status();
             tracing::info!("Request ended with status: {}", status.as_u16());
             span.record("http.status_code", status.as_u16());
@@ -59,7 +89,12 @@ impl ReqwestOtelSpanBackend for TimeTrace {
             };
 
             span.set_status(otel_status);
-        } else {
+        } else if let Err(err) = outcome {
+            // Error messages embed the request URL, credentials included
+            let scrubber = scrubber(extension);
+            span.record(OTEL_STATUS_CODE, "ERROR");
+            span.record(ERROR_MESSAGE, scrubber.text(&err.to_string()));
+            span.record(ERROR_CAUSE_CHAIN, scrubber.error_chain(err));
             span.record("http.status_code", 0);
             span.set_status(Status::error("Request failed"));
         }
@@ -68,7 +103,130 @@ impl ReqwestOtelSpanBackend for TimeTrace {
     }
 }
 
+/// Scrubber configured on the client, or the default one for requests that
+/// bypassed the client's request initialisers.
+fn scrubber(extension: &Extensions) -> Scrubber {
+    extension.get::<Scrubber>().cloned().unwrap_or_default()
+}
+
 /// Construct the middleware to be used in HttpClientBuilder
 pub fn tracing_middleware() -> TracingMiddleware<TimeTrace> {
     TracingMiddleware::<TimeTrace>::new()
 }
+
+/// Timing of a single request attempt, attached to the response extensions
+/// when the client was built with [`HttpClientBuilder::with_tracing`].
+///
+/// DNS, TCP and TLS are not split: `connect` covers the whole connection
+/// setup and is `None` unless this attempt opened the connection it used.
+///
+/// [`HttpClientBuilder::with_tracing`]: crate::HttpClientBuilder::with_tracing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RequestTiming {
+    /// Time from sending the request until response headers arrived.
+    pub ttfb: Duration,
+    /// Time spent establishing a new connection, if one was opened.
+    pub connect: Option<Duration>,
+    /// Whether a pooled connection served the request. `None` when a
+    /// connection was started but not observed to completion, so it is
+    /// unknown which one was used.
+    pub connection_reused: Option<bool>,
+}
+
+/// Records [`RequestTiming`] on the current span and the response.
+///
+/// Must run inside the span created by [`tracing_middleware`], which the
+/// builder guarantees by adding it right after.
+pub(crate) struct ConnectionTimingMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for ConnectionTimingMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let start = Instant::now();
+        let (outcome, observed) = CONNECT
+            .scope(Cell::default(), async {
+                let outcome = next.run(req, extensions).await;
+                (outcome, CONNECT.with(Cell::get))
+            })
+            .await;
+
+        let (connect, connection_reused) = match observed {
+            ConnectObservation::None => (None, Some(true)),
+            ConnectObservation::Started => (None, None),
+            ConnectObservation::Finished(connect) => (Some(connect), Some(false)),
+        };
+        let timing = RequestTiming {
+            ttfb: start.elapsed(),
+            connect,
+            connection_reused,
+        };
+
+        let span = Span::current();
+        span.record("http.ttfb_ms", timing.ttfb.as_millis() as i64);
+        if let Some(reused) = timing.connection_reused {
+            span.record("http.conn.reused", reused);
+        }
+        if let Some(connect) = timing.connect {
+            span.record("http.connect_ms", connect.as_millis() as i64);
+        }
+
+        outcome.map(|mut res| {
+            res.extensions_mut().insert(timing);
+            res
+        })
+    }
+}
+
+/// Connector layer measuring new connection setup for [`ConnectionTimingMiddleware`].
+///
+/// Connections are started on the requesting task. Those that finish on
+/// another task (e.g. after losing the race against a pooled connection)
+/// leave the request's reuse unknown rather than attributing them.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ConnectTimingLayer;
+
+impl<S> Layer<S> for ConnectTimingLayer {
+    type Service = ConnectTiming<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectTiming { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectTiming<S> {
+    inner: S,
+}
+
+impl<S, R> Service<R> for ConnectTiming<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let _ = CONNECT.try_with(|c| c.set(ConnectObservation::Started));
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            let start = Instant::now();
+            let conn = fut.await;
+            if conn.is_ok() {
+                let _ = CONNECT.try_with(|c| c.set(ConnectObservation::Finished(start.elapsed())));
+            }
+            conn
+        })
+    }
+}
@@ -0,0 +1,442 @@
+//! Destination guard against server-side request forgery.
+//!
+//! [`UrlGuardMiddleware`] refuses requests to hosts outside an allowlist and
+//! to addresses in denied networks (private, loopback, link-local and cloud
+//! metadata ranges by default) before any connection is attempted.
+//!
+//! The guard resolves names itself, honouring the builder's DNS overrides,
+//! and fails closed when a name does not resolve. Clients built with a guard
+//! also resolve through it when connecting, so redirect targets and names
+//! that resolve differently the second time are checked as well. It is a
+//! defense-in-depth layer, not a substitute for validating user-supplied
+//! URLs.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+};
+
+use http::Extensions;
+use reqwest::{
+    Request, Response, Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use reqwest_middleware::{Middleware, Next, Result};
+
+use crate::{
+    dns::{IpPreference, PreferenceResolver},
+    error::{BlockReason, BlockedDestination},
+};
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Networks denied by [`UrlGuardConfig::default`].
+pub const DEFAULT_DENIED_NETWORKS: &[&str] = &[
+    "0.0.0.0/8",
+    "10.0.0.0/8",
+    "100.64.0.0/10",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "224.0.0.0/4",
+    "255.255.255.255/32",
+    "::/128",
+    "::1/128",
+    "64:ff9b::/96",
+    "fc00::/7",
+    "fe80::/10",
+    "ff00::/8",
+];
+
+/// An IP network in CIDR notation, e.g. `10.0.0.0/8` or `fe80::/10`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpCidr {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, normalize(ip)) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_matches(&net.octets(), &ip.octets(), self.prefix)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(&net.octets(), &ip.octets(), self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("invalid network address: {s}"))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("invalid prefix length: {s}"))?,
+            None => max,
+        };
+
+        Ok(Self { addr, prefix })
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+fn prefix_matches(net: &[u8], ip: &[u8], prefix: u8) -> bool {
+    let full = usize::from(prefix / 8);
+    let rem = prefix % 8;
+
+    if net[..full] != ip[..full] {
+        return false;
+    }
+    if rem == 0 {
+        return true;
+    }
+
+    let mask = 0xffu8 << (8 - rem);
+    net[full] & mask == ip[full] & mask
+}
+
+/// Treat IPv4-mapped IPv6 addresses as the IPv4 address they carry.
+fn normalize(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        v4 => v4,
+    }
+}
+
+/// Per-request override of the guard, set as a request extension.
+///
+/// The override also applies to redirects reqwest follows for the request
+/// and to the addresses it connects to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GuardStrictness {
+    /// Enforce both the host allowlist and denied networks.
+    #[default]
+    Strict,
+    /// Enforce the host allowlist only.
+    HostsOnly,
+    /// Skip the guard entirely.
+    Disabled,
+}
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UrlGuardConfig {
+    /// Host patterns allowed as destinations. `*.example.com` matches any
+    /// subdomain of `example.com`. `None` allows every host.
+    pub allowed_hosts: Option<Vec<String>>,
+    /// Networks that requests may not reach.
+    pub denied_networks: Vec<IpCidr>,
+    /// Exceptions to `denied_networks`, e.g. a known internal service.
+    pub allowed_networks: Vec<IpCidr>,
+}
+
+impl Default for UrlGuardConfig {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: None,
+            denied_networks: DEFAULT_DENIED_NETWORKS
+                .iter()
+                .filter_map(|n| n.parse().ok())
+                .collect(),
+            allowed_networks: Vec::new(),
+        }
+    }
+}
+
+impl UrlGuardConfig {
+    pub fn with_allowed_hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_hosts = Some(hosts.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_denied_network(mut self, network: IpCidr) -> Self {
+        self.denied_networks.push(network);
+        self
+    }
+
+    pub fn with_allowed_network(mut self, network: IpCidr) -> Self {
+        self.allowed_networks.push(network);
+        self
+    }
+}
+
+tokio::task_local! {
+    /// Strictness of the request being sent, for the redirect policy and the
+    /// resolver, which reqwest runs while polling that request.
+    static STRICTNESS: GuardStrictness;
+}
+
+/// Strictness of the request in flight, `Strict` outside of one.
+fn current_strictness() -> GuardStrictness {
+    STRICTNESS.try_with(|s| *s).unwrap_or_default()
+}
+
+/// Shared policy used by the middleware, the redirect check and the resolver.
+#[derive(Debug)]
+pub(crate) struct UrlGuard {
+    config: UrlGuardConfig,
+    dns_overrides: HashMap<String, Vec<SocketAddr>>,
+    resolver: PreferenceResolver,
+}
+
+impl UrlGuard {
+    pub(crate) fn new(
+        config: UrlGuardConfig,
+        dns_overrides: HashMap<String, Vec<SocketAddr>>,
+        ip_preference: IpPreference,
+    ) -> Self {
+        let dns_overrides = dns_overrides
+            .into_iter()
+            .map(|(host, mut addrs)| {
+                ip_preference.order(&mut addrs);
+                (host.to_ascii_lowercase(), addrs)
+            })
+            .collect();
+        Self {
+            config,
+            dns_overrides,
+            resolver: PreferenceResolver::new(ip_preference),
+        }
+    }
+
+    fn host_allowed(&self, host: &str) -> bool {
+        let Some(patterns) = &self.config.allowed_hosts else {
+            return true;
+        };
+
+        patterns
+            .iter()
+            .any(|pattern| match pattern.strip_prefix("*.") {
+                Some(suffix) => host
+                    .strip_suffix(&suffix.to_ascii_lowercase())
+                    .is_some_and(|rest| rest.ends_with('.')),
+                None => pattern.eq_ignore_ascii_case(host),
+            })
+    }
+
+    fn address_allowed(&self, ip: IpAddr) -> bool {
+        self.config.allowed_networks.iter().any(|n| n.contains(ip))
+            || !self.config.denied_networks.iter().any(|n| n.contains(ip))
+    }
+
+    /// Checks that need no DNS lookup: host allowlist and IP literals.
+    fn check_static(
+        &self,
+        url: &Url,
+        strictness: GuardStrictness,
+    ) -> std::result::Result<Option<String>, BlockedDestination> {
+        let block = |reason| BlockedDestination::new(url.clone(), reason);
+
+        let Some(host) = url.host_str() else {
+            return Err(block(BlockReason::MissingHost));
+        };
+
+        let host = host.to_ascii_lowercase();
+        let ip = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .ok();
+
+        if !self.host_allowed(&host) {
+            return Err(block(BlockReason::HostNotAllowed(host)));
+        }
+
+        if strictness == GuardStrictness::HostsOnly {
+            return Ok(None);
+        }
+
+        match ip {
+            Some(ip) if !self.address_allowed(ip) => Err(block(BlockReason::DeniedAddress(ip))),
+            Some(_) => Ok(None),
+            None => Ok(Some(host)),
+        }
+    }
+
+    async fn check(
+        &self,
+        url: &Url,
+        strictness: GuardStrictness,
+    ) -> std::result::Result<(), BlockedDestination> {
+        if strictness == GuardStrictness::Disabled {
+            return Ok(());
+        }
+
+        let Some(host) = self.check_static(url, strictness)? else {
+            return Ok(());
+        };
+
+        // Fail closed: a name that does not resolve here is not let through
+        let addrs = match self.resolve(&host).await {
+            Ok(addrs) if !addrs.is_empty() => addrs,
+            _ => {
+                return Err(BlockedDestination::new(
+                    url.clone(),
+                    BlockReason::UnresolvedHost(host),
+                ));
+            }
+        };
+
+        match self.denied_address(&addrs) {
+            Some(ip) => Err(BlockedDestination::new(
+                url.clone(),
+                BlockReason::DeniedAddress(ip),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn denied_address(&self, addrs: &[SocketAddr]) -> Option<IpAddr> {
+        addrs
+            .iter()
+            .map(SocketAddr::ip)
+            .find(|ip| !self.address_allowed(*ip))
+    }
+
+    /// Resolve `host` like the client does: the builder's DNS overrides
+    /// first, then the system resolver with the configured IP preference.
+    async fn resolve(
+        &self,
+        host: &str,
+    ) -> std::result::Result<Vec<SocketAddr>, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(addrs) = self.dns_overrides.get(host) {
+            return Ok(addrs.clone());
+        }
+
+        let name: Name = host.parse()?;
+        Ok(self.resolver.resolve(name).await?.collect())
+    }
+
+    /// Redirect policy re-checking each hop with the request's strictness.
+    /// Only DNS-free checks run here; names are checked by [`GuardResolver`]
+    /// when reqwest connects.
+    pub(crate) fn redirect_policy(self: Arc<Self>) -> redirect::Policy {
+        redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+
+            let strictness = current_strictness();
+            if strictness == GuardStrictness::Disabled {
+                return attempt.follow();
+            }
+
+            match self.check_static(attempt.url(), strictness) {
+                Ok(_) => attempt.follow(),
+                Err(blocked) => attempt.error(blocked),
+            }
+        })
+    }
+}
+
+/// reqwest resolver for guarded clients, applying the denied networks to
+/// every name reqwest connects to.
+///
+/// This covers redirect targets, which the redirect policy cannot resolve,
+/// and names that resolve to a different address than the middleware saw.
+/// It replaces reqwest's own DNS overrides, which would bypass it.
+#[derive(Debug, Clone)]
+pub(crate) struct GuardResolver {
+    guard: Arc<UrlGuard>,
+}
+
+impl GuardResolver {
+    pub(crate) fn new(guard: Arc<UrlGuard>) -> Self {
+        Self { guard }
+    }
+}
+
+impl Resolve for GuardResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let guard = self.guard.clone();
+        let strictness = current_strictness();
+        Box::pin(async move {
+            let addrs = guard.resolve(name.as_str()).await?;
+
+            if strictness == GuardStrictness::Strict
+                && let Some(ip) = guard.denied_address(&addrs)
+            {
+                // Only the host is known at this point
+                let url = Url::parse(&format!("http://{}/", name.as_str()))?;
+                return Err(BlockedDestination::new(url, BlockReason::DeniedAddress(ip)).into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Middleware rejecting requests to disallowed hosts and networks with a
+/// [`BlockedDestination`] error.
+#[derive(Debug, Clone)]
+pub struct UrlGuardMiddleware {
+    guard: Arc<UrlGuard>,
+}
+
+impl UrlGuardMiddleware {
+    pub fn new(config: UrlGuardConfig) -> Self {
+        Self::from_guard(Arc::new(UrlGuard::new(
+            config,
+            HashMap::new(),
+            IpPreference::Auto,
+        )))
+    }
+
+    pub(crate) fn from_guard(guard: Arc<UrlGuard>) -> Self {
+        Self { guard }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for UrlGuardMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let strictness = extensions
+            .get::<GuardStrictness>()
+            .copied()
+            .unwrap_or_default();
+
+        self.guard
+            .check(req.url(), strictness)
+            .await
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+
+        STRICTNESS
+            .scope(strictness, next.run(req, extensions))
+            .await
+    }
+}
@@ -0,0 +1,525 @@
+//! Record-and-replay middleware for deterministic integration tests.
+//!
+//! In record mode every request goes to the network and the interaction is
+//! kept in memory, then written to a cassette file by
+//! [`VcrMiddleware::save`] or when the middleware is dropped. In replay mode
+//! requests are answered from the cassette and never reach the network.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use http::Extensions;
+use reqwest::{
+    Request, Response, ResponseBuilderExt, StatusCode, Url,
+    header::{HeaderMap, HeaderName, HeaderValue},
+};
+use reqwest_middleware::{Middleware, Next, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::scrub::Scrubber;
+
+/// Cassette format version written by this crate.
+pub const CASSETTE_VERSION: u32 = 1;
+
+const REDACTED: &str = "***";
+
+/// Request attributes compared when looking up a recorded interaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MatchOn {
+    Method,
+    Path,
+    Query,
+    BodyHash,
+}
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct VcrConfig {
+    /// Attributes that must be equal for a recorded interaction to match.
+    pub match_on: Vec<MatchOn>,
+    /// Header names (case-insensitive) whose values are replaced with `***`,
+    /// on top of the ones `scrubber` redacts.
+    pub redact_headers: Vec<String>,
+    /// Removes userinfo and sensitive query parameters from recorded URLs
+    /// and redacts sensitive headers, such as `X-Auth-Token`.
+    pub scrubber: Scrubber,
+    /// Response bodies longer than this are truncated in the cassette, and
+    /// replaying them fails with [`VcrError::TruncatedBody`].
+    pub max_body_bytes: usize,
+}
+
+impl Default for VcrConfig {
+    fn default() -> Self {
+        Self {
+            match_on: vec![MatchOn::Method, MatchOn::Path, MatchOn::BodyHash],
+            redact_headers: [
+                "authorization",
+                "proxy-authorization",
+                "cookie",
+                "set-cookie",
+                "x-api-key",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            scrubber: Scrubber::default(),
+            max_body_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl VcrConfig {
+    pub fn with_match_on(mut self, match_on: Vec<MatchOn>) -> Self {
+        self.match_on = match_on;
+        self
+    }
+
+    pub fn with_redact_header(mut self, name: impl Into<String>) -> Self {
+        self.redact_headers.push(name.into());
+        self
+    }
+
+    pub fn with_scrubber(mut self, scrubber: Scrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    fn is_redacted(&self, name: &HeaderName) -> bool {
+        self.redact_headers
+            .iter()
+            .any(|h| h.eq_ignore_ascii_case(name.as_str()))
+    }
+
+    fn snapshot_headers(&self, headers: &HeaderMap) -> Vec<(String, String)> {
+        self.scrubber
+            .headers(headers)
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.is_redacted(name) {
+                    REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.as_str().to_string(), value)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum VcrError {
+    #[error("cassette I/O failed for {path}")]
+    #[non_exhaustive]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("cassette {path} is malformed")]
+    #[non_exhaustive]
+    Format {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("cassette version {found} is not supported (expected {expected})")]
+    #[non_exhaustive]
+    UnsupportedVersion { found: u32, expected: u32 },
+
+    #[error("no recorded interaction matches {method} {}", Scrubber::default().text(.url))]
+    #[non_exhaustive]
+    NoMatch { method: String, url: String },
+
+    #[error(
+        "recorded response to {method} {} was truncated, raise max_body_bytes and record again",
+        Scrubber::default().text(.url)
+    )]
+    #[non_exhaustive]
+    TruncatedBody { method: String, url: String },
+}
+
+/// Serialized cassette file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cassette {
+    pub version: u32,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Self {
+            version: CASSETTE_VERSION,
+            interactions: Vec::new(),
+        }
+    }
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> std::result::Result<Self, VcrError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|source| VcrError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let cassette: Cassette =
+            serde_json::from_slice(&data).map_err(|source| VcrError::Format {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        if cassette.version != CASSETTE_VERSION {
+            return Err(VcrError::UnsupportedVersion {
+                found: cassette.version,
+                expected: CASSETTE_VERSION,
+            });
+        }
+
+        Ok(cassette)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::result::Result<(), VcrError> {
+        let path = path.as_ref();
+        let data = serde_json::to_vec_pretty(self).map_err(|source| VcrError::Format {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        std::fs::write(path, data).map_err(|source| VcrError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// Hex-encoded SHA-256 of the request body, if it was buffered.
+    pub body_sha256: Option<String>,
+}
+
+impl RecordedRequest {
+    fn from_request(req: &Request, config: &VcrConfig) -> Self {
+        Self {
+            method: req.method().as_str().to_string(),
+            // Scrubbed on both sides, so `MatchOn::Query` compares the
+            // redacted forms
+            url: config.scrubber.url(req.url()).to_string(),
+            headers: config.snapshot_headers(req.headers()),
+            body_sha256: req.body().and_then(|b| b.as_bytes()).map(sha256_hex),
+        }
+    }
+
+    fn matches(&self, other: &RecordedRequest, match_on: &[MatchOn]) -> bool {
+        let (Ok(url), Ok(other_url)) = (Url::parse(&self.url), Url::parse(&other.url)) else {
+            return false;
+        };
+
+        match_on.iter().all(|key| match key {
+            MatchOn::Method => self.method == other.method,
+            MatchOn::Path => url.path() == other_url.path(),
+            MatchOn::Query => url.query() == other_url.query(),
+            MatchOn::BodyHash => self.body_sha256 == other.body_sha256,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: CassetteBody,
+    /// Set when the body exceeded `max_body_bytes` and was cut.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Response body, stored as text when possible to keep cassettes diffable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "encoding", content = "data", rename_all = "lowercase")]
+pub enum CassetteBody {
+    Utf8(String),
+    Hex(String),
+}
+
+impl CassetteBody {
+    fn encode(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::Utf8(text.to_string()),
+            Err(_) => Self::Hex(hex_encode(bytes)),
+        }
+    }
+
+    fn decode(&self) -> Vec<u8> {
+        match self {
+            Self::Utf8(text) => text.as_bytes().to_vec(),
+            Self::Hex(hex) => (0..hex.len())
+                .step_by(2)
+                .filter_map(|i| hex.get(i..i + 2))
+                .filter_map(|b| u8::from_str_radix(b, 16).ok())
+                .collect(),
+        }
+    }
+}
+
+enum VcrState {
+    Record {
+        cassette: Cassette,
+        /// Interactions recorded since the last save
+        dirty: bool,
+    },
+    Replay {
+        cassette: Cassette,
+        used: Vec<bool>,
+    },
+}
+
+struct VcrInner {
+    path: PathBuf,
+    config: VcrConfig,
+    state: Mutex<VcrState>,
+}
+
+impl Drop for VcrInner {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(|e| e.into_inner());
+        if let VcrState::Record {
+            cassette,
+            dirty: true,
+        } = state
+            && let Err(e) = cassette.save(&self.path)
+        {
+            eprintln!("Failed to save VCR cassette: {e}");
+        }
+    }
+}
+
+/// Records or replays interactions, see the [module docs](self).
+///
+/// Clones share one cassette. Keep a clone next to the client to call
+/// [`save`](Self::save) and see write errors; otherwise the cassette is
+/// written when the last clone is dropped.
+#[derive(Clone)]
+pub struct VcrMiddleware {
+    inner: Arc<VcrInner>,
+}
+
+impl VcrMiddleware {
+    /// Record interactions to `path`, overwriting any existing cassette
+    /// when saved.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::record_with_config(path, VcrConfig::default())
+    }
+
+    pub fn record_with_config(path: impl Into<PathBuf>, config: VcrConfig) -> Self {
+        Self::new(
+            path.into(),
+            config,
+            VcrState::Record {
+                cassette: Cassette::default(),
+                dirty: false,
+            },
+        )
+    }
+
+    /// Replay interactions from the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> std::result::Result<Self, VcrError> {
+        Self::replay_with_config(path, VcrConfig::default())
+    }
+
+    pub fn replay_with_config(
+        path: impl Into<PathBuf>,
+        config: VcrConfig,
+    ) -> std::result::Result<Self, VcrError> {
+        let path = path.into();
+        let cassette = Cassette::load(&path)?;
+        let used = vec![false; cassette.interactions.len()];
+
+        Ok(Self::new(path, config, VcrState::Replay { cassette, used }))
+    }
+
+    fn new(path: PathBuf, config: VcrConfig, state: VcrState) -> Self {
+        Self {
+            inner: Arc::new(VcrInner {
+                path,
+                config,
+                state: Mutex::new(state),
+            }),
+        }
+    }
+
+    /// Write the interactions recorded so far to the cassette file. Does
+    /// nothing in replay mode or when nothing new was recorded.
+    pub fn save(&self) -> std::result::Result<(), VcrError> {
+        let mut state = self.lock();
+        if let VcrState::Record { cassette, dirty } = &mut *state
+            && *dirty
+        {
+            cassette.save(&self.inner.path)?;
+            *dirty = false;
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VcrState> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn record_interaction(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let config = &self.inner.config;
+        let recorded_req = RecordedRequest::from_request(&req, config);
+        let res = next.run(req, extensions).await?;
+
+        let builder = super::response_builder(&res);
+        let status = res.status();
+        let headers = res.headers().clone();
+        let body = res.bytes().await?;
+
+        let truncated = body.len() > config.max_body_bytes;
+        let stored = &body[..body.len().min(config.max_body_bytes)];
+        let interaction = Interaction {
+            request: recorded_req,
+            response: RecordedResponse {
+                status: status.as_u16(),
+                headers: config.snapshot_headers(&headers),
+                body: CassetteBody::encode(stored),
+                truncated,
+            },
+        };
+
+        if let VcrState::Record { cassette, dirty } = &mut *self.lock() {
+            cassette.interactions.push(interaction);
+            *dirty = true;
+        }
+
+        let res = builder
+            .body(body)
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+        Ok(Response::from(res))
+    }
+
+    fn replay_interaction(&self, req: &Request) -> Result<Response> {
+        let config = &self.inner.config;
+        let incoming = RecordedRequest::from_request(req, config);
+
+        let recorded = {
+            let mut state = self.lock();
+            let VcrState::Replay { cassette, used } = &mut *state else {
+                unreachable!("replay_interaction called in record mode");
+            };
+
+            let candidates: Vec<usize> = cassette
+                .interactions
+                .iter()
+                .enumerate()
+                .filter(|(_, i)| i.request.matches(&incoming, &config.match_on))
+                .map(|(idx, _)| idx)
+                .collect();
+
+            // Prefer interactions in recorded order, then keep replaying the
+            // last one for repeated requests.
+            let idx = candidates
+                .iter()
+                .copied()
+                .find(|&idx| !used[idx])
+                .or_else(|| candidates.last().copied());
+
+            let Some(idx) = idx else {
+                return Err(reqwest_middleware::Error::Middleware(
+                    VcrError::NoMatch {
+                        method: incoming.method,
+                        url: incoming.url,
+                    }
+                    .into(),
+                ));
+            };
+            used[idx] = true;
+            cassette.interactions[idx].response.clone()
+        };
+
+        if recorded.truncated {
+            return Err(reqwest_middleware::Error::Middleware(
+                VcrError::TruncatedBody {
+                    method: incoming.method,
+                    url: incoming.url,
+                }
+                .into(),
+            ));
+        }
+
+        let status = StatusCode::from_u16(recorded.status)
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+        let mut builder = http::Response::builder()
+            .status(status)
+            .url(req.url().clone());
+        if let Some(h) = builder.headers_mut() {
+            for (name, value) in &recorded.headers {
+                if let (Ok(name), Ok(value)) =
+                    (HeaderName::try_from(name), HeaderValue::try_from(value))
+                {
+                    h.append(name, value);
+                }
+            }
+        }
+        let res = builder
+            .body(recorded.body.decode())
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+        Ok(Response::from(res))
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for VcrMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let recording = matches!(*self.lock(), VcrState::Record { .. });
+        if recording {
+            self.record_interaction(req, extensions, next).await
+        } else {
+            self.replay_interaction(&req)
+        }
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex_encode(&Sha256::digest(bytes))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
@@ -0,0 +1,527 @@
+//! In-process mock transport for unit testing consumers of the client.
+//!
+//! [`MockTransport`] replaces the network at the bottom of the middleware
+//! stack, so retries and tracing run exactly as they would against a real
+//! server. Use it through [`HttpClientBuilder::build_with_mock`].
+//!
+//! [`HttpClientBuilder::build_with_mock`]: crate::HttpClientBuilder::build_with_mock
+
+use std::{
+    fmt,
+    future::{Ready, ready},
+    io,
+    sync::{Arc, Mutex, OnceLock},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use http::Extensions;
+use reqwest::{
+    Method, Request, Response, ResponseBuilderExt, StatusCode, Url,
+    header::{HeaderMap, HeaderName, HeaderValue},
+};
+use reqwest_middleware::{Middleware, Next, Result};
+use tower::{Layer, Service};
+
+const DEFAULT_PRIORITY: u8 = 5;
+
+type BodyPredicate = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// A request received by a [`MockTransport`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+impl RecordedRequest {
+    fn from_request(req: &Request) -> Self {
+        Self {
+            method: req.method().clone(),
+            url: req.url().clone(),
+            headers: req.headers().clone(),
+            body: req.body().and_then(|b| b.as_bytes()).map(<[u8]>::to_vec),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum MockOutcome {
+    Response {
+        status: StatusCode,
+        headers: HeaderMap,
+        body: Vec<u8>,
+    },
+    TransportError(io::ErrorKind),
+}
+
+/// Response (or transport failure) returned by a matched [`Mock`].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    outcome: MockOutcome,
+    delay: Option<Duration>,
+}
+
+impl MockResponse {
+    /// Create a response with the given status code and an empty body.
+    ///
+    /// # Panics
+    /// Panics if `status` is not a valid HTTP status code.
+    pub fn new(status: u16) -> Self {
+        Self {
+            outcome: MockOutcome::Response {
+                status: StatusCode::from_u16(status).expect("invalid mock status code"),
+                headers: HeaderMap::new(),
+                body: Vec::new(),
+            },
+            delay: None,
+        }
+    }
+
+    /// Fail the request at the transport level instead of returning a response.
+    ///
+    /// The error surfaces as a reqwest connect error caused by an
+    /// [`io::Error`] of the given kind, so retry policies treat it like a
+    /// real connection failure.
+    pub fn transport_error(kind: io::ErrorKind) -> Self {
+        Self {
+            outcome: MockOutcome::TransportError(kind),
+            delay: None,
+        }
+    }
+
+    /// Add a response header.
+    ///
+    /// # Panics
+    /// Panics if the name or value is not a valid header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let MockOutcome::Response { headers, .. } = &mut self.outcome {
+            headers.append(parse_header_name(name), parse_header_value(value));
+        }
+        self
+    }
+
+    /// Set the response body.
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        if let MockOutcome::Response { body: b, .. } = &mut self.outcome {
+            *b = body.into();
+        }
+        self
+    }
+
+    /// Wait before responding. Delays longer than the client timeout fail the
+    /// request with a reqwest timeout error caused by
+    /// [`io::ErrorKind::TimedOut`].
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+impl Default for MockResponse {
+    fn default() -> Self {
+        Self::new(200)
+    }
+}
+
+/// A request matcher paired with the response it produces.
+///
+/// All configured conditions must hold for a request to match. A mock with
+/// no conditions matches every request.
+#[derive(Clone)]
+pub struct Mock {
+    method: Option<Method>,
+    path: Option<String>,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    body: Option<BodyPredicate>,
+    priority: u8,
+    max_hits: Option<usize>,
+    response: MockResponse,
+}
+
+impl Mock {
+    pub fn new() -> Self {
+        Self {
+            method: None,
+            path: None,
+            headers: Vec::new(),
+            body: None,
+            priority: DEFAULT_PRIORITY,
+            max_hits: None,
+            response: MockResponse::default(),
+        }
+    }
+
+    /// Match on the request method.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Match on the exact URL path.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Match requests carrying this header value.
+    ///
+    /// # Panics
+    /// Panics if the name or value is not a valid header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers
+            .push((parse_header_name(name), parse_header_value(value)));
+        self
+    }
+
+    /// Match requests whose buffered body satisfies `predicate`.
+    ///
+    /// Requests with a streaming body are matched against an empty slice.
+    pub fn body_matches<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+    {
+        self.body = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Set the precedence of this mock. Lower values win; mocks with equal
+    /// priority are tried in registration order. Defaults to 5.
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Stop matching after `n` requests, letting lower-precedence mocks
+    /// answer subsequent ones.
+    pub fn up_to_n_times(mut self, n: usize) -> Self {
+        self.max_hits = Some(n);
+        self
+    }
+
+    pub fn respond_with(mut self, response: MockResponse) -> Self {
+        self.response = response;
+        self
+    }
+
+    fn matches(&self, req: &RecordedRequest) -> bool {
+        if self.method.as_ref().is_some_and(|m| m != req.method) {
+            return false;
+        }
+
+        if self.path.as_deref().is_some_and(|p| p != req.url.path()) {
+            return false;
+        }
+
+        if !self
+            .headers
+            .iter()
+            .all(|(name, value)| req.headers.get_all(name).iter().any(|v| v == value))
+        {
+            return false;
+        }
+
+        match &self.body {
+            Some(predicate) => predicate(req.body.as_deref().unwrap_or_default()),
+            None => true,
+        }
+    }
+}
+
+impl Default for Mock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Mock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mock")
+            .field("method", &self.method)
+            .field("path", &self.path)
+            .field("headers", &self.headers)
+            .field("body", &self.body.as_ref().map(|_| "<predicate>"))
+            .field("priority", &self.priority)
+            .field("max_hits", &self.max_hits)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct RegisteredMock {
+    mock: Mock,
+    hits: usize,
+}
+
+impl RegisteredMock {
+    fn exhausted(&self) -> bool {
+        self.mock.max_hits.is_some_and(|max| self.hits >= max)
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    mocks: Vec<RegisteredMock>,
+    received: Vec<RecordedRequest>,
+}
+
+/// Shared registry of mocks and received requests.
+///
+/// Cloning is cheap and clones share state, so a test can keep a handle for
+/// assertions after passing the transport to the builder.
+#[derive(Debug, Clone)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+    strict: bool,
+}
+
+impl MockTransport {
+    /// Create a strict transport, which panics on unmatched requests.
+    pub fn new() -> Self {
+        Self::strict()
+    }
+
+    /// Panic with the request and registered mocks when nothing matches.
+    pub fn strict() -> Self {
+        Self {
+            state: Arc::default(),
+            strict: true,
+        }
+    }
+
+    /// Answer unmatched requests with `404 Not Found`.
+    pub fn lenient() -> Self {
+        Self {
+            state: Arc::default(),
+            strict: false,
+        }
+    }
+
+    pub fn register(&self, mock: Mock) -> &Self {
+        self.lock().mocks.push(RegisteredMock { mock, hits: 0 });
+        self
+    }
+
+    /// All requests received so far, in arrival order.
+    pub fn received_requests(&self) -> Vec<RecordedRequest> {
+        self.lock().received.clone()
+    }
+
+    /// Forget registered mocks and received requests.
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.mocks.clear();
+        state.received.clear();
+    }
+
+    pub(crate) fn into_middleware(
+        self,
+        timeout: Option<Duration>,
+        default_headers: Option<HeaderMap>,
+    ) -> MockTransportMiddleware {
+        MockTransportMiddleware {
+            transport: self,
+            timeout,
+            default_headers: default_headers.unwrap_or_default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn select(&self, recorded: RecordedRequest) -> Option<MockResponse> {
+        let mut state = self.lock();
+
+        let selected = state
+            .mocks
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| !m.exhausted() && m.mock.matches(&recorded))
+            .min_by_key(|(idx, m)| (m.mock.priority, *idx))
+            .map(|(idx, _)| idx);
+
+        let response = selected.map(|idx| {
+            let entry = &mut state.mocks[idx];
+            entry.hits += 1;
+            entry.mock.response.clone()
+        });
+
+        if response.is_none() && self.strict {
+            let registered: Vec<_> = state.mocks.iter().map(|m| &m.mock).collect();
+            panic!(
+                "MockTransport: no mock matched {} {}\nheaders: {:?}\nregistered mocks: {:#?}",
+                recorded.method, recorded.url, recorded.headers, registered
+            );
+        }
+
+        state.received.push(recorded);
+        response
+    }
+}
+
+impl Default for MockTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Terminal middleware answering requests from a [`MockTransport`].
+pub(crate) struct MockTransportMiddleware {
+    transport: MockTransport,
+    timeout: Option<Duration>,
+    default_headers: HeaderMap,
+}
+
+impl MockTransportMiddleware {
+    async fn respond(response: MockResponse, method: Method, url: Url) -> Result<Response> {
+        if let Some(delay) = response.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        match response.outcome {
+            MockOutcome::Response {
+                status,
+                headers,
+                body,
+            } => {
+                let mut builder = http::Response::builder().status(status).url(url);
+                if let Some(h) = builder.headers_mut() {
+                    h.extend(headers);
+                }
+                let res = builder
+                    .body(body)
+                    .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+                Ok(Response::from(res))
+            }
+            MockOutcome::TransportError(kind) => {
+                Err(transport_error(kind, "mock transport error", method, url).await)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for MockTransportMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        _extensions: &mut Extensions,
+        _next: Next<'_>,
+    ) -> Result<Response> {
+        // Mirror reqwest, which merges default headers at send time
+        for (name, value) in &self.default_headers {
+            if !req.headers().contains_key(name) {
+                req.headers_mut().insert(name, value.clone());
+            }
+        }
+
+        let recorded = RecordedRequest::from_request(&req);
+        let method = recorded.method.clone();
+        let url = recorded.url.clone();
+
+        let Some(response) = self.transport.select(recorded) else {
+            let res = http::Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .url(url)
+                .body("no mock matched the request")
+                .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+            return Ok(Response::from(res));
+        };
+
+        let timeout = req.timeout().copied().or(self.timeout);
+        match timeout {
+            Some(timeout) => {
+                let respond = Self::respond(response, method.clone(), url.clone());
+                match tokio::time::timeout(timeout, respond).await {
+                    Ok(outcome) => outcome,
+                    Err(_) => Err(transport_error(
+                        io::ErrorKind::TimedOut,
+                        "request timed out",
+                        method,
+                        url,
+                    )
+                    .await),
+                }
+            }
+            None => Self::respond(response, method, url).await,
+        }
+    }
+}
+
+tokio::task_local! {
+    /// The failure [`FailConnect`] reports for the request being sent.
+    static FAILURE: (io::ErrorKind, &'static str);
+}
+
+/// Produce the error reqwest itself returns when connecting fails, by sending
+/// the request through a client whose connector always fails.
+async fn transport_error(
+    kind: io::ErrorKind,
+    msg: &'static str,
+    method: Method,
+    url: Url,
+) -> reqwest_middleware::Error {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    let client = CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .no_proxy()
+            .connector_layer(FailConnectLayer)
+            .build()
+            .expect("mock failing client builds")
+    });
+    FAILURE
+        .scope((kind, msg), client.request(method, url).send())
+        .await
+        .expect_err("mock connector always fails")
+        .into()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FailConnectLayer;
+
+impl<S> Layer<S> for FailConnectLayer {
+    type Service = FailConnect<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FailConnect { _inner: inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FailConnect<S> {
+    _inner: S,
+}
+
+impl<S, R> Service<R> for FailConnect<S>
+where
+    S: Service<R>,
+    S::Error: From<io::Error>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Ready<std::result::Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _req: R) -> Self::Future {
+        // hyper connects on the task sending the request, where the scope is set
+        let (kind, msg) = FAILURE
+            .try_with(|failure| *failure)
+            .unwrap_or((io::ErrorKind::Other, "mock transport error"));
+        ready(Err(io::Error::new(kind, msg).into()))
+    }
+}
+
+fn parse_header_name(name: &str) -> HeaderName {
+    HeaderName::try_from(name).unwrap_or_else(|_| panic!("invalid mock header name: {name:?}"))
+}
+
+fn parse_header_value(value: &str) -> HeaderValue {
+    HeaderValue::try_from(value).unwrap_or_else(|_| panic!("invalid mock header value: {value:?}"))
+}
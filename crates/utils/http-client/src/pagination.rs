@@ -0,0 +1,236 @@
+//! Follow paginated REST endpoints until they are exhausted.
+//!
+//! [`paginate`] sends each page request through the full middleware stack,
+//! so retries, rate limiting and tracing apply per page.
+
+use std::{collections::HashSet, time::Duration};
+
+use futures_util::{Stream, stream};
+use reqwest::{
+    Request, StatusCode, Url,
+    header::{HeaderMap, LINK},
+};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::de::DeserializeOwned;
+
+use crate::{error::HttpError, scrub::Scrubber};
+
+type CursorFn<T> = Box<dyn Fn(&Request, &T) -> Option<Request> + Send + Sync>;
+
+enum NextPage<T> {
+    LinkHeader,
+    Cursor(CursorFn<T>),
+}
+
+/// Strategy for finding the next page, plus pagination limits.
+pub struct Paginator<T> {
+    next: NextPage<T>,
+    max_pages: Option<usize>,
+}
+
+impl<T> Paginator<T> {
+    /// Follow RFC 8288 `Link: <...>; rel="next"` response headers.
+    pub fn link_header() -> Self {
+        Self {
+            next: NextPage::LinkHeader,
+            max_pages: None,
+        }
+    }
+
+    /// Derive the next request from the previous request and the decoded
+    /// page, returning `None` on the last page.
+    pub fn cursor<F>(next: F) -> Self
+    where
+        F: Fn(&Request, &T) -> Option<Request> + Send + Sync + 'static,
+    {
+        Self {
+            next: NextPage::Cursor(Box::new(next)),
+            max_pages: None,
+        }
+    }
+
+    /// Stop after yielding `max_pages` pages, even if more are available.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// The request for the page after `page`, from a copy of the request
+    /// that fetched it.
+    fn next_request(&self, mut prev: Request, page: &Page<T>) -> Option<Request> {
+        match &self.next {
+            NextPage::LinkHeader => {
+                *prev.url_mut() = next_link(&page.headers, &page.url)?;
+                Some(prev)
+            }
+            NextPage::Cursor(next) => next(&prev, &page.items),
+        }
+    }
+}
+
+/// A single decoded page.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Page<T> {
+    pub items: T,
+    pub url: Url,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+}
+
+struct PageState<T> {
+    client: ClientWithMiddleware,
+    paginator: Paginator<T>,
+    next: Option<Request>,
+    seen: HashSet<(String, Vec<u8>)>,
+    pages: usize,
+    timeout: Option<Duration>,
+}
+
+/// Stream every page starting from `initial`.
+///
+/// Follow-up requests inherit the initial request's timeout when they do not
+/// set their own. The stream ends after the last page, after `max_pages`, or
+/// after the first error; requesting the same URL and body twice yields
+/// [`HttpError::PaginationLoop`]. Each request is copied to derive the next
+/// one, so a request whose body cannot be cloned yields
+/// [`HttpError::NotCloneable`] without being sent.
+pub fn paginate<T>(
+    client: &ClientWithMiddleware,
+    initial: Request,
+    paginator: Paginator<T>,
+) -> impl Stream<Item = Result<Page<T>, HttpError>>
+where
+    T: DeserializeOwned,
+{
+    let state = PageState {
+        client: client.clone(),
+        paginator,
+        timeout: initial.timeout().copied(),
+        next: Some(initial),
+        seen: HashSet::new(),
+        pages: 0,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        let mut req = state.next.take()?;
+        if state
+            .paginator
+            .max_pages
+            .is_some_and(|max| state.pages >= max)
+        {
+            return None;
+        }
+
+        let key = request_key(&req);
+        if !state.seen.insert(key) {
+            let err = HttpError::PaginationLoop {
+                url: req.url().clone(),
+            };
+            return Some((Err(err), state));
+        }
+
+        if req.timeout().is_none()
+            && let Some(timeout) = state.timeout
+        {
+            *req.timeout_mut() = Some(timeout);
+        }
+
+        // Keep a copy to derive the next request from once the page arrives,
+        // checked before sending so a streaming body fails up front
+        let Some(prev) = req.try_clone() else {
+            let err = HttpError::NotCloneable {
+                url: req.url().clone(),
+            };
+            return Some((Err(err), state));
+        };
+        let page = match fetch_page::<T>(&state.client, req).await {
+            Ok(page) => page,
+            Err(err) => return Some((Err(err), state)),
+        };
+        state.pages += 1;
+        state.next = state.paginator.next_request(prev, &page);
+
+        Some((Ok(page), state))
+    })
+}
+
+async fn fetch_page<T: DeserializeOwned>(
+    client: &ClientWithMiddleware,
+    req: Request,
+) -> Result<Page<T>, HttpError> {
+    let res = client.execute(req).await?;
+    let url = res.url().clone();
+    let status = res.status();
+
+    if !status.is_success() {
+        return Err(HttpError::Status { status, url });
+    }
+
+    let headers = res.headers().clone();
+    let items = res.json::<T>().await.map_err(|mut source| {
+        Scrubber::default().reqwest_error(&mut source);
+        HttpError::Decode {
+            url: url.clone(),
+            source,
+        }
+    })?;
+
+    Ok(Page {
+        items,
+        url,
+        status,
+        headers,
+    })
+}
+
+fn request_key(req: &Request) -> (String, Vec<u8>) {
+    let body = req
+        .body()
+        .and_then(|b| b.as_bytes())
+        .map(<[u8]>::to_vec)
+        .unwrap_or_default();
+    (format!("{} {}", req.method(), req.url()), body)
+}
+
+/// Extract the `rel="next"` target from `Link` headers, resolving relative
+/// references against `base`.
+pub(crate) fn next_link(headers: &HeaderMap, base: &Url) -> Option<Url> {
+    headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(parse_link_header)
+        .find(|(_, rels)| {
+            rels.split_ascii_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("next"))
+        })
+        .and_then(|(target, _)| base.join(target).ok())
+}
+
+/// Split a `Link` header value into `(target, rel)` pairs.
+fn parse_link_header(value: &str) -> Vec<(&str, &str)> {
+    let mut links = Vec::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let target = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let params_end = rest.find('<').unwrap_or(rest.len());
+        let rel = rest[..params_end]
+            .split(';')
+            .filter_map(|param| param.trim().split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("rel"))
+            .map(|(_, value)| value.trim().trim_end_matches(',').trim().trim_matches('"'))
+            .unwrap_or_default();
+
+        links.push((target, rel));
+        rest = &rest[params_end..];
+    }
+
+    links
+}
@@ -0,0 +1,229 @@
+//! Credential scrubbing for URLs, headers and error messages.
+//!
+//! Anything this crate writes to spans or error messages goes through a
+//! [`Scrubber`] first: userinfo is removed from URLs and sensitive query
+//! parameters, fragment parameters and headers are replaced with `***`.
+//! Requests and responses themselves are never modified, so the raw URL is
+//! still available from [`reqwest::Request::url`] and
+//! [`reqwest::Response::url`].
+
+use std::{borrow::Cow, error::Error as StdError, sync::Arc};
+
+use reqwest::{
+    Url,
+    header::{
+        AUTHORIZATION, COOKIE, HeaderMap, HeaderName, HeaderValue, PROXY_AUTHORIZATION, SET_COOKIE,
+    },
+};
+
+/// Parameter and header names redacted by [`Scrubber::default`].
+pub const DEFAULT_SENSITIVE_NAMES: &[&str] = &["api_key", "token", "signature"];
+
+/// Headers that always carry credentials and are redacted regardless of the
+/// configured names.
+const CREDENTIAL_HEADERS: &[HeaderName] = &[AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE, SET_COOKIE];
+
+const REDACTED: &str = "***";
+
+/// Redacts credentials before they reach logs and error messages.
+///
+/// A query parameter or header is sensitive when its lowercased name, with
+/// `-` read as `_`, contains one of the configured names, so `token` also
+/// covers `access_token` and `X-Auth-Token`.
+#[derive(Debug, Clone)]
+pub struct Scrubber {
+    sensitive_names: Arc<[String]>,
+}
+
+impl Default for Scrubber {
+    fn default() -> Self {
+        Self::new(DEFAULT_SENSITIVE_NAMES.iter().copied())
+    }
+}
+
+impl Scrubber {
+    pub fn new<I, S>(sensitive_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            sensitive_names: sensitive_names
+                .into_iter()
+                .map(|n| normalize_name(n.as_ref()))
+                .collect(),
+        }
+    }
+
+    pub fn is_sensitive(&self, name: &str) -> bool {
+        let name = normalize_name(name);
+        self.sensitive_names
+            .iter()
+            .any(|s| name.contains(s.as_str()))
+    }
+
+    /// Copy of `url` without userinfo and with sensitive query and fragment
+    /// parameter values replaced by `***`.
+    pub fn url(&self, url: &Url) -> Url {
+        let mut url = url.clone();
+        // Fails only for URLs that cannot carry userinfo in the first place
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+
+        if let Some(query) = url.query().map(|q| self.params(q))
+            && let Cow::Owned(query) = query
+        {
+            url.set_query(Some(&query));
+        }
+        if let Some(fragment) = url.fragment().map(|f| self.params(f))
+            && let Cow::Owned(fragment) = fragment
+        {
+            url.set_fragment(Some(&fragment));
+        }
+
+        url
+    }
+
+    /// Copy of `headers` with sensitive values replaced by `***`.
+    pub fn headers(&self, headers: &HeaderMap) -> HeaderMap {
+        let mut scrubbed = headers.clone();
+        for (name, value) in scrubbed.iter_mut() {
+            if CREDENTIAL_HEADERS.contains(name) || self.is_sensitive(name.as_str()) {
+                *value = HeaderValue::from_static(REDACTED);
+            }
+        }
+        scrubbed
+    }
+
+    /// Scrub every `http://` or `https://` URL embedded in free text, such as
+    /// an error message. URLs that fail to parse lose everything after the
+    /// scheme.
+    pub fn text(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = find_url_start(rest) {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let end = rest
+                .find(|c: char| {
+                    c.is_whitespace() || matches!(c, ')' | '"' | '\'' | '<' | '>' | '`')
+                })
+                .unwrap_or(rest.len());
+            match Url::parse(&rest[..end]) {
+                Ok(url) => out.push_str(self.url(&url).as_str()),
+                Err(_) => {
+                    let scheme_end = rest.find("://").map_or(0, |i| i + 3);
+                    out.push_str(&rest[..scheme_end]);
+                    out.push_str(REDACTED);
+                }
+            }
+            rest = &rest[end..];
+        }
+
+        out.push_str(rest);
+        out
+    }
+
+    /// `err` and its sources joined with `: `, scrubbed with [`Scrubber::text`].
+    pub fn error_chain(&self, err: &(dyn StdError + 'static)) -> String {
+        let mut chain = err.to_string();
+        let mut source = err.source();
+        while let Some(err) = source {
+            chain.push_str(": ");
+            chain.push_str(&err.to_string());
+            source = err.source();
+        }
+        self.text(&chain)
+    }
+
+    /// Scrub the URL carried by `err` and any reqwest error nested in it,
+    /// including errors wrapped by the retry middleware.
+    pub(crate) fn middleware_error(&self, err: &mut reqwest_middleware::Error) {
+        match err {
+            reqwest_middleware::Error::Reqwest(e) => self.reqwest_error(e),
+            reqwest_middleware::Error::Middleware(e) => {
+                if let Some(e) = e.downcast_mut::<reqwest::Error>() {
+                    self.reqwest_error(e);
+                } else if let Some(
+                    reqwest_retry::RetryError::WithRetries { err, .. }
+                    | reqwest_retry::RetryError::Error(err),
+                ) = e.downcast_mut::<reqwest_retry::RetryError>()
+                {
+                    self.middleware_error(err);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn reqwest_error(&self, err: &mut reqwest::Error) {
+        if let Some(url) = err.url_mut() {
+            *url = self.url(url);
+        }
+    }
+
+    /// Redact sensitive values in a `name=value&...` string, keeping the
+    /// original encoding of everything else.
+    fn params<'a>(&self, params: &'a str) -> Cow<'a, str> {
+        let mut changed = false;
+        let scrubbed: Vec<Cow<'_, str>> = params
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if self.is_sensitive(&percent_decode(name)) => {
+                    changed = true;
+                    Cow::Owned(format!("{name}={REDACTED}"))
+                }
+                _ => Cow::Borrowed(pair),
+            })
+            .collect();
+
+        if changed {
+            Cow::Owned(scrubbed.join("&"))
+        } else {
+            Cow::Borrowed(params)
+        }
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace('-', "_")
+}
+
+fn find_url_start(text: &str) -> Option<usize> {
+    let lower = text.to_ascii_lowercase();
+    ["http://", "https://"]
+        .iter()
+        .filter_map(|scheme| lower.find(scheme))
+        .min()
+}
+
+/// Decode `%XX` escapes and `+`, leaving malformed escapes as they are.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = match bytes.get(i..i + 3) {
+            Some([b'%', hi, lo]) => hex(*hi).zip(hex(*lo)).map(|(hi, lo)| hi << 4 | lo),
+            _ => None,
+        };
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+            (None, b'+') => out.push(b' '),
+            (None, byte) => out.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex(digit: u8) -> Option<u8> {
+    char::from(digit).to_digit(16).map(|d| d as u8)
+}
@@ -0,0 +1,102 @@
+//! Variant clients sharing one connection pool.
+//!
+//! [`SharedClient`] holds a single `reqwest::Client`, which is reference
+//! counted internally, and wraps it in a new middleware stack per
+//! [`SharedClient::derive`] call. Everything owned by the inner client is
+//! fixed at [`HttpClientBuilder::build_shared`] time and has no field in
+//! [`DeriveOptions`]: TLS and pinned certificates, proxies, pool and
+//! keepalive settings, timeouts, compression, DNS overrides and the URL
+//! guard's redirect policy.
+//!
+//! [`HttpClientBuilder::build_shared`]: crate::HttpClientBuilder::build_shared
+
+use std::sync::Arc;
+
+use reqwest::{Client, header::HeaderMap};
+use reqwest_middleware::{ClientWithMiddleware, Middleware};
+
+use crate::{
+    builder::{middleware_stack, retry_middleware},
+    middleware::{
+        headers::DefaultHeadersMiddleware, retry::ErrorClassifier, url_guard::UrlGuardMiddleware,
+    },
+};
+
+/// Settings a derived client may change, initialised from the builder.
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct DeriveOptions {
+    /// Headers added to requests that do not set them.
+    pub default_headers: HeaderMap,
+    pub retry_enabled: bool,
+    pub max_retries: u32,
+    pub retry_classifier: Option<Arc<dyn ErrorClassifier>>,
+    /// Middleware added with the builder, in order. The URL guard is not
+    /// part of this list and always stays in place.
+    pub middleware: Vec<Arc<dyn Middleware>>,
+}
+
+impl DeriveOptions {
+    pub fn with_middleware<M>(&mut self, middleware: M) -> &mut Self
+    where
+        M: Middleware + Send + Sync + 'static,
+    {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+}
+
+/// Connection pool and TLS session cache shared by derived clients.
+#[derive(Clone)]
+pub struct SharedClient {
+    client: Client,
+    template: DeriveOptions,
+    url_guard: Option<UrlGuardMiddleware>,
+    sensitive_names: Option<Vec<String>>,
+}
+
+impl SharedClient {
+    pub(crate) fn new(
+        client: Client,
+        template: DeriveOptions,
+        url_guard: Option<UrlGuardMiddleware>,
+        sensitive_names: Option<Vec<String>>,
+    ) -> Self {
+        Self {
+            client,
+            template,
+            url_guard,
+            sensitive_names,
+        }
+    }
+
+    /// Create a client over the shared pool, adjusting the builder's
+    /// settings with `configure`.
+    pub fn derive<F>(&self, configure: F) -> ClientWithMiddleware
+    where
+        F: FnOnce(&mut DeriveOptions),
+    {
+        let mut options = self.template.clone();
+        configure(&mut options);
+
+        let default_headers = (!options.default_headers.is_empty())
+            .then(|| DefaultHeadersMiddleware::new(options.default_headers));
+        middleware_stack(
+            self.client.clone(),
+            retry_middleware(
+                options.retry_enabled,
+                options.max_retries,
+                options.retry_classifier,
+            ),
+            self.url_guard.clone(),
+            default_headers,
+            options.middleware,
+            self.sensitive_names.clone(),
+        )
+    }
+
+    /// Client with the builder's settings unchanged.
+    pub fn client(&self) -> ClientWithMiddleware {
+        self.derive(|_| {})
+    }
+}
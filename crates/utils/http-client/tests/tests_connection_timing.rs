@@ -0,0 +1,126 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use http_client::{HttpClientBuilder, builder::HttpClientBuilderConfig, middleware::RequestTiming};
+use tracing::{
+    Subscriber,
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{Registry, layer::Context, layer::SubscriberExt};
+use wiremock::{Mock, MockServer, ResponseTemplate, matchers::any};
+
+fn no_retry_config() -> HttpClientBuilderConfig {
+    HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        ..Default::default()
+    }
+}
+
+async fn delayed_server(delay: Duration) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200).set_delay(delay))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[derive(Clone, Default)]
+struct RecordedFields(Arc<Mutex<Vec<HashMap<String, String>>>>);
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for RecordedFields
+where
+    S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_record(&self, _id: &span::Id, values: &span::Record<'_>, _ctx: Context<'_, S>) {
+        let mut fields = HashMap::new();
+        values.record(&mut FieldVisitor(&mut fields));
+        self.0.lock().unwrap().push(fields);
+    }
+}
+
+impl RecordedFields {
+    fn value_of(&self, name: &str) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|fields| fields.get(name).cloned())
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_timing_reports_ttfb_and_connection_reuse() {
+    let server = delayed_server(Duration::from_millis(100)).await;
+    let client = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_tracing()
+        .build();
+
+    let first = client.get(server.uri()).send().await.unwrap();
+    let first_timing = *first.extensions().get::<RequestTiming>().unwrap();
+    first.bytes().await.unwrap();
+
+    let second = client.get(server.uri()).send().await.unwrap();
+    let second_timing = *second.extensions().get::<RequestTiming>().unwrap();
+
+    assert!(first_timing.ttfb >= Duration::from_millis(100));
+    assert!(first_timing.ttfb < Duration::from_secs(5));
+    assert_eq!(first_timing.connection_reused, Some(false));
+    assert!(first_timing.connect.is_some());
+
+    assert!(second_timing.ttfb >= Duration::from_millis(100));
+    assert_eq!(second_timing.connection_reused, Some(true));
+    assert_eq!(second_timing.connect, None);
+}
+
+#[tokio::test]
+async fn test_timing_recorded_on_span() {
+    let recorded = RecordedFields::default();
+    let subscriber = Registry::default().with(recorded.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let server = delayed_server(Duration::from_millis(20)).await;
+    let client = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_tracing()
+        .build();
+
+    client
+        .get(server.uri())
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    client.get(server.uri()).send().await.unwrap();
+
+    assert_eq!(recorded.value_of("http.conn.reused"), ["false", "true"]);
+    assert_eq!(recorded.value_of("http.connect_ms").len(), 1);
+
+    let ttfb = recorded.value_of("http.ttfb_ms");
+    assert_eq!(ttfb.len(), 2);
+    assert!(ttfb.iter().all(|ms| ms.parse::<i64>().unwrap() >= 20));
+}
+
+#[tokio::test]
+async fn test_timing_absent_without_tracing() {
+    let server = delayed_server(Duration::ZERO).await;
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build();
+
+    let res = client.get(server.uri()).send().await.unwrap();
+    assert!(res.extensions().get::<RequestTiming>().is_none());
+}
@@ -0,0 +1,187 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use http_client::{ConfigError, HttpClientBuilder, IpPreference, builder::HttpClientBuilderConfig};
+use tokio::net::TcpListener;
+use wiremock::{Mock, MockServer, ResponseTemplate, matchers::any};
+
+#[test]
+fn test_ip_preference_orders_addresses() {
+    let v4a: SocketAddr = "10.0.0.1:80".parse().unwrap();
+    let v4b: SocketAddr = "10.0.0.2:80".parse().unwrap();
+    let v6a: SocketAddr = "[2001:db8::1]:80".parse().unwrap();
+    let v6b: SocketAddr = "[2001:db8::2]:80".parse().unwrap();
+    let resolved = [v6a, v4a, v6b, v4b];
+
+    let cases = [
+        (IpPreference::Auto, [v6a, v4a, v6b, v4b]),
+        (IpPreference::PreferIpv4, [v4a, v4b, v6a, v6b]),
+        (IpPreference::PreferIpv6, [v6a, v6b, v4a, v4b]),
+    ];
+
+    for (preference, expected) in cases {
+        let mut addrs = resolved;
+        preference.order(&mut addrs);
+        assert_eq!(addrs, expected, "{preference:?}");
+    }
+}
+
+async fn dual_stack_client(
+    preference: IpPreference,
+) -> (MockServer, http_client::ClientWithMiddleware) {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    // The server only listens on IPv4; 100::/64 is a discard-only prefix
+    let port = server.address().port();
+    let overrides = HashMap::from([(
+        "dual.test".to_string(),
+        vec![
+            SocketAddr::new("100::1".parse().unwrap(), port),
+            SocketAddr::new("127.0.0.1".parse().unwrap(), port),
+        ],
+    )]);
+
+    let client = HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        tcp_keepalive: Some(Duration::from_secs(30)),
+        ip_preference: Some(preference),
+        dns_overrides: Some(overrides),
+        ..Default::default()
+    }))
+    .build();
+
+    (server, client)
+}
+
+/// Listen on the IPv6 loopback at `port`, counting and dropping connections.
+/// `None` when the host has no IPv6 loopback.
+async fn counting_ipv6_listener(port: u16) -> Option<Arc<AtomicUsize>> {
+    let listener = TcpListener::bind(("::1", port)).await.ok()?;
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+    tokio::spawn(async move {
+        while listener.accept().await.is_ok() {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    Some(connections)
+}
+
+#[tokio::test]
+async fn test_prefer_ipv4_connects_to_ipv4_first() {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let port = server.address().port();
+    let Some(ipv6_connections) = counting_ipv6_listener(port).await else {
+        eprintln!("skipping: no IPv6 loopback");
+        return;
+    };
+
+    // Listed IPv6 first, so only the preference moves IPv4 to the front
+    let overrides = HashMap::from([(
+        "dual.test".to_string(),
+        vec![
+            SocketAddr::new("::1".parse().unwrap(), port),
+            SocketAddr::new("127.0.0.1".parse().unwrap(), port),
+        ],
+    )]);
+    let client = HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        ip_preference: Some(IpPreference::PreferIpv4),
+        dns_overrides: Some(overrides),
+        ..Default::default()
+    }))
+    .build();
+
+    let res = client
+        .get(format!("http://dual.test:{port}/"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), 200);
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    assert_eq!(ipv6_connections.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_validate_rejects_unsupported_combinations() {
+    assert_eq!(HttpClientBuilderConfig::default().validate(), Ok(()));
+
+    let zero_keepalive = HttpClientBuilderConfig {
+        tcp_keepalive: Some(Duration::ZERO),
+        ..Default::default()
+    };
+    assert_eq!(zero_keepalive.validate(), Err(ConfigError::ZeroKeepalive));
+
+    let short_connect_timeout = |ip_preference| HttpClientBuilderConfig {
+        connect_timeout: Some(Duration::from_millis(200)),
+        ip_preference: Some(ip_preference),
+        ..Default::default()
+    };
+    assert!(matches!(
+        short_connect_timeout(IpPreference::PreferIpv6).validate(),
+        Err(ConfigError::NoFamilyFallback {
+            connect_timeout,
+            ip_preference: IpPreference::PreferIpv6,
+            ..
+        }) if connect_timeout == Duration::from_millis(200)
+    ));
+    assert_eq!(short_connect_timeout(IpPreference::Auto).validate(), Ok(()));
+}
+
+#[test]
+fn test_try_build_returns_config_error() {
+    let err = HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        tcp_keepalive: Some(Duration::ZERO),
+        ..Default::default()
+    }))
+    .try_build()
+    .unwrap_err();
+
+    assert_eq!(err, ConfigError::ZeroKeepalive);
+}
+
+#[tokio::test]
+async fn test_prefer_ipv6_falls_back_to_ipv4() {
+    let (server, client) = dual_stack_client(IpPreference::PreferIpv6).await;
+    let url = format!("http://dual.test:{}/", server.address().port());
+
+    let res = client.get(&url).send().await.unwrap();
+
+    assert_eq!(res.status(), 200);
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_default_keepalive_client_still_connects() {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+    let client = HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        tcp_keepalive: None,
+        ip_preference: Some(IpPreference::PreferIpv4),
+        ..Default::default()
+    }))
+    .build();
+
+    // Resolved by the system resolver wrapped with the preference
+    let url = format!("http://localhost:{}/", server.address().port());
+    assert_eq!(client.get(&url).send().await.unwrap().status(), 204);
+}
@@ -0,0 +1,294 @@
+use std::{error::Error as _, io, time::Duration};
+
+use http_client::{
+    HttpClientBuilder,
+    builder::HttpClientBuilderConfig,
+    mock::{Mock, MockResponse, MockTransport},
+};
+use reqwest::{Method, StatusCode};
+
+fn no_retry_config() -> HttpClientBuilderConfig {
+    HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        ..Default::default()
+    }
+}
+
+/// Kind of the I/O error behind a reqwest connect error.
+fn io_error_kind(err: &reqwest_middleware::Error) -> Option<io::ErrorKind> {
+    let reqwest_middleware::Error::Reqwest(err) = err else {
+        return None;
+    };
+    assert!(err.is_connect(), "not a connect error: {err:?}");
+
+    let mut source = err.source();
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<io::Error>() {
+            return Some(io.kind());
+        }
+        source = err.source();
+    }
+    None
+}
+
+#[tokio::test]
+async fn test_mock_lower_priority_value_wins() {
+    let mock = MockTransport::new();
+    mock.register(Mock::new().respond_with(MockResponse::new(500)))
+        .register(
+            Mock::new()
+                .path("/users")
+                .priority(1)
+                .respond_with(MockResponse::new(200).body("users")),
+        );
+
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock);
+
+    let res = client.get("http://api.test/users").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "users");
+
+    let res = client.get("http://api.test/other").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_mock_equal_priority_uses_registration_order() {
+    let mock = MockTransport::new();
+    mock.register(
+        Mock::new()
+            .method(Method::GET)
+            .respond_with(MockResponse::new(201)),
+    )
+    .register(
+        Mock::new()
+            .method(Method::GET)
+            .respond_with(MockResponse::new(202)),
+    );
+
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock);
+
+    let res = client.get("http://api.test/").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_mock_matches_header_and_body() {
+    let mock = MockTransport::new();
+    mock.register(
+        Mock::new()
+            .method(Method::POST)
+            .header("x-api-key", "secret")
+            .body_matches(|b| b == b"ping")
+            .respond_with(MockResponse::new(200).header("x-reply", "pong")),
+    )
+    .register(
+        Mock::new()
+            .priority(10)
+            .respond_with(MockResponse::new(403)),
+    );
+
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock);
+
+    let res = client
+        .post("http://api.test/echo")
+        .header("x-api-key", "secret")
+        .body("ping")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-reply"], "pong");
+
+    let res = client
+        .post("http://api.test/echo")
+        .header("x-api-key", "wrong")
+        .body("ping")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_mock_records_requests_with_default_headers() {
+    let mock = MockTransport::new();
+    mock.register(Mock::new());
+
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock.clone());
+
+    client
+        .put("http://api.test/items/1")
+        .body("payload")
+        .send()
+        .await
+        .unwrap();
+
+    let received = mock.received_requests();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].method, Method::PUT);
+    assert_eq!(received[0].url.path(), "/items/1");
+    assert_eq!(received[0].body.as_deref(), Some(&b"payload"[..]));
+    assert_eq!(received[0].headers["accept"], "application/json");
+}
+
+#[tokio::test]
+async fn test_mock_retries_transient_status_through_middleware() {
+    let mock = MockTransport::new();
+    mock.register(
+        Mock::new()
+            .up_to_n_times(2)
+            .respond_with(MockResponse::new(503)),
+    )
+    .register(Mock::new().respond_with(MockResponse::new(200)));
+
+    let client = HttpClientBuilder::new(None).build_with_mock(mock.clone());
+
+    let res = client.get("http://api.test/flaky").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(mock.received_requests().len(), 3);
+}
+
+#[tokio::test]
+async fn test_mock_delay_within_timeout_succeeds() {
+    let mock = MockTransport::new();
+    mock.register(
+        Mock::new().respond_with(MockResponse::new(200).delay(Duration::from_millis(10))),
+    );
+
+    let config = HttpClientBuilderConfig {
+        timeout: Some(Duration::from_millis(500)),
+        ..no_retry_config()
+    };
+    let client = HttpClientBuilder::new(Some(config)).build_with_mock(mock);
+
+    let res = client.get("http://api.test/slow").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_mock_delay_beyond_timeout_times_out() {
+    let mock = MockTransport::new();
+    mock.register(Mock::new().respond_with(MockResponse::new(200).delay(Duration::from_secs(5))));
+
+    let config = HttpClientBuilderConfig {
+        timeout: Some(Duration::from_millis(20)),
+        ..no_retry_config()
+    };
+    let client = HttpClientBuilder::new(Some(config)).build_with_mock(mock);
+
+    let err = client.get("http://api.test/slow").send().await.unwrap_err();
+    assert_eq!(io_error_kind(&err), Some(io::ErrorKind::TimedOut));
+    assert!(matches!(&err, reqwest_middleware::Error::Reqwest(e) if e.is_timeout()));
+}
+
+#[tokio::test]
+async fn test_mock_per_request_timeout_overrides_client_timeout() {
+    let mock = MockTransport::new();
+    mock.register(Mock::new().respond_with(MockResponse::new(200).delay(Duration::from_secs(5))));
+
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock);
+
+    let err = client
+        .get("http://api.test/slow")
+        .timeout(Duration::from_millis(20))
+        .send()
+        .await
+        .unwrap_err();
+    assert_eq!(io_error_kind(&err), Some(io::ErrorKind::TimedOut));
+}
+
+#[tokio::test]
+async fn test_mock_transport_error() {
+    let mock = MockTransport::new();
+    mock.register(Mock::new().respond_with(MockResponse::transport_error(
+        io::ErrorKind::ConnectionRefused,
+    )));
+
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock);
+
+    let err = client.get("http://api.test/").send().await.unwrap_err();
+    assert_eq!(io_error_kind(&err), Some(io::ErrorKind::ConnectionRefused));
+    assert!(matches!(
+        reqwest_retry::default_on_request_failure(&err),
+        Some(reqwest_retry::Retryable::Transient)
+    ));
+}
+
+#[tokio::test]
+async fn test_mock_concurrent_transport_errors_keep_their_kind() {
+    let kinds = [
+        io::ErrorKind::ConnectionRefused,
+        io::ErrorKind::ConnectionReset,
+        io::ErrorKind::ConnectionAborted,
+        io::ErrorKind::NotConnected,
+    ];
+    let mock = MockTransport::new();
+    for (i, kind) in kinds.into_iter().enumerate() {
+        mock.register(
+            Mock::new()
+                .path(format!("/{i}"))
+                .respond_with(MockResponse::transport_error(kind)),
+        );
+    }
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock);
+
+    let tasks: Vec<_> = (0..64)
+        .map(|n| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                let i = n % kinds.len();
+                let url = format!("http://api.test/{i}");
+                let err = client.post(&url).send().await.unwrap_err();
+                assert_eq!(io_error_kind(&err), Some(kinds[i]));
+                assert_eq!(err.url().map(|u| u.as_str()), Some(url.as_str()));
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_mock_transport_error_is_retried() {
+    let mock = MockTransport::new();
+    mock.register(Mock::new().priority(1).up_to_n_times(1).respond_with(
+        MockResponse::transport_error(io::ErrorKind::ConnectionReset),
+    ))
+    .register(Mock::new());
+
+    let config = HttpClientBuilderConfig {
+        retry_enabled: Some(true),
+        max_retries: Some(1),
+        ..Default::default()
+    };
+    let client = HttpClientBuilder::new(Some(config)).build_with_mock(mock.clone());
+
+    let res = client.get("http://api.test/").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(mock.received_requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_mock_lenient_returns_not_found() {
+    let mock = MockTransport::lenient();
+    mock.register(Mock::new().path("/known"));
+
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock.clone());
+
+    let res = client.get("http://api.test/unknown").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert_eq!(mock.received_requests().len(), 1);
+}
+
+#[tokio::test]
+#[should_panic(expected = "no mock matched GET http://api.test/unknown")]
+async fn test_mock_strict_panics_on_unmatched() {
+    let mock = MockTransport::strict();
+    mock.register(Mock::new().path("/known"));
+
+    let client = HttpClientBuilder::new(Some(no_retry_config())).build_with_mock(mock);
+
+    let _ = client.get("http://api.test/unknown").send().await;
+}
@@ -0,0 +1,243 @@
+use std::time::Duration;
+
+use futures_util::{StreamExt, TryStreamExt};
+use http_client::{
+    HttpClientBuilder, HttpError, Paginator, builder::HttpClientBuilderConfig, paginate,
+};
+use serde::Deserialize;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path, query_param},
+};
+
+fn client() -> http_client::ClientWithMiddleware {
+    HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        ..Default::default()
+    }))
+    .build()
+}
+
+async fn mount_page(server: &MockServer, page: u32, items: &[u32], link: Option<&str>) {
+    let mut response = ResponseTemplate::new(200).set_body_json(items);
+    if let Some(link) = link {
+        response = response.insert_header("link", link);
+    }
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(query_param("page", page.to_string()))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+async fn linked_server() -> MockServer {
+    let server = MockServer::start().await;
+    let base = server.uri();
+    mount_page(
+        &server,
+        1,
+        &[1, 2],
+        Some(&format!(
+            r#"<{base}/items?page=2>; rel="next", <{base}/items?page=3>; rel="last""#
+        )),
+    )
+    .await;
+    // Relative target with the rel parameter not last
+    mount_page(
+        &server,
+        2,
+        &[3, 4],
+        Some(r#"</items?page=3>; rel="next"; title="more""#),
+    )
+    .await;
+    mount_page(&server, 3, &[5], Some(r#"</items?page=1>; rel="first""#)).await;
+    server
+}
+
+#[tokio::test]
+async fn test_paginate_follows_link_headers_in_order() {
+    let server = linked_server().await;
+    let client = client();
+    let initial = client
+        .get(format!("{}/items?page=1", server.uri()))
+        .build()
+        .unwrap();
+
+    let pages: Vec<_> = paginate::<Vec<u32>>(&client, initial, Paginator::link_header())
+        .try_collect()
+        .await
+        .unwrap();
+
+    let items: Vec<u32> = pages.iter().flat_map(|p| p.items.clone()).collect();
+    assert_eq!(items, [1, 2, 3, 4, 5]);
+    assert_eq!(pages[2].url.query(), Some("page=3"));
+}
+
+#[tokio::test]
+async fn test_paginate_truncates_at_max_pages() {
+    let server = linked_server().await;
+    let client = client();
+    let initial = client
+        .get(format!("{}/items?page=1", server.uri()))
+        .build()
+        .unwrap();
+
+    let pages: Vec<_> =
+        paginate::<Vec<u32>>(&client, initial, Paginator::link_header().max_pages(2))
+            .try_collect()
+            .await
+            .unwrap();
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_paginate_detects_link_loop() {
+    let server = MockServer::start().await;
+    mount_page(&server, 1, &[1], Some(r#"</items?page=2>; rel="next""#)).await;
+    mount_page(&server, 2, &[2], Some(r#"</items?page=1>; rel="next""#)).await;
+
+    let client = client();
+    let initial = client
+        .get(format!("{}/items?page=1", server.uri()))
+        .build()
+        .unwrap();
+
+    let results: Vec<_> = paginate::<Vec<u32>>(&client, initial, Paginator::link_header())
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok() && results[1].is_ok());
+    assert!(matches!(results[2], Err(HttpError::PaginationLoop { .. })));
+}
+
+#[derive(Debug, Deserialize)]
+struct CursorPage {
+    items: Vec<u32>,
+    next_cursor: Option<String>,
+}
+
+#[tokio::test]
+async fn test_paginate_with_body_cursor() {
+    let server = MockServer::start().await;
+    Mock::given(path("/feed"))
+        .and(query_param("cursor", "b"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "items": [3], "next_cursor": null })),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(path("/feed"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "items": [1, 2], "next_cursor": "b" })),
+        )
+        .with_priority(10)
+        .mount(&server)
+        .await;
+
+    let client = client();
+    let initial = client
+        .get(format!("{}/feed", server.uri()))
+        .timeout(Duration::from_secs(3))
+        .build()
+        .unwrap();
+
+    let paginator = Paginator::cursor(|prev: &reqwest::Request, page: &CursorPage| {
+        let cursor = page.next_cursor.as_ref()?;
+        let mut url = prev.url().clone();
+        url.query_pairs_mut().clear().append_pair("cursor", cursor);
+        Some(reqwest::Request::new(prev.method().clone(), url))
+    });
+
+    let pages: Vec<_> = paginate(&client, initial, paginator)
+        .try_collect()
+        .await
+        .unwrap();
+
+    let items: Vec<u32> = pages.iter().flat_map(|p| p.items.items.clone()).collect();
+    assert_eq!(items, [1, 2, 3]);
+}
+
+#[tokio::test]
+async fn test_paginate_next_pages_inherit_initial_timeout() {
+    let server = MockServer::start().await;
+    mount_page(&server, 1, &[1], Some(r#"</items?page=2>; rel="next""#)).await;
+    Mock::given(query_param("page", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json([2])
+                .set_delay(Duration::from_secs(2)),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client();
+    let initial = client
+        .get(format!("{}/items?page=1", server.uri()))
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    // The cursor builds a fresh request without a timeout of its own
+    let paginator = Paginator::cursor(|prev: &reqwest::Request, _: &Vec<u32>| {
+        let mut url = prev.url().clone();
+        url.set_query(Some("page=2"));
+        (prev.url() != &url).then(|| reqwest::Request::new(prev.method().clone(), url))
+    });
+
+    let results: Vec<_> = paginate(&client, initial, paginator).collect().await;
+
+    assert_eq!(results.len(), 2);
+    assert!(matches!(results[1], Err(HttpError::Request { .. })));
+}
+
+#[tokio::test]
+async fn test_paginate_stops_on_error_status() {
+    let server = MockServer::start().await;
+    mount_page(&server, 1, &[1], Some(r#"</items?page=2>; rel="next""#)).await;
+    Mock::given(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let client = client();
+    let initial = client
+        .get(format!("{}/items?page=1", server.uri()))
+        .build()
+        .unwrap();
+
+    let results: Vec<_> = paginate::<Vec<u32>>(&client, initial, Paginator::link_header())
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(matches!(
+        results[1],
+        Err(HttpError::Status { status, .. }) if status == 404
+    ));
+}
+
+#[tokio::test]
+async fn test_paginate_rejects_non_cloneable_initial_request() {
+    let server = linked_server().await;
+    let client = client();
+    let body = futures_util::stream::iter([Ok::<_, std::io::Error>(b"{}".to_vec())]);
+    let initial = client
+        .get(format!("{}/items?page=1", server.uri()))
+        .body(reqwest::Body::wrap_stream(body))
+        .build()
+        .unwrap();
+
+    let results: Vec<_> = paginate::<Vec<u32>>(&client, initial, Paginator::link_header())
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(HttpError::NotCloneable { .. })));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
@@ -0,0 +1,292 @@
+use std::{
+    io,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use http_client::{
+    BlockReason, BlockedDestination, HttpClientBuilder,
+    builder::HttpClientBuilderConfig,
+    middleware::{DefaultClassifier, ErrorClassifier, Retryable},
+};
+use reqwest::Response;
+use rustls::CertificateError;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+fn classify_error(err: reqwest_middleware::Error) -> Option<Retryable> {
+    DefaultClassifier.classify(&Err(err))
+}
+
+fn classify_response(status: u16, retry_after: Option<&str>) -> Option<Retryable> {
+    let mut res = http::Response::builder().status(status);
+    if let Some(value) = retry_after {
+        res = res.header("retry-after", value);
+    }
+    DefaultClassifier.classify(&Ok(Response::from(res.body("").unwrap())))
+}
+
+fn closed_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+#[test]
+fn test_default_classifier_io_errors() {
+    let cases = [
+        (
+            io::Error::from(io::ErrorKind::ConnectionRefused),
+            Retryable::Transient,
+        ),
+        (
+            io::Error::from(io::ErrorKind::ConnectionReset),
+            Retryable::Transient,
+        ),
+        (
+            io::Error::from(io::ErrorKind::ConnectionAborted),
+            Retryable::Transient,
+        ),
+        (
+            io::Error::from(io::ErrorKind::TimedOut),
+            Retryable::Transient,
+        ),
+        (
+            io::Error::from(io::ErrorKind::UnexpectedEof),
+            Retryable::Transient,
+        ),
+        (
+            io::Error::from(io::ErrorKind::PermissionDenied),
+            Retryable::Fatal,
+        ),
+        (
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                rustls::Error::InvalidCertificate(CertificateError::Expired),
+            ),
+            Retryable::Fatal,
+        ),
+    ];
+
+    for (err, expected) in cases {
+        let message = err.to_string();
+        assert_eq!(
+            classify_error(reqwest_middleware::Error::middleware(err)),
+            Some(expected),
+            "classifying {message}"
+        );
+    }
+}
+
+#[test]
+fn test_default_classifier_tls_validation_is_fatal() {
+    let err = rustls::Error::InvalidCertificate(CertificateError::UnknownIssuer);
+
+    assert_eq!(
+        classify_error(reqwest_middleware::Error::middleware(err)),
+        Some(Retryable::Fatal)
+    );
+}
+
+#[test]
+fn test_default_classifier_statuses() {
+    let cases = [
+        (200, None, None),
+        (404, None, None),
+        (400, Some("5"), None),
+        (408, None, Some(Retryable::Transient)),
+        (429, None, Some(Retryable::Transient)),
+        (
+            429,
+            Some("2"),
+            Some(Retryable::RateLimited(Duration::from_secs(2))),
+        ),
+        (500, None, Some(Retryable::Transient)),
+        (500, Some("2"), Some(Retryable::Transient)),
+        (502, None, Some(Retryable::Transient)),
+        (
+            503,
+            Some(" 7 "),
+            Some(Retryable::RateLimited(Duration::from_secs(7))),
+        ),
+        (
+            503,
+            Some("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Retryable::Transient),
+        ),
+    ];
+
+    for (status, retry_after, expected) in cases {
+        assert_eq!(
+            classify_response(status, retry_after),
+            expected,
+            "classifying {status} with Retry-After {retry_after:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_default_classifier_reqwest_errors() {
+    let client = reqwest::Client::new();
+
+    let refused = client
+        .get(format!("http://127.0.0.1:{}/", closed_port()))
+        .send()
+        .await
+        .unwrap_err();
+    assert_eq!(classify_error(refused.into()), Some(Retryable::Transient));
+
+    let build = client.get("not a url").send().await.unwrap_err();
+    assert!(build.is_builder());
+    assert_eq!(classify_error(build.into()), Some(Retryable::Fatal));
+
+    // TLS handshake against a plain HTTP server
+    let server = MockServer::start().await;
+    let tls = client
+        .get(format!("https://{}/", server.address()))
+        .send()
+        .await
+        .unwrap_err();
+    assert_eq!(classify_error(tls.into()), Some(Retryable::Fatal));
+
+    // Lookup failures are connect errors, whatever the resolver's message
+    let dns = client
+        .get("http://unknown-host.invalid/")
+        .send()
+        .await
+        .unwrap_err();
+    assert!(dns.is_connect());
+    assert_eq!(classify_error(dns.into()), Some(Retryable::Transient));
+}
+
+#[test]
+fn test_default_classifier_blocked_destination_is_fatal() {
+    let blocked = BlockedDestination::new(
+        "http://169.254.169.254/".parse().unwrap(),
+        BlockReason::DeniedAddress("169.254.169.254".parse().unwrap()),
+    );
+    // As raised from the guard's resolver, below a connect error
+    let err = io::Error::new(io::ErrorKind::ConnectionRefused, blocked);
+
+    assert_eq!(
+        classify_error(reqwest_middleware::Error::middleware(err)),
+        Some(Retryable::Fatal)
+    );
+}
+
+fn retrying_client(max_retries: u32) -> http_client::ClientWithMiddleware {
+    HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        retry_enabled: Some(true),
+        max_retries: Some(max_retries),
+        ..Default::default()
+    }))
+    .build()
+}
+
+async fn flaky_server(status: u16, retry_after: Option<&str>) -> MockServer {
+    let server = MockServer::start().await;
+    let mut failure = ResponseTemplate::new(status);
+    if let Some(value) = retry_after {
+        failure = failure.insert_header("retry-after", value);
+    }
+    Mock::given(method("GET"))
+        .and(path("/flaky"))
+        .respond_with(failure)
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/flaky"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_retry_recovers_from_transient_statuses() {
+    for status in [408, 500, 502] {
+        let server = flaky_server(status, None).await;
+
+        let res = retrying_client(2)
+            .get(format!("{}/flaky", server.uri()))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), 200, "after {status}");
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+}
+
+#[tokio::test]
+async fn test_retry_skips_client_errors() {
+    let server = flaky_server(404, None).await;
+
+    let res = retrying_client(2)
+        .get(format!("{}/flaky", server.uri()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), 404);
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_retry_honours_retry_after() {
+    let server = flaky_server(429, Some("1")).await;
+
+    let start = Instant::now();
+    let res = retrying_client(2)
+        .get(format!("{}/flaky", server.uri()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), 200);
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_retry_gives_up_after_max_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let res = retrying_client(2).get(server.uri()).send().await.unwrap();
+
+    assert_eq!(res.status(), 503);
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_retry_with_custom_classifier() {
+    let server = flaky_server(404, None).await;
+    let classifier = |outcome: &reqwest_middleware::Result<Response>| match outcome {
+        Ok(res) if res.status() == 404 => Some(Retryable::Transient),
+        _ => DefaultClassifier.classify(outcome),
+    };
+    let client = HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        max_retries: Some(1),
+        retry_classifier: Some(Arc::new(classifier)),
+        ..Default::default()
+    }))
+    .build();
+
+    let res = client
+        .get(format!("{}/flaky", server.uri()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), 200);
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
@@ -0,0 +1,126 @@
+use http_client::{HttpClientBuilder, builder::HttpClientBuilderConfig, middleware::RequestTiming};
+use reqwest::header::{HeaderValue, USER_AGENT};
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{header, path},
+};
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(path("/fail"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    Mock::given(path("/ok"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .mount(&server)
+        .await;
+    server
+}
+
+async fn requests_with_variant(server: &MockServer, variant: &str) -> usize {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.headers.get("x-variant").is_some_and(|v| v == variant))
+        .count()
+}
+
+#[tokio::test]
+async fn test_derived_clients_diverge_in_headers_and_retries() {
+    let server = server().await;
+    let shared = HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        ..Default::default()
+    }))
+    .build_shared();
+
+    let plain = shared.derive(|opts| {
+        opts.default_headers
+            .insert("x-variant", HeaderValue::from_static("plain"));
+    });
+    let retrying = shared.derive(|opts| {
+        opts.default_headers
+            .insert("x-variant", HeaderValue::from_static("retrying"));
+        opts.retry_enabled = true;
+        opts.max_retries = 2;
+    });
+
+    let url = format!("{}/fail", server.uri());
+    assert_eq!(plain.get(&url).send().await.unwrap().status(), 500);
+    assert_eq!(retrying.get(&url).send().await.unwrap().status(), 500);
+
+    assert_eq!(requests_with_variant(&server, "plain").await, 1);
+    assert_eq!(requests_with_variant(&server, "retrying").await, 3);
+}
+
+#[tokio::test]
+async fn test_derived_client_keeps_builder_headers_unless_overridden() {
+    let server = MockServer::start().await;
+    Mock::given(header("accept", "application/json"))
+        .and(header("user-agent", "custom"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+
+    let shared = HttpClientBuilder::new(None).build_shared();
+    let client = shared.derive(|opts| {
+        opts.default_headers
+            .insert(USER_AGENT, HeaderValue::from_static("default"));
+    });
+
+    let res = client
+        .get(server.uri())
+        .header(USER_AGENT, "custom")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), 204);
+}
+
+#[tokio::test]
+async fn test_derived_clients_share_connection_pool() {
+    let server = server().await;
+    let shared = HttpClientBuilder::new(None).with_tracing().build_shared();
+    let first = shared.derive(|opts| {
+        opts.default_headers
+            .insert("x-variant", HeaderValue::from_static("first"));
+    });
+    let second = shared.derive(|opts| {
+        opts.retry_enabled = false;
+    });
+    let url = format!("{}/ok", server.uri());
+
+    let res = first.get(&url).send().await.unwrap();
+    assert_eq!(
+        res.extensions()
+            .get::<RequestTiming>()
+            .unwrap()
+            .connection_reused,
+        Some(false)
+    );
+    res.bytes().await.unwrap();
+
+    let res = second.get(&url).send().await.unwrap();
+    assert_eq!(
+        res.extensions()
+            .get::<RequestTiming>()
+            .unwrap()
+            .connection_reused,
+        Some(true)
+    );
+
+    // A separately built client opens its own connection
+    let separate = HttpClientBuilder::new(None).with_tracing().build();
+    let res = separate.get(&url).send().await.unwrap();
+    assert_eq!(
+        res.extensions()
+            .get::<RequestTiming>()
+            .unwrap()
+            .connection_reused,
+        Some(false)
+    );
+}
@@ -0,0 +1,205 @@
+use std::time::{Duration, Instant};
+
+use http_client::{
+    HttpClientBuilder,
+    builder::HttpClientBuilderConfig,
+    middleware::{ThrottleBypass, ThrottleMiddleware, ThrottlePolicy},
+};
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+const BODY_BYTES: usize = 30_000;
+
+fn no_retry_config() -> HttpClientBuilderConfig {
+    HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        ..Default::default()
+    }
+}
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/report"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![b'x'; BODY_BYTES]))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_throttle_paces_download() {
+    let server = server().await;
+    // (30_000 - 5_000) bytes at 20_000 B/s: roughly 1.25s
+    let throttle = ThrottleMiddleware::new(ThrottlePolicy::new(Some(20_000), None, 5_000));
+    let client = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(throttle)
+        .build();
+
+    let start = Instant::now();
+    let body = client
+        .get(format!("{}/report", server.uri()))
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    let elapsed = start.elapsed();
+
+    assert_eq!(body.len(), BODY_BYTES);
+    assert!(
+        elapsed >= Duration::from_millis(1_000),
+        "too fast: {elapsed:?}"
+    );
+    assert!(elapsed < Duration::from_secs(4), "too slow: {elapsed:?}");
+}
+
+#[tokio::test]
+async fn test_throttle_paces_upload() {
+    let server = server().await;
+    let throttle = ThrottleMiddleware::new(ThrottlePolicy::new(None, Some(20_000), 5_000));
+    let client = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(throttle)
+        .build();
+
+    let start = Instant::now();
+    let res = client
+        .post(format!("{}/upload", server.uri()))
+        .body(vec![b'y'; BODY_BYTES])
+        .send()
+        .await
+        .unwrap();
+    let elapsed = start.elapsed();
+
+    assert_eq!(res.status(), 204);
+    assert!(
+        elapsed >= Duration::from_millis(1_000),
+        "too fast: {elapsed:?}"
+    );
+
+    let received = server.received_requests().await.unwrap();
+    let upload = received.iter().find(|r| r.url.path() == "/upload").unwrap();
+    assert_eq!(upload.body.len(), BODY_BYTES);
+    // Sent with its length, not chunked
+    assert_eq!(
+        upload.headers.get("content-length").unwrap(),
+        &BODY_BYTES.to_string()
+    );
+    assert!(upload.headers.get("transfer-encoding").is_none());
+}
+
+#[tokio::test]
+async fn test_throttle_bypass_extension() {
+    let server = server().await;
+    let throttle = ThrottleMiddleware::new(ThrottlePolicy::new(Some(1_000), Some(1_000), 100));
+    let client = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(throttle)
+        .build();
+
+    let start = Instant::now();
+    let body = client
+        .get(format!("{}/report", server.uri()))
+        .with_extension(ThrottleBypass)
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+
+    assert_eq!(body.len(), BODY_BYTES);
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_throttle_handle_lifts_limit() {
+    let server = server().await;
+    let throttle = ThrottleMiddleware::new(ThrottlePolicy::new(Some(1_000), None, 100));
+    let handle = throttle.handle();
+    let client = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(throttle)
+        .build();
+
+    handle.set_rate(None, None);
+
+    let start = Instant::now();
+    client
+        .get(format!("{}/report", server.uri()))
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_throttle_zero_rate_pauses_until_raised() {
+    let server = server().await;
+    let throttle = ThrottleMiddleware::new(ThrottlePolicy::new(Some(1_000_000), None, 100));
+    let handle = throttle.handle();
+    let client = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(throttle)
+        .build();
+
+    handle.set_rate(Some(0), Some(0));
+    let download = async {
+        client
+            .get(format!("{}/report", server.uri()))
+            .send()
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap()
+    };
+    let mut download = std::pin::pin!(download);
+
+    let paused = tokio::time::timeout(Duration::from_millis(300), download.as_mut()).await;
+    assert!(paused.is_err(), "download went through a zero rate");
+
+    handle.set_rate(None, None);
+    let body = tokio::time::timeout(Duration::from_secs(5), download)
+        .await
+        .expect("raising the rate did not resume the download");
+    assert_eq!(body.len(), BODY_BYTES);
+}
+
+#[tokio::test]
+async fn test_throttle_handle_wakes_waiting_transfer() {
+    let server = server().await;
+    // About 30s at this rate, unless the wait notices the new limit
+    let throttle = ThrottleMiddleware::new(ThrottlePolicy::new(None, Some(1_000), 100));
+    let handle = throttle.handle();
+    let client = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(throttle)
+        .build();
+
+    let start = Instant::now();
+    let upload = client
+        .post(format!("{}/upload", server.uri()))
+        .body(vec![b'y'; BODY_BYTES])
+        .send();
+    let lift = async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        handle.set_rate(None, None);
+    };
+    let (res, ()) = tokio::join!(upload, lift);
+
+    assert_eq!(res.unwrap().status(), 204);
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "still waiting on the old rate: {:?}",
+        start.elapsed()
+    );
+}
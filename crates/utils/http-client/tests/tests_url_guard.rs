@@ -0,0 +1,250 @@
+use std::{collections::HashMap, error::Error as _, net::SocketAddr};
+
+use http_client::{
+    BlockReason, BlockedDestination, HttpClientBuilder,
+    builder::HttpClientBuilderConfig,
+    middleware::{GuardStrictness, UrlGuardConfig},
+};
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+fn guarded_client(
+    guard: UrlGuardConfig,
+    dns_overrides: HashMap<String, Vec<SocketAddr>>,
+) -> http_client::ClientWithMiddleware {
+    HttpClientBuilder::new(Some(HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        url_guard: Some(guard),
+        dns_overrides: Some(dns_overrides),
+        ..Default::default()
+    }))
+    .build()
+}
+
+fn block_reason(err: &reqwest_middleware::Error) -> Option<BlockReason> {
+    match err {
+        reqwest_middleware::Error::Middleware(e) => e
+            .downcast_ref::<BlockedDestination>()
+            .map(|b| b.reason.clone()),
+        reqwest_middleware::Error::Reqwest(e) => {
+            let mut source = e.source();
+            while let Some(err) = source {
+                if let Some(blocked) = err.downcast_ref::<BlockedDestination>() {
+                    return Some(blocked.reason.clone());
+                }
+                source = err.source();
+            }
+            None
+        }
+    }
+}
+
+async fn ok_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ok"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_url_guard_blocks_denied_ip_literal() {
+    let client = guarded_client(UrlGuardConfig::default(), HashMap::new());
+
+    let err = client
+        .get("http://10.0.0.1/admin")
+        .send()
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        block_reason(&err),
+        Some(BlockReason::DeniedAddress("10.0.0.1".parse().unwrap()))
+    );
+}
+
+#[tokio::test]
+async fn test_url_guard_blocks_name_resolving_to_denied_network() {
+    let overrides = HashMap::from([(
+        "internal.test".to_string(),
+        vec!["192.168.1.10:80".parse().unwrap()],
+    )]);
+    let client = guarded_client(UrlGuardConfig::default(), overrides);
+
+    let err = client
+        .get("http://internal.test/")
+        .send()
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        block_reason(&err),
+        Some(BlockReason::DeniedAddress("192.168.1.10".parse().unwrap()))
+    );
+}
+
+#[tokio::test]
+async fn test_url_guard_enforces_host_allowlist() {
+    let server = ok_server().await;
+    let addr = *server.address();
+    let overrides = HashMap::from([
+        ("api.test".to_string(), vec![addr]),
+        ("other.test".to_string(), vec![addr]),
+    ]);
+    let guard = UrlGuardConfig::default()
+        .with_allowed_hosts(["api.test"])
+        .with_allowed_network("127.0.0.0/8".parse().unwrap());
+    let client = guarded_client(guard, overrides);
+
+    let res = client
+        .get(format!("http://api.test:{}/ok", addr.port()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let err = client
+        .get(format!("http://other.test:{}/ok", addr.port()))
+        .send()
+        .await
+        .unwrap_err();
+    assert_eq!(
+        block_reason(&err),
+        Some(BlockReason::HostNotAllowed("other.test".to_string()))
+    );
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_url_guard_blocks_redirect_into_denied_network() {
+    let server = MockServer::start().await;
+    Mock::given(path("/redirect"))
+        .respond_with(ResponseTemplate::new(302).insert_header("location", "http://10.1.2.3/"))
+        .mount(&server)
+        .await;
+    let guard = UrlGuardConfig::default().with_allowed_network("127.0.0.0/8".parse().unwrap());
+    let client = guarded_client(guard, HashMap::new());
+
+    let err = client
+        .get(format!("{}/redirect", server.uri()))
+        .send()
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        block_reason(&err),
+        Some(BlockReason::DeniedAddress("10.1.2.3".parse().unwrap()))
+    );
+}
+
+#[tokio::test]
+async fn test_url_guard_disabled_per_request() {
+    let server = ok_server().await;
+    let client = guarded_client(UrlGuardConfig::default(), HashMap::new());
+    let url = format!("{}/ok", server.uri());
+
+    let err = client.get(&url).send().await.unwrap_err();
+    assert!(matches!(
+        block_reason(&err),
+        Some(BlockReason::DeniedAddress(_))
+    ));
+
+    let res = client
+        .get(&url)
+        .with_extension(GuardStrictness::Disabled)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+}
+
+#[tokio::test]
+async fn test_url_guard_blocks_redirect_to_name_resolving_to_denied_network() {
+    let server = MockServer::start().await;
+    Mock::given(path("/redirect"))
+        .respond_with(
+            ResponseTemplate::new(302).insert_header("location", "http://internal.test/admin"),
+        )
+        .mount(&server)
+        .await;
+    let overrides = HashMap::from([(
+        "internal.test".to_string(),
+        vec!["169.254.169.254:80".parse().unwrap()],
+    )]);
+    let guard = UrlGuardConfig::default().with_allowed_network("127.0.0.0/8".parse().unwrap());
+    let client = guarded_client(guard, overrides);
+
+    let err = client
+        .get(format!("{}/redirect", server.uri()))
+        .send()
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        block_reason(&err),
+        Some(BlockReason::DeniedAddress(
+            "169.254.169.254".parse().unwrap()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_url_guard_redirect_honours_request_strictness() {
+    let server = ok_server().await;
+    Mock::given(path("/redirect"))
+        .respond_with(
+            ResponseTemplate::new(302).insert_header("location", format!("{}/ok", server.uri())),
+        )
+        .mount(&server)
+        .await;
+    let client = guarded_client(UrlGuardConfig::default(), HashMap::new());
+
+    let res = client
+        .get(format!("{}/redirect", server.uri()))
+        .with_extension(GuardStrictness::Disabled)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+}
+
+#[tokio::test]
+async fn test_url_guard_fails_closed_on_lookup_error() {
+    let client = guarded_client(UrlGuardConfig::default(), HashMap::new());
+
+    let err = client
+        .get("http://does-not-exist.invalid/")
+        .send()
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        block_reason(&err),
+        Some(BlockReason::UnresolvedHost(
+            "does-not-exist.invalid".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_url_guard_default_denies_nat64_multicast_and_broadcast() {
+    let client = guarded_client(UrlGuardConfig::default(), HashMap::new());
+
+    for (url, ip) in [
+        ("http://[64:ff9b::a00:1]/", "64:ff9b::a00:1"),
+        ("http://224.0.0.251/", "224.0.0.251"),
+        ("http://[ff02::1]/", "ff02::1"),
+        ("http://255.255.255.255/", "255.255.255.255"),
+    ] {
+        let err = client.get(url).send().await.unwrap_err();
+        assert_eq!(
+            block_reason(&err),
+            Some(BlockReason::DeniedAddress(ip.parse().unwrap())),
+            "{url}"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+
+use http_client::{
+    HttpClientBuilder,
+    builder::HttpClientBuilderConfig,
+    middleware::vcr::{CASSETTE_VERSION, Cassette, MatchOn, VcrConfig, VcrError, VcrMiddleware},
+};
+use reqwest::StatusCode;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+fn cassette_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "http-client-vcr-{}-{name}.json",
+        std::process::id()
+    ))
+}
+
+fn no_retry_config() -> HttpClientBuilderConfig {
+    HttpClientBuilderConfig {
+        retry_enabled: Some(false),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_vcr_record_then_replay_without_network() {
+    let cassette = cassette_path("roundtrip");
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orders"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("set-cookie", "session=abc")
+                .set_body_string(r#"{"orders":[1,2]}"#),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/orders"))
+        .respond_with(ResponseTemplate::new(201).set_body_string("created"))
+        .mount(&server)
+        .await;
+
+    let vcr = VcrMiddleware::record(&cassette);
+    let recorder = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(vcr.clone())
+        .build();
+
+    let url = format!("{}/orders", server.uri());
+    let secret_url = format!(
+        "http://user:hunter2@{}/orders?api_key=sk-live-123&page=2",
+        server.address()
+    );
+    let live_get = recorder
+        .get(&secret_url)
+        .header("authorization", "Bearer top-secret")
+        .header("x-auth-token", "tok-456")
+        .send()
+        .await
+        .unwrap();
+    let live_status = live_get.status();
+    let live_body = live_get.text().await.unwrap();
+    let live_post = recorder.post(&url).body("item").send().await.unwrap();
+    assert_eq!(live_post.status(), StatusCode::CREATED);
+
+    // Nothing is written until the cassette is saved
+    assert!(!cassette.exists());
+    vcr.save().unwrap();
+    drop(server);
+
+    let replayer = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(
+            VcrMiddleware::replay_with_config(
+                &cassette,
+                VcrConfig::default().with_match_on(vec![
+                    MatchOn::Method,
+                    MatchOn::Path,
+                    MatchOn::Query,
+                    MatchOn::BodyHash,
+                ]),
+            )
+            .unwrap(),
+        )
+        .build();
+
+    let replayed = replayer.get(&secret_url).send().await.unwrap();
+    assert_eq!(replayed.status(), live_status);
+    assert_eq!(replayed.text().await.unwrap(), live_body);
+
+    let replayed = replayer.post(&url).body("item").send().await.unwrap();
+    assert_eq!(replayed.status(), StatusCode::CREATED);
+    assert_eq!(replayed.text().await.unwrap(), "created");
+
+    // A different body hash does not match the recorded POST
+    let err = replayer.post(&url).body("other").send().await.unwrap_err();
+    let reqwest_middleware::Error::Middleware(err) = err else {
+        panic!("expected middleware error");
+    };
+    assert!(matches!(
+        err.downcast_ref::<VcrError>(),
+        Some(VcrError::NoMatch { .. })
+    ));
+
+    let raw = std::fs::read_to_string(&cassette).unwrap();
+    assert!(!raw.contains("top-secret"));
+    assert!(!raw.contains("tok-456"));
+    assert!(!raw.contains("session=abc"));
+    assert!(!raw.contains("sk-live-123"));
+    assert!(!raw.contains("hunter2"));
+    assert!(!raw.contains("user:"));
+    assert!(raw.contains("api_key=***&page=2"));
+    assert!(raw.contains("***"));
+
+    let _ = std::fs::remove_file(&cassette);
+}
+
+#[tokio::test]
+async fn test_vcr_truncates_large_bodies() {
+    let cassette = cassette_path("truncate");
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("0123456789"))
+        .mount(&server)
+        .await;
+
+    let recorder = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(VcrMiddleware::record_with_config(
+            &cassette,
+            VcrConfig::default().with_max_body_bytes(4),
+        ))
+        .build();
+
+    let res = recorder.get(server.uri()).send().await.unwrap();
+    assert_eq!(res.text().await.unwrap(), "0123456789");
+
+    // Dropping the last handle writes the cassette
+    drop(recorder);
+    let stored = Cassette::load(&cassette).unwrap();
+    assert_eq!(stored.version, CASSETTE_VERSION);
+    assert!(stored.interactions[0].response.truncated);
+
+    // The cut body is never replayed as if it were complete
+    let replayer = HttpClientBuilder::new(Some(no_retry_config()))
+        .with_middleware(VcrMiddleware::replay(&cassette).unwrap())
+        .build();
+    let err = replayer.get(server.uri()).send().await.unwrap_err();
+    let reqwest_middleware::Error::Middleware(err) = err else {
+        panic!("expected middleware error");
+    };
+    assert!(matches!(
+        err.downcast_ref::<VcrError>(),
+        Some(VcrError::TruncatedBody { .. })
+    ));
+
+    let _ = std::fs::remove_file(&cassette);
+}
+
+#[test]
+fn test_vcr_rejects_unknown_cassette_version() {
+    let cassette = cassette_path("version");
+    std::fs::write(&cassette, r#"{"version":99,"interactions":[]}"#).unwrap();
+
+    let err = VcrMiddleware::replay(&cassette).err().unwrap();
+    assert!(matches!(
+        err,
+        VcrError::UnsupportedVersion {
+            found: 99,
+            expected: CASSETTE_VERSION,
+            ..
+        }
+    ));
+
+    let _ = std::fs::remove_file(&cassette);
+}
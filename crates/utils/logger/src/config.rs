@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "otel", feature = "metrics"))]
+use std::fmt;
 #[cfg(feature = "otel")]
 use std::time::Duration;
 
@@ -32,6 +34,10 @@ pub struct LoggerConfig {
     pub output_mode: OutputMode,
     #[cfg(feature = "file")]
     pub file: Option<FileConfig>,
+    /// Second file output, `error.log` with only ERROR events unless its
+    /// `prefix` and `min_level` say otherwise
+    #[cfg(feature = "file")]
+    pub error_file: Option<FileConfig>,
     #[cfg(feature = "otel")]
     pub otel: Option<OtelConfig>,
     pub format: Option<FormatConfig>,
@@ -44,6 +50,8 @@ impl Default for LoggerConfig {
             output_mode: OutputMode::default(),
             #[cfg(feature = "file")]
             file: None,
+            #[cfg(feature = "file")]
+            error_file: None,
             #[cfg(feature = "otel")]
             otel: None,
             format: Some(FormatConfig::default()),
@@ -73,6 +81,12 @@ impl LoggerConfig {
         self
     }
 
+    #[cfg(feature = "file")]
+    pub fn with_error_file(mut self, error_file: FileConfig) -> Self {
+        self.error_file = Some(error_file);
+        self
+    }
+
     #[cfg(feature = "otel")]
     pub fn with_otel(mut self, otel: OtelConfig) -> Self {
         self.otel = Some(otel);
@@ -139,14 +153,45 @@ impl FormatConfig {
     }
 }
 
+/// When the file appender starts a new log file.
+#[cfg(feature = "file")]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Rotation {
+    /// Writes to `{app}.log` and renames it to `{app}-YYYYMMDD-{n}.log` past `max_size`
+    #[default]
+    Size,
+    /// One file per UTC day, `{app}.YYYY-MM-DD.log`
+    Daily,
+    /// One file per UTC hour, `{app}.YYYY-MM-DD-HH.log`
+    Hourly,
+    /// One file per UTC minute, `{app}.YYYY-MM-DD-HH-mm.log`
+    Minutely,
+    /// A single `{app}.log` that is never rotated
+    Never,
+}
+
 #[cfg(feature = "file")]
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[non_exhaustive]
 pub struct FileConfig {
-    /// Maximum size in bytes before rotation
+    /// Maximum size in bytes before rotation, used by `Rotation::Size` only
     pub max_size: u64,
+    /// When to start a new log file
+    #[serde(default)]
+    pub rotation: Rotation,
+    /// Number of rotated files to keep, oldest deleted first
+    pub max_files: Option<usize>,
+    /// Delete rotated files dated more than this many days ago
+    pub max_age_days: Option<u32>,
     /// Directory path for log files
     pub path: String,
+    /// File name prefix, the app name when unset
+    pub prefix: Option<String>,
+    /// Least severe level written to this file, e.g. "WARN" for warnings and
+    /// errors; every level when unset
+    pub min_level: Option<String>,
     /// Enable file logging
     pub enabled: bool,
     /// Format configuration for file output (overrides global format if set)
@@ -158,7 +203,12 @@ impl Default for FileConfig {
     fn default() -> Self {
         Self {
             max_size: 100 * 1024 * 1024, // 100MB
+            rotation: Rotation::default(),
+            max_files: None,
+            max_age_days: None,
             path: "./logs".to_string(),
+            prefix: None,
+            min_level: None,
             enabled: false,
             format: None,
         }
@@ -172,11 +222,36 @@ impl FileConfig {
         self
     }
 
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_min_level(mut self, level: impl Into<String>) -> Self {
+        self.min_level = Some(level.into());
+        self
+    }
+
     pub fn with_max_size(mut self, bytes: u64) -> Self {
         self.max_size = bytes;
         self
     }
 
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = Some(max_files);
+        self
+    }
+
+    pub fn with_max_age_days(mut self, days: u32) -> Self {
+        self.max_age_days = Some(days);
+        self
+    }
+
     pub fn with_enabled(mut self, enabled: bool) -> Self {
         self.enabled = enabled;
         self
@@ -189,7 +264,7 @@ impl FileConfig {
 }
 
 #[cfg(feature = "otel")]
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[non_exhaustive]
 pub struct OtelConfig {
     /// OpenTelemetry collector endpoint (base URL)
@@ -200,9 +275,22 @@ pub struct OtelConfig {
     pub logs_path: Option<String>,
     /// Optional path suffix for metrics endpoint (e.g., "/v1/metrics")
     pub metrics_path: Option<String>,
+    /// Headers sent with every export, as gRPC metadata or HTTP headers.
+    /// Values usually carry credentials and are redacted from `Debug` output
     pub headers: Option<std::collections::HashMap<String, String>>,
-    /// Custom resource attributes (e.g., app.environment, app.mode)
+    /// Custom resource attributes (e.g., app.environment, app.mode). A
+    /// `service.name` here is ignored, the service name is always `app_name`
+    #[serde(alias = "resource_attributes")]
     pub attributes: Option<std::collections::HashMap<String, String>>,
+    /// Resource `service.version`, overriding the same key in `attributes`
+    #[serde(default)]
+    pub service_version: Option<String>,
+    /// Resource `service.namespace`, overriding the same key in `attributes`
+    #[serde(default)]
+    pub service_namespace: Option<String>,
+    /// Resource `deployment.environment`, overriding the same key in `attributes`
+    #[serde(default)]
+    pub environment: Option<String>,
     /// Protocol to use for exporting (grpc or http)
     #[serde(default = "default_protocol")]
     pub protocol: ProtocolConfig,
@@ -231,6 +319,48 @@ pub struct OtelConfig {
     pub max_attributes_per_span: u32,
 }
 
+#[cfg(feature = "otel")]
+impl fmt::Debug for OtelConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtelConfig")
+            .field("endpoint", &self.endpoint)
+            .field("traces_path", &self.traces_path)
+            .field("logs_path", &self.logs_path)
+            .field("metrics_path", &self.metrics_path)
+            .field("headers", &self.headers.as_ref().map(RedactedHeaders))
+            .field("attributes", &self.attributes)
+            .field("service_version", &self.service_version)
+            .field("service_namespace", &self.service_namespace)
+            .field("environment", &self.environment)
+            .field("protocol", &self.protocol)
+            .field("enabled", &self.enabled)
+            .field("sampler", &self.sampler)
+            .field("timeout_secs", &self.timeout_secs)
+            .field("max_queue_size", &self.max_queue_size)
+            .field("scheduled_delay_ms", &self.scheduled_delay_ms)
+            .field("max_export_batch_size", &self.max_export_batch_size)
+            .field("max_events_per_span", &self.max_events_per_span)
+            .field("max_attributes_per_span", &self.max_attributes_per_span)
+            .finish()
+    }
+}
+
+/// Debug view of exporter headers that keeps the names and hides the values
+#[cfg(any(feature = "otel", feature = "metrics"))]
+pub(crate) struct RedactedHeaders<'a>(pub(crate) &'a std::collections::HashMap<String, String>);
+
+#[cfg(any(feature = "otel", feature = "metrics"))]
+impl fmt::Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Sorted so the output is stable across runs
+        let mut names: Vec<&String> = self.0.keys().collect();
+        names.sort_unstable();
+        f.debug_map()
+            .entries(names.into_iter().map(|name| (name, "[REDACTED]")))
+            .finish()
+    }
+}
+
 #[cfg(any(feature = "otel", feature = "metrics"))]
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -285,6 +415,9 @@ impl Default for OtelConfig {
             metrics_path: None,
             headers: None,
             attributes: None,
+            service_version: None,
+            service_namespace: None,
+            environment: None,
             protocol: ProtocolConfig::Grpc,
             enabled: false,
             sampler: Some(SamplerConfig::default()),
@@ -332,6 +465,34 @@ impl OtelConfig {
         self.timeout_secs = secs;
         self
     }
+
+    pub fn with_headers(mut self, headers: std::collections::HashMap<String, String>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+
+    pub fn with_attributes(
+        mut self,
+        attributes: std::collections::HashMap<String, String>,
+    ) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    pub fn with_service_version(mut self, version: impl Into<String>) -> Self {
+        self.service_version = Some(version.into());
+        self
+    }
+
+    pub fn with_service_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.service_namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn with_environment(mut self, environment: impl Into<String>) -> Self {
+        self.environment = Some(environment.into());
+        self
+    }
 }
 
 #[cfg(feature = "otel")]
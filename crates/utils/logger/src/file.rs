@@ -1,4 +1,6 @@
-use crate::{FileAppenderError, FileAppenderErrorKind, FileConfig, SetupLogging, SetupLoggingKind};
+use crate::{
+    FileAppenderError, FileAppenderErrorKind, FileConfig, Rotation, SetupLogging, SetupLoggingKind,
+};
 use std::{
     io::Write,
     path::{Path, PathBuf},
@@ -12,12 +14,18 @@ pub struct SizeBasedRollingWriter {
     path: std::path::PathBuf,
     prefix: String,
     max_size: u64,
+    retention: Retention,
     current_file: Arc<Mutex<std::fs::File>>,
     current_size: Arc<Mutex<u64>>,
 }
 
 impl SizeBasedRollingWriter {
-    fn new(path: &Path, prefix: &str, max_size: u64) -> Result<Self, FileAppenderError> {
+    fn new(
+        path: &Path,
+        prefix: &str,
+        max_size: u64,
+        retention: Retention,
+    ) -> Result<Self, FileAppenderError> {
         let log_path = path.join(format!("{}.log", prefix));
 
         // Get current file size if it exists
@@ -42,6 +50,7 @@ impl SizeBasedRollingWriter {
             path: path.to_path_buf(),
             prefix: prefix.to_string(),
             max_size,
+            retention,
             current_file: Arc::new(Mutex::new(file)),
             current_size: Arc::new(Mutex::new(current_size)),
         })
@@ -58,8 +67,19 @@ impl SizeBasedRollingWriter {
         let now = time::OffsetDateTime::now_utc();
         let date_str = format!("{:04}{:02}{:02}", now.year(), now.month() as u8, now.day());
 
-        // Find next available increment
-        let mut increment = 1;
+        // Find next available increment, after any kept by pruning so the
+        // newest file always has the highest one
+        let mut increment = std::fs::read_dir(&self.path)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                let (date, _, _, increment) =
+                    rotated_key(name.to_str()?, &self.prefix, Rotation::Size)?;
+                (date == now.date()).then_some(increment + 1)
+            })
+            .max()
+            .unwrap_or(1);
         let rotated_path = loop {
             let rotated_name = format!("{}-{}-{}.log", self.prefix, date_str, increment);
             let path = self.path.join(&rotated_name);
@@ -85,7 +105,10 @@ impl SizeBasedRollingWriter {
         let mut size = self.current_size.lock().unwrap();
         *file = new_file;
         *size = 0;
+        drop(file);
+        drop(size);
 
+        prune_rotated_files(&self.path, &self.prefix, Rotation::Size, self.retention);
         Ok(())
     }
 }
@@ -115,6 +138,156 @@ impl Write for SizeBasedRollingWriter {
     }
 }
 
+/// `tracing_appender` rolling writer that prunes old files once per period,
+/// after the write that moved it to a new file
+struct PruningRollingWriter {
+    inner: tracing_appender::rolling::RollingFileAppender,
+    path: PathBuf,
+    prefix: String,
+    rotation: Rotation,
+    retention: Retention,
+    period_secs: i64,
+    period: i64,
+}
+
+impl PruningRollingWriter {
+    fn current_period(period_secs: i64) -> i64 {
+        time::OffsetDateTime::now_utc()
+            .unix_timestamp()
+            .div_euclid(period_secs)
+    }
+}
+
+impl Write for PruningRollingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+
+        let period = Self::current_period(self.period_secs);
+        if period != self.period {
+            self.period = period;
+            prune_rotated_files(&self.path, &self.prefix, self.rotation, self.retention);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Limits on how many rotated files are kept, from `FileConfig`
+#[derive(Debug, Clone, Copy)]
+struct Retention {
+    max_files: Option<usize>,
+    max_age_days: Option<u32>,
+}
+
+impl Retention {
+    fn is_unbounded(&self) -> bool {
+        self.max_files.is_none() && self.max_age_days.is_none()
+    }
+}
+
+/// Date, hour, minute and increment of a rotated file, in the order they sort
+type RotatedKey = (time::Date, u8, u8, u64);
+
+/// Parse a file name written by `rotation` for `prefix`
+///
+/// Size: {prefix}-YYYYMMDD-{increment}.log
+/// Time: {prefix}.YYYY-MM-DD.log, with -HH for hourly and -HH-mm for minutely
+fn rotated_key(name: &str, prefix: &str, rotation: Rotation) -> Option<RotatedKey> {
+    let stem = name.strip_suffix(".log")?.strip_prefix(prefix)?;
+    let is_digits =
+        |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    let date = |year: &str, month: &str, day: &str| {
+        let month = time::Month::try_from(month.parse::<u8>().ok()?).ok()?;
+        time::Date::from_calendar_date(year.parse().ok()?, month, day.parse().ok()?).ok()
+    };
+
+    match rotation {
+        Rotation::Size => {
+            let (ymd, increment) = stem.strip_prefix('-')?.split_once('-')?;
+            if !is_digits(ymd, 8) || increment.is_empty() || !is_digits(increment, increment.len())
+            {
+                return None;
+            }
+            let date = date(&ymd[..4], &ymd[4..6], &ymd[6..])?;
+            Some((date, 0, 0, increment.parse().ok()?))
+        }
+        Rotation::Daily | Rotation::Hourly | Rotation::Minutely => {
+            let parts: Vec<&str> = stem.strip_prefix('.')?.split('-').collect();
+            let widths: &[usize] = match rotation {
+                Rotation::Daily => &[4, 2, 2],
+                Rotation::Hourly => &[4, 2, 2, 2],
+                _ => &[4, 2, 2, 2, 2],
+            };
+            if parts.len() != widths.len()
+                || !parts
+                    .iter()
+                    .zip(widths)
+                    .all(|(part, width)| is_digits(part, *width))
+            {
+                return None;
+            }
+            let date = date(parts[0], parts[1], parts[2])?;
+            let hour = parts.get(3).map_or(Some(0), |hour| hour.parse().ok())?;
+            let minute = parts.get(4).map_or(Some(0), |minute| minute.parse().ok())?;
+            Some((date, hour, minute, 0))
+        }
+        Rotation::Never => None,
+    }
+}
+
+/// Delete the oldest rotated files of `prefix` beyond `retention`
+///
+/// Only names `rotation` writes for this app are considered, so other files
+/// in a shared directory are left alone. With time-based rotation the newest
+/// match is the file being written and is always kept. Filesystem errors go
+/// to stderr, as this runs on the writer's worker thread where logging would
+/// re-enter the subscriber.
+fn prune_rotated_files(dir: &Path, prefix: &str, rotation: Rotation, retention: Retention) {
+    if retention.is_unbounded() {
+        return;
+    }
+
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!(
+                "Failed to list log directory {} for pruning: {e}",
+                dir.display()
+            );
+            return;
+        }
+    };
+    let mut rotated: Vec<(RotatedKey, PathBuf)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let key = rotated_key(entry.file_name().to_str()?, prefix, rotation)?;
+            Some((key, entry.path()))
+        })
+        .collect();
+
+    // Newest first
+    rotated.sort_unstable_by_key(|(key, _)| std::cmp::Reverse(*key));
+    if rotation != Rotation::Size && !rotated.is_empty() {
+        rotated.remove(0);
+    }
+
+    let today = time::OffsetDateTime::now_utc().date();
+    for (index, ((date, ..), path)) in rotated.iter().enumerate() {
+        let excess = retention.max_files.is_some_and(|max| index >= max);
+        let expired = retention
+            .max_age_days
+            .is_some_and(|days| (today - *date).whole_days() > i64::from(days));
+        if (excess || expired)
+            && let Err(e) = std::fs::remove_file(path)
+        {
+            eprintln!("Failed to delete old log file {}: {e}", path.display());
+        }
+    }
+}
+
 pub fn setup_file_appender(
     app_name: String,
     file_logger_config: FileConfig,
@@ -126,7 +299,7 @@ pub fn setup_file_appender(
     SetupLogging,
 > {
     let path = PathBuf::from(&file_logger_config.path);
-    let prefix = app_name;
+    let prefix = file_logger_config.prefix.clone().unwrap_or(app_name);
 
     if !path.exists() {
         use std::fs;
@@ -160,9 +333,62 @@ pub fn setup_file_appender(
         }));
     }
 
-    // size-based rolling writer
-    let writer = SizeBasedRollingWriter::new(&path, &prefix, file_logger_config.max_size)
-        .map_err(|e| SetupLogging::new(SetupLoggingKind::FileAppender { source: e }))?;
-    let (non_blocking_writer, guard) = tracing_appender::non_blocking(writer);
-    Ok((non_blocking_writer, guard))
+    let time_based = match file_logger_config.rotation {
+        Rotation::Size => None,
+        Rotation::Daily => Some(tracing_appender::rolling::Rotation::DAILY),
+        Rotation::Hourly => Some(tracing_appender::rolling::Rotation::HOURLY),
+        Rotation::Minutely => Some(tracing_appender::rolling::Rotation::MINUTELY),
+        Rotation::Never => Some(tracing_appender::rolling::Rotation::NEVER),
+    };
+
+    let retention = Retention {
+        max_files: file_logger_config.max_files,
+        max_age_days: file_logger_config.max_age_days,
+    };
+
+    let Some(time_rotation) = time_based else {
+        // size-based rolling writer
+        let writer =
+            SizeBasedRollingWriter::new(&path, &prefix, file_logger_config.max_size, retention)
+                .map_err(|e| SetupLogging::new(SetupLoggingKind::FileAppender { source: e }))?;
+        prune_rotated_files(&path, &prefix, Rotation::Size, retention);
+        return Ok(tracing_appender::non_blocking(writer));
+    };
+
+    // time-based rolling writer: {prefix}.{date}.log, or {prefix}.log for Never
+    let writer = tracing_appender::rolling::RollingFileAppender::builder()
+        .rotation(time_rotation)
+        .filename_prefix(&prefix)
+        .filename_suffix("log")
+        .build(&path)
+        .map_err(|e| {
+            SetupLogging::new(SetupLoggingKind::FileAppender {
+                source: FileAppenderError::new(FileAppenderErrorKind::BuildRollingAppender {
+                    path: path.clone(),
+                    source: e,
+                }),
+            })
+        })?;
+
+    let rotation = file_logger_config.rotation;
+    let period_secs = match rotation {
+        Rotation::Daily => 86_400,
+        Rotation::Hourly => 3_600,
+        Rotation::Minutely => 60,
+        Rotation::Size | Rotation::Never => return Ok(tracing_appender::non_blocking(writer)),
+    };
+    if retention.is_unbounded() {
+        return Ok(tracing_appender::non_blocking(writer));
+    }
+
+    prune_rotated_files(&path, &prefix, rotation, retention);
+    Ok(tracing_appender::non_blocking(PruningRollingWriter {
+        inner: writer,
+        path,
+        prefix,
+        rotation,
+        retention,
+        period_secs,
+        period: PruningRollingWriter::current_period(period_secs),
+    }))
 }
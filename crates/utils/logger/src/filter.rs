@@ -0,0 +1,68 @@
+use crate::{SetupLogging, SetupLoggingKind};
+use std::sync::Arc;
+use tracing::Level;
+use tracing_subscriber::{EnvFilter, filter::LevelFilter, reload};
+
+type Reload = dyn Fn(EnvFilter, LevelFilter) -> Result<(), reload::Error> + Send + Sync;
+
+/// Changes the filter of a running subscriber without restarting it
+///
+/// Cloned from `LoggingGuard::filter_handle`, so an admin endpoint can keep
+/// one while the guard lives elsewhere. Replaces both the `EnvFilter` and the
+/// `max_level` cap set up by `setup_logging`.
+#[derive(Clone)]
+pub struct FilterHandle {
+    reload: Arc<Reload>,
+}
+
+impl FilterHandle {
+    pub(crate) fn new<E, L>(
+        env_filter: reload::Handle<EnvFilter, E>,
+        level_filter: reload::Handle<LevelFilter, L>,
+    ) -> Self
+    where
+        E: 'static,
+        L: 'static,
+        reload::Handle<EnvFilter, E>: Send + Sync,
+        reload::Handle<LevelFilter, L>: Send + Sync,
+    {
+        Self {
+            reload: Arc::new(move |env, level| {
+                env_filter.reload(env)?;
+                level_filter.reload(level)
+            }),
+        }
+    }
+
+    /// Replace the filter with env filter directives, e.g. "info,my_crate=debug"
+    ///
+    /// The max level cap follows the most verbose directive. Invalid
+    /// directives return `SetupLoggingKind::InvalidEnvFilter` and leave the
+    /// current filter in place.
+    pub fn set_filter(&self, directives: &str) -> Result<(), SetupLogging> {
+        let env_filter = EnvFilter::try_new(directives)
+            .map_err(|e| SetupLogging::invalid_env_filter(directives, e))?;
+        let max_level = env_filter.max_level_hint().unwrap_or(LevelFilter::TRACE);
+        self.reload(env_filter, max_level)
+    }
+
+    /// Log everything at `level` and above
+    pub fn set_level(&self, level: Level) -> Result<(), SetupLogging> {
+        let level_filter = LevelFilter::from_level(level);
+        self.reload(
+            EnvFilter::default().add_directive(level_filter.into()),
+            level_filter,
+        )
+    }
+
+    fn reload(&self, env_filter: EnvFilter, level_filter: LevelFilter) -> Result<(), SetupLogging> {
+        (self.reload)(env_filter, level_filter)
+            .map_err(|source| SetupLogging::new(SetupLoggingKind::ReloadFilter { source }))
+    }
+}
+
+impl std::fmt::Debug for FilterHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilterHandle").finish_non_exhaustive()
+    }
+}
@@ -1,6 +1,7 @@
 pub mod config;
 #[cfg(feature = "file")]
 pub mod file;
+pub mod filter;
 pub mod macros;
 #[cfg(feature = "metrics")]
 pub mod metrics;
@@ -8,11 +9,13 @@ pub mod metrics;
 pub mod otel;
 #[cfg(any(feature = "otel", feature = "metrics"))]
 pub(crate) mod otlp_helpers;
+mod shutdown;
 pub mod tracing_unwrap;
 pub mod util;
 
 #[cfg(feature = "file")]
 use crate::file::setup_file_appender;
+pub use crate::filter::FilterHandle;
 #[cfg(feature = "otel")]
 use crate::otel::setup_otel;
 pub use crate::util::{utc_offset_hms, utc_offset_hours};
@@ -56,6 +59,21 @@ pub enum SetupLoggingKind {
     #[non_exhaustive]
     MissingConfig { config_type: &'static str },
 
+    #[error("invalid log level '{level}'")]
+    #[non_exhaustive]
+    InvalidLevel {
+        level: String,
+        #[source]
+        source: tracing::metadata::ParseLevelError,
+    },
+
+    #[error("failed to reload log filter")]
+    #[non_exhaustive]
+    ReloadFilter {
+        #[source]
+        source: tracing_subscriber::reload::Error,
+    },
+
     #[error("failed to set global subscriber")]
     #[non_exhaustive]
     SetGlobalSubscriber {
@@ -98,6 +116,16 @@ impl SetupLogging {
     pub fn missing_config(config_type: &'static str) -> Self {
         Self::new(SetupLoggingKind::MissingConfig { config_type })
     }
+
+    pub fn invalid_level(
+        level: impl Into<String>,
+        source: tracing::metadata::ParseLevelError,
+    ) -> Self {
+        Self::new(SetupLoggingKind::InvalidLevel {
+            level: level.into(),
+            source,
+        })
+    }
 }
 
 #[cfg(feature = "file")]
@@ -137,6 +165,14 @@ pub enum FileAppenderErrorKind {
         #[source]
         source: std::io::Error,
     },
+
+    #[error("failed to build rolling file appender in '{}'", path.display())]
+    #[non_exhaustive]
+    BuildRollingAppender {
+        path: PathBuf,
+        #[source]
+        source: tracing_appender::rolling::InitError,
+    },
 }
 
 #[cfg(feature = "file")]
@@ -191,6 +227,7 @@ impl OtelExporterError {
 
 #[cfg(feature = "file")]
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[cfg(feature = "sysinfo")]
 /// Error that occurs when collecting system information
@@ -235,10 +272,58 @@ impl SysInfoError {
     }
 }
 
+/// Error that occurs when shutting down logging providers
+#[derive(Debug, thiserror::Error)]
+#[error("failed to shutdown logging")]
+#[non_exhaustive]
+pub struct ShutdownError {
+    #[source]
+    pub kind: ShutdownErrorKind,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ShutdownErrorKind {
+    #[error("{} did not shut down within {timeout:?}", providers.join(", "))]
+    #[non_exhaustive]
+    TimedOut {
+        timeout: Duration,
+        providers: Vec<&'static str>,
+    },
+
+    #[error("failed to shutdown {provider} provider")]
+    #[non_exhaustive]
+    Provider {
+        provider: &'static str,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[cfg(feature = "tokio")]
+    #[error("shutdown task failed")]
+    #[non_exhaustive]
+    Join {
+        #[source]
+        source: tokio::task::JoinError,
+    },
+}
+
+impl ShutdownError {
+    pub fn new(kind: ShutdownErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// How long `Drop` waits for providers, use `LoggingGuard::shutdown` for more
+const DROP_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
 pub struct LoggingGuard {
     #[cfg(feature = "file")]
     /// Need to keep the guard alive to keep the file appender open
     pub file_guard: tracing_appender::non_blocking::WorkerGuard,
+    #[cfg(feature = "file")]
+    /// Keeps the error file's worker alive, like `file_guard`
+    pub error_file_guard: tracing_appender::non_blocking::WorkerGuard,
     #[cfg(feature = "otel")]
     /// Keep tracer provider alive for proper shutdown
     pub tracer_provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
@@ -251,53 +336,82 @@ pub struct LoggingGuard {
     #[cfg(feature = "stdout")]
     /// Keep stdout guard alive to ensure all logs are flushed
     pub stdout_guard: tracing_appender::non_blocking::WorkerGuard,
-    /// Dummy field to ensure struct is never empty when all features are disabled
-    #[cfg(not(any(feature = "file", feature = "stdout", feature = "otel")))]
-    _dummy: (),
+    /// Reloads the env filter and max level of the installed subscriber
+    filter: FilterHandle,
 }
 
-impl Drop for LoggingGuard {
-    /// Shutdown all logging providers gracefully
-    fn drop(&mut self) {
-        #[cfg(feature = "otel")]
-        if let Some(ref tracer) = self.tracer_provider
-            && let Err(e) = tracer.force_flush()
-        {
-            eprintln!("Failed to force flush tracer provider: {}", e);
-        }
+impl LoggingGuard {
+    /// Replace the env filter, e.g. "info,my_crate=debug", see
+    /// [`FilterHandle::set_filter`]
+    pub fn set_filter(&self, directives: &str) -> Result<(), SetupLogging> {
+        self.filter.set_filter(directives)
+    }
 
-        #[cfg(feature = "otel")]
-        if let Some(ref logger) = self.logger_provider
-            && let Err(e) = logger.force_flush()
-        {
-            eprintln!("Failed to force flush logger provider: {}", e);
-        }
+    /// Log everything at `level` and above, see [`FilterHandle::set_level`]
+    pub fn set_level(&self, level: Level) -> Result<(), SetupLogging> {
+        self.filter.set_level(level)
+    }
 
-        #[cfg(feature = "otel")]
-        if let Some(ref meter) = self.meter_provider
-            && let Err(e) = meter.force_flush()
-        {
-            eprintln!("Failed to force flush meter provider: {}", e);
-        }
-        #[cfg(feature = "otel")]
-        if let Some(ref tracer) = self.tracer_provider
-            && let Err(e) = tracer.shutdown()
-        {
-            eprintln!("Failed to shutdown tracer provider: {}", e);
-        }
+    /// Cloneable handle that outlives moves of the guard, e.g. for an admin endpoint
+    pub fn filter_handle(&self) -> FilterHandle {
+        self.filter.clone()
+    }
+
+    /// Flush and shut down the OTel providers, giving up after `timeout`
+    ///
+    /// Each provider runs on its own thread, and the ones still running at
+    /// the deadline are abandoned and listed in `ShutdownErrorKind::TimedOut`.
+    /// The stdout and file workers are flushed afterwards, when the guard drops.
+    pub fn shutdown(mut self, timeout: Duration) -> Result<(), ShutdownError> {
+        self.shutdown_providers(timeout)
+    }
+
+    /// `shutdown` on tokio's blocking pool, for async shutdown hooks
+    #[cfg(feature = "tokio")]
+    pub async fn shutdown_async(self, timeout: Duration) -> Result<(), ShutdownError> {
+        tokio::task::spawn_blocking(move || self.shutdown(timeout))
+            .await
+            .map_err(|source| ShutdownError::new(ShutdownErrorKind::Join { source }))?
+    }
+
+    /// Take the providers out of the guard and shut them down in parallel
+    fn shutdown_providers(&mut self, timeout: Duration) -> Result<(), ShutdownError> {
+        #[cfg_attr(not(feature = "otel"), allow(unused_mut))]
+        let mut tasks = Vec::new();
 
         #[cfg(feature = "otel")]
-        if let Some(ref logger) = self.logger_provider
-            && let Err(e) = logger.shutdown()
         {
-            eprintln!("Failed to shutdown logger provider: {}", e);
+            use crate::shutdown::ShutdownTask;
+
+            if let Some(tracer) = self.tracer_provider.take() {
+                tasks.push(ShutdownTask::new("tracer", move || {
+                    let flushed = tracer.force_flush();
+                    flushed.and(tracer.shutdown()).map_err(Into::into)
+                }));
+            }
+            if let Some(logger) = self.logger_provider.take() {
+                tasks.push(ShutdownTask::new("logger", move || {
+                    let flushed = logger.force_flush();
+                    flushed.and(logger.shutdown()).map_err(Into::into)
+                }));
+            }
+            if let Some(meter) = self.meter_provider.take() {
+                tasks.push(ShutdownTask::new("meter", move || {
+                    let flushed = meter.force_flush();
+                    flushed.and(meter.shutdown()).map_err(Into::into)
+                }));
+            }
         }
 
-        #[cfg(feature = "otel")]
-        if let Some(ref meter) = self.meter_provider
-            && let Err(e) = meter.shutdown()
-        {
-            eprintln!("Failed to shutdown meter provider: {}", e);
+        shutdown::run_with_timeout(tasks, timeout)
+    }
+}
+
+impl Drop for LoggingGuard {
+    /// Best-effort shutdown of the providers left, capped at one second
+    fn drop(&mut self) {
+        if let Err(e) = self.shutdown_providers(DROP_SHUTDOWN_TIMEOUT) {
+            eprintln!("Failed to shutdown logging providers: {}", e.kind);
         }
     }
 }
@@ -367,44 +481,73 @@ pub fn setup_logging(
         Option<()>,
     ) = (None, None, None);
 
+    // Reloadable so LoggingGuard can change them at runtime
+    let (env_filter, env_filter_handle) = tracing_subscriber::reload::Layer::new(env_filter);
+    let (level_filter, level_filter_handle) = tracing_subscriber::reload::Layer::new(level_filter);
+    let filter = FilterHandle::new(env_filter_handle, level_filter_handle);
     let registry = registry.with(env_filter).with(level_filter);
 
     #[cfg(feature = "file")]
-    let (registry, file_guard) = {
-        let file_layer = logger_config
-            .file
-            .as_ref()
-            .filter(|fc| fc.enabled)
-            .filter(|_| logger_config.output_mode.enables_file())
-            .map(|file_config| {
-                let (non_blocking, guard) =
-                    setup_file_appender(app_name.clone(), file_config.clone())?;
-                let file_format = file_config
-                    .format
-                    .as_ref()
-                    .or(logger_config.format.as_ref());
-                let layer = tracing_subscriber::fmt::Layer::default()
-                    .with_writer(non_blocking)
-                    .with_timer(timer.clone())
-                    .with_ansi(file_format.map(|f| f.ansi).unwrap_or(false))
-                    .with_target(file_format.map(|f| f.target).unwrap_or(true))
-                    .with_file(file_format.map(|f| f.file).unwrap_or(true))
-                    .with_line_number(file_format.map(|f| f.line_number).unwrap_or(true))
-                    .with_span_events(if file_format.map(|f| f.with_span_events).unwrap_or(true) {
-                        tracing_subscriber::fmt::format::FmtSpan::FULL
-                    } else {
-                        tracing_subscriber::fmt::format::FmtSpan::NONE
-                    });
-                Ok::<_, SetupLogging>((layer, guard))
-            })
-            .transpose()?;
-
-        let (layer, guard) = file_layer.unzip();
-        let guard = guard.unwrap_or_else(|| {
+    let (registry, file_guard, error_file_guard) = {
+        use tracing_subscriber::Layer;
+
+        // Each file gets its own worker, filtered to its min_level
+        let file_layer = |file_config: &FileConfig, file_name: String, default_level: Level| {
+            // Checked before the worker starts, so a typo does not leave it running
+            let min_level = match &file_config.min_level {
+                Some(level) => level
+                    .parse::<Level>()
+                    .map_err(|e| SetupLogging::invalid_level(level, e))?,
+                None => default_level,
+            };
+            let (non_blocking, guard) = setup_file_appender(file_name, file_config.clone())?;
+            let file_format = file_config
+                .format
+                .as_ref()
+                .or(logger_config.format.as_ref());
+            let layer = tracing_subscriber::fmt::Layer::default()
+                .with_writer(non_blocking)
+                .with_timer(timer.clone())
+                .with_ansi(file_format.map(|f| f.ansi).unwrap_or(false))
+                .with_target(file_format.map(|f| f.target).unwrap_or(true))
+                .with_file(file_format.map(|f| f.file).unwrap_or(true))
+                .with_line_number(file_format.map(|f| f.line_number).unwrap_or(true))
+                .with_span_events(if file_format.map(|f| f.with_span_events).unwrap_or(true) {
+                    tracing_subscriber::fmt::format::FmtSpan::FULL
+                } else {
+                    tracing_subscriber::fmt::format::FmtSpan::NONE
+                })
+                .with_filter(tracing_subscriber::filter::LevelFilter::from_level(
+                    min_level,
+                ));
+            Ok::<_, SetupLogging>((layer, guard))
+        };
+        let sink_guard = || {
             let (_, g) = tracing_appender::non_blocking(std::io::sink());
             g
-        });
-        (registry.with(layer), guard)
+        };
+
+        let enabled_file = |file_config: &Option<FileConfig>| {
+            file_config
+                .clone()
+                .filter(|fc| fc.enabled)
+                .filter(|_| logger_config.output_mode.enables_file())
+        };
+
+        let (layer, guard) = enabled_file(&logger_config.file)
+            .map(|file_config| file_layer(&file_config, app_name.clone(), Level::TRACE))
+            .transpose()?
+            .unzip();
+        let (error_layer, error_guard) = enabled_file(&logger_config.error_file)
+            .map(|file_config| file_layer(&file_config, "error".to_string(), Level::ERROR))
+            .transpose()?
+            .unzip();
+
+        (
+            registry.with(Layer::and_then(layer, error_layer)),
+            guard.unwrap_or_else(sink_guard),
+            error_guard.unwrap_or_else(sink_guard),
+        )
     };
 
     #[cfg(not(feature = "file"))]
@@ -454,9 +597,23 @@ pub fn setup_logging(
         })?;
     }
 
+    #[cfg(feature = "otel")]
+    if let Some(key) = logger_config
+        .otel
+        .as_ref()
+        .and_then(|otel| otlp_helpers::ignored_resource_attribute(otel.attributes.as_ref()))
+    {
+        warn!(
+            "Ignoring resource attribute, the service name is set by the app name",
+            key = key
+        );
+    }
+
     Ok(LoggingGuard {
         #[cfg(feature = "file")]
         file_guard,
+        #[cfg(feature = "file")]
+        error_file_guard,
         #[cfg(feature = "otel")]
         tracer_provider,
         #[cfg(feature = "otel")]
@@ -465,7 +622,6 @@ pub fn setup_logging(
         meter_provider,
         #[cfg(feature = "stdout")]
         stdout_guard,
-        #[cfg(not(any(feature = "file", feature = "stdout", feature = "otel")))]
-        _dummy: (),
+        filter,
     })
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::config::{ProtocolConfig, default_protocol, default_timeout_secs};
+use crate::config::{ProtocolConfig, RedactedHeaders, default_protocol, default_timeout_secs};
 
 fn default_interval_secs() -> u64 {
     60
@@ -33,7 +33,7 @@ impl MetricsConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[non_exhaustive]
 pub struct OtlpMetricsConfig {
     #[serde(default)]
@@ -47,10 +47,26 @@ pub struct OtlpMetricsConfig {
     pub interval_secs: u64,
     #[serde(default = "default_timeout_secs")]
     pub timeout_secs: u64,
+    /// Headers sent with every export. Values are redacted from `Debug` output
     pub headers: Option<std::collections::HashMap<String, String>>,
     pub attributes: Option<std::collections::HashMap<String, String>>,
 }
 
+impl std::fmt::Debug for OtlpMetricsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtlpMetricsConfig")
+            .field("enabled", &self.enabled)
+            .field("endpoint", &self.endpoint)
+            .field("metrics_path", &self.metrics_path)
+            .field("protocol", &self.protocol)
+            .field("interval_secs", &self.interval_secs)
+            .field("timeout_secs", &self.timeout_secs)
+            .field("headers", &self.headers.as_ref().map(RedactedHeaders))
+            .field("attributes", &self.attributes)
+            .finish()
+    }
+}
+
 impl Default for OtlpMetricsConfig {
     fn default() -> Self {
         Self {
@@ -99,6 +115,11 @@ impl OtlpMetricsConfig {
         self.timeout_secs = secs;
         self
     }
+
+    pub fn with_headers(mut self, headers: std::collections::HashMap<String, String>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -5,7 +5,10 @@ mod provider;
 mod system;
 
 pub use config::*;
-pub use provider::{MetricsGuard, SetupMetricsError, SetupMetricsErrorKind, setup_metrics};
+pub use provider::{
+    MetricsGuard, SetupMetricsError, SetupMetricsErrorKind, setup_metrics,
+    setup_metrics_with_resource,
+};
 
 pub fn meter(name: &'static str) -> opentelemetry::metrics::Meter {
     opentelemetry::global::meter(name)
@@ -1,5 +1,8 @@
 use super::config::MetricsConfig;
-use crate::{OtelExporterError, otlp_helpers::build_metric_exporter};
+use crate::{
+    OtelExporterError,
+    otlp_helpers::{build_metric_exporter, custom_resource_attributes, ignored_resource_attribute},
+};
 use opentelemetry_sdk::{Resource, metrics::SdkMeterProvider};
 use tokio::task::JoinHandle;
 
@@ -49,9 +52,37 @@ impl Drop for MetricsGuard {
     }
 }
 
+/// Start exporting metrics, with a resource of `service_name` and
+/// `config.otlp.attributes`
+///
+/// A `service.name` in the attributes is dropped and logged as a warning, so
+/// call this after `setup_logging`. Use [`setup_metrics_with_resource`] to
+/// share the resource `otel::build_resource` builds for traces and logs.
 pub fn setup_metrics(
     service_name: &'static str,
     config: MetricsConfig,
+) -> Result<MetricsGuard, SetupMetricsError> {
+    let attributes = config.otlp.attributes.as_ref();
+    if let Some(key) = ignored_resource_attribute(attributes) {
+        crate::warn!(
+            "Ignoring resource attribute, the service name is set by the app name",
+            key = key
+        );
+    }
+    let resource = Resource::builder()
+        .with_service_name(service_name)
+        .with_attributes(custom_resource_attributes(attributes))
+        .build();
+
+    setup_metrics_with_resource(service_name, config, resource)
+}
+
+/// [`setup_metrics`] with the given resource in place of one built from
+/// `config.otlp.attributes`
+pub fn setup_metrics_with_resource(
+    service_name: &'static str,
+    config: MetricsConfig,
+    resource: Resource,
 ) -> Result<MetricsGuard, SetupMetricsError> {
     let otlp = &config.otlp;
 
@@ -66,17 +97,6 @@ pub fn setup_metrics(
         SetupMetricsError::new(SetupMetricsErrorKind::BuildMetricExporter { source: e })
     })?;
 
-    let mut resource_builder = Resource::builder().with_service_name(service_name);
-
-    if let Some(attributes) = &otlp.attributes {
-        for (key, value) in attributes {
-            resource_builder = resource_builder
-                .with_attribute(opentelemetry::KeyValue::new(key.clone(), value.clone()));
-        }
-    }
-
-    let resource = resource_builder.build();
-
     let meter_provider = SdkMeterProvider::builder()
         .with_reader(
             opentelemetry_sdk::metrics::PeriodicReader::builder(metric_exporter)
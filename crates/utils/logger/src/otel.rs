@@ -1,19 +1,13 @@
-use crate::{
-    OpenTelemetryLayer, OtelConfig, OtelExporterError, OtelExporterErrorKind, SetupLogging,
-    SetupLoggingKind,
-};
-use opentelemetry::trace::TracerProvider;
-use opentelemetry_otlp::{Protocol, WithExportConfig, WithHttpConfig, WithTonicConfig};
+use crate::{OpenTelemetryLayer, OtelConfig, SetupLogging, SetupLoggingKind};
+use opentelemetry::{KeyValue, trace::TracerProvider};
 use opentelemetry_sdk::{
     Resource,
     trace::{RandomIdGenerator, Sampler},
 };
-use std::convert::TryFrom;
+use opentelemetry_semantic_conventions::resource::SERVICE_VERSION;
 pub use time::UtcOffset;
 use tracing_subscriber::Registry;
 
-use crate::ProtocolConfig;
-
 pub type OtelProviders = (
     OpenTelemetryLayer<Registry, opentelemetry_sdk::trace::Tracer>,
     opentelemetry_sdk::trace::SdkTracerProvider,
@@ -21,6 +15,36 @@ pub type OtelProviders = (
     Option<opentelemetry_sdk::metrics::SdkMeterProvider>,
 );
 
+/// The resource shared by the tracer, logger and meter providers. With the
+/// `metrics` feature pass it to `setup_metrics_with_resource`, so metrics
+/// carry the same attributes
+///
+/// `service.name` is always `app_name`. The custom `attributes` are applied
+/// first, so `service_version`, `service_namespace` and `environment` win
+/// over the same keys there.
+pub fn build_resource(app_name: &str, otel_config: &OtelConfig) -> Resource {
+    let mut resource_builder = Resource::builder()
+        .with_service_name(app_name.to_owned())
+        .with_attributes(crate::otlp_helpers::custom_resource_attributes(
+            otel_config.attributes.as_ref(),
+        ));
+
+    for (key, value) in [
+        (SERVICE_VERSION, &otel_config.service_version),
+        // These semconv constants are experimental-only, and
+        // `deployment.environment` has since been renamed to
+        // `deployment.environment.name`
+        ("service.namespace", &otel_config.service_namespace),
+        ("deployment.environment", &otel_config.environment),
+    ] {
+        if let Some(value) = value {
+            resource_builder = resource_builder.with_attribute(KeyValue::new(key, value.clone()));
+        }
+    }
+
+    resource_builder.build()
+}
+
 pub fn setup_otel(
     app_name: String,
     otel_config: OtelConfig,
@@ -40,71 +64,16 @@ pub fn setup_otel(
         .unwrap_or(Sampler::AlwaysOn);
 
     // Setup trace exporter for spans with timeout
-    let trace_exporter = match otel_config.protocol {
-        ProtocolConfig::Grpc => {
-            let mut builder = opentelemetry_otlp::SpanExporter::builder()
-                .with_tonic()
-                .with_endpoint(&otel_endpoint)
-                .with_protocol(Protocol::Grpc)
-                .with_timeout(timeout);
-
-            if let Some(headers) = &headers {
-                let mut metadata = tonic::metadata::MetadataMap::new();
-                for (key, value) in headers {
-                    if let (Ok(key), Ok(value)) = (
-                        tonic::metadata::MetadataKey::from_bytes(key.as_bytes()),
-                        tonic::metadata::MetadataValue::try_from(value.as_str()),
-                    ) {
-                        metadata.insert(key, value);
-                    }
-                }
-                builder = builder.with_metadata(metadata);
-            }
-
-            builder.build()
-        }
-        ProtocolConfig::Http => {
-            let endpoint = if let Some(path) = &otel_config.traces_path {
-                format!("{}{}", otel_endpoint, path)
-            } else {
-                otel_endpoint.clone()
-            };
-            let mut builder = opentelemetry_otlp::SpanExporter::builder()
-                .with_http()
-                .with_endpoint(&endpoint)
-                .with_protocol(Protocol::HttpBinary)
-                .with_timeout(timeout);
-
-            if let Some(headers) = &headers {
-                let mut header_map = std::collections::HashMap::new();
-                for (key, value) in headers {
-                    header_map.insert(key.clone(), value.clone());
-                }
-                builder = builder.with_headers(header_map);
-            }
-
-            builder.build()
-        }
-    }
-    .map_err(|e| {
-        SetupLogging::new(SetupLoggingKind::OtelExporter {
-            source: OtelExporterError::new(OtelExporterErrorKind::BuildSpanExporter {
-                source: Box::new(e),
-            }),
-        })
-    })?;
-
-    // Create resource with service name and custom attributes
-    let mut resource_builder = Resource::builder().with_service_name(app_name.clone());
-
-    if let Some(attributes) = &otel_config.attributes {
-        for (key, value) in attributes {
-            resource_builder = resource_builder
-                .with_attribute(opentelemetry::KeyValue::new(key.clone(), value.clone()));
-        }
-    }
+    let trace_exporter = crate::otlp_helpers::build_span_exporter(
+        &otel_endpoint,
+        &otel_config.protocol,
+        timeout,
+        headers.as_ref(),
+        otel_config.traces_path.as_deref(),
+    )
+    .map_err(|e| SetupLogging::new(SetupLoggingKind::OtelExporter { source: e }))?;
 
-    let resource = resource_builder.build();
+    let resource = build_resource(&app_name, &otel_config);
 
     // Configure batch span processor with configurable settings
     let batch_config = opentelemetry_sdk::trace::BatchConfigBuilder::default()
@@ -129,59 +98,14 @@ pub fn setup_otel(
     opentelemetry::global::set_tracer_provider(tracer_provider.clone());
 
     // Setup log exporter with timeout
-    let log_exporter = match otel_config.protocol {
-        ProtocolConfig::Grpc => {
-            let mut builder = opentelemetry_otlp::LogExporter::builder()
-                .with_tonic()
-                .with_endpoint(&otel_endpoint)
-                .with_protocol(Protocol::Grpc)
-                .with_timeout(timeout);
-
-            if let Some(headers) = &headers {
-                let mut metadata = tonic::metadata::MetadataMap::new();
-                for (key, value) in headers {
-                    if let (Ok(key), Ok(value)) = (
-                        tonic::metadata::MetadataKey::from_bytes(key.as_bytes()),
-                        tonic::metadata::MetadataValue::try_from(value.as_str()),
-                    ) {
-                        metadata.insert(key, value);
-                    }
-                }
-                builder = builder.with_metadata(metadata);
-            }
-
-            builder.build()
-        }
-        ProtocolConfig::Http => {
-            let endpoint = if let Some(path) = &otel_config.logs_path {
-                format!("{}{}", otel_endpoint, path)
-            } else {
-                otel_endpoint.clone()
-            };
-            let mut builder = opentelemetry_otlp::LogExporter::builder()
-                .with_http()
-                .with_endpoint(&endpoint)
-                .with_protocol(Protocol::HttpBinary)
-                .with_timeout(timeout);
-
-            if let Some(headers) = &headers {
-                let mut header_map = std::collections::HashMap::new();
-                for (key, value) in headers {
-                    header_map.insert(key.clone(), value.clone());
-                }
-                builder = builder.with_headers(header_map);
-            }
-
-            builder.build()
-        }
-    }
-    .map_err(|e| {
-        SetupLogging::new(SetupLoggingKind::OtelExporter {
-            source: OtelExporterError::new(OtelExporterErrorKind::BuildLogExporter {
-                source: Box::new(e),
-            }),
-        })
-    })?;
+    let log_exporter = crate::otlp_helpers::build_log_exporter(
+        &otel_endpoint,
+        &otel_config.protocol,
+        timeout,
+        headers.as_ref(),
+        otel_config.logs_path.as_deref(),
+    )
+    .map_err(|e| SetupLogging::new(SetupLoggingKind::OtelExporter { source: e }))?;
 
     // Configure batch log processor
     let log_batch_config = opentelemetry_sdk::logs::BatchConfigBuilder::default()
@@ -217,6 +141,7 @@ pub fn setup_otel(
                     .with_interval(std::time::Duration::from_secs(60))
                     .build(),
             )
+            .with_resource(resource)
             .build();
 
         opentelemetry::global::set_meter_provider(meter_provider.clone());
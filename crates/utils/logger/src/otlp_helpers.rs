@@ -1,12 +1,144 @@
 use crate::{OtelExporterError, OtelExporterErrorKind, config::ProtocolConfig};
 use opentelemetry_otlp::{Protocol, WithExportConfig, WithHttpConfig, WithTonicConfig};
-use std::convert::TryFrom;
+use opentelemetry_semantic_conventions::resource::SERVICE_NAME;
+use std::{collections::HashMap, convert::TryFrom};
+
+/// gRPC metadata for the configured headers, skipping names or values tonic
+/// cannot encode
+fn tonic_metadata(headers: &HashMap<String, String>) -> tonic::metadata::MetadataMap {
+    let mut metadata = tonic::metadata::MetadataMap::new();
+    for (key, value) in headers {
+        if let (Ok(key), Ok(value)) = (
+            tonic::metadata::MetadataKey::from_bytes(key.as_bytes()),
+            tonic::metadata::MetadataValue::try_from(value.as_str()),
+        ) {
+            metadata.insert(key, value);
+        }
+    }
+    metadata
+}
+
+/// Custom resource attributes as key-values, without `service.name`, which
+/// always comes from the app name. See [`ignored_resource_attribute`]
+pub(crate) fn custom_resource_attributes(
+    attributes: Option<&HashMap<String, String>>,
+) -> impl Iterator<Item = opentelemetry::KeyValue> + '_ {
+    attributes
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| key.as_str() != SERVICE_NAME)
+        .map(|(key, value)| opentelemetry::KeyValue::new(key.clone(), value.clone()))
+}
+
+/// The configured attribute [`custom_resource_attributes`] drops, for the
+/// caller to report once logging is up
+pub(crate) fn ignored_resource_attribute(
+    attributes: Option<&HashMap<String, String>>,
+) -> Option<&'static str> {
+    attributes
+        .is_some_and(|attributes| attributes.contains_key(SERVICE_NAME))
+        .then_some(SERVICE_NAME)
+}
+
+fn http_endpoint(endpoint: &str, path: Option<&str>) -> String {
+    if let Some(path) = path {
+        format!("{}{}", endpoint, path)
+    } else {
+        endpoint.to_owned()
+    }
+}
+
+#[cfg(feature = "otel")]
+pub(crate) fn build_span_exporter(
+    endpoint: &str,
+    protocol: &ProtocolConfig,
+    timeout: std::time::Duration,
+    headers: Option<&HashMap<String, String>>,
+    traces_path: Option<&str>,
+) -> Result<opentelemetry_otlp::SpanExporter, OtelExporterError> {
+    match protocol {
+        ProtocolConfig::Grpc => {
+            let mut builder = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .with_protocol(Protocol::Grpc)
+                .with_timeout(timeout);
+
+            if let Some(headers) = headers {
+                builder = builder.with_metadata(tonic_metadata(headers));
+            }
+
+            builder.build()
+        }
+        ProtocolConfig::Http => {
+            let mut builder = opentelemetry_otlp::SpanExporter::builder()
+                .with_http()
+                .with_endpoint(http_endpoint(endpoint, traces_path))
+                .with_protocol(Protocol::HttpBinary)
+                .with_timeout(timeout);
+
+            if let Some(headers) = headers {
+                builder = builder.with_headers(headers.clone());
+            }
+
+            builder.build()
+        }
+    }
+    .map_err(|e| {
+        OtelExporterError::new(OtelExporterErrorKind::BuildSpanExporter {
+            source: Box::new(e),
+        })
+    })
+}
+
+#[cfg(feature = "otel")]
+pub(crate) fn build_log_exporter(
+    endpoint: &str,
+    protocol: &ProtocolConfig,
+    timeout: std::time::Duration,
+    headers: Option<&HashMap<String, String>>,
+    logs_path: Option<&str>,
+) -> Result<opentelemetry_otlp::LogExporter, OtelExporterError> {
+    match protocol {
+        ProtocolConfig::Grpc => {
+            let mut builder = opentelemetry_otlp::LogExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .with_protocol(Protocol::Grpc)
+                .with_timeout(timeout);
+
+            if let Some(headers) = headers {
+                builder = builder.with_metadata(tonic_metadata(headers));
+            }
+
+            builder.build()
+        }
+        ProtocolConfig::Http => {
+            let mut builder = opentelemetry_otlp::LogExporter::builder()
+                .with_http()
+                .with_endpoint(http_endpoint(endpoint, logs_path))
+                .with_protocol(Protocol::HttpBinary)
+                .with_timeout(timeout);
+
+            if let Some(headers) = headers {
+                builder = builder.with_headers(headers.clone());
+            }
+
+            builder.build()
+        }
+    }
+    .map_err(|e| {
+        OtelExporterError::new(OtelExporterErrorKind::BuildLogExporter {
+            source: Box::new(e),
+        })
+    })
+}
 
 pub(crate) fn build_metric_exporter(
     endpoint: &str,
     protocol: &ProtocolConfig,
     timeout: std::time::Duration,
-    headers: Option<&std::collections::HashMap<String, String>>,
+    headers: Option<&HashMap<String, String>>,
     metrics_path: Option<&str>,
 ) -> Result<opentelemetry_otlp::MetricExporter, OtelExporterError> {
     match protocol {
@@ -18,38 +150,20 @@ pub(crate) fn build_metric_exporter(
                 .with_timeout(timeout);
 
             if let Some(headers) = headers {
-                let mut metadata = tonic::metadata::MetadataMap::new();
-                for (key, value) in headers {
-                    if let (Ok(key), Ok(value)) = (
-                        tonic::metadata::MetadataKey::from_bytes(key.as_bytes()),
-                        tonic::metadata::MetadataValue::try_from(value.as_str()),
-                    ) {
-                        metadata.insert(key, value);
-                    }
-                }
-                builder = builder.with_metadata(metadata);
+                builder = builder.with_metadata(tonic_metadata(headers));
             }
 
             builder.build()
         }
         ProtocolConfig::Http => {
-            let endpoint = if let Some(path) = metrics_path {
-                format!("{}{}", endpoint, path)
-            } else {
-                endpoint.to_owned()
-            };
             let mut builder = opentelemetry_otlp::MetricExporter::builder()
                 .with_http()
-                .with_endpoint(&endpoint)
+                .with_endpoint(http_endpoint(endpoint, metrics_path))
                 .with_protocol(Protocol::HttpBinary)
                 .with_timeout(timeout);
 
             if let Some(headers) = headers {
-                let mut header_map = std::collections::HashMap::new();
-                for (key, value) in headers {
-                    header_map.insert(key.clone(), value.clone());
-                }
-                builder = builder.with_headers(header_map);
+                builder = builder.with_headers(headers.clone());
             }
 
             builder.build()
@@ -61,3 +175,72 @@ pub(crate) fn build_metric_exporter(
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn headers() -> HashMap<String, String> {
+        HashMap::from([
+            (
+                "authorization".to_string(),
+                "Bearer secret-token".to_string(),
+            ),
+            ("x-tenant-id".to_string(), "acme".to_string()),
+            // Not a valid header name, skipped rather than failing the build
+            ("bad header".to_string(), "value".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_tonic_metadata_skips_invalid_headers() {
+        let metadata = tonic_metadata(&headers());
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(
+            metadata.get("authorization").unwrap(),
+            "Bearer secret-token"
+        );
+        assert_eq!(metadata.get("x-tenant-id").unwrap(), "acme");
+    }
+
+    #[test]
+    fn test_service_name_attribute_is_ignored() {
+        let attributes = HashMap::from([
+            ("service.name".to_string(), "from-config".to_string()),
+            ("team".to_string(), "platform".to_string()),
+        ]);
+
+        let kept: Vec<_> = custom_resource_attributes(Some(&attributes)).collect();
+        assert_eq!(kept, [opentelemetry::KeyValue::new("team", "platform")]);
+        assert_eq!(
+            ignored_resource_attribute(Some(&attributes)),
+            Some("service.name")
+        );
+        assert_eq!(ignored_resource_attribute(None), None);
+    }
+
+    #[test]
+    fn test_build_exporters_with_headers() {
+        // tonic spawns the lazy channel's worker onto the current runtime
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let _enter = runtime.enter();
+
+        let headers = headers();
+        let timeout = Duration::from_secs(1);
+        for (endpoint, protocol) in [
+            ("http://localhost:4317", ProtocolConfig::Grpc),
+            ("http://localhost:4318", ProtocolConfig::Http),
+        ] {
+            #[cfg(feature = "otel")]
+            {
+                build_span_exporter(endpoint, &protocol, timeout, Some(&headers), None).unwrap();
+                build_log_exporter(endpoint, &protocol, timeout, Some(&headers), None).unwrap();
+            }
+            build_metric_exporter(endpoint, &protocol, timeout, Some(&headers), None).unwrap();
+        }
+    }
+}
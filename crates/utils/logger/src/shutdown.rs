@@ -0,0 +1,146 @@
+use crate::{ShutdownError, ShutdownErrorKind};
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A provider's flush and shutdown, run on a thread of its own
+pub(crate) struct ShutdownTask {
+    name: &'static str,
+    run: Box<dyn FnOnce() -> Result<(), BoxError> + Send>,
+}
+
+impl ShutdownTask {
+    #[cfg_attr(not(any(feature = "otel", test)), allow(dead_code))]
+    pub(crate) fn new(
+        name: &'static str,
+        run: impl FnOnce() -> Result<(), BoxError> + Send + 'static,
+    ) -> Self {
+        Self {
+            name,
+            run: Box::new(run),
+        }
+    }
+}
+
+/// Run `tasks` in parallel and wait for them until `timeout`
+///
+/// Tasks still running at the deadline are abandoned on their detached
+/// threads and reported as timed out, as is a task that panicked. Otherwise
+/// the first failure is returned.
+pub(crate) fn run_with_timeout(
+    tasks: Vec<ShutdownTask>,
+    timeout: Duration,
+) -> Result<(), ShutdownError> {
+    let deadline = Instant::now() + timeout;
+    let (tx, rx) = mpsc::channel();
+    let mut pending = Vec::with_capacity(tasks.len());
+    let mut failed = None;
+
+    for ShutdownTask { name, run } in tasks {
+        let tx = tx.clone();
+        let spawned = thread::Builder::new()
+            .name(format!("logger-shutdown-{name}"))
+            .spawn(move || {
+                // The receiver is gone once the deadline has passed
+                let _ = tx.send((name, run()));
+            });
+        match spawned {
+            Ok(_) => pending.push(name),
+            Err(e) => {
+                failed.get_or_insert(ShutdownError::new(ShutdownErrorKind::Provider {
+                    provider: name,
+                    source: Box::new(e),
+                }));
+            }
+        }
+    }
+    drop(tx);
+
+    while !pending.is_empty() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let Ok((name, result)) = rx.recv_timeout(remaining) else {
+            break;
+        };
+        pending.retain(|pending| *pending != name);
+        if let Err(source) = result {
+            failed.get_or_insert(ShutdownError::new(ShutdownErrorKind::Provider {
+                provider: name,
+                source,
+            }));
+        }
+    }
+
+    if !pending.is_empty() {
+        return Err(ShutdownError::new(ShutdownErrorKind::TimedOut {
+            timeout,
+            providers: pending,
+        }));
+    }
+    failed.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_with_timeout_success() {
+        let tasks = vec![
+            ShutdownTask::new("tracer", || Ok(())),
+            ShutdownTask::new("logger", || Ok(())),
+        ];
+        assert!(run_with_timeout(tasks, Duration::from_secs(1)).is_ok());
+        assert!(run_with_timeout(Vec::new(), Duration::ZERO).is_ok());
+    }
+
+    #[test]
+    fn test_run_with_timeout_abandons_slow_tasks() {
+        let tasks = vec![
+            ShutdownTask::new("tracer", || Ok(())),
+            ShutdownTask::new("meter", || {
+                thread::sleep(Duration::from_secs(5));
+                Ok(())
+            }),
+        ];
+        let start = Instant::now();
+        let err = run_with_timeout(tasks, Duration::from_millis(100)).unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        match err.kind {
+            ShutdownErrorKind::TimedOut { timeout, providers } => {
+                assert_eq!(timeout, Duration::from_millis(100));
+                assert_eq!(providers, ["meter"]);
+            }
+            other => panic!("Expected TimedOut, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_run_with_timeout_reports_failures() {
+        let tasks = vec![
+            ShutdownTask::new("tracer", || Ok(())),
+            ShutdownTask::new("logger", || Err("exporter unavailable".into())),
+        ];
+        let err = run_with_timeout(tasks, Duration::from_secs(1)).unwrap_err();
+        match err.kind {
+            ShutdownErrorKind::Provider { provider, source } => {
+                assert_eq!(provider, "logger");
+                assert_eq!(source.to_string(), "exporter unavailable");
+            }
+            other => panic!("Expected Provider, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_run_with_timeout_counts_panics_as_unfinished() {
+        let tasks = vec![ShutdownTask::new("tracer", || panic!("exporter panicked"))];
+        let err = run_with_timeout(tasks, Duration::from_secs(1)).unwrap_err();
+        assert!(matches!(
+            err.kind,
+            ShutdownErrorKind::TimedOut { ref providers, .. } if providers == &["tracer"]
+        ));
+    }
+}
@@ -93,9 +93,49 @@ fn test_file_config_default() {
     assert_eq!(config.max_size, 100 * 1024 * 1024);
     assert_eq!(config.path, "./logs");
     assert!(!config.enabled);
+    assert_eq!(config.rotation, Rotation::Size);
+    assert_eq!(config.max_files, None);
+    assert_eq!(config.max_age_days, None);
+    assert_eq!(config.prefix, None);
+    assert_eq!(config.min_level, None);
     assert!(config.format.is_none());
 }
 
+#[cfg(feature = "file")]
+#[test]
+fn test_rotation_serde() {
+    let variants = vec![
+        (Rotation::Size, "\"size\""),
+        (Rotation::Daily, "\"daily\""),
+        (Rotation::Hourly, "\"hourly\""),
+        (Rotation::Minutely, "\"minutely\""),
+        (Rotation::Never, "\"never\""),
+    ];
+
+    for (variant, expected_json) in variants {
+        let json = serde_json::to_string(&variant).unwrap();
+        assert_eq!(json, expected_json, "serialize {variant:?}");
+        let deserialized: Rotation = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, variant, "roundtrip {variant:?}");
+    }
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_rotation_backward_compat() {
+    // File config JSON without rotation field should keep size-based rotation
+    let json = r#"{"max_size":1024,"path":"./logs","enabled":true,"format":null}"#;
+    let config: FileConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.rotation, Rotation::Size);
+    assert_eq!(config.max_files, None);
+    assert_eq!(config.max_age_days, None);
+
+    let json =
+        r#"{"max_size":1024,"path":"./logs","enabled":true,"format":null,"rotation":"daily"}"#;
+    let config: FileConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.rotation, Rotation::Daily);
+}
+
 #[cfg(feature = "file")]
 #[test]
 fn test_file_config_custom() {
@@ -160,6 +200,101 @@ fn test_otel_config_scheduled_delay() {
     );
 }
 
+#[cfg(feature = "otel")]
+#[test]
+fn test_otel_config_debug_redacts_headers() {
+    let config = OtelConfig::default().with_headers(std::collections::HashMap::from([
+        (
+            "authorization".to_string(),
+            "Bearer secret-token".to_string(),
+        ),
+        ("x-api-key".to_string(), "hunter2".to_string()),
+    ]));
+
+    let debug = format!("{config:?}");
+    assert!(
+        debug.contains(
+            r#"headers: Some({"authorization": "[REDACTED]", "x-api-key": "[REDACTED]"})"#
+        ),
+        "{debug}"
+    );
+    assert!(!debug.contains("secret-token"), "{debug}");
+    assert!(!debug.contains("hunter2"), "{debug}");
+    assert!(
+        debug.contains(r#"endpoint: "http://localhost:4317""#),
+        "{debug}"
+    );
+
+    let debug = format!("{:?}", OtelConfig::default());
+    assert!(debug.contains("headers: None"), "{debug}");
+}
+
+#[cfg(feature = "otel")]
+#[test]
+fn test_otel_config_headers_deserialize() {
+    let json = r#"{
+        "endpoint": "https://otlp.example.com",
+        "enabled": true,
+        "protocol": "http",
+        "headers": { "authorization": "Bearer secret-token" }
+    }"#;
+    let config: OtelConfig = serde_json::from_str(json).unwrap();
+    let headers = config.headers.unwrap();
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers["authorization"], "Bearer secret-token");
+}
+
+#[cfg(feature = "otel")]
+#[test]
+fn test_otel_resource_attributes() {
+    use opentelemetry::{Key, Value};
+
+    let config = OtelConfig::default()
+        .with_attributes(std::collections::HashMap::from([
+            ("team".to_string(), "platform".to_string()),
+            ("service.version".to_string(), "0.0.0".to_string()),
+            ("service.name".to_string(), "from-config".to_string()),
+        ]))
+        .with_service_version("1.4.2")
+        .with_service_namespace("payments")
+        .with_environment("staging");
+
+    let resource = logger::otel::build_resource("billing", &config);
+    let get = |key: &'static str| resource.get(&Key::from_static_str(key));
+    assert_eq!(get("team"), Some(Value::from("platform")));
+    assert_eq!(get("service.version"), Some(Value::from("1.4.2")));
+    assert_eq!(get("service.namespace"), Some(Value::from("payments")));
+    assert_eq!(get("deployment.environment"), Some(Value::from("staging")));
+    // Reserved: the service name always comes from the app name
+    assert_eq!(get("service.name"), Some(Value::from("billing")));
+}
+
+#[cfg(feature = "otel")]
+#[test]
+fn test_otel_resource_attributes_deserialize() {
+    use opentelemetry::{Key, Value};
+
+    let json = r#"{
+        "endpoint": "http://otel:4317",
+        "enabled": true,
+        "environment": "production",
+        "resource_attributes": { "region": "eu-west-1" }
+    }"#;
+    let config: OtelConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.environment.as_deref(), Some("production"));
+    assert_eq!(config.service_version, None);
+
+    let resource = logger::otel::build_resource("billing", &config);
+    assert_eq!(
+        resource.get(&Key::from_static_str("region")),
+        Some(Value::from("eu-west-1"))
+    );
+    assert_eq!(
+        resource.get(&Key::from_static_str("deployment.environment")),
+        Some(Value::from("production"))
+    );
+}
+
 #[cfg(feature = "otel")]
 #[test]
 fn test_sampler_config_default() {
@@ -265,6 +400,36 @@ fn test_logger_config_builder_with_file() {
     assert!(config.file.unwrap().enabled);
 }
 
+#[cfg(feature = "file")]
+#[test]
+fn test_logger_config_builder_with_error_file() {
+    assert!(LoggerConfig::default().error_file.is_none());
+
+    let config = LoggerConfig::default().with_error_file(
+        FileConfig::default()
+            .with_enabled(true)
+            .with_prefix("app-errors")
+            .with_min_level("WARN")
+            .with_rotation(Rotation::Daily),
+    );
+    let error_file = config.error_file.unwrap();
+    assert!(error_file.enabled);
+    assert_eq!(error_file.prefix.as_deref(), Some("app-errors"));
+    assert_eq!(error_file.min_level.as_deref(), Some("WARN"));
+    assert_eq!(error_file.rotation, Rotation::Daily);
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_error_file_serde() {
+    let json = r#"{"max_level":"INFO","error_file":{"max_size":1024,"path":"/var/log/app","enabled":true,"format":null,"min_level":"WARN"}}"#;
+    let config: LoggerConfig = serde_json::from_str(json).unwrap();
+    let error_file = config.error_file.unwrap();
+    assert_eq!(error_file.min_level.as_deref(), Some("WARN"));
+    assert_eq!(error_file.prefix, None);
+    assert!(config.file.is_none());
+}
+
 #[cfg(feature = "otel")]
 #[test]
 fn test_logger_config_builder_with_otel() {
@@ -310,6 +475,23 @@ fn test_file_config_builder() {
     assert!(config.enabled);
 }
 
+#[cfg(feature = "file")]
+#[test]
+fn test_file_config_builder_with_rotation() {
+    let config = FileConfig::default().with_rotation(Rotation::Daily);
+    assert_eq!(config.rotation, Rotation::Daily);
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_file_config_builder_with_retention() {
+    let config = FileConfig::default()
+        .with_max_files(7)
+        .with_max_age_days(30);
+    assert_eq!(config.max_files, Some(7));
+    assert_eq!(config.max_age_days, Some(30));
+}
+
 #[cfg(feature = "file")]
 #[test]
 fn test_file_config_builder_with_format() {
@@ -378,6 +560,23 @@ fn test_otlp_metrics_config_builder() {
     assert_eq!(config.timeout_secs, 10);
 }
 
+#[cfg(feature = "metrics")]
+#[test]
+fn test_otlp_metrics_config_debug_redacts_headers() {
+    use logger::metrics::*;
+
+    let config = OtlpMetricsConfig::default().with_headers(std::collections::HashMap::from([(
+        "authorization".to_string(),
+        "Bearer secret-token".to_string(),
+    )]));
+    let debug = format!("{config:?}");
+    assert!(
+        debug.contains(r#"headers: Some({"authorization": "[REDACTED]"})"#),
+        "{debug}"
+    );
+    assert!(!debug.contains("secret-token"), "{debug}");
+}
+
 #[cfg(feature = "metrics")]
 #[test]
 fn test_system_metrics_config_builder() {
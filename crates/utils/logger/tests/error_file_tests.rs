@@ -0,0 +1,50 @@
+#![cfg(feature = "file")]
+
+// Its own test binary, so this test installs the global subscriber
+
+use logger::{FileConfig, LoggerConfig, error, info, setup_logging, warn};
+
+#[test]
+fn test_error_file_only_receives_errors() {
+    let temp_dir = std::env::temp_dir().join("logger_test_error_file");
+    std::fs::remove_dir_all(&temp_dir).ok();
+    let main_dir = temp_dir.join("main");
+    let error_dir = temp_dir.join("errors");
+
+    let mut config = LoggerConfig::default()
+        .with_max_level("INFO")
+        .with_file(
+            FileConfig::default()
+                .with_path(main_dir.to_string_lossy())
+                .with_enabled(true),
+        )
+        .with_error_file(
+            FileConfig::default()
+                .with_path(error_dir.to_string_lossy())
+                .with_enabled(true),
+        );
+    config.format = None;
+    #[cfg(feature = "otel")]
+    {
+        config.otel = None;
+    }
+
+    let guard = setup_logging("error_file_app", None, config, None).unwrap();
+    info!("routine info event");
+    warn!("routine warn event");
+    error!("payment failed event");
+    // Flushes both workers
+    drop(guard);
+
+    let main_log = std::fs::read_to_string(main_dir.join("error_file_app.log")).unwrap();
+    assert!(main_log.contains("routine info event"), "{main_log}");
+    assert!(main_log.contains("payment failed event"), "{main_log}");
+
+    let error_log = std::fs::read_to_string(error_dir.join("error.log")).unwrap();
+    assert!(error_log.contains("payment failed event"), "{error_log}");
+    assert!(!error_log.contains("routine info event"), "{error_log}");
+    assert!(!error_log.contains("routine warn event"), "{error_log}");
+    assert_eq!(error_log.lines().count(), 1, "{error_log}");
+
+    std::fs::remove_dir_all(temp_dir).ok();
+}
@@ -131,6 +131,31 @@ fn test_logging_guard_drop() {
     }
 }
 
+#[test]
+fn test_logging_guard_shutdown() {
+    let config = LoggerConfig::default();
+    // Allow the test to pass if logger is already initialized
+    if let Ok(guard) = setup_logging("test_app", None, config, None) {
+        logger::info!("Before shutdown");
+        let result = guard.shutdown(std::time::Duration::from_secs(1));
+        assert!(result.is_ok(), "{result:?}");
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_logging_guard_shutdown_async() {
+    let config = LoggerConfig::default();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    // Allow the test to pass if logger is already initialized
+    if let Ok(guard) = setup_logging("test_app", None, config, None) {
+        let result = runtime.block_on(guard.shutdown_async(std::time::Duration::from_secs(1)));
+        assert!(result.is_ok(), "{result:?}");
+    }
+}
+
 #[cfg(feature = "file")]
 #[test]
 fn test_setup_logging_with_file_missing_config() {
@@ -183,6 +208,289 @@ fn test_setup_logging_with_file_config() {
     std::fs::remove_dir_all(temp_dir).ok();
 }
 
+#[cfg(feature = "file")]
+#[test]
+fn test_setup_logging_invalid_min_level() {
+    use logger::{SetupLoggingKind, config::FileConfig};
+
+    let temp_dir = std::env::temp_dir().join("logger_test_invalid_min_level");
+    let config = LoggerConfig::default().with_error_file(
+        FileConfig::default()
+            .with_path(temp_dir.to_string_lossy())
+            .with_min_level("LOUD")
+            .with_enabled(true),
+    );
+
+    let Err(err) = setup_logging("test_app", None, config, None) else {
+        panic!("Expected an invalid min_level to fail setup");
+    };
+    match err.kind {
+        SetupLoggingKind::InvalidLevel { level, .. } => assert_eq!(level, "LOUD"),
+        other => panic!("Expected InvalidLevel error kind, got {other:?}"),
+    }
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_daily_rotation_filename() {
+    use logger::config::{FileConfig, Rotation};
+
+    let temp_dir = std::env::temp_dir().join("logger_test_daily");
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    let date = |now: time::OffsetDateTime| {
+        format!(
+            "{:04}-{:02}-{:02}",
+            now.year(),
+            now.month() as u8,
+            now.day()
+        )
+    };
+    let before = date(time::OffsetDateTime::now_utc());
+    let file_cfg = FileConfig::default()
+        .with_path(temp_dir.to_string_lossy())
+        .with_rotation(Rotation::Daily)
+        .with_enabled(true);
+    let (writer, guard) =
+        logger::file::setup_file_appender("daily_app".to_string(), file_cfg).unwrap();
+    drop(writer);
+    drop(guard);
+    let after = date(time::OffsetDateTime::now_utc());
+
+    let names: Vec<String> = std::fs::read_dir(&temp_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names.len(), 1, "{names:?}");
+    // Allow the day to change between the two reads
+    assert!(
+        names[0] == format!("daily_app.{before}.log")
+            || names[0] == format!("daily_app.{after}.log"),
+        "{names:?}"
+    );
+
+    std::fs::remove_dir_all(temp_dir).ok();
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_setup_logging_with_each_rotation() {
+    use logger::config::{FileConfig, Rotation};
+
+    let rotations = [
+        Rotation::Size,
+        Rotation::Daily,
+        Rotation::Hourly,
+        Rotation::Minutely,
+        Rotation::Never,
+    ];
+    for rotation in rotations {
+        let temp_dir = std::env::temp_dir().join(format!("logger_test_rotation_{rotation:?}"));
+        std::fs::remove_dir_all(&temp_dir).ok();
+
+        // Only the config changes between rotation kinds, not the call
+        let config = LoggerConfig::default().with_file(
+            FileConfig::default()
+                .with_path(temp_dir.to_string_lossy())
+                .with_rotation(rotation)
+                .with_enabled(true),
+        );
+        let result = setup_logging("rotation_app", None, config, None);
+        // Allow test to pass if dispatcher is already set
+        assert!(result.is_ok() || result.is_err());
+        drop(result);
+
+        let names: Vec<String> = std::fs::read_dir(&temp_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names.len(), 1, "{rotation:?}: {names:?}");
+        assert!(
+            names[0].starts_with("rotation_app"),
+            "{rotation:?}: {names:?}"
+        );
+        assert!(names[0].ends_with(".log"), "{rotation:?}: {names:?}");
+        if matches!(rotation, Rotation::Size | Rotation::Never) {
+            assert_eq!(names[0], "rotation_app.log");
+        }
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+}
+
+#[cfg(feature = "file")]
+fn file_names(dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_size_rotation_prunes_excess_files() {
+    use logger::config::FileConfig;
+
+    let temp_dir = std::env::temp_dir().join("logger_test_prune_size");
+    std::fs::remove_dir_all(&temp_dir).ok();
+    std::fs::create_dir_all(&temp_dir).unwrap();
+
+    let fake = [
+        "prune_app-20240101-1.log",
+        "prune_app-20240101-2.log",
+        "prune_app-20240102-1.log",
+        "prune_app-20240103-1.log",
+        "prune_app.log",
+        // Unrelated files in the shared directory
+        "other_app-20230101-1.log",
+        "prune_app_v2-20230101-1.log",
+        "prune_app-notes.log",
+        "prune_app-20240101-1.txt",
+    ];
+    for name in fake {
+        std::fs::write(temp_dir.join(name), "old").unwrap();
+    }
+
+    let file_cfg = FileConfig::default()
+        .with_path(temp_dir.to_string_lossy())
+        .with_max_files(2)
+        .with_enabled(true);
+    let (writer, guard) =
+        logger::file::setup_file_appender("prune_app".to_string(), file_cfg).unwrap();
+    drop(writer);
+    drop(guard);
+
+    assert_eq!(
+        file_names(&temp_dir),
+        [
+            "other_app-20230101-1.log",
+            "prune_app-20240101-1.txt",
+            "prune_app-20240102-1.log",
+            "prune_app-20240103-1.log",
+            "prune_app-notes.log",
+            "prune_app.log",
+            "prune_app_v2-20230101-1.log",
+        ]
+    );
+
+    std::fs::remove_dir_all(temp_dir).ok();
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_size_rotation_prunes_on_rotate() {
+    use logger::config::FileConfig;
+    use std::io::Write;
+
+    let temp_dir = std::env::temp_dir().join("logger_test_prune_rotate");
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    let file_cfg = FileConfig::default()
+        .with_path(temp_dir.to_string_lossy())
+        .with_max_size(10)
+        .with_max_files(1)
+        .with_enabled(true);
+    let (mut writer, guard) =
+        logger::file::setup_file_appender("rotate_app".to_string(), file_cfg).unwrap();
+    // Every write after the first rotates
+    for _ in 0..5 {
+        writer.write_all(b"a line longer than ten\n").unwrap();
+    }
+    drop(writer);
+    drop(guard);
+
+    let names = file_names(&temp_dir);
+    assert_eq!(names.len(), 2, "{names:?}");
+    assert!(names[0].starts_with("rotate_app-"), "{names:?}");
+    assert!(names[0].ends_with("-4.log"), "{names:?}");
+    assert_eq!(names[1], "rotate_app.log");
+
+    std::fs::remove_dir_all(temp_dir).ok();
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_daily_rotation_prunes_by_age_and_count() {
+    use logger::config::{FileConfig, Rotation};
+
+    let temp_dir = std::env::temp_dir().join("logger_test_prune_daily");
+    let name = |days_ago: i64| {
+        let date = time::OffsetDateTime::now_utc().date() - time::Duration::days(days_ago);
+        format!(
+            "aged_app.{:04}-{:02}-{:02}.log",
+            date.year(),
+            date.month() as u8,
+            date.day()
+        )
+    };
+    let setup = |file_cfg: FileConfig| {
+        let (writer, guard) =
+            logger::file::setup_file_appender("aged_app".to_string(), file_cfg).unwrap();
+        drop(writer);
+        drop(guard);
+    };
+    let file_cfg = FileConfig::default()
+        .with_path(temp_dir.to_string_lossy())
+        .with_rotation(Rotation::Daily)
+        .with_enabled(true);
+
+    // Older than the age limit
+    std::fs::remove_dir_all(&temp_dir).ok();
+    std::fs::create_dir_all(&temp_dir).unwrap();
+    for days_ago in [1, 3, 10, 40] {
+        std::fs::write(temp_dir.join(name(days_ago)), "old").unwrap();
+    }
+    std::fs::write(temp_dir.join("aged_app.log"), "unrelated").unwrap();
+    setup(file_cfg.clone().with_max_age_days(5));
+    let mut expected = vec![name(0), name(1), name(3), "aged_app.log".to_string()];
+    expected.sort();
+    assert_eq!(file_names(&temp_dir), expected);
+
+    // Beyond the file count, the file being written is not counted
+    setup(file_cfg.with_max_files(1));
+    let mut expected = vec![name(0), name(1), "aged_app.log".to_string()];
+    expected.sort();
+    assert_eq!(file_names(&temp_dir), expected);
+
+    std::fs::remove_dir_all(temp_dir).ok();
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_prune_errors_do_not_abort_setup() {
+    use logger::config::FileConfig;
+
+    let temp_dir = std::env::temp_dir().join("logger_test_prune_error");
+    std::fs::remove_dir_all(&temp_dir).ok();
+    std::fs::create_dir_all(&temp_dir).unwrap();
+
+    // A directory with a rotated file's name cannot be removed as a file
+    std::fs::create_dir(temp_dir.join("error_app-20240101-1.log")).unwrap();
+    std::fs::write(temp_dir.join("error_app-20240102-1.log"), "old").unwrap();
+    std::fs::write(temp_dir.join("error_app-20240103-1.log"), "old").unwrap();
+
+    let file_cfg = FileConfig::default()
+        .with_path(temp_dir.to_string_lossy())
+        .with_max_files(1)
+        .with_enabled(true);
+    let result = logger::file::setup_file_appender("error_app".to_string(), file_cfg);
+    assert!(result.is_ok());
+    drop(result);
+
+    assert_eq!(
+        file_names(&temp_dir),
+        [
+            "error_app-20240101-1.log",
+            "error_app-20240103-1.log",
+            "error_app.log",
+        ]
+    );
+
+    std::fs::remove_dir_all(temp_dir).ok();
+}
+
 #[test]
 fn test_multiple_directives() {
     let config = LoggerConfig::default();
@@ -0,0 +1,58 @@
+#![cfg(feature = "file")]
+
+// Its own test binary, so this test installs the global subscriber
+
+use logger::{FileConfig, Level, LoggerConfig, SetupLoggingKind, debug, info, setup_logging};
+
+#[test]
+fn test_reload_filter_at_runtime() {
+    let temp_dir = std::env::temp_dir().join("logger_test_reload");
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    let mut config = LoggerConfig::default().with_max_level("INFO").with_file(
+        FileConfig::default()
+            .with_path(temp_dir.to_string_lossy())
+            .with_enabled(true),
+    );
+    config.format = None;
+    #[cfg(feature = "otel")]
+    {
+        config.otel = None;
+    }
+
+    let guard = setup_logging("reload_app", None, config, Some(vec!["info"])).unwrap();
+    debug!("debug before bump");
+    info!("info before bump");
+
+    // The handle is cloned out, as an admin endpoint would keep it
+    let handle = guard.filter_handle();
+    handle.set_level(Level::DEBUG).unwrap();
+    debug!("debug after bump");
+
+    // A bad directive is rejected and the DEBUG filter stays
+    let err = guard.set_filter("info,reload_tests=loud").unwrap_err();
+    assert!(matches!(
+        err.kind,
+        SetupLoggingKind::InvalidEnvFilter { ref directive, .. }
+            if directive == "info,reload_tests=loud"
+    ));
+    debug!("debug after invalid directive");
+
+    guard.set_filter("info").unwrap();
+    debug!("debug after reset");
+    guard.set_filter("warn,reload_tests=debug").unwrap();
+    debug!("debug from target directive");
+
+    // Flushes the file worker
+    drop(guard);
+
+    let log = std::fs::read_to_string(temp_dir.join("reload_app.log")).unwrap();
+    assert!(!log.contains("debug before bump"), "{log}");
+    assert!(log.contains("info before bump"), "{log}");
+    assert!(log.contains("debug after bump"), "{log}");
+    assert!(log.contains("debug after invalid directive"), "{log}");
+    assert!(!log.contains("debug after reset"), "{log}");
+    assert!(log.contains("debug from target directive"), "{log}");
+
+    std::fs::remove_dir_all(temp_dir).ok();
+}